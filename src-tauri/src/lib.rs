@@ -73,6 +73,16 @@ pub fn run() {
         .plugin(tauri_plugin_eco_paste::init())
         // 自定义判断是否自动启动的插件
         .plugin(tauri_plugin_eco_autostart::init())
+        // 密钥存储插件
+        .plugin(tauri_plugin_eco_secret::init())
+        // 剪贴板内容处理插件
+        .plugin(tauri_plugin_eco_clipboard::init())
+        // 同步插件
+        .plugin(tauri_plugin_eco_sync::init())
+        // 定时备份插件
+        .plugin(tauri_plugin_eco_backup::init())
+        // 缩写展开插件
+        .plugin(tauri_plugin_eco_expander::init())
         // 划词助手插件
         .plugin(tauri_plugin_eco_selection::init())
         .on_window_event(|window, event| match event {
@@ -0,0 +1,17 @@
+const COMMANDS: &[&str] = &[
+    "start_auto_backup",
+    "stop_auto_backup",
+    "backup_now",
+    "list_backups",
+    "export_history",
+    "import_history",
+    "migrate_history",
+    "relocate_data_dir",
+    "check_database",
+    "maintain_database",
+    "last_maintenance",
+];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,392 @@
+//! 备份文件
+//!
+//! 格式与手动导出的相同：gzip 压缩的 tar，条目是存储目录下的文件和目录名，可以直接在偏好设置中导入。
+
+use chrono::Local;
+use flate2::{write::GzEncoder, Compression};
+use rusqlite::{backup::Backup, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use crate::error::BackupError;
+
+/// 自动备份保存在存储目录下的这个目录中
+pub const BACKUPS_DIR_NAME: &str = "backups";
+
+// 复制数据库时每一步复制的页数，两步之间让出数据库给前端写入
+const BACKUP_PAGES_PER_STEP: i32 = 256;
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+/// 备份的间隔
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupInterval {
+    Daily,
+    Weekly,
+}
+
+impl BackupInterval {
+    pub fn duration(self) -> Duration {
+        let days = match self {
+            Self::Daily => 1,
+            Self::Weekly => 7,
+        };
+
+        Duration::from_secs(days * 24 * 60 * 60)
+    }
+}
+
+/// 备份的内容和保存方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// 存储数据的目录
+    pub data_dir: PathBuf,
+    /// 数据库的文件名，通过 SQLite 的备份接口复制，不会复制到写入了一半的数据
+    pub database: String,
+    /// 其它需要备份的文件或目录名，不存在时跳过
+    pub includes: Vec<String>,
+    /// 备份文件的扩展名，与手动导出的相同
+    pub extension: String,
+    pub interval: BackupInterval,
+    /// 保留最近的几份备份
+    pub keep: usize,
+}
+
+/// 已有的备份文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// 修改时间的时间戳（毫秒）
+    pub created_at: u64,
+}
+
+impl BackupConfig {
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir.join(BACKUPS_DIR_NAME)
+    }
+
+    /// 计算备份内容的指纹，所有文件的大小和修改时间都没有变化时指纹相同
+    pub fn fingerprint(&self) -> Result<String, BackupError> {
+        let mut hasher = Sha256::new();
+
+        // 数据库使用 WAL 模式时新写入的数据先保存在 `-wal` 文件中
+        let wal = format!("{}-wal", self.database);
+
+        let names = [&self.database, &wal].into_iter().chain(&self.includes);
+
+        for name in names {
+            let root = self.data_dir.join(name);
+
+            if !root.exists() {
+                continue;
+            }
+
+            for entry in WalkDir::new(&root).sort_by_file_name() {
+                let entry = entry.map_err(|e| BackupError::Io(e.into()))?;
+                let metadata = entry.metadata().map_err(|e| BackupError::Io(e.into()))?;
+
+                let modified = metadata
+                    .modified()?
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+
+                let relative = entry
+                    .path()
+                    .strip_prefix(&self.data_dir)
+                    .unwrap_or(entry.path());
+
+                hasher.update(relative.to_string_lossy().as_bytes());
+                hasher.update(metadata.len().to_le_bytes());
+                hasher.update(modified.as_nanos().to_le_bytes());
+            }
+        }
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// 写入一份新的备份，返回备份文件的路径
+    pub fn create(&self) -> Result<PathBuf, BackupError> {
+        let dir = self.backups_dir();
+
+        fs::create_dir_all(&dir)?;
+
+        let name = format!(
+            "{}.{}",
+            Local::now().format("%Y_%m_%d_%H_%M_%S"),
+            self.extension
+        );
+
+        let path = dir.join(&name);
+        let temp_path = dir.join(format!(".{}.tmp", name));
+
+        // 先写入临时文件，中途失败时不会留下不完整的备份
+        if let Err(error) = self.write(&temp_path) {
+            let _ = fs::remove_file(&temp_path);
+
+            return Err(error);
+        }
+
+        fs::rename(&temp_path, &path)?;
+
+        Ok(path)
+    }
+
+    fn write(&self, path: &Path) -> Result<(), BackupError> {
+        let file = File::create(path)?;
+        let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+        let database_path = self.data_dir.join(&self.database);
+
+        if database_path.is_file() {
+            let snapshot = path.with_extension("db");
+
+            let result = copy_database(&database_path, &snapshot).and_then(|_| {
+                let mut file = File::open(&snapshot)?;
+
+                tar.append_file(&self.database, &mut file)?;
+
+                Ok(())
+            });
+
+            let _ = fs::remove_file(&snapshot);
+
+            result?;
+        }
+
+        for name in &self.includes {
+            let include = self.data_dir.join(name);
+
+            if include.is_dir() {
+                tar.append_dir_all(name, &include)?;
+            } else if include.is_file() {
+                tar.append_file(name, &mut File::open(&include)?)?;
+            }
+        }
+
+        tar.into_inner()?.finish()?.sync_all()?;
+
+        Ok(())
+    }
+
+    /// 已有的备份，最新的在前
+    pub fn list(&self) -> Result<Vec<BackupFile>, BackupError> {
+        let dir = self.backups_dir();
+
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let suffix = format!(".{}", self.extension);
+
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            if name.starts_with('.') || !name.ends_with(&suffix) {
+                continue;
+            }
+
+            let metadata = entry.metadata()?;
+
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let created_at = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+
+            files.push(BackupFile {
+                name,
+                path: entry.path(),
+                size: metadata.len(),
+                created_at,
+            });
+        }
+
+        // 文件名是备份的时间，按文件名排序即可
+        files.sort_by(|a, b| b.name.cmp(&a.name));
+
+        Ok(files)
+    }
+
+    /// 删除多余的旧备份，至少保留一份
+    pub fn rotate(&self) -> Result<(), BackupError> {
+        for file in self.list()?.into_iter().skip(self.keep.max(1)) {
+            fs::remove_file(&file.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 使用 SQLite 的备份接口复制数据库，前端同时写入时会重新开始复制
+fn copy_database(source: &Path, target: &Path) -> Result<(), BackupError> {
+    let source = Connection::open_with_flags(
+        source,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+
+    let mut target = Connection::open(target)?;
+
+    Backup::new(&source, &mut target)?.run_to_completion(
+        BACKUP_PAGES_PER_STEP,
+        BACKUP_STEP_PAUSE,
+        None,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_config(keep: usize) -> BackupConfig {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let data_dir = std::env::temp_dir().join(format!(
+            "eco-backup-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(data_dir.join("images")).unwrap();
+
+        BackupConfig {
+            data_dir,
+            database: "EcoPaste.db".into(),
+            includes: vec!["images".into(), ".store.dat".into()],
+            extension: "eco".into(),
+            interval: BackupInterval::Daily,
+            keep,
+        }
+    }
+
+    // 按文件名中的时间写入几份空的备份，另外放入不属于备份的文件
+    fn write_backups(config: &BackupConfig, count: usize) {
+        let dir = config.backups_dir();
+
+        fs::create_dir_all(&dir).unwrap();
+
+        for day in 1..=count {
+            fs::write(dir.join(format!("2024_01_{:02}_00_00_00.eco", day)), b"").unwrap();
+        }
+
+        fs::write(dir.join(".2024_02_01_00_00_00.eco.tmp"), b"").unwrap();
+        fs::write(dir.join("backup-state.json"), b"{}").unwrap();
+    }
+
+    fn names(config: &BackupConfig) -> Vec<String> {
+        config
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|file| file.name)
+            .collect()
+    }
+
+    #[test]
+    fn rotate_keeps_newest() {
+        let config = temp_config(3);
+
+        write_backups(&config, 5);
+        config.rotate().unwrap();
+
+        assert_eq!(
+            names(&config),
+            [
+                "2024_01_05_00_00_00.eco",
+                "2024_01_04_00_00_00.eco",
+                "2024_01_03_00_00_00.eco",
+            ]
+        );
+
+        // 不是备份的文件不会被删除
+        assert!(config.backups_dir().join("backup-state.json").exists());
+        assert!(config
+            .backups_dir()
+            .join(".2024_02_01_00_00_00.eco.tmp")
+            .exists());
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn rotate_keeps_at_least_one() {
+        let config = temp_config(0);
+
+        write_backups(&config, 2);
+        config.rotate().unwrap();
+
+        assert_eq!(names(&config), ["2024_01_02_00_00_00.eco"]);
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn rotate_keeps_all_when_fewer() {
+        let config = temp_config(3);
+
+        write_backups(&config, 2);
+        config.rotate().unwrap();
+
+        assert_eq!(names(&config).len(), 2);
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn creates_archive_with_database_and_includes() {
+        let config = temp_config(3);
+
+        Connection::open(config.data_dir.join(&config.database))
+            .unwrap()
+            .execute_batch("CREATE TABLE history (id TEXT); INSERT INTO history VALUES ('1');")
+            .unwrap();
+        fs::write(config.data_dir.join("images/1.png"), b"png").unwrap();
+
+        let path = config.create().unwrap();
+        let file = File::open(&path).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+
+        let mut entries: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                entry
+                    .unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        entries.sort();
+
+        assert_eq!(entries, ["EcoPaste.db", "images/", "images/1.png"]);
+
+        // 没有留下临时文件
+        assert_eq!(fs::read_dir(config.backups_dir()).unwrap().count(), 1);
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+}
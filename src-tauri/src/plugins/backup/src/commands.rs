@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+use tauri::{async_runtime::spawn_blocking, command, AppHandle, Emitter, Manager, Runtime};
+
+use crate::archive::{BackupConfig, BackupFile};
+use crate::history::{self, ExportOptions, ExportReport, ImportOptions, ImportReport};
+use crate::maintenance::{self, MaintainOptions, MaintainReport, Maintenance};
+use crate::migrate::{self, MigrateOptions, MigrateReport};
+use crate::relocate::{self, RelocateOptions, RelocateProgress, RelocateReport};
+use crate::scheduler::{AutoBackup, BackupReport};
+
+/// 一次定时备份结束，内容为备份的结果
+pub const BACKUP_EVENT: &str = "backup:finished";
+
+/// 移动数据目录的进度
+pub const RELOCATE_EVENT: &str = "backup:relocate-progress";
+
+/// 数据库维护完成，内容为维护的结果
+pub const MAINTAIN_EVENT: &str = "backup:maintained";
+
+/// 启动定时备份，已经启动时按新的配置重新启动
+#[command]
+pub async fn start_auto_backup<R: Runtime>(
+    app: AppHandle<R>,
+    config: BackupConfig,
+) -> Result<(), String> {
+    spawn_blocking(move || {
+        let on_finish = {
+            let app = app.clone();
+
+            move |report: &BackupReport| {
+                if let Err(e) = app.emit(BACKUP_EVENT, report) {
+                    log::error!("Failed to emit backup event: {}", e);
+                }
+            }
+        };
+
+        app.state::<AutoBackup>().start(config, on_finish);
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 停止定时备份，正在进行的备份会先完成
+#[command]
+pub async fn stop_auto_backup<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    spawn_blocking(move || {
+        app.state::<AutoBackup>().stop();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 立即备份一次，返回备份文件的路径
+#[command]
+pub async fn backup_now<R: Runtime>(
+    app: AppHandle<R>,
+    config: BackupConfig,
+) -> Result<PathBuf, String> {
+    spawn_blocking(move || {
+        app.state::<AutoBackup>()
+            .backup_now(&config)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 已有的自动备份，最新的在前
+#[command]
+pub async fn list_backups(config: BackupConfig) -> Result<Vec<BackupFile>, String> {
+    config.list().map_err(|e| e.to_string())
+}
+
+/// 按条件导出历史记录
+#[command]
+pub async fn export_history(options: ExportOptions) -> Result<ExportReport, String> {
+    spawn_blocking(move || history::export(&options).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 把导出的历史记录合并到数据库，ID 已存在的记录保持不变
+#[command]
+pub async fn import_history(options: ImportOptions) -> Result<ImportReport, String> {
+    spawn_blocking(move || history::import(&options).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 从其它剪贴板工具导入历史记录，已有相同内容的记录保持不变
+#[command]
+pub async fn migrate_history(options: MigrateOptions) -> Result<MigrateReport, String> {
+    spawn_blocking(move || migrate::migrate(&options).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 移动数据目录，调用前需要关闭数据库
+///
+/// 移动期间定时备份会暂停，失败时按原来的配置恢复；成功后旧的配置指向已删除的目录，
+/// 定时备份保持停止，由调用方按新的目录重新启动
+#[command]
+pub async fn relocate_data_dir<R: Runtime>(
+    app: AppHandle<R>,
+    options: RelocateOptions,
+) -> Result<RelocateReport, String> {
+    spawn_blocking(move || {
+        let auto_backup = app.state::<AutoBackup>();
+
+        // 等待正在进行的备份完成，避免备份写入旧的目录
+        let suspended = auto_backup.suspend();
+
+        let result = relocate::relocate(&options, |progress: &RelocateProgress| {
+            if let Err(e) = app.emit(RELOCATE_EVENT, progress) {
+                log::error!("Failed to emit relocate event: {}", e);
+            }
+        });
+
+        if let (Err(_), Some(suspended)) = (&result, suspended) {
+            auto_backup.resume(suspended);
+        }
+
+        result.map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 检查数据库的完整性，返回发现的问题，没有问题时为空
+#[command]
+pub async fn check_database(database_path: PathBuf) -> Result<Vec<String>, String> {
+    spawn_blocking(move || maintenance::check(&database_path).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 检查数据库，损坏时用读取到的记录重建，否则整理数据库，调用前需要关闭数据库
+#[command]
+pub async fn maintain_database<R: Runtime>(
+    app: AppHandle<R>,
+    options: MaintainOptions,
+) -> Result<MaintainReport, String> {
+    spawn_blocking(move || {
+        let report = app
+            .state::<Maintenance>()
+            .run(&options)
+            .map_err(|e| e.to_string())?;
+
+        if let Err(e) = app.emit(MAINTAIN_EVENT, &report) {
+            log::error!("Failed to emit maintain event: {}", e);
+        }
+
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 最近一次数据库维护的结果
+#[command]
+pub async fn last_maintenance<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<MaintainReport>, String> {
+    Ok(app.state::<Maintenance>().last())
+}
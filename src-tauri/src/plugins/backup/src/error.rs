@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// 备份失败的原因
+#[derive(Debug)]
+pub enum BackupError {
+    /// 读写文件失败
+    Io(std::io::Error),
+    /// 复制数据库失败
+    Database(String),
+    /// 读写备份状态失败
+    State(String),
+    /// 导入的文件格式错误
+    Format(String),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Database(message) => write!(f, "Database error: {}", message),
+            Self::State(message) => write!(f, "State error: {}", message),
+            Self::Format(message) => write!(f, "Format error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<std::io::Error> for BackupError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<rusqlite::Error> for BackupError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Database(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for BackupError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::State(error.to_string())
+    }
+}
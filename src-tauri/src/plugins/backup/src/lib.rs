@@ -0,0 +1,60 @@
+use tauri::{
+    generate_handler,
+    plugin::{Builder, TauriPlugin},
+    Manager, RunEvent, Runtime,
+};
+
+mod archive;
+mod commands;
+mod error;
+mod history;
+mod maintenance;
+mod migrate;
+mod relocate;
+mod scheduler;
+
+pub use archive::{BackupConfig, BackupFile, BackupInterval, BACKUPS_DIR_NAME};
+pub use commands::*;
+pub use error::BackupError;
+pub use history::{
+    ExportFormat, ExportOptions, ExportReport, HistoryFilter, ImageMode, ImportOptions,
+    ImportReport,
+};
+pub use maintenance::{DatabaseStatus, MaintainOptions, MaintainReport, Maintenance};
+pub use migrate::{MigrateOptions, MigrateReport, MigrateSource};
+pub use relocate::{RelocateOptions, RelocateProgress, RelocateReport, RelocateStage};
+pub use scheduler::{AutoBackup, BackupReport, Suspended};
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("eco-backup")
+        .invoke_handler(generate_handler![
+            commands::start_auto_backup,
+            commands::stop_auto_backup,
+            commands::backup_now,
+            commands::list_backups,
+            commands::export_history,
+            commands::import_history,
+            commands::migrate_history,
+            commands::relocate_data_dir,
+            commands::check_database,
+            commands::maintain_database,
+            commands::last_maintenance,
+        ])
+        .setup(|app, _api| {
+            // 定时备份默认关闭，由前端在用户开启后启动
+            app.manage(AutoBackup::default());
+
+            app.manage(Maintenance::default());
+
+            Ok(())
+        })
+        .on_event(|app, event| {
+            // 退出时等待正在进行的备份完成，避免留下临时文件
+            if let RunEvent::Exit = event {
+                if let Some(backup) = app.try_state::<AutoBackup>() {
+                    backup.stop();
+                }
+            }
+        })
+        .build()
+}
@@ -0,0 +1,729 @@
+//! 数据库的检查和修复
+//!
+//! 用 `PRAGMA integrity_check` 检查数据库，没有问题时只整理（VACUUM）和更新统计信息（ANALYZE）。
+//! 数据库损坏时按原来的结构新建数据库，逐行读取还能读取的记录，遇到损坏的页面时跳过后继续读取，
+//! 完成后替换原来的数据库。损坏的数据库重命名后保留在原来的目录，不会删除。
+//! 只有 SQLite 确认数据库损坏时才重建，无法打开、没有权限或磁盘已满等错误直接返回，不会改动数据库。
+//! 调用前需要关闭前端的数据库连接。
+
+use chrono::Local;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, ErrorCode, OpenFlags, Statement};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::error::BackupError;
+use crate::history::BUSY_TIMEOUT;
+
+// 检查结果中最多保留的问题数量
+const MAX_PROBLEMS: usize = 20;
+
+// 读取失败后向后跳过的次数上限，每次跳过的范围翻倍，足够跳过整个 rowid 的范围
+const MAX_SKIPS: u32 = 64;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintainOptions {
+    pub database_path: PathBuf,
+}
+
+/// 数据库的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DatabaseStatus {
+    /// 没有发现问题，已经整理
+    Healthy,
+    /// 发现损坏，已经用读取到的记录重建
+    Repaired,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintainReport {
+    pub status: DatabaseStatus,
+    /// 检查发现的问题
+    pub problems: Vec<String>,
+    /// 重建时读取到的记录数量
+    pub salvaged: u64,
+    /// 损坏的数据库重命名后的路径
+    pub corrupt_path: Option<PathBuf>,
+    pub size_before: u64,
+    pub size_after: u64,
+    /// 完成的时间戳（毫秒）
+    pub finished_at: i64,
+}
+
+/// 数据库维护，同一时间只运行一次，并记下最近一次的结果
+#[derive(Default)]
+pub struct Maintenance {
+    running: Mutex<()>,
+    last: Mutex<Option<MaintainReport>>,
+}
+
+impl Maintenance {
+    /// 检查并修复或整理数据库
+    pub fn run(&self, options: &MaintainOptions) -> Result<MaintainReport, BackupError> {
+        let _running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+
+        let report = maintain(&options.database_path)?;
+
+        *self.report() = Some(report.clone());
+
+        Ok(report)
+    }
+
+    /// 最近一次的结果，应用启动后还没有运行过时为空
+    pub fn last(&self) -> Option<MaintainReport> {
+        self.report().clone()
+    }
+
+    fn report(&self) -> MutexGuard<'_, Option<MaintainReport>> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 检查数据库的完整性，返回发现的问题，没有问题时为空
+pub fn check(path: &Path) -> Result<Vec<String>, BackupError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let result = (|| {
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+
+        let sql = format!("PRAGMA integrity_check({})", MAX_PROBLEMS);
+
+        let messages = connection
+            .prepare(&sql)?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    })();
+
+    match result {
+        Ok(messages) if messages == ["ok"] => Ok(Vec::new()),
+        Ok(messages) => Ok(messages),
+        // 只有确定损坏时才需要修复，无法打开、没有权限、磁盘已满、正在写入等情况不能重建数据库
+        Err(error) if is_corrupt(&error) => Ok(vec![error.to_string()]),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn maintain(path: &Path) -> Result<MaintainReport, BackupError> {
+    let size_before = database_size(path);
+
+    let problems = check(path)?;
+
+    let mut report = MaintainReport {
+        status: DatabaseStatus::Healthy,
+        problems,
+        salvaged: 0,
+        corrupt_path: None,
+        size_before,
+        size_after: size_before,
+        finished_at: 0,
+    };
+
+    if report.problems.is_empty() {
+        if path.exists() {
+            optimize(&Connection::open(path)?)?;
+        }
+    } else {
+        let (salvaged, corrupt_path) = repair(path)?;
+
+        report.status = DatabaseStatus::Repaired;
+        report.salvaged = salvaged;
+        report.corrupt_path = Some(corrupt_path);
+    }
+
+    report.size_after = database_size(path);
+    report.finished_at = Local::now().timestamp_millis();
+
+    Ok(report)
+}
+
+// 用读取到的记录重建数据库，返回读取到的记录数量和损坏的数据库的新路径
+fn repair(path: &Path) -> Result<(u64, PathBuf), BackupError> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| BackupError::State("invalid database path".into()))?;
+
+    let temp_path = path.with_file_name(format!("{}.repairing", file_name));
+
+    remove_database(&temp_path)?;
+
+    let salvaged = {
+        let target = Connection::open(&temp_path)?;
+
+        // 数据库头损坏时无法打开，只能新建空的数据库
+        let salvaged = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+            Ok(source) => salvage(&source, &target)?,
+            Err(error) => {
+                log::warn!("Failed to open the corrupt database: {}", error);
+
+                0
+            }
+        };
+
+        optimize(&target)?;
+
+        salvaged
+    };
+
+    let timestamp = Local::now().format("%Y%m%d%H%M%S");
+    let mut corrupt_path = path.with_file_name(format!("{}.corrupt-{}", file_name, timestamp));
+
+    // 不覆盖之前保留的数据库
+    for index in 1.. {
+        if !corrupt_path.exists() {
+            break;
+        }
+
+        corrupt_path =
+            path.with_file_name(format!("{}.corrupt-{}-{}", file_name, timestamp, index));
+    }
+
+    // 预写日志属于损坏的数据库，一起保留，共享内存文件会重新生成
+    fs::rename(path, &corrupt_path)?;
+
+    for suffix in ["-wal", "-journal"] {
+        let from = with_suffix(path, suffix);
+
+        if from.exists() {
+            fs::rename(from, with_suffix(&corrupt_path, suffix))?;
+        }
+    }
+
+    let _ = fs::remove_file(with_suffix(path, "-shm"));
+
+    if let Err(error) = fs::rename(&temp_path, path) {
+        // 放回原来的数据库，不让应用在没有数据库的情况下启动
+        let _ = fs::rename(&corrupt_path, path);
+
+        return Err(error.into());
+    }
+
+    Ok((salvaged, corrupt_path))
+}
+
+// 先按原来的结构建表并复制记录，最后创建索引、触发器和视图，避免唯一索引导致记录无法写入
+fn salvage(source: &Connection, target: &Connection) -> Result<u64, BackupError> {
+    source.busy_timeout(BUSY_TIMEOUT)?;
+
+    // 文件被截断时，数据库头中的页数大于文件的大小，只有打开 writable_schema 才能读取，
+    // 连接是只读的，不会写入
+    if let Err(error) = source.execute_batch("PRAGMA writable_schema = ON") {
+        log::warn!("Failed to enable writable_schema: {}", error);
+    }
+
+    let schema = read_schema(source);
+
+    if let Ok(version) = source.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0)) {
+        target.execute_batch(&format!("PRAGMA user_version = {}", version))?;
+    }
+
+    let mut salvaged = 0;
+
+    for (kind, name, sql) in &schema {
+        if kind != "table" {
+            continue;
+        }
+
+        if let Err(error) = target.execute_batch(sql) {
+            log::warn!("Failed to create table {}: {}", name, error);
+
+            continue;
+        }
+
+        salvaged += salvage_table(source, target, name)?;
+    }
+
+    for (kind, name, sql) in &schema {
+        if kind == "table" {
+            continue;
+        }
+
+        if let Err(error) = target.execute_batch(sql) {
+            log::warn!("Failed to create {} {}: {}", kind, name, error);
+        }
+    }
+
+    Ok(salvaged)
+}
+
+// 数据库中的结构，读取失败时只返回已经读取到的部分
+fn read_schema(source: &Connection) -> Vec<(String, String, String)> {
+    let mut schema = Vec::new();
+
+    let Ok(mut statement) = source.prepare(
+        "SELECT type, name, sql FROM sqlite_master \
+         WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' ORDER BY rowid",
+    ) else {
+        return schema;
+    };
+
+    let Ok(mut rows) = statement.query([]) else {
+        return schema;
+    };
+
+    while let Ok(Some(row)) = rows.next() {
+        if let (Ok(kind), Ok(name), Ok(sql)) = (row.get(0), row.get(1), row.get(2)) {
+            schema.push((kind, name, sql));
+        }
+    }
+
+    schema
+}
+
+fn salvage_table(
+    source: &Connection,
+    target: &Connection,
+    table: &str,
+) -> Result<u64, BackupError> {
+    let columns = target
+        .prepare("SELECT name FROM pragma_table_info(?1)")?
+        .query_map([table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if columns.is_empty() {
+        return Ok(0);
+    }
+
+    let names = columns
+        .iter()
+        .map(|column| quote(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let insert_sql = format!(
+        "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+        quote(table),
+        names,
+        vec!["?"; columns.len()].join(", ")
+    );
+
+    let transaction = target.unchecked_transaction()?;
+    let mut insert = transaction.prepare(&insert_sql)?;
+    let mut salvaged = 0;
+
+    let mut on_row = |values: Vec<Value>| -> Result<(), BackupError> {
+        insert.execute(params_from_iter(values))?;
+
+        salvaged += 1;
+
+        Ok(())
+    };
+
+    let by_rowid = format!(
+        "SELECT rowid, {} FROM {} WHERE rowid >= ?1 ORDER BY rowid",
+        names,
+        quote(table)
+    );
+
+    match source.prepare(&by_rowid) {
+        Ok(mut statement) => {
+            // 下一条要读取的记录的 rowid
+            let mut from = i64::MIN;
+
+            loop {
+                let mut failed = false;
+
+                if let Ok(mut rows) = statement.query([from]) {
+                    loop {
+                        match rows.next() {
+                            Ok(Some(row)) => {
+                                let Ok(rowid) = row.get::<_, i64>(0) else {
+                                    failed = true;
+                                    break;
+                                };
+
+                                if let Some(values) = read_values(row, 1, columns.len()) {
+                                    on_row(values)?;
+                                }
+
+                                let Some(next) = rowid.checked_add(1) else {
+                                    break;
+                                };
+
+                                from = next;
+                            }
+                            Ok(None) => break,
+                            Err(_) => {
+                                failed = true;
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    failed = true;
+                }
+
+                if !failed {
+                    break;
+                }
+
+                match next_readable(&mut statement, from) {
+                    Some(next) => from = next,
+                    None => break,
+                }
+            }
+        }
+        // 没有 rowid 的表只能顺序读取到第一个损坏的位置
+        Err(_) => {
+            let sql = format!("SELECT {} FROM {}", names, quote(table));
+
+            if let Ok(mut statement) = source.prepare(&sql) {
+                if let Ok(mut rows) = statement.query([]) {
+                    while let Ok(Some(row)) = rows.next() {
+                        if let Some(values) = read_values(row, 0, columns.len()) {
+                            on_row(values)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    drop(insert);
+
+    transaction.commit()?;
+
+    if salvaged > 0 {
+        log::info!("Salvaged {} rows from {}", salvaged, table);
+    }
+
+    Ok(salvaged)
+}
+
+// 跳过无法读取的记录，返回下一个可以开始读取的 rowid
+//
+// 连续失败时扩大跳过的范围，找到可以读取的位置后再二分缩小，不丢掉损坏页面之后的记录
+fn next_readable(statement: &mut Statement, mut failed: i64) -> Option<i64> {
+    let mut skip = 1i64;
+    let mut found = None;
+
+    for _ in 0..MAX_SKIPS {
+        if failed == i64::MAX {
+            return None;
+        }
+
+        let next = failed.saturating_add(skip);
+
+        if readable(statement, next) {
+            found = Some(next);
+            break;
+        }
+
+        failed = next;
+        skip = skip.saturating_mul(2);
+    }
+
+    let mut found = found?;
+
+    while found.abs_diff(failed) > 1 {
+        let middle = failed + (found.abs_diff(failed) / 2) as i64;
+
+        if readable(statement, middle) {
+            found = middle;
+        } else {
+            failed = middle;
+        }
+    }
+
+    Some(found)
+}
+
+fn readable(statement: &mut Statement, from: i64) -> bool {
+    statement
+        .query([from])
+        .and_then(|mut rows| rows.next().map(|_| ()))
+        .is_ok()
+}
+
+fn read_values(row: &rusqlite::Row, start: usize, length: usize) -> Option<Vec<Value>> {
+    (start..start + length)
+        .map(|index| row.get::<_, Value>(index).ok())
+        .collect()
+}
+
+fn optimize(connection: &Connection) -> Result<(), BackupError> {
+    connection.busy_timeout(BUSY_TIMEOUT)?;
+    connection.execute_batch("VACUUM; ANALYZE;")?;
+
+    Ok(())
+}
+
+fn is_corrupt(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+
+    path.push(suffix);
+
+    PathBuf::from(path)
+}
+
+fn remove_database(path: &Path) -> Result<(), BackupError> {
+    for suffix in ["", "-wal", "-shm", "-journal"] {
+        let path = with_suffix(path, suffix);
+
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+// 数据库和预写日志的大小
+fn database_size(path: &Path) -> u64 {
+    ["", "-wal"]
+        .iter()
+        .filter_map(|suffix| fs::metadata(with_suffix(path, suffix)).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PAGE_SIZE: usize = 4096;
+    const ROWS: usize = 1000;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+            let dir = std::env::temp_dir().join(format!(
+                "eco-maintenance-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+
+            Self(dir)
+        }
+
+        fn database_path(&self) -> PathBuf {
+            self.0.join("EcoPaste.db")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn marker(index: usize) -> String {
+        format!("row-{:04}-", index)
+    }
+
+    // 每条记录都有可以在文件中找到的标记，只在表的页面中出现，一个页面放不下几条记录
+    fn create_database(path: &Path) {
+        let connection = Connection::open(path).unwrap();
+
+        connection
+            .execute_batch(&format!(
+                "PRAGMA page_size = {}; PRAGMA user_version = 3;
+                 CREATE TABLE history (id TEXT PRIMARY KEY, value TEXT, count INTEGER);
+                 CREATE INDEX history_count ON history (count);",
+                PAGE_SIZE
+            ))
+            .unwrap();
+
+        let transaction = connection.unchecked_transaction().unwrap();
+
+        for index in 1..=ROWS {
+            transaction
+                .execute(
+                    "INSERT INTO history (id, value, count) VALUES (?1, ?2, ?3)",
+                    (
+                        format!("{:04}", index),
+                        format!("{}{}", marker(index), "x".repeat(200)),
+                        index,
+                    ),
+                )
+                .unwrap();
+        }
+
+        transaction.commit().unwrap();
+    }
+
+    // 页面中的记录
+    fn rows_in(bytes: &[u8]) -> BTreeSet<usize> {
+        let text = String::from_utf8_lossy(bytes);
+
+        (1..=ROWS)
+            .filter(|index| text.contains(&marker(*index)))
+            .collect()
+    }
+
+    fn read_ids(path: &Path) -> BTreeSet<usize> {
+        let connection = Connection::open(path).unwrap();
+        let mut statement = connection.prepare("SELECT id FROM history").unwrap();
+
+        let ids = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+            .map(|id| id.unwrap().parse().unwrap())
+            .collect();
+
+        ids
+    }
+
+    // 修复后的数据库可以正常使用，返回其中的记录
+    fn assert_repaired(path: &Path, report: &MaintainReport, original: &[u8]) -> BTreeSet<usize> {
+        assert_eq!(report.status, DatabaseStatus::Repaired);
+        assert!(!report.problems.is_empty());
+        assert!(check(path).unwrap().is_empty());
+
+        let corrupt_path = report.corrupt_path.as_ref().unwrap();
+        assert_eq!(fs::read(corrupt_path).unwrap(), original);
+
+        let ids = read_ids(path);
+        assert_eq!(ids.len() as u64, report.salvaged);
+
+        let connection = Connection::open(path).unwrap();
+        let version: i64 = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 3);
+
+        let index: i64 = connection
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'history_count'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(index, 1);
+
+        ids
+    }
+
+    #[test]
+    fn optimizes_healthy_database() {
+        let dir = TempDir::new();
+        let path = dir.database_path();
+
+        create_database(&path);
+
+        let report = maintain(&path).unwrap();
+
+        assert_eq!(report.status, DatabaseStatus::Healthy);
+        assert!(report.problems.is_empty());
+        assert_eq!(report.corrupt_path, None);
+        assert_eq!(read_ids(&path).len(), ROWS);
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn keeps_database_that_cannot_be_opened() {
+        let dir = TempDir::new();
+        let path = dir.database_path();
+
+        // 无法打开不代表数据库损坏，不能当作损坏的数据库重命名
+        fs::create_dir(&path).unwrap();
+
+        assert!(check(&path).is_err());
+        assert!(maintain(&path).is_err());
+        assert!(path.is_dir());
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn salvages_rows_around_corrupt_page() {
+        let dir = TempDir::new();
+        let path = dir.database_path();
+
+        create_database(&path);
+
+        let mut bytes = fs::read(&path).unwrap();
+        let original_rows = rows_in(&bytes);
+        assert_eq!(original_rows.len(), ROWS);
+
+        // 破坏中间一条记录所在的页面
+        let needle = marker(ROWS / 2);
+        let offset = bytes
+            .windows(needle.len())
+            .position(|window| window == needle.as_bytes())
+            .unwrap();
+        let page = offset / PAGE_SIZE * PAGE_SIZE..(offset / PAGE_SIZE + 1) * PAGE_SIZE;
+
+        let lost = rows_in(&bytes[page.clone()]);
+        assert!(!lost.is_empty() && lost.len() < 50);
+
+        bytes[page].fill(0xff);
+        fs::write(&path, &bytes).unwrap();
+
+        let report = maintain(&path).unwrap();
+        let ids = assert_repaired(&path, &report, &bytes);
+
+        // 只丢失损坏页面中的记录，前后的记录都在
+        let expected: BTreeSet<_> = original_rows.difference(&lost).copied().collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn salvages_rows_from_truncated_database() {
+        let dir = TempDir::new();
+        let path = dir.database_path();
+
+        create_database(&path);
+
+        let bytes = fs::read(&path).unwrap();
+        let pages = bytes.len() / PAGE_SIZE;
+
+        // 文件只剩下前一半的页面
+        let truncated = &bytes[..pages / 2 * PAGE_SIZE];
+        fs::write(&path, truncated).unwrap();
+
+        let report = maintain(&path).unwrap();
+        let ids = assert_repaired(&path, &report, truncated);
+
+        let expected = rows_in(truncated);
+        assert!(!expected.is_empty() && expected.len() < ROWS);
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn replaces_database_with_broken_header() {
+        let dir = TempDir::new();
+        let path = dir.database_path();
+
+        fs::write(&path, vec![0x5a; PAGE_SIZE * 2]).unwrap();
+
+        let report = maintain(&path).unwrap();
+
+        assert_eq!(report.status, DatabaseStatus::Repaired);
+        assert_eq!(report.salvaged, 0);
+        assert_eq!(
+            fs::read(report.corrupt_path.unwrap()).unwrap(),
+            vec![0x5a; PAGE_SIZE * 2]
+        );
+        assert!(check(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//! Clipy 导出的片段
+//!
+//! Clipy 的历史记录保存在 Realm 数据库中，无法直接读取，这里导入在片段编辑器中导出的 `snippets.xml`：
+//! `<folders>` 中每个 `<folder>` 有 `<title>` 和 `<snippets>`，每个 `<snippet>` 有 `<title>` 和 `<content>`。
+//! 片段都导入为收藏的文本，备注为文件夹和片段的标题。
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+
+use super::{xml_error, Entry};
+use crate::error::BackupError;
+
+pub(super) fn read(path: &Path) -> Result<Vec<Entry>, BackupError> {
+    let xml = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let mut reader = Reader::from_str(&xml);
+
+    reader.config_mut().trim_text(false);
+
+    let mut entries = Vec::new();
+    let mut found = false;
+    // 当前所在的元素，从外到内
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut folder = String::new();
+    let mut title = String::new();
+    let mut content = String::new();
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(element) => {
+                let name = element.name().as_ref().to_vec();
+
+                match name.as_slice() {
+                    b"folders" => found = true,
+                    b"folder" => folder.clear(),
+                    b"snippet" => {
+                        title.clear();
+                        content.clear();
+                    }
+                    _ => {}
+                }
+
+                stack.push(name);
+            }
+            Event::End(element) => {
+                stack.pop();
+
+                if element.name().as_ref() == b"snippet" {
+                    let note = [folder.trim(), title.trim()]
+                        .into_iter()
+                        .filter(|part| !part.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" / ");
+
+                    entries.push(Entry {
+                        text: Some(content.clone()),
+                        favorite: true,
+                        note: Some(note),
+                        ..Default::default()
+                    });
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(xml_error)?;
+
+                push_text(&stack, &text, &mut folder, &mut title, &mut content);
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8_lossy(&data);
+
+                push_text(&stack, &text, &mut folder, &mut title, &mut content);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // 读取到文件结尾时还有没有结束的元素，文件不完整
+    if !stack.is_empty() {
+        return Err(xml_error("unexpected end of file"));
+    }
+
+    if !found {
+        return Err(BackupError::Format("not a Clipy snippets file".into()));
+    }
+
+    Ok(entries)
+}
+
+// 按所在的元素区分文件夹标题、片段标题和片段内容
+fn push_text(
+    stack: &[Vec<u8>],
+    text: &str,
+    folder: &mut String,
+    title: &mut String,
+    content: &mut String,
+) {
+    let [.., parent, current] = stack else {
+        return;
+    };
+
+    match (parent.as_slice(), current.as_slice()) {
+        (b"folder", b"title") => folder.push_str(text),
+        (b"snippet", b"title") => title.push_str(text),
+        (b"snippet", b"content") => content.push_str(text),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrate::tests::temp_file;
+
+    const SNIPPETS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<folders>
+    <folder>
+        <title>Mail</title>
+        <snippets>
+            <snippet>
+                <title>Sign</title>
+                <content>Best regards,
+Tom &amp; Jerry</content>
+            </snippet>
+            <snippet>
+                <title></title>
+                <content><![CDATA[<b>raw</b>]]></content>
+            </snippet>
+        </snippets>
+    </folder>
+    <folder>
+        <title></title>
+        <snippets>
+            <snippet>
+                <title>Empty folder</title>
+                <content>text</content>
+            </snippet>
+        </snippets>
+    </folder>
+</folders>"#;
+
+    #[test]
+    fn reads_snippets() {
+        let entries = read(&temp_file("snippets.xml", SNIPPETS)).unwrap();
+
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(
+            entries[0].text.as_deref(),
+            Some("Best regards,\nTom & Jerry")
+        );
+        assert_eq!(entries[0].note.as_deref(), Some("Mail / Sign"));
+        assert!(entries[0].favorite);
+
+        assert_eq!(entries[1].text.as_deref(), Some("<b>raw</b>"));
+        assert_eq!(entries[1].note.as_deref(), Some("Mail"));
+
+        assert_eq!(entries[2].note.as_deref(), Some("Empty folder"));
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let end = SNIPPETS.find("</snippet>").unwrap();
+
+        for length in [end, end + 3, SNIPPETS.len() - 1] {
+            let path = temp_file("snippets.xml", &SNIPPETS[..length]);
+
+            assert!(read(&path).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_file() {
+        let files = [
+            "<folders><folder></snippet></folders>",
+            "<folders><folder><title>a &unknown; b</title></folder></folders>",
+            "<folders><folder title=\"unclosed></folder></folders>",
+            "<snippets><snippet><content>a</content></snippet></snippets>",
+            "",
+            "\u{0}\u{1}binary",
+        ];
+
+        for file in files {
+            assert!(read(&temp_file("snippets.xml", file)).is_err(), "{}", file);
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! CopyQ 的标签页数据文件
+//!
+//! 每个标签页保存为一个 `copyq_tab_*.dat`，内容是 QDataStream 序列化的记录：
+//! 先是记录数量，之后每条记录是 MIME 类型到内容的映射。
+//! 新格式的记录以 `-2` 开头，MIME 类型用数字前缀缩写，内容可能用 `qCompress` 压缩；
+//! 旧格式的记录直接以数量开头，内容都经过压缩。
+//! 加密插件保存的标签页无法读取。
+
+use flate2::read::ZlibDecoder;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::{parse_uri_list, Entry};
+use crate::error::BackupError;
+
+// 新格式记录的标记
+const FORMAT_V2: i32 = -2;
+
+// 缩写的 MIME 类型前缀，按匹配的先后顺序排列
+const MIME_PREFIXES: &[(char, &str)] = &[
+    ('0', ""),
+    ('1', "application/x-copyq-item"),
+    ('2', "text/plain"),
+    ('3', "application/x-copyq-"),
+    ('4', "text/"),
+    ('5', "application/"),
+    ('6', "image/"),
+];
+
+const MIME_NOTES: &str = "application/x-copyq-item-notes";
+
+const MIME_PINNED: &str = "application/x-copyq-item-pinned";
+
+const IMAGE_MIMES: &[&str] = &[
+    "image/png",
+    "image/bmp",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/tiff",
+];
+
+// 单条内容的上限，超过时认为数据已损坏
+const MAX_LENGTH: usize = 512 * 1024 * 1024;
+
+type ItemData = HashMap<String, Vec<u8>>;
+
+pub(super) fn read(path: &Path) -> Result<Vec<Entry>, BackupError> {
+    let data = fs::read(path)?;
+
+    let items = parse(&data).ok_or_else(|| {
+        BackupError::Format("unrecognized CopyQ tab data, it may be encrypted".into())
+    })?;
+
+    Ok(items.into_iter().map(to_entry).collect())
+}
+
+// 先按完整的结构读取，失败时在文件中查找新格式的记录，兼容带有文件头的版本
+fn parse(data: &[u8]) -> Option<Vec<ItemData>> {
+    if data.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut stream = Stream::new(data);
+
+    let parsed = (|| {
+        let length = usize::try_from(stream.i32()?).ok()?;
+        let mut items = Vec::new();
+
+        for _ in 0..length {
+            items.push(stream.item()?);
+        }
+
+        stream.is_end().then_some(items)
+    })();
+
+    if parsed.is_some() {
+        return parsed;
+    }
+
+    let marker = FORMAT_V2.to_be_bytes();
+    let mut items = Vec::new();
+    let mut position = 0;
+
+    while let Some(offset) = data[position..]
+        .windows(marker.len())
+        .position(|window| window == marker)
+    {
+        let start = position + offset;
+        let mut stream = Stream::new(&data[start..]);
+
+        match stream.item() {
+            Some(item) if !item.is_empty() => {
+                items.push(item);
+                position = start + stream.position;
+            }
+            _ => position = start + 1,
+        }
+    }
+
+    (!items.is_empty()).then_some(items)
+}
+
+fn to_entry(mut item: ItemData) -> Entry {
+    let mut take_text = |mime: &str| {
+        item.remove(mime)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    };
+
+    let text = take_text("text/plain;charset=utf-8").or_else(|| take_text("text/plain"));
+    let html = take_text("text/html");
+    let rtf = take_text("text/rtf").or_else(|| take_text("application/rtf"));
+    let note = take_text(MIME_NOTES);
+    let files = take_text("text/uri-list")
+        .map(|list| parse_uri_list(&list))
+        .unwrap_or_default();
+
+    let image = IMAGE_MIMES.iter().find_map(|mime| item.remove(*mime));
+
+    Entry {
+        text,
+        html,
+        rtf,
+        files,
+        image,
+        favorite: item.contains_key(MIME_PINNED),
+        note,
+        time: None,
+    }
+}
+
+fn expand_mime(mime: String) -> String {
+    let Some(first) = mime.chars().next() else {
+        return mime;
+    };
+
+    match MIME_PREFIXES.iter().find(|(id, _)| *id == first) {
+        Some((_, prefix)) => format!("{}{}", prefix, &mime[1..]),
+        None => mime,
+    }
+}
+
+// `qUncompress`：4 字节的原始长度，之后是 zlib 数据
+fn uncompress(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 4 {
+        return None;
+    }
+
+    let mut data = Vec::new();
+
+    ZlibDecoder::new(&bytes[4..]).read_to_end(&mut data).ok()?;
+
+    Some(data)
+}
+
+// QDataStream 的读取，数字都是大端序
+struct Stream<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn is_end(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(length)?;
+        let bytes = self.data.get(self.position..end)?;
+
+        self.position = end;
+
+        Some(bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        Some(i32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.take(1)? {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+
+    // 长度为 `0xFFFFFFFF` 时是空值
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let length = self.i32()?;
+
+        if length == -1 {
+            return Some(&[]);
+        }
+
+        let length = usize::try_from(length)
+            .ok()
+            .filter(|length| *length <= MAX_LENGTH)?;
+
+        self.take(length)
+    }
+
+    // QString 是 UTF-16 BE
+    fn string(&mut self) -> Option<String> {
+        let bytes = self.bytes()?;
+
+        if bytes.len() % 2 != 0 {
+            return None;
+        }
+
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+
+        String::from_utf16(&units).ok()
+    }
+
+    fn mime(&mut self) -> Option<String> {
+        let mime = self.string()?;
+
+        // MIME 类型只会是可见的 ASCII 字符，用来排除误识别的数据
+        mime.bytes()
+            .all(|byte| byte.is_ascii_graphic())
+            .then_some(mime)
+    }
+
+    fn item(&mut self) -> Option<ItemData> {
+        let length = self.i32()?;
+
+        if length == FORMAT_V2 {
+            let length = usize::try_from(self.i32()?).ok()?;
+            let mut item = ItemData::new();
+
+            for _ in 0..length {
+                let mime = expand_mime(self.mime()?);
+                let compressed = self.bool()?;
+                let bytes = self.bytes()?;
+
+                let bytes = if compressed {
+                    uncompress(bytes)?
+                } else {
+                    bytes.to_vec()
+                };
+
+                item.insert(mime, bytes);
+            }
+
+            return Some(item);
+        }
+
+        let length = usize::try_from(length).ok()?;
+        let mut item = ItemData::new();
+
+        for _ in 0..length {
+            let mime = self.mime()?;
+            let bytes = self.bytes()?;
+
+            let bytes = if bytes.is_empty() {
+                Vec::new()
+            } else {
+                uncompress(bytes)?
+            };
+
+            item.insert(mime, bytes);
+        }
+
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrate::tests::temp_file;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    // 按 QDataStream 的格式写入测试数据
+    #[derive(Default)]
+    struct Writer(Vec<u8>);
+
+    impl Writer {
+        fn i32(mut self, value: i32) -> Self {
+            self.0.extend_from_slice(&value.to_be_bytes());
+            self
+        }
+
+        fn bool(mut self, value: bool) -> Self {
+            self.0.push(u8::from(value));
+            self
+        }
+
+        fn bytes(self, bytes: &[u8]) -> Self {
+            let mut writer = self.i32(bytes.len() as i32);
+            writer.0.extend_from_slice(bytes);
+            writer
+        }
+
+        fn string(self, value: &str) -> Self {
+            let bytes: Vec<u8> = value.encode_utf16().flat_map(u16::to_be_bytes).collect();
+
+            self.bytes(&bytes)
+        }
+
+        fn raw(mut self, bytes: &[u8]) -> Self {
+            self.0.extend_from_slice(bytes);
+            self
+        }
+    }
+
+    // `qCompress`
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+
+        let mut bytes = (data.len() as u32).to_be_bytes().to_vec();
+        bytes.extend(encoder.finish().unwrap());
+        bytes
+    }
+
+    // 新格式的记录，MIME 类型使用缩写
+    fn item_v2(formats: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut writer = Writer::default().i32(FORMAT_V2).i32(formats.len() as i32);
+
+        for (mime, data, compressed) in formats {
+            writer = writer.string(mime).bool(*compressed);
+            writer = if *compressed {
+                writer.bytes(&compress(data))
+            } else {
+                writer.bytes(data)
+            };
+        }
+
+        writer.0
+    }
+
+    fn tab(items: &[Vec<u8>]) -> Vec<u8> {
+        let mut writer = Writer::default().i32(items.len() as i32);
+
+        for item in items {
+            writer = writer.raw(item);
+        }
+
+        writer.0
+    }
+
+    #[test]
+    fn reads_v2_items() {
+        let data = tab(&[
+            item_v2(&[
+                ("2", b"hello", false),
+                ("1-notes", b"note", false),
+                ("1-pinned", b"", false),
+            ]),
+            item_v2(&[("4html", b"<b>hi</b>", true), ("2", b"hi", true)]),
+            item_v2(&[("4uri-list", b"file:///tmp/a%20b\n", false)]),
+        ]);
+
+        let entries = read(&temp_file("copyq_tab.dat", data)).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].text.as_deref(), Some("hello"));
+        assert_eq!(entries[0].note.as_deref(), Some("note"));
+        assert!(entries[0].favorite);
+        assert_eq!(entries[1].html.as_deref(), Some("<b>hi</b>"));
+        assert_eq!(entries[1].text.as_deref(), Some("hi"));
+        assert!(!entries[1].favorite);
+        assert_eq!(entries[2].files, [PathBuf::from("/tmp/a b")]);
+    }
+
+    #[test]
+    fn reads_v1_items() {
+        let item = Writer::default()
+            .i32(2)
+            .string("text/plain")
+            .bytes(&compress(b"hello"))
+            .string("image/png")
+            .bytes(&[])
+            .0;
+
+        let items = parse(&tab(&[item])).unwrap();
+
+        assert_eq!(items[0]["text/plain"], b"hello");
+        assert!(items[0]["image/png"].is_empty());
+    }
+
+    #[test]
+    fn reads_items_after_header() {
+        let mut data = b"CopyQ tab header".to_vec();
+        data.extend(item_v2(&[("2", b"one", false)]));
+        data.extend(item_v2(&[("2", b"two", false)]));
+
+        let items = parse(&data).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1]["text/plain"], b"two");
+    }
+
+    #[test]
+    fn reads_empty_tab() {
+        assert!(read(&temp_file("copyq_tab.dat", [])).unwrap().is_empty());
+        assert!(parse(&tab(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_tab() {
+        let data = tab(&[item_v2(&[("2", b"hello", false), ("4html", b"<b>", true)])]);
+
+        for length in 1..data.len() {
+            assert!(parse(&data[..length]).is_none(), "length {}", length);
+        }
+
+        assert!(read(&temp_file("copyq_tab.dat", &data[..data.len() - 1])).is_err());
+    }
+
+    #[test]
+    fn keeps_complete_items_of_truncated_tab() {
+        let data = tab(&[
+            item_v2(&[("2", b"one", false)]),
+            item_v2(&[("2", b"two", false)]),
+        ]);
+
+        let items = parse(&data[..data.len() - 2]).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["text/plain"], b"one");
+    }
+
+    #[test]
+    fn rejects_invalid_lengths() {
+        // 长度超过剩余的数据
+        let item = Writer::default()
+            .i32(FORMAT_V2)
+            .i32(1)
+            .string("2")
+            .bool(false)
+            .i32(i32::MAX)
+            .raw(b"hello")
+            .0;
+        assert!(parse(&tab(&[item])).is_none());
+
+        // 除了 -1 以外的负数长度
+        let item = Writer::default()
+            .i32(FORMAT_V2)
+            .i32(1)
+            .string("2")
+            .bool(false)
+            .i32(-5)
+            .0;
+        assert!(parse(&tab(&[item])).is_none());
+
+        // 数量远大于实际的记录
+        let data = Writer::default().i32(i32::MAX).raw(&item_v2(&[])).0;
+        assert!(parse(&data).is_none());
+
+        // 奇数长度的 UTF-16 字符串
+        let item = Writer::default().i32(FORMAT_V2).i32(1).bytes(b"abc").0;
+        assert!(parse(&tab(&[item])).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_content() {
+        // 无效的压缩数据
+        let item = Writer::default()
+            .i32(FORMAT_V2)
+            .i32(1)
+            .string("2")
+            .bool(true)
+            .bytes(b"\0\0\0\x05not zlib")
+            .0;
+        assert!(parse(&tab(&[item])).is_none());
+
+        // 布尔值只能是 0 或 1
+        let item = Writer::default()
+            .i32(FORMAT_V2)
+            .i32(1)
+            .string("2")
+            .raw(&[7])
+            .bytes(b"hello")
+            .0;
+        assert!(parse(&tab(&[item])).is_none());
+
+        // MIME 类型中有控制字符
+        let item = Writer::default()
+            .i32(FORMAT_V2)
+            .i32(1)
+            .string("text\n")
+            .bool(false)
+            .bytes(b"hello")
+            .0;
+        assert!(parse(&tab(&[item])).is_none());
+
+        assert!(read(&temp_file("copyq_tab.dat", b"encrypted data")).is_err());
+    }
+}
@@ -0,0 +1,370 @@
+//! Ditto 的数据库
+//!
+//! `Main` 表中每行是一条记录，`lDate` 是 Unix 时间戳，`bIsGroup` 为真的行是分组。
+//! 内容保存在 `Data` 表中，`lParentID` 对应记录的 `lID`，按 Windows 剪贴板格式的名称区分，
+//! 例如 `CF_UNICODETEXT`、`HTML Format`、`CF_HDROP` 和 `CF_DIB`。
+//! 设置为「永不自动删除」的记录导入为收藏。
+
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{decode_utf16_le, from_timestamp, trim_nul, Entry};
+use crate::error::BackupError;
+
+// BITMAPFILEHEADER 的长度
+const FILE_HEADER_LEN: u32 = 14;
+
+// BITMAPINFOHEADER 的长度，此时 BI_BITFIELDS 的颜色掩码在信息头之后
+const INFO_HEADER_LEN: u32 = 40;
+
+const BI_BITFIELDS: u32 = 3;
+
+pub(super) fn read(path: &Path) -> Result<Vec<Entry>, BackupError> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let has_column = |name: &str| -> Result<bool, BackupError> {
+        Ok(connection
+            .prepare("SELECT 1 FROM pragma_table_info('Main') WHERE name = ?1")?
+            .exists([name])?)
+    };
+
+    let favorite = if has_column("lDontAutoDelete")? {
+        "lDontAutoDelete"
+    } else {
+        "0"
+    };
+
+    let group = if has_column("bIsGroup")? {
+        "bIsGroup = 0"
+    } else {
+        "1"
+    };
+
+    let mut formats: HashMap<i64, HashMap<String, Vec<u8>>> = HashMap::new();
+
+    {
+        let mut statement =
+            connection.prepare("SELECT lParentID, strClipBoardFormat, ooData FROM Data")?;
+        let mut rows = statement.query([])?;
+
+        while let Some(row) = rows.next()? {
+            let data: Option<Vec<u8>> = row.get(2)?;
+
+            formats
+                .entry(row.get(0)?)
+                .or_default()
+                .insert(row.get(1)?, data.unwrap_or_default());
+        }
+    }
+
+    let sql = format!(
+        "SELECT lID, lDate, mText, {} FROM Main WHERE {} ORDER BY lDate DESC",
+        favorite, group
+    );
+
+    let mut statement = connection.prepare(&sql)?;
+    let mut rows = statement.query([])?;
+    let mut entries = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let date: Option<i64> = row.get(1)?;
+        let description: Option<String> = row.get(2)?;
+        let favorite: Option<i64> = row.get(3)?;
+
+        let formats = formats.remove(&id).unwrap_or_default();
+        let format = |name: &str| formats.get(name).filter(|data| !data.is_empty());
+
+        // `mText` 是 Ditto 列表中显示的描述，图片记录也有，只在没有内容时作为文本导入
+        let text = format("CF_UNICODETEXT")
+            .map(|data| decode_utf16_le(data))
+            .or_else(|| {
+                format("CF_TEXT").map(|data| String::from_utf8_lossy(trim_nul(data)).into_owned())
+            })
+            .or_else(|| description.filter(|_| formats.is_empty()));
+
+        entries.push(Entry {
+            text,
+            html: format("HTML Format").and_then(|data| parse_cf_html(data)),
+            rtf: format("Rich Text Format")
+                .map(|data| String::from_utf8_lossy(trim_nul(data)).into_owned()),
+            files: format("CF_HDROP")
+                .map(|data| parse_drop_files(data))
+                .unwrap_or_default(),
+            image: format("PNG")
+                .cloned()
+                .or_else(|| format("CF_DIBV5").and_then(|data| dib_to_bmp(data)))
+                .or_else(|| format("CF_DIB").and_then(|data| dib_to_bmp(data))),
+            favorite: favorite.unwrap_or_default() != 0,
+            note: None,
+            time: date.and_then(from_timestamp),
+        });
+    }
+
+    Ok(entries)
+}
+
+// CF_HTML 的开头是描述偏移量的文本，例如 `StartHTML:0000000105`
+fn parse_cf_html(data: &[u8]) -> Option<String> {
+    let data = trim_nul(data);
+    let header = String::from_utf8_lossy(&data[..data.len().min(512)]);
+
+    let offset = |key: &str| -> Option<usize> {
+        let line = header.lines().find_map(|line| line.strip_prefix(key))?;
+
+        line.trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|value| usize::try_from(value).ok())
+    };
+
+    let start = offset("StartHTML:").or_else(|| offset("StartFragment:"))?;
+    let end = offset("EndHTML:")
+        .or_else(|| offset("EndFragment:"))
+        .unwrap_or(data.len())
+        .min(data.len());
+
+    let html = data.get(start..end)?;
+
+    Some(String::from_utf8_lossy(html).into_owned())
+}
+
+// DROPFILES 结构之后是以空字符分隔、以两个空字符结尾的路径列表
+fn parse_drop_files(data: &[u8]) -> Vec<PathBuf> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            data.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let (Some(offset), Some(wide)) = (read_u32(0), read_u32(16)) else {
+        return Vec::new();
+    };
+
+    let Some(list) = data.get(offset as usize..) else {
+        return Vec::new();
+    };
+
+    let paths: Vec<String> = if wide != 0 {
+        let units: Vec<u16> = list
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        units
+            .split(|unit| *unit == 0)
+            .take_while(|path| !path.is_empty())
+            .map(String::from_utf16_lossy)
+            .collect()
+    } else {
+        list.split(|byte| *byte == 0)
+            .take_while(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect()
+    };
+
+    paths.into_iter().map(PathBuf::from).collect()
+}
+
+// CF_DIB 是去掉文件头的 BMP，补上文件头后才能解码
+fn dib_to_bmp(dib: &[u8]) -> Option<Vec<u8>> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            dib.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let header_len = read_u32(0)?;
+    let bit_count = u16::from_le_bytes(dib.get(14..16)?.try_into().ok()?);
+    let compression = read_u32(16)?;
+    let colors_used = read_u32(32)?;
+
+    let colors = match colors_used {
+        0 if bit_count <= 8 => 1 << bit_count,
+        colors => colors,
+    };
+
+    let masks = if header_len == INFO_HEADER_LEN && compression == BI_BITFIELDS {
+        12
+    } else {
+        0
+    };
+
+    // 长度来自文件中的数据，损坏时可能溢出
+    let offset = colors
+        .checked_mul(4)?
+        .checked_add(FILE_HEADER_LEN + masks)?
+        .checked_add(header_len)?;
+    let size = u32::try_from(dib.len())
+        .ok()?
+        .checked_add(FILE_HEADER_LEN)?;
+
+    let mut bmp = Vec::with_capacity(size as usize);
+
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&size.to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&offset.to_le_bytes());
+    bmp.extend_from_slice(dib);
+
+    Some(bmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrate::tests::{temp_file, temp_path};
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    // 1x1 的 24 位 BITMAPINFOHEADER 图片
+    fn dib() -> Vec<u8> {
+        let mut dib = Vec::new();
+
+        for value in [40u32, 1, 1] {
+            dib.extend_from_slice(&value.to_le_bytes());
+        }
+
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&24u16.to_le_bytes());
+        dib.extend_from_slice(&[0; 24]);
+        dib.extend_from_slice(&[0, 0, 255, 0]);
+
+        dib
+    }
+
+    fn drop_files(paths: &[&str]) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        for value in [20u32, 0, 0, 0, 1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        for path in paths {
+            data.extend(utf16(path));
+        }
+
+        data.extend_from_slice(&[0, 0]);
+
+        data
+    }
+
+    fn database() -> PathBuf {
+        let path = temp_path("Ditto.db");
+        let connection = Connection::open(&path).unwrap();
+
+        connection
+            .execute_batch(
+                "CREATE TABLE Main (lID INTEGER PRIMARY KEY, lDate INTEGER, mText TEXT,
+                    lDontAutoDelete INTEGER, bIsGroup INTEGER);
+                CREATE TABLE Data (lParentID INTEGER, strClipBoardFormat TEXT, ooData BLOB);
+                INSERT INTO Main VALUES (1, 1700000003, 'hello', 1, 0);
+                INSERT INTO Main VALUES (2, 1700000002, 'html', 0, 0);
+                INSERT INTO Main VALUES (3, 1700000001, 'files', NULL, 0);
+                INSERT INTO Main VALUES (4, 1700000000, 'image', 0, 0);
+                INSERT INTO Main VALUES (5, 1700000004, 'group', 0, 1);
+                INSERT INTO Main VALUES (6, NULL, 'description', 0, 0);",
+            )
+            .unwrap();
+
+        let html = "Version:0.9\r\nStartHTML:0000000055\r\nEndHTML:0000000066\r\n<b>html</b>\0";
+        assert_eq!(&html[55..66], "<b>html</b>");
+
+        let formats: [(i64, &str, Vec<u8>); 5] = [
+            (1, "CF_UNICODETEXT", utf16("hello")),
+            (2, "HTML Format", html.as_bytes().to_vec()),
+            (2, "CF_TEXT", b"html\0".to_vec()),
+            (3, "CF_HDROP", drop_files(&["C:\\a.txt", "C:\\b.txt"])),
+            (4, "CF_DIB", dib()),
+        ];
+
+        for (id, format, data) in formats {
+            connection
+                .execute(
+                    "INSERT INTO Data VALUES (?1, ?2, ?3)",
+                    rusqlite::params![id, format, data],
+                )
+                .unwrap();
+        }
+
+        path
+    }
+
+    #[test]
+    fn reads_entries() {
+        let entries = read(&database()).unwrap();
+
+        // 分组不会导入，没有时间的记录排在最后
+        assert_eq!(entries.len(), 5);
+
+        assert_eq!(entries[0].text.as_deref(), Some("hello"));
+        assert!(entries[0].favorite);
+        assert_eq!(entries[0].time, from_timestamp(1_700_000_003));
+
+        assert_eq!(entries[1].html.as_deref(), Some("<b>html</b>"));
+        assert_eq!(entries[1].text.as_deref(), Some("html"));
+        assert!(!entries[1].favorite);
+
+        assert_eq!(
+            entries[2].files,
+            [PathBuf::from("C:\\a.txt"), PathBuf::from("C:\\b.txt")]
+        );
+
+        let image = entries[3].image.as_deref().unwrap();
+        assert!(image.starts_with(b"BM"));
+        assert!(image::load_from_memory(image).is_ok());
+        assert!(entries[3].text.is_none());
+
+        assert_eq!(entries[4].text.as_deref(), Some("description"));
+        assert!(entries[4].time.is_none());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(read(&temp_file("Ditto.db", b"not a database")).is_err());
+
+        let path = temp_path("Ditto.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE other (id INTEGER)")
+            .unwrap();
+
+        assert!(read(&path).is_err());
+        assert!(read(&temp_path("missing.db")).is_err());
+    }
+
+    #[test]
+    fn ignores_invalid_formats() {
+        assert_eq!(parse_cf_html(b"StartHTML:100\r\nEndHTML:10\r\n<b>"), None);
+        assert_eq!(parse_cf_html(b"StartHTML:-1\r\n<b>"), None);
+        assert_eq!(parse_cf_html(b"<b>no header</b>"), None);
+        assert_eq!(
+            parse_cf_html(b"StartHTML:0000000022\r\nEndHTML:99999\r\n<b>"),
+            Some("EndHTML:99999\r\n<b>".into())
+        );
+
+        assert!(parse_drop_files(b"").is_empty());
+        assert!(parse_drop_files(&[0xff; 20]).is_empty());
+        assert_eq!(
+            parse_drop_files(&drop_files(&["C:\\a"])[..28]),
+            [PathBuf::from("C:\\a")]
+        );
+
+        assert!(dib_to_bmp(&dib()[..10]).is_none());
+
+        // 信息头中的长度和颜色数量超出范围
+        let mut dib = dib();
+        dib[0..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(dib_to_bmp(&dib).is_none());
+
+        let mut dib = self::dib();
+        dib[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(dib_to_bmp(&dib).is_none());
+    }
+}
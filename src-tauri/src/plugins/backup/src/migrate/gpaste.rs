@@ -0,0 +1,275 @@
+//! GPaste 的历史记录
+//!
+//! 保存在 `~/.local/share/gpaste/history.xml`，每个 `<item>` 是一条记录，`kind` 为
+//! `Text`、`Uris`、`Image` 或 `Password`。2.0 格式的内容在 `<value>` 中，1.0 格式直接在 `<item>` 中。
+//! `Uris` 的内容每行一个文件，`Image` 的内容是 GPaste 保存的图片路径。
+//! 密码不会导入。
+
+use chrono::{DateTime, Local};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+
+use super::{from_timestamp, parse_uri_list, xml_error, Entry};
+use crate::error::BackupError;
+
+// 正在读取的记录
+struct Item {
+    kind: String,
+    time: Option<DateTime<Local>>,
+    // `<value>` 中的内容
+    value: Option<Content>,
+    // 直接在 `<item>` 中的内容
+    direct: Content,
+}
+
+// 文本和 CDATA 分开保存，有 CDATA 时忽略格式化产生的空白
+#[derive(Default)]
+struct Content {
+    text: String,
+    cdata: Option<String>,
+}
+
+impl Content {
+    fn into_string(self) -> String {
+        self.cdata.unwrap_or(self.text)
+    }
+}
+
+pub(super) fn read(path: &Path) -> Result<Vec<Entry>, BackupError> {
+    let xml = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let mut reader = Reader::from_str(&xml);
+
+    reader.config_mut().trim_text(false);
+
+    let mut entries = Vec::new();
+    let mut found = false;
+    let mut item: Option<Item> = None;
+    let mut in_value = false;
+    // 没有结束的元素数量，文件不完整时读取到结尾仍不为 0
+    let mut depth = 0usize;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(element) => {
+                depth += 1;
+
+                match element.name().as_ref() {
+                    b"history" => found = true,
+                    b"item" => item = Some(start_item(&element)?),
+                    b"value" => {
+                        if let Some(item) = item.as_mut() {
+                            item.value = Some(Content::default());
+                            in_value = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(element) => {
+                depth = depth.saturating_sub(1);
+
+                match element.name().as_ref() {
+                    b"value" => in_value = false,
+                    b"item" => {
+                        if let Some(item) = item.take() {
+                            entries.push(to_entry(item));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                if let Some(content) = current(&mut item, in_value) {
+                    content.text.push_str(&text.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(content) = current(&mut item, in_value) {
+                    content
+                        .cdata
+                        .get_or_insert_with(String::new)
+                        .push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(xml_error("unexpected end of file"));
+    }
+
+    if !found {
+        return Err(BackupError::Format("not a GPaste history file".into()));
+    }
+
+    Ok(entries)
+}
+
+fn start_item(element: &BytesStart) -> Result<Item, BackupError> {
+    let attribute = |name: &str| -> Result<Option<String>, BackupError> {
+        let Some(attribute) = element.try_get_attribute(name).map_err(xml_error)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            attribute.unescape_value().map_err(xml_error)?.into_owned(),
+        ))
+    };
+
+    // 新版本的时间是 Unix 时间戳，旧版本是 ISO 8601
+    let time = attribute("date")?.and_then(|date| match date.parse::<i64>() {
+        Ok(seconds) => from_timestamp(seconds),
+        Err(_) => DateTime::parse_from_rfc3339(&date)
+            .ok()
+            .map(|time| time.with_timezone(&Local)),
+    });
+
+    Ok(Item {
+        kind: attribute("kind")?.unwrap_or_else(|| "Text".into()),
+        time,
+        value: None,
+        direct: Content::default(),
+    })
+}
+
+fn current(item: &mut Option<Item>, in_value: bool) -> Option<&mut Content> {
+    let item = item.as_mut()?;
+
+    if in_value {
+        item.value.as_mut()
+    } else {
+        Some(&mut item.direct)
+    }
+}
+
+fn to_entry(item: Item) -> Entry {
+    let Item {
+        kind,
+        time,
+        value,
+        direct,
+    } = item;
+
+    let value = value.unwrap_or(direct).into_string();
+
+    let mut entry = Entry {
+        time,
+        ..Default::default()
+    };
+
+    match kind.as_str() {
+        "Text" => entry.text = Some(value),
+        "Uris" => entry.files = parse_uri_list(&value),
+        "Image" => entry.image = fs::read(value.trim()).ok(),
+        _ => {}
+    }
+
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrate::tests::{temp_file, temp_path};
+    use std::path::PathBuf;
+
+    #[test]
+    fn reads_v2_history() {
+        let image = temp_file("image.png", b"png");
+
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<history version="2.0">
+  <item kind="Text" uuid="1" date="1700000000">
+    <value><![CDATA[hello
+world]]></value>
+  </item>
+  <item kind="Uris" uuid="2" date="2023-11-14T22:13:20Z">
+    <value><![CDATA[file:///home/a%20b.txt
+/home/c.txt]]></value>
+  </item>
+  <item kind="Image" uuid="3">
+    <value><![CDATA[{}]]></value>
+  </item>
+  <item kind="Password" uuid="4" name="mail">
+    <value><![CDATA[secret]]></value>
+  </item>
+  <item kind="Text" uuid="5" date="not a date">
+    <value>a &amp; b</value>
+  </item>
+</history>"#,
+            image.display()
+        );
+
+        let entries = read(&temp_file("history.xml", xml)).unwrap();
+
+        assert_eq!(entries.len(), 5);
+
+        assert_eq!(entries[0].text.as_deref(), Some("hello\nworld"));
+        assert_eq!(entries[0].time, from_timestamp(1_700_000_000));
+
+        assert_eq!(
+            entries[1].files,
+            [PathBuf::from("/home/a b.txt"), PathBuf::from("/home/c.txt")]
+        );
+        assert_eq!(entries[1].time, from_timestamp(1_700_000_000));
+
+        assert_eq!(entries[2].image.as_deref(), Some(&b"png"[..]));
+        assert!(entries[2].time.is_none());
+
+        // 密码没有可导入的内容
+        assert!(entries[3].text.is_none() && entries[3].image.is_none());
+
+        assert_eq!(entries[4].text.as_deref(), Some("a & b"));
+        assert!(entries[4].time.is_none());
+    }
+
+    #[test]
+    fn reads_v1_history() {
+        let xml = r#"<history>
+  <item kind="Text"><![CDATA[hello]]></item>
+  <item>plain</item>
+  <item kind="Image">/missing/image.png</item>
+</history>"#;
+
+        let entries = read(&temp_file("history.xml", xml)).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].text.as_deref(), Some("hello"));
+        assert_eq!(entries[1].text.as_deref(), Some("plain"));
+        assert!(entries[2].image.is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let xml = r#"<history version="2.0"><item kind="Text" date="1"><value><![CDATA[hello]]></value></item></history>"#;
+        let end = xml.find("</item>").unwrap();
+
+        for length in [end - 10, end, end + 3] {
+            let path = temp_file("history.xml", &xml[..length]);
+
+            assert!(read(&path).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_file() {
+        let files = [
+            r#"<history><item kind="Text"></value></item></history>"#,
+            r#"<history><item kind="Text" date="1 >a</item></history>"#,
+            r#"<history><item>a &unknown; b</item></history>"#,
+            r#"<clipboard><item>a</item></clipboard>"#,
+            "",
+        ];
+
+        for file in files {
+            assert!(read(&temp_file("history.xml", file)).is_err(), "{}", file);
+        }
+
+        assert!(read(&temp_path("missing.xml")).is_err());
+    }
+}
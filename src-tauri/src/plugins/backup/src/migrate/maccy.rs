@@ -0,0 +1,254 @@
+//! Maccy 的数据库
+//!
+//! Maccy 用 Core Data（2.0 之后是 SwiftData）保存历史记录，表名和列名都带有 `Z` 前缀。
+//! `ZHISTORYITEM` 中每行是一条记录，时间是从 2001-01-01 开始的秒数，`ZPIN` 有值时是固定的记录；
+//! 内容保存在 `ZHISTORYITEMCONTENT` 中，按 `ZITEM` 对应记录，`ZTYPE` 是 UTI，例如 `public.html`。
+//! 固定的记录导入为收藏。
+
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{decode_utf16_le, file_uri_to_path, from_timestamp, trim_nul, Entry};
+use crate::error::BackupError;
+
+// Core Data 的时间起点 2001-01-01 00:00:00 UTC 的 Unix 时间戳
+const REFERENCE_DATE: i64 = 978_307_200;
+
+const IMAGE_TYPES: &[&str] = &[
+    "public.png",
+    "public.tiff",
+    "public.jpeg",
+    "com.compuserve.gif",
+];
+
+pub(super) fn read(path: &Path) -> Result<Vec<Entry>, BackupError> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let columns: Vec<String> = connection
+        .prepare("SELECT name FROM pragma_table_info('ZHISTORYITEM')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    if columns.is_empty() {
+        return Err(BackupError::Format("not a Maccy database".into()));
+    }
+
+    let column = |name: &str| {
+        if columns.iter().any(|column| column == name) {
+            name.to_string()
+        } else {
+            "NULL".to_string()
+        }
+    };
+
+    let mut contents: HashMap<i64, Vec<(String, Vec<u8>)>> = HashMap::new();
+
+    {
+        let mut statement =
+            connection.prepare("SELECT ZITEM, ZTYPE, ZVALUE FROM ZHISTORYITEMCONTENT")?;
+        let mut rows = statement.query([])?;
+
+        while let Some(row) = rows.next()? {
+            let (Some(item), Some(kind), Some(value)) = (
+                row.get::<_, Option<i64>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<Vec<u8>>>(2)?,
+            ) else {
+                continue;
+            };
+
+            contents.entry(item).or_default().push((kind, value));
+        }
+    }
+
+    // 旧版本只有 ZFIRSTCOPIEDAT
+    let sql = format!(
+        "SELECT Z_PK, COALESCE({}, {}), {}, {} FROM ZHISTORYITEM ORDER BY 2 DESC",
+        column("ZLASTCOPIEDAT"),
+        column("ZFIRSTCOPIEDAT"),
+        column("ZPIN"),
+        column("ZTITLE"),
+    );
+
+    let mut statement = connection.prepare(&sql)?;
+    let mut rows = statement.query([])?;
+    let mut entries = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let time: Option<f64> = row.get(1)?;
+        let pin: Option<String> = row.get(2)?;
+        let title: Option<String> = row.get(3)?;
+
+        let contents = contents.remove(&id).unwrap_or_default();
+
+        let content = |kinds: &[&str]| {
+            kinds.iter().find_map(|kind| {
+                contents
+                    .iter()
+                    .find(|(name, value)| name == kind && !value.is_empty())
+                    .map(|(_, value)| value.as_slice())
+            })
+        };
+
+        let string = |kinds: &[&str]| {
+            content(kinds).map(|value| String::from_utf8_lossy(trim_nul(value)).into_owned())
+        };
+
+        let files = contents
+            .iter()
+            .filter(|(kind, _)| kind == "public.file-url")
+            .filter_map(|(_, value)| file_uri_to_path(&String::from_utf8_lossy(trim_nul(value))))
+            .collect();
+
+        let text = string(&["public.utf8-plain-text", "NSStringPboardType"])
+            .or_else(|| content(&["public.utf16-plain-text"]).map(decode_utf16_le));
+
+        let image = IMAGE_TYPES
+            .iter()
+            .find_map(|kind| content(&[kind]))
+            .map(<[u8]>::to_vec);
+
+        entries.push(Entry {
+            // 标题是 Maccy 列表中显示的描述，只在没有内容时作为文本导入
+            text: text.or_else(|| title.filter(|_| contents.is_empty())),
+            html: string(&["public.html", "Apple HTML pasteboard type"]),
+            rtf: string(&["public.rtf", "NeXT Rich Text Format v1.0 pasteboard type"]),
+            files,
+            image,
+            favorite: pin.is_some_and(|pin| !pin.is_empty()),
+            note: None,
+            time: time.and_then(|time| from_timestamp(REFERENCE_DATE.checked_add(time as i64)?)),
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrate::tests::{temp_file, temp_path};
+    use std::path::PathBuf;
+
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f,
+        0x15, 0xc4, 0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8,
+        0xcf, 0xc0, 0xf0, 0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99, 0x3d, 0x1d, 0x00, 0x00,
+        0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    fn database(items: &str) -> PathBuf {
+        let path = temp_path("Storage.sqlite");
+        let connection = Connection::open(&path).unwrap();
+
+        connection
+            .execute_batch(&format!(
+                "CREATE TABLE ZHISTORYITEMCONTENT (Z_PK INTEGER PRIMARY KEY, ZITEM INTEGER,
+                    ZTYPE VARCHAR, ZVALUE BLOB);
+                {}",
+                items
+            ))
+            .unwrap();
+
+        let contents: [(i64, &str, &[u8]); 6] = [
+            (1, "public.utf8-plain-text", b"hello"),
+            (2, "public.html", b"<b>hi</b>"),
+            (2, "public.utf16-plain-text", b"h\0i\0"),
+            (3, "public.file-url", b"file:///Users/a/b%20c.txt\0"),
+            (4, "public.png", PNG),
+            (4, "public.tiff", b""),
+        ];
+
+        for (item, kind, value) in contents {
+            connection
+                .execute(
+                    "INSERT INTO ZHISTORYITEMCONTENT (ZITEM, ZTYPE, ZVALUE) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![item, kind, value],
+                )
+                .unwrap();
+        }
+
+        path
+    }
+
+    #[test]
+    fn reads_entries() {
+        let path = database(
+            "CREATE TABLE ZHISTORYITEM (Z_PK INTEGER PRIMARY KEY, ZFIRSTCOPIEDAT TIMESTAMP,
+                ZLASTCOPIEDAT TIMESTAMP, ZPIN VARCHAR, ZTITLE VARCHAR);
+            INSERT INTO ZHISTORYITEM VALUES (1, 100, 400, 'b', 'hello');
+            INSERT INTO ZHISTORYITEM VALUES (2, 300, NULL, NULL, 'hi');
+            INSERT INTO ZHISTORYITEM VALUES (3, 200, NULL, '', 'b c.txt');
+            INSERT INTO ZHISTORYITEM VALUES (4, 100, NULL, NULL, 'Image');
+            INSERT INTO ZHISTORYITEM VALUES (5, 50, NULL, NULL, 'title only');",
+        );
+
+        let entries = read(&path).unwrap();
+
+        assert_eq!(entries.len(), 5);
+
+        assert_eq!(entries[0].text.as_deref(), Some("hello"));
+        assert!(entries[0].favorite);
+        assert_eq!(entries[0].time, from_timestamp(REFERENCE_DATE + 400));
+
+        assert_eq!(entries[1].html.as_deref(), Some("<b>hi</b>"));
+        assert_eq!(entries[1].text.as_deref(), Some("hi"));
+        assert!(!entries[1].favorite);
+
+        assert_eq!(entries[2].files, [PathBuf::from("/Users/a/b c.txt")]);
+        assert!(entries[2].text.is_none());
+        assert!(!entries[2].favorite);
+
+        assert_eq!(entries[3].image.as_deref(), Some(PNG));
+
+        // 没有内容的记录导入标题
+        assert_eq!(entries[4].text.as_deref(), Some("title only"));
+    }
+
+    #[test]
+    fn reads_old_schema() {
+        let path = database(
+            "CREATE TABLE ZHISTORYITEM (Z_PK INTEGER PRIMARY KEY, ZFIRSTCOPIEDAT TIMESTAMP);
+            INSERT INTO ZHISTORYITEM VALUES (1, 100);",
+        );
+
+        let entries = read(&path).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text.as_deref(), Some("hello"));
+        assert_eq!(entries[0].time, from_timestamp(REFERENCE_DATE + 100));
+        assert!(!entries[0].favorite);
+    }
+
+    #[test]
+    fn ignores_invalid_times() {
+        let path = database(
+            "CREATE TABLE ZHISTORYITEM (Z_PK INTEGER PRIMARY KEY, ZLASTCOPIEDAT TIMESTAMP);
+            INSERT INTO ZHISTORYITEM VALUES (1, 1e300);
+            INSERT INTO ZHISTORYITEM VALUES (2, -1e300);",
+        );
+
+        let entries = read(&path).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.time.is_none()));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(read(&temp_file("Storage.sqlite", b"not a database")).is_err());
+
+        let path = temp_path("Storage.sqlite");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE ZHISTORYITEM (Z_PK INTEGER PRIMARY KEY)")
+            .unwrap();
+
+        // 缺少内容表
+        assert!(read(&path).is_err());
+        assert!(read(&temp_path("missing.sqlite")).is_err());
+    }
+}
@@ -0,0 +1,711 @@
+//! 移动数据目录
+//!
+//! 数据先复制到新目录下的临时目录，逐个文件比较 SHA-256，确认完整后再移到新目录，
+//! 然后替换配置文件中的数据目录，全部成功后才删除旧目录中的数据。中途失败时删除已经复制的数据，旧目录保持不变。
+//! 复制期间持有数据库的写锁，其它连接只能读取，不会有记录写入旧的数据库而丢失。
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::archive::BACKUPS_DIR_NAME;
+use crate::error::BackupError;
+use crate::history::BUSY_TIMEOUT;
+
+/// 复制中的临时目录，在新的数据目录下
+pub const RELOCATING_DIR_NAME: &str = ".relocating";
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocateOptions {
+    /// 当前的数据目录
+    pub source: PathBuf,
+    /// 新的数据目录
+    pub target: PathBuf,
+    /// 数据库的文件名，预写日志会一起移动
+    pub database: String,
+    /// 其它需要移动的文件和目录，相对于数据目录，不存在的会被忽略
+    pub includes: Vec<String>,
+    /// 保存配置项的文件，移动完成后把其中的数据目录改为新的目录
+    pub store_path: PathBuf,
+}
+
+/// 移动的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelocateStage {
+    Copying,
+    Verifying,
+}
+
+/// 移动的进度，按字节计算
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocateProgress {
+    pub stage: RelocateStage,
+    pub processed: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocateReport {
+    /// 移动的文件数量
+    pub files: usize,
+    /// 移动的字节数
+    pub bytes: u64,
+    /// 旧目录中的数据是否已经删除，删除失败时数据已经在新目录中使用，只需要手动清理
+    pub cleaned: bool,
+}
+
+// 需要复制的文件，路径相对于数据目录
+struct FileEntry {
+    path: PathBuf,
+    size: u64,
+    hash: Option<Vec<u8>>,
+}
+
+/// 移动数据目录，成功后配置文件中的数据目录已经是新的目录
+pub fn relocate(
+    options: &RelocateOptions,
+    mut on_progress: impl FnMut(&RelocateProgress),
+) -> Result<RelocateReport, BackupError> {
+    let RelocateOptions { source, target, .. } = options;
+
+    let names = entry_names(options);
+
+    check_target(source, target, &names)?;
+
+    // 合并预写日志并持有写锁，直到配置切换到新的目录
+    let lock = lock_database(&source.join(&options.database))?;
+
+    let (dirs, mut files) = list(source, &names)?;
+    let total = files.iter().map(|file| file.size).sum();
+
+    let created = !target.exists();
+
+    fs::create_dir_all(target)?;
+
+    // 上次中断时留下的临时目录
+    let staging = target.join(RELOCATING_DIR_NAME);
+
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+
+    let mut moved = Vec::new();
+
+    let result = (|| {
+        for dir in &dirs {
+            fs::create_dir_all(staging.join(dir))?;
+        }
+
+        let mut progress = Progress::new(RelocateStage::Copying, total, &mut on_progress);
+
+        for file in files.iter_mut() {
+            file.hash = Some(copy(
+                &source.join(&file.path),
+                &staging.join(&file.path),
+                &mut progress,
+            )?);
+        }
+
+        let mut progress = Progress::new(RelocateStage::Verifying, total, &mut on_progress);
+
+        for file in &files {
+            let hash = hash(&staging.join(&file.path), &mut progress)?;
+
+            if file.hash.as_ref() != Some(&hash) {
+                return Err(BackupError::State(format!(
+                    "verification failed: {}",
+                    file.path.display()
+                )));
+            }
+        }
+
+        for name in &names {
+            let staged = staging.join(name);
+
+            if staged.exists() {
+                fs::rename(staged, target.join(name))?;
+
+                moved.push(name.clone());
+            }
+        }
+
+        fs::remove_dir_all(&staging)?;
+
+        switch_store(&options.store_path, target)
+    })();
+
+    if let Err(error) = result {
+        let _ = fs::remove_dir_all(&staging);
+
+        for name in &moved {
+            let _ = remove_entry(&target.join(name));
+        }
+
+        if created {
+            let _ = fs::remove_dir(target);
+        }
+
+        return Err(error);
+    }
+
+    drop(lock);
+
+    let mut cleaned = true;
+
+    for name in &names {
+        if let Err(error) = remove_entry(&source.join(name)) {
+            log::warn!(
+                "Failed to remove {}: {}",
+                source.join(name).display(),
+                error
+            );
+
+            cleaned = false;
+        }
+    }
+
+    Ok(RelocateReport {
+        files: files.len(),
+        bytes: total,
+        cleaned,
+    })
+}
+
+// 需要移动的文件和目录，数据库的共享内存文件由 SQLite 重新生成，删除旧目录时一起删除
+fn entry_names(options: &RelocateOptions) -> Vec<String> {
+    let database = &options.database;
+
+    let mut names = vec![
+        database.clone(),
+        format!("{}-wal", database),
+        format!("{}-shm", database),
+        BACKUPS_DIR_NAME.to_string(),
+    ];
+
+    for name in &options.includes {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    names
+}
+
+fn check_target(source: &Path, target: &Path, names: &[String]) -> Result<(), BackupError> {
+    let source = fs::canonicalize(source)?;
+
+    // 新目录可能还不存在，按已存在的上级目录比较
+    let mut existing = target;
+    let mut rest = Vec::new();
+
+    while !existing.exists() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            break;
+        };
+
+        rest.push(name);
+        existing = parent;
+    }
+
+    let mut resolved = fs::canonicalize(existing)?;
+
+    resolved.extend(rest.iter().rev());
+
+    if resolved == source {
+        return Err(BackupError::State(
+            "the target is the current data directory".into(),
+        ));
+    }
+
+    for name in names {
+        if resolved.starts_with(source.join(name)) {
+            return Err(BackupError::State(format!("the target is inside {}", name)));
+        }
+
+        if target.join(name).exists() && source.join(name).exists() {
+            return Err(BackupError::State(format!(
+                "{} already exists in the target directory",
+                name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn lock_database(path: &Path) -> Result<Option<Connection>, BackupError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let connection = Connection::open(path)?;
+
+    connection.busy_timeout(BUSY_TIMEOUT)?;
+
+    // 还有其它连接在读取时无法合并，说明数据库仍在使用
+    let busy: i64 =
+        connection.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))?;
+
+    if busy != 0 {
+        return Err(BackupError::Database("the database is in use".into()));
+    }
+
+    connection.execute_batch("BEGIN IMMEDIATE")?;
+
+    Ok(Some(connection))
+}
+
+// 目录和文件，路径相对于数据目录，共享内存文件不复制
+fn list(source: &Path, names: &[String]) -> Result<(Vec<PathBuf>, Vec<FileEntry>), BackupError> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+
+    for name in names.iter().filter(|name| !name.ends_with("-shm")) {
+        let root = source.join(name);
+
+        if !root.exists() {
+            continue;
+        }
+
+        for entry in WalkDir::new(&root) {
+            let entry = entry.map_err(|e| BackupError::Io(e.into()))?;
+
+            let Ok(path) = entry.path().strip_prefix(source) else {
+                continue;
+            };
+
+            let file_type = entry.file_type();
+
+            if file_type.is_dir() {
+                dirs.push(path.to_path_buf());
+            } else if file_type.is_file() {
+                files.push(FileEntry {
+                    path: path.to_path_buf(),
+                    size: entry
+                        .metadata()
+                        .map_err(|e| BackupError::Io(e.into()))?
+                        .len(),
+                    hash: None,
+                });
+            }
+        }
+    }
+
+    Ok((dirs, files))
+}
+
+// 复制文件，返回读取到的内容的哈希
+fn copy(from: &Path, to: &Path, progress: &mut Progress) -> Result<Vec<u8>, BackupError> {
+    let mut reader = File::open(from)?;
+    let mut writer = File::create(to)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+
+        progress.advance(read as u64);
+    }
+
+    writer.sync_all()?;
+
+    Ok(hasher.finalize().to_vec())
+}
+
+fn hash(path: &Path, progress: &mut Progress) -> Result<Vec<u8>, BackupError> {
+    let mut reader = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..read]);
+
+        progress.advance(read as u64);
+    }
+
+    Ok(hasher.finalize().to_vec())
+}
+
+// 修改配置文件中的数据目录，先写入临时文件再替换，不会留下不完整的配置
+fn switch_store(store_path: &Path, target: &Path) -> Result<(), BackupError> {
+    if !store_path.exists() {
+        return Ok(());
+    }
+
+    let mut store: Value = serde_json::from_str(&fs::read_to_string(store_path)?)?;
+
+    let env = store
+        .pointer_mut("/globalStore/env")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| BackupError::State("invalid store file".into()))?;
+
+    env.insert(
+        "saveDataDir".into(),
+        Value::String(target.to_string_lossy().into_owned()),
+    );
+
+    let temp_path = store_path.with_extension("tmp");
+
+    let mut file = File::create(&temp_path)?;
+
+    file.write_all(serde_json::to_string_pretty(&store)?.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(temp_path, store_path)?;
+
+    Ok(())
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+// 每前进 1% 通知一次，避免发送过多的事件
+struct Progress<'a> {
+    stage: RelocateStage,
+    processed: u64,
+    total: u64,
+    percent: u64,
+    on_progress: &'a mut dyn FnMut(&RelocateProgress),
+}
+
+impl<'a> Progress<'a> {
+    fn new(
+        stage: RelocateStage,
+        total: u64,
+        on_progress: &'a mut dyn FnMut(&RelocateProgress),
+    ) -> Self {
+        let mut progress = Self {
+            stage,
+            processed: 0,
+            total,
+            percent: 0,
+            on_progress,
+        };
+
+        progress.notify();
+
+        progress
+    }
+
+    fn advance(&mut self, bytes: u64) {
+        self.processed += bytes;
+
+        let percent = (self.processed * 100)
+            .checked_div(self.total)
+            .unwrap_or(100);
+
+        if percent != self.percent {
+            self.percent = percent;
+
+            self.notify();
+        }
+    }
+
+    fn notify(&mut self) {
+        (self.on_progress)(&RelocateProgress {
+            stage: self.stage,
+            processed: self.processed,
+            total: self.total,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const DATABASE: &str = "EcoPaste.db";
+
+    struct Fixture {
+        root: PathBuf,
+        options: RelocateOptions,
+    }
+
+    impl Fixture {
+        // 数据目录中有数据库、图片和备份，配置文件在数据目录之外
+        fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+            let root = std::env::temp_dir().join(format!(
+                "eco-relocate-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+
+            let _ = fs::remove_dir_all(&root);
+
+            let source = root.join("old/EcoPaste");
+
+            fs::create_dir_all(source.join("images/nested")).unwrap();
+            fs::create_dir_all(source.join(BACKUPS_DIR_NAME)).unwrap();
+            fs::write(source.join("images/1.png"), b"png").unwrap();
+            fs::write(source.join("images/nested/2.png"), vec![7; 200_000]).unwrap();
+            fs::write(source.join(BACKUPS_DIR_NAME).join("1.eco"), b"eco").unwrap();
+            fs::write(source.join(".store-backup.json"), b"{}").unwrap();
+            fs::write(source.join("unrelated.txt"), b"keep").unwrap();
+
+            let connection = Connection::open(source.join(DATABASE)).unwrap();
+            connection
+                .execute_batch(
+                    "PRAGMA journal_mode = WAL;
+                    CREATE TABLE history (id TEXT PRIMARY KEY, value TEXT);
+                    INSERT INTO history VALUES ('1', 'hello');",
+                )
+                .unwrap();
+            drop(connection);
+
+            let store_path = root.join(".store.json");
+            fs::write(
+                &store_path,
+                json!({
+                    "globalStore": { "env": { "saveDataDir": source, "platform": "linux" } },
+                    "clipboardStore": { "window": {} }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+            let options = RelocateOptions {
+                target: root.join("new/EcoPaste"),
+                source,
+                database: DATABASE.into(),
+                includes: vec!["images".into(), ".store-backup.json".into()],
+                store_path,
+            };
+
+            Self { root, options }
+        }
+
+        fn store(&self) -> Value {
+            serde_json::from_slice(&fs::read(&self.options.store_path).unwrap()).unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn moves_data() {
+        let fixture = Fixture::new();
+        let RelocateOptions { source, target, .. } = &fixture.options;
+        let mut progress = Vec::new();
+
+        let report = relocate(&fixture.options, |event| progress.push(event.clone())).unwrap();
+
+        // 合并后的预写日志是空文件，也会一起移动
+        assert_eq!(report.files, 6);
+        assert_eq!(
+            fs::metadata(target.join(format!("{}-wal", DATABASE)))
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            report.bytes,
+            fs::metadata(target.join(DATABASE)).unwrap().len() + 200_008
+        );
+        assert!(report.cleaned);
+
+        assert_eq!(fs::read(target.join("images/1.png")).unwrap(), b"png");
+        assert_eq!(
+            fs::read(target.join("images/nested/2.png")).unwrap().len(),
+            200_000
+        );
+        assert!(target.join(BACKUPS_DIR_NAME).join("1.eco").exists());
+        assert!(target.join(".store-backup.json").exists());
+        assert!(!target.join(RELOCATING_DIR_NAME).exists());
+
+        let value: String = Connection::open(target.join(DATABASE))
+            .unwrap()
+            .query_row("SELECT value FROM history WHERE id = '1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(value, "hello");
+
+        // 旧目录中只留下不属于数据的文件
+        for name in [DATABASE, "images", BACKUPS_DIR_NAME, ".store-backup.json"] {
+            assert!(!source.join(name).exists(), "{}", name);
+        }
+        assert!(source.join("unrelated.txt").exists());
+        assert!(!target.join("unrelated.txt").exists());
+
+        let store = fixture.store();
+        assert_eq!(
+            store.pointer("/globalStore/env/saveDataDir"),
+            Some(&json!(target))
+        );
+        assert_eq!(
+            store.pointer("/globalStore/env/platform"),
+            Some(&json!("linux"))
+        );
+        assert!(store.pointer("/clipboardStore/window").is_some());
+
+        // 先复制再校验，每个阶段都从 0 开始并以总量结束
+        let total = report.bytes;
+        let stages: Vec<_> = progress
+            .iter()
+            .filter(|event| event.processed == 0 || event.processed == total)
+            .map(|event| (event.stage, event.processed))
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                (RelocateStage::Copying, 0),
+                (RelocateStage::Copying, total),
+                (RelocateStage::Verifying, 0),
+                (RelocateStage::Verifying, total),
+            ]
+        );
+        assert!(progress.iter().all(|event| event.total == total));
+    }
+
+    #[test]
+    fn rolls_back_when_verification_fails() {
+        let fixture = Fixture::new();
+        let RelocateOptions { source, target, .. } = &fixture.options;
+        let store = fixture.store();
+        let staged = target.join(RELOCATING_DIR_NAME).join("images/1.png");
+
+        // 复制完成后、校验开始前修改临时目录中的文件
+        let result = relocate(&fixture.options, |event| {
+            if event.stage == RelocateStage::Verifying && event.processed == 0 {
+                fs::write(&staged, b"corrupted").unwrap();
+            }
+        });
+
+        let Err(BackupError::State(message)) = result else {
+            panic!("expected a verification error");
+        };
+        assert!(message.contains("1.png"), "{}", message);
+
+        // 新目录是这次创建的，连同复制的数据一起删除，旧目录和配置保持不变
+        assert!(!target.exists());
+        assert_eq!(fs::read(source.join("images/1.png")).unwrap(), b"png");
+        assert!(source.join(DATABASE).exists());
+        assert!(source.join(BACKUPS_DIR_NAME).join("1.eco").exists());
+        assert_eq!(fixture.store(), store);
+    }
+
+    #[test]
+    fn keeps_existing_target_on_rollback() {
+        let fixture = Fixture::new();
+        let target = &fixture.options.target;
+
+        fs::create_dir_all(target).unwrap();
+        fs::write(target.join("other.txt"), b"other").unwrap();
+
+        let staged = target.join(RELOCATING_DIR_NAME).join("images/1.png");
+
+        let result = relocate(&fixture.options, |event| {
+            if event.stage == RelocateStage::Verifying && event.processed == 0 {
+                fs::write(&staged, b"corrupted").unwrap();
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(target).unwrap().count(), 1);
+        assert!(target.join("other.txt").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_targets() {
+        let fixture = Fixture::new();
+        let source = &fixture.options.source;
+        let names = entry_names(&fixture.options);
+
+        let check = |target: PathBuf| check_target(source, &target, &names);
+
+        assert!(check(source.clone()).is_err());
+        assert!(check(source.join("../EcoPaste")).is_err());
+
+        // 新目录在需要移动的目录中，还不存在时也能识别
+        assert!(check(source.join("images")).is_err());
+        assert!(check(source.join("images/nested")).is_err());
+        assert!(check(source.join("images/missing/EcoPaste")).is_err());
+        assert!(check(source.join(BACKUPS_DIR_NAME).join("EcoPaste")).is_err());
+
+        // 不会被移动的子目录可以作为新目录
+        assert!(check(source.join("EcoPaste")).is_ok());
+        assert!(check(fixture.root.join("new/EcoPaste")).is_ok());
+
+        // 新目录中已有同名的数据
+        let target = fixture.root.join("existing");
+        fs::create_dir_all(target.join("images")).unwrap();
+
+        let Err(BackupError::State(message)) = check(target) else {
+            panic!("expected an existing data error");
+        };
+        assert!(message.contains("images"), "{}", message);
+    }
+
+    #[test]
+    fn rewrites_store() {
+        let fixture = Fixture::new();
+        let target = fixture.root.join("other");
+
+        switch_store(&fixture.options.store_path, &target).unwrap();
+
+        let store = fixture.store();
+        assert_eq!(
+            store.pointer("/globalStore/env/saveDataDir"),
+            Some(&json!(target))
+        );
+        assert_eq!(
+            store.pointer("/globalStore/env/platform"),
+            Some(&json!("linux"))
+        );
+        assert!(!fixture.options.store_path.with_extension("tmp").exists());
+
+        // 没有配置文件时不需要修改
+        assert!(switch_store(&fixture.root.join("missing.json"), &target).is_ok());
+
+        let invalid = fixture.root.join("invalid.json");
+
+        fs::write(&invalid, r#"{"globalStore":{}}"#).unwrap();
+        assert!(matches!(
+            switch_store(&invalid, &target),
+            Err(BackupError::State(_))
+        ));
+
+        fs::write(&invalid, "not json").unwrap();
+        assert!(switch_store(&invalid, &target).is_err());
+        assert_eq!(fs::read_to_string(&invalid).unwrap(), "not json");
+    }
+}
@@ -0,0 +1,381 @@
+//! 定时备份
+//!
+//! 备份在单独的线程中按间隔执行。上次检查的时间和备份内容的指纹保存在备份目录，
+//! 重启应用后仍按原来的时间备份，内容没有变化时跳过这次备份。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::archive::BackupConfig;
+use crate::error::BackupError;
+
+const STATE_FILE_NAME: &str = "backup-state.json";
+
+// 电脑休眠后线程的等待时间不准确，定期醒来检查是否到了备份时间
+const MAX_WAIT: Duration = Duration::from_secs(10 * 60);
+
+// 备份失败后重试的间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 一次定时备份的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum BackupReport {
+    /// 写入了新的备份
+    Created {
+        path: PathBuf,
+    },
+    /// 内容没有变化，跳过这次备份
+    Skipped,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct BackupState {
+    // 上次备份的内容指纹
+    fingerprint: Option<String>,
+    // 上次检查的时间戳（毫秒），跳过备份时也会更新
+    last_run_at: u64,
+}
+
+impl BackupState {
+    fn path(config: &BackupConfig) -> PathBuf {
+        config.backups_dir().join(STATE_FILE_NAME)
+    }
+
+    fn load(config: &BackupConfig) -> Self {
+        fs::read(Self::path(config))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, config: &BackupConfig) -> Result<(), BackupError> {
+        let path = Self::path(config);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+type OnFinish = Arc<dyn Fn(&BackupReport) + Send + Sync>;
+
+struct Worker {
+    sender: Sender<()>,
+    handle: JoinHandle<()>,
+    config: BackupConfig,
+    on_finish: OnFinish,
+}
+
+/// 暂停的定时备份，交给 [`AutoBackup::resume`] 按原来的配置重新启动
+pub struct Suspended {
+    config: BackupConfig,
+    on_finish: OnFinish,
+}
+
+/// 定时备份，同一时间只运行一个备份线程，手动备份和定时备份不会同时进行
+#[derive(Default)]
+pub struct AutoBackup {
+    worker: Mutex<Option<Worker>>,
+    running: Arc<Mutex<()>>,
+}
+
+impl AutoBackup {
+    /// 启动备份线程，到了备份时间立即备份，已经启动时先停止
+    ///
+    /// 每次备份结束后调用 `on_finish`
+    pub fn start(
+        &self,
+        config: BackupConfig,
+        on_finish: impl Fn(&BackupReport) + Send + Sync + 'static,
+    ) {
+        self.stop();
+        self.spawn(config, Arc::new(on_finish));
+    }
+
+    /// 停止备份线程，正在进行的备份会先完成
+    pub fn stop(&self) {
+        self.suspend();
+    }
+
+    /// 停止备份线程并返回当前的配置，没有启动时为 None
+    pub fn suspend(&self) -> Option<Suspended> {
+        let worker = self.worker().take()?;
+
+        let _ = worker.sender.send(());
+        let _ = worker.handle.join();
+
+        Some(Suspended {
+            config: worker.config,
+            on_finish: worker.on_finish,
+        })
+    }
+
+    /// 按暂停前的配置重新启动，期间已经按新的配置启动时保持不变
+    pub fn resume(&self, suspended: Suspended) {
+        if self.worker().is_some() {
+            return;
+        }
+
+        self.spawn(suspended.config, suspended.on_finish);
+    }
+
+    fn spawn(&self, config: BackupConfig, on_finish: OnFinish) {
+        let (sender, receiver) = mpsc::channel();
+        let running = self.running.clone();
+
+        // 配置和回调留一份在 Worker 中，暂停后可以按原来的配置恢复
+        let handle = thread::spawn({
+            let config = config.clone();
+            let on_finish = on_finish.clone();
+
+            move || {
+                let interval = config.interval.duration().as_millis() as u64;
+                let mut retry_at = 0;
+
+                loop {
+                    let state = BackupState::load(&config);
+                    let due = (state.last_run_at + interval).max(retry_at);
+                    let now = now();
+
+                    if now < due {
+                        let wait = Duration::from_millis(due - now).min(MAX_WAIT);
+
+                        match receiver.recv_timeout(wait) {
+                            Err(RecvTimeoutError::Timeout) => continue,
+                            _ => break,
+                        }
+                    }
+
+                    let report = match run(&running, &config, false) {
+                        Ok(Some(path)) => BackupReport::Created { path },
+                        Ok(None) => BackupReport::Skipped,
+                        Err(e) => {
+                            log::error!("Automatic backup failed: {}", e);
+
+                            retry_at = now + RETRY_INTERVAL.as_millis() as u64;
+
+                            BackupReport::Failed {
+                                error: e.to_string(),
+                            }
+                        }
+                    };
+
+                    on_finish(&report);
+                }
+            }
+        });
+
+        *self.worker() = Some(Worker {
+            sender,
+            handle,
+            config,
+            on_finish,
+        });
+    }
+
+    /// 立即备份一次，内容没有变化时也会备份
+    pub fn backup_now(&self, config: &BackupConfig) -> Result<PathBuf, BackupError> {
+        let path = run(&self.running, config, true)?;
+
+        Ok(path.unwrap_or_default())
+    }
+
+    fn worker(&self) -> MutexGuard<'_, Option<Worker>> {
+        self.worker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// 备份并删除多余的旧备份，内容没有变化且不是强制备份时返回 None
+fn run(
+    running: &Mutex<()>,
+    config: &BackupConfig,
+    force: bool,
+) -> Result<Option<PathBuf>, BackupError> {
+    let _running = running.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut state = BackupState::load(config);
+    let fingerprint = config.fingerprint()?;
+
+    state.last_run_at = now();
+
+    if !force && state.fingerprint.as_deref() == Some(fingerprint.as_str()) {
+        state.save(config)?;
+
+        return Ok(None);
+    }
+
+    let path = config.create()?;
+
+    // 新的备份已经写入，删除旧备份失败不影响这次备份
+    if let Err(e) = config.rotate() {
+        log::warn!("Failed to remove old backups: {}", e);
+    }
+
+    state.fingerprint = Some(fingerprint);
+    state.save(config)?;
+
+    Ok(Some(path))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::BackupInterval;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_config() -> BackupConfig {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let data_dir = std::env::temp_dir().join(format!(
+            "eco-scheduler-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(data_dir.join("images")).unwrap();
+
+        BackupConfig {
+            data_dir,
+            database: "EcoPaste.db".into(),
+            includes: vec!["images".into()],
+            extension: "eco".into(),
+            interval: BackupInterval::Daily,
+            keep: 3,
+        }
+    }
+
+    #[test]
+    fn skips_unchanged_data() {
+        let config = temp_config();
+        let running = Mutex::new(());
+
+        fs::write(config.data_dir.join("images/1.png"), b"png").unwrap();
+
+        assert!(run(&running, &config, false).unwrap().is_some());
+        assert!(run(&running, &config, false).unwrap().is_none());
+
+        let state = BackupState::load(&config);
+        assert!(state.fingerprint.is_some());
+        assert!(state.last_run_at > 0);
+
+        // 内容变化后重新备份
+        fs::write(config.data_dir.join("images/2.png"), b"png").unwrap();
+
+        assert!(run(&running, &config, false).unwrap().is_some());
+        assert!(run(&running, &config, false).unwrap().is_none());
+
+        // 手动备份不检查内容是否变化
+        assert!(run(&running, &config, true).unwrap().is_some());
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn backs_up_when_due() {
+        let config = temp_config();
+        let backup = AutoBackup::default();
+        let (sender, receiver) = mpsc::channel();
+
+        backup.start(config.clone(), move |report| {
+            let _ = sender.send(report.clone());
+        });
+
+        let report = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(report, BackupReport::Created { .. }));
+
+        // 下次备份在一天后，停止时不会再备份
+        backup.stop();
+
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(config.list().unwrap().len(), 1);
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn resumes_suspended_backup() {
+        let backup = AutoBackup::default();
+
+        assert!(backup.suspend().is_none());
+
+        let config = temp_config();
+        let (sender, receiver) = mpsc::channel();
+
+        backup.start(config.clone(), move |report| {
+            let _ = sender.send(report.clone());
+        });
+
+        receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+
+        let suspended = backup.suspend().unwrap();
+        assert_eq!(suspended.config.data_dir, config.data_dir);
+        assert!(backup.worker().is_none());
+
+        // 恢复后使用原来的回调，下次备份时间没到，不会立即备份
+        backup.resume(suspended);
+        assert!(backup.worker().is_some());
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        // 到了备份时间后恢复，内容没有变化，通过原来的回调报告跳过
+        let suspended = backup.suspend().unwrap();
+
+        let mut state = BackupState::load(&config);
+        state.last_run_at = 0;
+        state.save(&config).unwrap();
+
+        backup.resume(suspended);
+
+        let report = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(report, BackupReport::Skipped));
+
+        backup.stop();
+
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_newer_start_when_resuming() {
+        let backup = AutoBackup::default();
+        let old = temp_config();
+        let new = temp_config();
+
+        backup.start(old.clone(), |_| {});
+
+        let suspended = backup.suspend().unwrap();
+
+        backup.start(new.clone(), |_| {});
+        backup.resume(suspended);
+
+        assert_eq!(
+            backup.worker().as_ref().unwrap().config.data_dir,
+            new.data_dir
+        );
+
+        backup.stop();
+
+        fs::remove_dir_all(&old.data_dir).unwrap();
+        fs::remove_dir_all(&new.data_dir).unwrap();
+    }
+}
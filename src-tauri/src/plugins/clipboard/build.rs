@@ -0,0 +1,10 @@
+const COMMANDS: &[&str] = &[
+    "convert_content",
+    "sanitize_html",
+    "classify_texts",
+    "highlight_style",
+];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,337 @@
+//! 代码语言识别
+//!
+//! 每种语言有一组带权重的特征，文本匹配到的特征权重之和最高并且达到阈值的语言为结果。
+//! 每个特征只计算一次，避免普通文字中重复出现的单词累积出很高的分数。
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+// 识别为代码需要的最低分数
+const MIN_SCORE: u32 = 4;
+
+// 编译后的特征和权重
+type Features = Vec<(Regex, u32)>;
+
+struct Language {
+    name: &'static str,
+    features: &'static [(&'static str, u32)],
+}
+
+const JAVASCRIPT: &[(&str, u32)] = &[
+    (r"\b(const|let|var)\s+\w+\s*=", 2),
+    (r"=>", 1),
+    (r"\bfunction\s*\w*\s*\(", 3),
+    (r"\bconsole\.\w+\(", 3),
+    (r#"\brequire\(['"]"#, 3),
+    (r#"(?m)^\s*import\s+.+\s+from\s+['"]"#, 3),
+    (
+        r"(?m)^\s*export\s+(default|const|function|class|async)\b",
+        3,
+    ),
+    (r"\b(document|window)\.\w+", 2),
+    (r"===|!==", 2),
+];
+
+const TYPESCRIPT: &[(&str, u32)] = &[
+    (r":\s*(string|number|boolean|any|void|unknown|never)\b", 3),
+    (
+        r"(?m)^\s*(export\s+)?(interface|type)\s+\w+(<.*>)?\s*[={]",
+        3,
+    ),
+    (r"\b(public|private|readonly)\s+\w+\s*:", 2),
+];
+
+// 按顺序比较，分数相同时靠前的语言优先
+const LANGUAGES: &[Language] = &[
+    Language {
+        name: "rust",
+        features: &[
+            (r"\bfn\s+\w+\s*(<[^>]*>)?\s*\(", 3),
+            (r"\blet\s+mut\b", 3),
+            (r"(?m)^\s*use\s+[\w:]+(::\{[^}]*\})?;", 3),
+            (r"\b\w+!\(", 2),
+            (r"\bpub(\(crate\))?\s+(fn|struct|enum|mod|use|trait)\b", 3),
+            (r"\bimpl(<[^>]*>)?\s+\w+", 2),
+            (r"&mut\s", 2),
+            (r"#\[derive\(", 4),
+            (r"\b(Option|Result|Vec|String)<", 1),
+        ],
+    },
+    Language {
+        name: "python",
+        features: &[
+            (r"(?m)^\s*def\s+\w+\(.*\)\s*(->.*)?:\s*$", 4),
+            (r"(?m)^\s*import\s+[\w.]+(\s+as\s+\w+)?\s*$", 3),
+            (r"(?m)^\s*from\s+[\w.]+\s+import\s+", 4),
+            (r"(?m)^\s*(elif|except|finally)\b.*:\s*$", 3),
+            (r"\bself\.\w+", 2),
+            (r"(?m)^\s*class\s+\w+(\(.*\))?:\s*$", 4),
+            (r"\bprint\(", 1),
+            (r"(?m)^\s*(if|for|while)\s.*:\s*$", 2),
+            (r"__\w+__", 2),
+            (r"\b(None|True|False)\b", 1),
+        ],
+    },
+    Language {
+        name: "javascript",
+        features: JAVASCRIPT,
+    },
+    Language {
+        name: "typescript",
+        features: TYPESCRIPT,
+    },
+    Language {
+        name: "java",
+        features: &[
+            (r"\bpublic\s+(static\s+)?(class|void|final|interface)\b", 3),
+            (r"\bSystem\.out\.print", 4),
+            (r"(?m)^\s*import\s+java\.", 4),
+            (r"\bprivate\s+\w+(<.*>)?\s+\w+\s*[;=]", 2),
+            (r"@Override\b", 3),
+            (r"(?m)^\s*package\s+[\w.]+;", 3),
+            (r"\bnew\s+\w+(<.*>)?\(", 1),
+        ],
+    },
+    Language {
+        name: "kotlin",
+        features: &[
+            (r"\bfun\s+\w+\s*\(", 4),
+            (r"\bval\s+\w+\s*[:=]", 2),
+            (r"\bvar\s+\w+\s*:\s*\w+", 1),
+            (r"\bprintln\(", 1),
+        ],
+    },
+    Language {
+        name: "c",
+        features: &[
+            (r#"(?m)^\s*#include\s*[<"]"#, 4),
+            (r"\bint\s+main\s*\(", 3),
+            (r"\bprintf\(", 2),
+            (r"\b(malloc|free|sizeof)\(", 2),
+            (r"->\w+", 1),
+        ],
+    },
+    Language {
+        name: "cpp",
+        features: &[
+            (r#"(?m)^\s*#include\s*[<"]"#, 4),
+            (r"\bstd::", 3),
+            (r"\bcout\s*<<", 3),
+            (r"\btemplate\s*<", 3),
+            (r"\bnamespace\s+\w+", 2),
+        ],
+    },
+    Language {
+        name: "csharp",
+        features: &[
+            (r"(?m)^\s*using\s+System(\.\w+)*;", 4),
+            (r"\bnamespace\s+[\w.]+", 2),
+            (r"\bConsole\.Write", 4),
+            (r"\bpublic\s+(class|static|void|async)\b", 2),
+            (r"\bvar\s+\w+\s*=\s*new\b", 2),
+            (r"\{\s*get;\s*set;\s*\}", 4),
+        ],
+    },
+    Language {
+        name: "go",
+        features: &[
+            (r"(?m)^\s*package\s+\w+\s*$", 4),
+            (r"\bfunc\s+(\(\w+\s+\*?\w+\)\s*)?\w+\(", 4),
+            (r":=", 2),
+            (r"\bfmt\.\w+\(", 3),
+            (r"(?m)^\s*import\s+\($", 3),
+            (r"\berr\s*!=\s*nil\b", 4),
+        ],
+    },
+    Language {
+        name: "php",
+        features: &[
+            (r"<\?php", 6),
+            (r"\$\w+\s*=", 2),
+            (r"\$\w+->\w+", 2),
+            (r"\bfunction\s+\w+\(\$", 4),
+        ],
+    },
+    Language {
+        name: "ruby",
+        features: &[
+            (r"(?m)^\s*def\s+\w+[?!]?(\(.*\))?\s*$", 3),
+            (r"(?m)^\s*end\s*$", 2),
+            (r"\bputs\s", 3),
+            (r"\battr_(accessor|reader|writer)\b", 4),
+            (r#"(?m)^\s*require\s+['"]"#, 2),
+            (r"\bdo\s*\|\w+(,\s*\w+)*\|", 4),
+        ],
+    },
+    Language {
+        name: "swift",
+        features: &[
+            (r"\bfunc\s+\w+\(.*\)\s*(->\s*\w+\s*)?\{", 3),
+            (r"(?m)^\s*import\s+(UIKit|SwiftUI|Foundation)\s*$", 5),
+            (r"\b(guard|if)\s+let\b", 4),
+            (r"\bvar\s+\w+\s*:\s*\w+", 1),
+        ],
+    },
+    Language {
+        name: "sql",
+        features: &[
+            (r"(?im)^\s*select\s+[\w*,.\s()]+\s+from\s+\w+", 4),
+            (r"(?i)\binsert\s+into\s+\w+", 4),
+            (r"(?i)\bupdate\s+\w+\s+set\s+\w+\s*=", 4),
+            (r"(?i)\bcreate\s+(table|index|view|database)\b", 4),
+            (r"(?i)\bdelete\s+from\s+\w+", 4),
+            (r"\b(WHERE|JOIN|GROUP BY|ORDER BY|LIMIT)\b", 1),
+        ],
+    },
+    Language {
+        name: "html",
+        features: &[
+            (r"(?i)<!doctype\s+html", 6),
+            (
+                r"(?i)<(html|head|body|div|span|p|a|ul|ol|li|table|script|style|section|button)\b[^>]*>",
+                3,
+            ),
+            (r"</\w+>", 2),
+        ],
+    },
+    Language {
+        name: "xml",
+        features: &[(r"^\s*<\?xml\b", 6), (r"</\w+:\w+>", 2)],
+    },
+    Language {
+        name: "css",
+        features: &[
+            (r"(?m)^\s*[.#]?[\w-]+([\s,>+~]+[.#]?[\w:-]+)*\s*\{\s*$", 2),
+            (r"(?m)^\s*[\w-]+\s*:\s*[^;]+;\s*$", 2),
+            (r"@(media|keyframes|import|font-face)\b", 3),
+        ],
+    },
+    Language {
+        name: "shell",
+        features: &[
+            (r"^#!\s*/(usr/)?bin/(env\s+)?(ba|z)?sh", 6),
+            (r"(?m)^\s*(if|for|while)\s.*;\s*(then|do)\s*$", 4),
+            (r"(?m)^\s*(fi|done|esac)\s*$", 3),
+            (r"\$\{\w+\}", 1),
+        ],
+    },
+];
+
+/// 猜测一段代码的语言，不像代码时返回 None
+pub fn guess_language(text: &str) -> Option<&'static str> {
+    if looks_like_prose(text) {
+        return None;
+    }
+
+    if let Some(language) = guess_yaml(text) {
+        return Some(language);
+    }
+
+    let mut scores: Vec<(&'static str, u32)> = compiled()
+        .iter()
+        .map(|(name, features)| {
+            let score = features
+                .iter()
+                .filter(|(regex, _)| regex.is_match(text))
+                .map(|(_, weight)| weight)
+                .sum();
+
+            (*name, score)
+        })
+        .collect();
+
+    // TypeScript 同时也满足 JavaScript 的特征
+    let javascript = score_of(&scores, "javascript");
+
+    for (name, score) in &mut scores {
+        if *name == "typescript" && *score > 0 {
+            *score += javascript;
+        }
+    }
+
+    // 分数相同时靠前的语言优先
+    scores
+        .iter()
+        .fold(
+            None,
+            |best: Option<(&'static str, u32)>, &(name, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((name, score)),
+            },
+        )
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .map(|(name, _)| name)
+}
+
+fn score_of(scores: &[(&str, u32)], language: &str) -> u32 {
+    scores
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map_or(0, |(_, score)| *score)
+}
+
+fn compiled() -> &'static [(&'static str, Features)] {
+    static COMPILED: OnceLock<Vec<(&'static str, Features)>> = OnceLock::new();
+
+    COMPILED.get_or_init(|| {
+        LANGUAGES
+            .iter()
+            .map(|language| {
+                let features = language
+                    .features
+                    .iter()
+                    .map(|(pattern, weight)| (Regex::new(pattern).expect("invalid regex"), *weight))
+                    .collect();
+
+                (language.name, features)
+            })
+            .collect()
+    })
+}
+
+// 大部分行以句号、问号或感叹号结尾的是普通文字
+fn looks_like_prose(text: &str) -> bool {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let sentences = lines
+        .iter()
+        .filter(|line| line.ends_with(['.', '!', '?', '。', '！', '？']))
+        .count();
+
+    sentences * 2 > lines.len()
+}
+
+// YAML 没有明显的关键字，按照每一行的结构判断
+fn guess_yaml(text: &str) -> Option<&'static str> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+
+    if lines.len() < 3 {
+        return None;
+    }
+
+    let is_yaml_line = |line: &&str| {
+        let line = line.trim_start();
+        let line = line.strip_prefix("- ").unwrap_or(line);
+
+        line == "---"
+            || line.split_once(':').is_some_and(|(key, value)| {
+                !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+                    && (value.is_empty() || value.starts_with(' '))
+            })
+    };
+
+    // 需要有嵌套结构，避免把「名称: 值」形式的普通文字当作 YAML
+    let nested = lines.iter().any(|line| line.starts_with([' ', '-']));
+
+    (nested && lines.iter().all(is_yaml_line)).then_some("yaml")
+}
@@ -0,0 +1,230 @@
+//! 文本内容识别
+//!
+//! 按顺序匹配各个类型，越具体的类型越靠前，例如 JWT 在代码之前、时间戳在手机号之前。
+//! 所有类型都只根据文本本身判断，不访问文件系统和网络。
+
+use serde::{Deserialize, Serialize};
+
+use crate::highlight::highlight;
+use crate::secret::detect_secret;
+
+mod code;
+mod color;
+mod patterns;
+
+pub use code::guess_language;
+
+// 超过这个长度的文本只判断 JSON 和代码
+const MAX_SHORT_LEN: usize = 2048;
+
+/// 文本的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Subtype {
+    Url,
+    Email,
+    Color,
+    Path,
+    Phone,
+    Ip,
+    Json,
+    Code,
+    Uuid,
+    Jwt,
+    /// 通过 Luhn 校验的银行卡号
+    Card,
+    /// Unix 时间戳或者 ISO 8601 时间
+    Timestamp,
+    Command,
+}
+
+/// 识别结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Classification {
+    pub subtype: Option<Subtype>,
+    /// 代码的语言，只有 `subtype` 为 `Code` 时有值
+    pub language: Option<String>,
+    /// 代码和 JSON 高亮后的 HTML，保存为记录的 `render`，包含密钥时不生成
+    pub render: Option<String>,
+    /// 匹配到的密钥规则，例如 `aws-access-key`
+    pub secret: Option<String>,
+}
+
+impl Classification {
+    fn subtype(subtype: Subtype) -> Self {
+        Self {
+            subtype: Some(subtype),
+            ..Default::default()
+        }
+    }
+}
+
+/// 识别一段文本的类型，并检查是否包含密钥
+pub fn classify(text: &str) -> Classification {
+    let mut classification = classify_subtype(text);
+
+    if let Some(secret) = detect_secret(text) {
+        // 高亮后的内容会直接显示在列表中
+        classification.render = None;
+        classification.secret = Some(secret.to_string());
+    }
+
+    classification
+}
+
+fn classify_subtype(text: &str) -> Classification {
+    let value = text.trim();
+
+    if value.is_empty() {
+        return Classification::default();
+    }
+
+    if value.len() <= MAX_SHORT_LEN && !value.contains('\n') {
+        if let Some(subtype) = classify_line(value) {
+            return Classification::subtype(subtype);
+        }
+    }
+
+    if patterns::is_json(value) {
+        return Classification {
+            render: highlight(text, "json"),
+            ..Classification::subtype(Subtype::Json)
+        };
+    }
+
+    if patterns::is_command(value) {
+        return Classification::subtype(Subtype::Command);
+    }
+
+    match guess_language(value) {
+        Some(language) => Classification {
+            subtype: Some(Subtype::Code),
+            language: Some(language.to_string()),
+            render: highlight(text, language),
+            ..Default::default()
+        },
+        None => Classification::default(),
+    }
+}
+
+/// 批量识别，结果和 `texts` 一一对应
+pub fn classify_all<S: AsRef<str>>(texts: &[S]) -> Vec<Classification> {
+    texts.iter().map(|text| classify(text.as_ref())).collect()
+}
+
+// 单行文本的类型
+fn classify_line(value: &str) -> Option<Subtype> {
+    type Check = fn(&str) -> bool;
+
+    let checks: &[(Subtype, Check)] = &[
+        (Subtype::Jwt, patterns::is_jwt),
+        (Subtype::Uuid, patterns::is_uuid),
+        (Subtype::Url, patterns::is_url),
+        (Subtype::Email, patterns::is_email),
+        (Subtype::Ip, patterns::is_ip),
+        (Subtype::Timestamp, patterns::is_timestamp),
+        (Subtype::Card, patterns::is_card),
+        (Subtype::Phone, patterns::is_phone),
+        (Subtype::Color, color::is_color),
+        (Subtype::Path, is_path),
+    ];
+
+    checks
+        .iter()
+        .find(|(_, check)| check(value))
+        .map(|(subtype, _)| *subtype)
+}
+
+// 只按格式判断，不检查文件是否存在：每次复制都访问文件系统太慢，
+// Windows 上访问 `\\host\share` 还会连接对应的主机并发送当前用户的凭据
+fn is_path(value: &str) -> bool {
+    if value.chars().any(char::is_control) {
+        return false;
+    }
+
+    if let Some(rest) = value.strip_prefix("\\\\") {
+        return is_unc_path(rest);
+    }
+
+    if let Some(rest) = value.strip_prefix("~/").or_else(|| value.strip_prefix('/')) {
+        // 排除单独的 `/` 和 `// 注释`
+        return rest
+            .chars()
+            .next()
+            .is_some_and(|ch| ch != '/' && !ch.is_whitespace());
+    }
+
+    let mut chars = value.chars();
+
+    let is_drive = chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+        && chars.next() == Some(':')
+        && matches!(chars.next(), Some('\\' | '/'));
+
+    is_drive && !value[3..].contains(['<', '>', '"', '|', '?', '*'])
+}
+
+// `\\host\share` 之后的部分，主机名和共享名都不能为空
+fn is_unc_path(rest: &str) -> bool {
+    let mut parts = rest.split('\\');
+
+    let host = parts.next().unwrap_or_default();
+    let share = parts.next().unwrap_or_default();
+
+    !host.is_empty()
+        && !share.is_empty()
+        && !host.contains([' ', '/'])
+        && !rest.contains(['<', '>', '"', '|', '?', '*'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_paths_without_touching_filesystem() {
+        for value in [
+            "/usr/local/bin",
+            "/nonexistent/zzz",
+            "~/.bashrc",
+            "C:\\Windows\\System32",
+            "d:/Projects/My App",
+            "\\\\fileserver\\share",
+            "\\\\10.0.0.1\\public\\report.docx",
+        ] {
+            assert!(is_path(value), "{value}");
+            assert_eq!(classify(value).subtype, Some(Subtype::Path), "{value}");
+        }
+    }
+
+    #[test]
+    fn rejects_path_like_text() {
+        for value in [
+            "/",
+            "~/",
+            "// comment",
+            "/ hello",
+            "\\\\",
+            "\\\\host",
+            "\\\\host\\",
+            "\\\\host name\\share",
+            "C:",
+            "C:\\what?",
+            "1:/x",
+            "/tmp/a\tb",
+        ] {
+            assert!(!is_path(value), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn prefers_specific_subtypes() {
+        assert_eq!(
+            classify("https://example.com/a?b=1").subtype,
+            Some(Subtype::Url)
+        );
+        assert_eq!(classify("#ff0000").subtype, Some(Subtype::Color));
+        assert_eq!(classify("{\"a\": [1, 2]}").subtype, Some(Subtype::Json));
+        assert_eq!(classify("cat is cute").subtype, None);
+    }
+}
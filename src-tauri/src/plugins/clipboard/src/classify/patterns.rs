@@ -0,0 +1,252 @@
+//! 各个类型的匹配规则
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use regex::Regex;
+use serde::de::IgnoredAny;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+
+// 只编译一次的正则表达式
+macro_rules! regex {
+    ($pattern:expr) => {{
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+
+        REGEX.get_or_init(|| Regex::new($pattern).expect("invalid regex"))
+    }};
+}
+
+pub(super) use regex;
+
+// 常见的命令行程序，第一行以这些命令开头时识别为命令
+const COMMANDS: &[&str] = &[
+    "apt",
+    "apt-get",
+    "brew",
+    "bun",
+    "cargo",
+    "chmod",
+    "chown",
+    "cp",
+    "curl",
+    "deno",
+    "docker",
+    "docker-compose",
+    "dnf",
+    "gh",
+    "git",
+    "gradle",
+    "grep",
+    "helm",
+    "kubectl",
+    "ls",
+    "mkdir",
+    "mv",
+    "mvn",
+    "node",
+    "npm",
+    "npx",
+    "pacman",
+    "pip",
+    "pip3",
+    "pnpm",
+    "python",
+    "python3",
+    "rm",
+    "rsync",
+    "rustup",
+    "scp",
+    "sed",
+    "ssh",
+    "sudo",
+    "systemctl",
+    "tar",
+    "unzip",
+    "wget",
+    "winget",
+    "yarn",
+    "yum",
+];
+
+// 同时也是常用英文单词的命令，需要参数中带有 shell 语法才识别为命令
+const WORD_COMMANDS: &[&str] = &[
+    "awk", "cat", "cd", "echo", "export", "find", "go", "kill", "ln", "make", "touch",
+];
+
+// 参数中常见的 shell 语法
+const SHELL_SYNTAX: &[char] = &['-', '/', '.', '=', '|', '>', '$', '~', '\'', '"', '*'];
+
+/// http、https、ftp 等带协议的链接
+pub fn is_url(value: &str) -> bool {
+    regex!(r"^(?i)(https?|ftp|wss?|file)://[^\s/?#]+[^\s]*$").is_match(value)
+        || regex!(r"^(?i)www\.[a-z0-9-]+(\.[a-z0-9-]+)+(/\S*)?$").is_match(value)
+}
+
+pub fn is_email(value: &str) -> bool {
+    regex!(r"^[\w.%+-]+@[a-zA-Z0-9-]+(\.[a-zA-Z0-9-]+)*\.[a-zA-Z]{2,}$").is_match(value)
+}
+
+/// IPv4 或 IPv6 地址，可以带端口或者 CIDR 前缀
+pub fn is_ip(value: &str) -> bool {
+    if value.parse::<IpAddr>().is_ok() || value.parse::<SocketAddr>().is_ok() {
+        return true;
+    }
+
+    value.split_once('/').is_some_and(|(address, prefix)| {
+        let Ok(address) = address.parse::<IpAddr>() else {
+            return false;
+        };
+
+        let max = if address.is_ipv4() { 32 } else { 128 };
+
+        prefix.parse::<u8>().is_ok_and(|prefix| prefix <= max)
+    })
+}
+
+pub fn is_uuid(value: &str) -> bool {
+    regex!(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$")
+        .is_match(value)
+}
+
+/// 头部可以解码为带 alg 字段的 JSON
+pub fn is_jwt(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('.').collect();
+
+    if parts.len() != 3 || !parts[0].starts_with("eyJ") || !parts[1].starts_with("eyJ") {
+        return false;
+    }
+
+    if !parts
+        .iter()
+        .all(|part| regex!(r"^[A-Za-z0-9_-]*$").is_match(part))
+    {
+        return false;
+    }
+
+    URL_SAFE_NO_PAD
+        .decode(parts[0])
+        .ok()
+        .and_then(|header| serde_json::from_slice::<serde_json::Value>(&header).ok())
+        .is_some_and(|header| header.get("alg").is_some())
+}
+
+/// 2000 年到 2100 年之间的秒级或毫秒级时间戳，或者 ISO 8601 格式的日期和时间
+pub fn is_timestamp(value: &str) -> bool {
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        let Ok(number) = value.parse::<u64>() else {
+            return false;
+        };
+
+        return match value.len() {
+            10 => (946_684_800..4_102_444_800).contains(&number),
+            13 => (946_684_800_000..4_102_444_800_000).contains(&number),
+            _ => false,
+        };
+    }
+
+    regex!(r"^\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d{1,9})?)?(Z|[+-]\d{2}:?\d{2})?)?$")
+        .is_match(value)
+}
+
+/// 13 到 19 位、可以用空格或短横线分组并且通过 Luhn 校验的数字
+pub fn is_card(value: &str) -> bool {
+    if !regex!(r"^[2-6]\d{3}([ -]?\d{2,6}){2,4}$").is_match(value) {
+        return false;
+    }
+
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+
+    (13..=19).contains(&digits.len()) && luhn(&digits)
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, &digit)| {
+            if index % 2 == 1 {
+                let double = digit * 2;
+
+                if double > 9 {
+                    double - 9
+                } else {
+                    double
+                }
+            } else {
+                digit
+            }
+        })
+        .sum();
+
+    sum % 10 == 0
+}
+
+/// 国际格式或者带分隔符的电话号码，以及中国大陆的手机号
+pub fn is_phone(value: &str) -> bool {
+    if regex!(r"^(\+?86[ -]?)?1[3-9]\d[ -]?\d{4}[ -]?\d{4}$").is_match(value) {
+        return true;
+    }
+
+    // 不支持点号分隔，避免把小数和版本号当作电话号码
+    if !regex!(r"^\+?\(?\d{1,4}\)?([ -]?\(?\d{1,5}\)?){2,6}$").is_match(value) {
+        return false;
+    }
+
+    let digits = value.chars().filter(char::is_ascii_digit).count();
+
+    // 没有分隔符的纯数字太容易误判
+    let formatted = value.starts_with('+') || value.contains([' ', '-', '(']);
+
+    formatted && (7..=15).contains(&digits)
+}
+
+/// 以对象或数组开头的合法 JSON
+pub fn is_json(value: &str) -> bool {
+    let first = value.chars().next();
+    let last = value.chars().last();
+
+    matches!(
+        (first, last),
+        (Some('{'), Some('}')) | (Some('['), Some(']'))
+    ) && serde_json::from_str::<IgnoredAny>(value).is_ok()
+}
+
+/// 每一行都是命令、续行或者以 $ 开头的提示符
+pub fn is_command(value: &str) -> bool {
+    let lines: Vec<&str> = value
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    if lines.is_empty() || lines.len() > 10 {
+        return false;
+    }
+
+    let mut continued = false;
+
+    lines.iter().all(|line| {
+        let is_command = continued || is_command_line(line);
+
+        continued = line.ends_with('\\') || line.ends_with("&&") || line.ends_with('|');
+
+        is_command
+    })
+}
+
+fn is_command_line(line: &str) -> bool {
+    let line = line
+        .strip_prefix("$ ")
+        .or_else(|| line.strip_prefix("> "))
+        .unwrap_or(line)
+        .trim_start();
+
+    let Some((program, args)) = line.split_once(char::is_whitespace) else {
+        // 只有程序名不算命令，例如单独的 ls 更可能是普通文字
+        return line.starts_with("./") && line.len() > 2;
+    };
+
+    COMMANDS.contains(&program)
+        || WORD_COMMANDS.contains(&program) && args.contains(SHELL_SYNTAX)
+        || program.starts_with("./") && program.len() > 2
+}
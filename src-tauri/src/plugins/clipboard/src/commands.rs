@@ -0,0 +1,31 @@
+use tauri::{async_runtime, command};
+
+use crate::classify::{self, Classification};
+use crate::convert::{self, ContentFormat, OutputFormat};
+use crate::highlight;
+
+/// 把 HTML 或 RTF 转为 Markdown 或纯文本
+#[command]
+pub async fn convert_content(value: String, from: ContentFormat, to: OutputFormat) -> String {
+    convert::convert(&value, from, to)
+}
+
+/// 清理 HTML，去掉脚本、事件属性和远程资源，结果用于在列表中渲染
+#[command]
+pub async fn sanitize_html(value: String) -> String {
+    convert::sanitize_html(&value)
+}
+
+/// 批量识别文本的类型，结果和 `values` 一一对应
+#[command]
+pub async fn classify_texts(values: Vec<String>) -> Result<Vec<Classification>, String> {
+    async_runtime::spawn_blocking(move || classify::classify_all(&values))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 代码高亮使用的 CSS
+#[command]
+pub async fn highlight_style() -> Result<String, String> {
+    highlight::style().map_err(|e| e.to_string())
+}
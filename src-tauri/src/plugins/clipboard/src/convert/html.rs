@@ -0,0 +1,608 @@
+//! HTML 转 Markdown 和纯文本
+//!
+//! 保留标题、段落、列表、引用、代码块、链接和表格的结构，忽略样式和脚本。
+
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::{ElementData, NodeData, NodeRef};
+
+use super::writer::TextWriter;
+use super::{attribute, escape_markdown, OutputFormat};
+
+// 不输出内容的元素
+const SKIPPED: &[&str] = &[
+    "head", "script", "style", "title", "noscript", "template", "iframe", "object", "embed", "svg",
+    "canvas", "button", "input", "select", "textarea", "meta", "link",
+];
+
+// 前后各空一行的块
+const PARAGRAPHS: &[&str] = &["p", "figure", "details", "dl", "address", "fieldset"];
+
+// 前后各换一行的块
+const BLOCKS: &[&str] = &[
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "nav",
+    "aside",
+    "figcaption",
+    "summary",
+    "center",
+    "form",
+    "dt",
+    "dd",
+    "caption",
+    "body",
+];
+
+// 超过这个嵌套深度的元素只输出文字，避免递归过深导致栈溢出
+const MAX_DEPTH: usize = 256;
+
+/// 把 HTML 转为 `format` 格式的文本
+pub fn convert(html: &str, format: OutputFormat) -> String {
+    let document = kuchikiki::parse_html().one(html).document_node;
+
+    let mut converter = Converter::new(format);
+    converter.children(&document);
+    converter.writer.finish()
+}
+
+struct Converter {
+    format: OutputFormat,
+    writer: TextWriter,
+    // 每一层列表下一项的序号，无序列表为 None
+    lists: Vec<Option<u32>>,
+    // 当前元素的嵌套深度
+    depth: usize,
+    // 正在转换链接文字、强调或表格单元格，只能输出一行
+    inline: bool,
+}
+
+impl Converter {
+    fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            writer: TextWriter::new(),
+            lists: Vec::new(),
+            depth: 0,
+            inline: false,
+        }
+    }
+
+    fn markdown(&self) -> bool {
+        self.format == OutputFormat::Markdown
+    }
+
+    fn children(&mut self, node: &NodeRef) {
+        for child in node.children() {
+            self.node(&child);
+        }
+    }
+
+    fn node(&mut self, node: &NodeRef) {
+        match node.data() {
+            NodeData::Text(text) => self.text(&text.borrow()),
+            NodeData::Element(_) if self.depth >= MAX_DEPTH => self.text(&node.text_contents()),
+            NodeData::Element(element) => {
+                self.depth += 1;
+                self.element(node, element);
+                self.depth -= 1;
+            }
+            NodeData::Document(_) | NodeData::DocumentFragment => self.children(node),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.markdown() {
+            self.writer.text(&escape_markdown(text));
+        } else {
+            self.writer.text(text);
+        }
+    }
+
+    fn element(&mut self, node: &NodeRef, element: &ElementData) {
+        let name = &*element.name.local;
+
+        match name {
+            _ if SKIPPED.contains(&name) => {}
+            "br" => self.writer.newline(),
+            "hr" => {
+                self.writer.block(2);
+
+                if self.markdown() {
+                    self.writer.marker("---");
+                }
+
+                self.writer.block(2);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.writer.block(2);
+
+                if self.markdown() {
+                    let level = name[1..].parse().unwrap_or(1);
+
+                    self.writer.marker(&format!("{} ", "#".repeat(level)));
+                }
+
+                self.children(node);
+                self.writer.block(2);
+            }
+            "ul" | "ol" => self.list(node, element, name == "ol"),
+            "li" => self.list_item(node),
+            "blockquote" => {
+                self.writer.block(2);
+
+                if self.markdown() {
+                    self.writer.push_prefix("> ");
+                    self.children(node);
+                    self.writer.pop_prefix();
+                } else {
+                    self.children(node);
+                }
+
+                self.writer.block(2);
+            }
+            "pre" => self.code_block(node, element),
+            "code" | "kbd" | "samp" => self.inline_code(node),
+            "strong" | "b" => self.emphasis(node, "**"),
+            "em" | "i" | "cite" => self.emphasis(node, "*"),
+            "del" | "s" | "strike" => self.emphasis(node, "~~"),
+            "a" => self.link(node, element),
+            "img" => self.image(element),
+            "table" => self.table(node),
+            _ if PARAGRAPHS.contains(&name) => {
+                self.writer.block(2);
+                self.children(node);
+                self.writer.block(2);
+            }
+            _ if BLOCKS.contains(&name) => {
+                self.writer.block(1);
+                self.children(node);
+                self.writer.block(1);
+            }
+            _ => self.children(node),
+        }
+    }
+
+    fn list(&mut self, node: &NodeRef, element: &ElementData, ordered: bool) {
+        // 嵌套的列表紧跟在上一级列表项之后
+        let spacing = if self.lists.is_empty() { 2 } else { 1 };
+
+        let start = attribute(element, "start")
+            .and_then(|start| start.trim().parse().ok())
+            .unwrap_or(1);
+
+        self.writer.block(spacing);
+        self.lists.push(ordered.then_some(start));
+        self.children(node);
+        self.lists.pop();
+        self.writer.block(spacing);
+    }
+
+    fn list_item(&mut self, node: &NodeRef) {
+        let bullet = if self.markdown() { "- " } else { "• " };
+
+        let marker = match self.lists.last_mut() {
+            Some(Some(index)) => {
+                let marker = format!("{}. ", index);
+                *index += 1;
+                marker
+            }
+            _ => bullet.to_string(),
+        };
+
+        self.writer.block(1);
+        self.writer.item(&marker);
+        self.children(node);
+        self.writer.pop_prefix();
+        self.writer.block(1);
+    }
+
+    fn code_block(&mut self, node: &NodeRef, element: &ElementData) {
+        let code = node.text_contents();
+        let code = code.trim_end_matches(['\n', '\r']);
+
+        self.writer.block(2);
+
+        if self.markdown() {
+            // 语言写在 pre 或者内部 code 的 class 中，例如 language-rust
+            let language = code_language(element)
+                .or_else(|| {
+                    node.children()
+                        .find_map(|child| child.as_element().and_then(code_language))
+                })
+                .unwrap_or_default();
+
+            let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+
+            self.writer.marker(&format!("{}{}", fence, language));
+            self.writer.newline();
+            self.writer.raw(code);
+            self.writer.newline();
+            self.writer.marker(&fence);
+        } else {
+            self.writer.raw(code);
+        }
+
+        self.writer.block(2);
+    }
+
+    fn inline_code(&mut self, node: &NodeRef) {
+        let code = node.text_contents();
+
+        if !self.markdown() {
+            self.writer.text(&code);
+            return;
+        }
+
+        let code = collapse_whitespace(&code);
+
+        if code.is_empty() {
+            return;
+        }
+
+        let fence = "`".repeat(longest_run(&code, '`') + 1);
+        let padding = if code.starts_with('`') || code.ends_with('`') {
+            " "
+        } else {
+            ""
+        };
+
+        self.space_before(node);
+        self.writer
+            .marker(&format!("{fence}{padding}{code}{padding}{fence}"));
+        self.space_after(node);
+    }
+
+    fn emphasis(&mut self, node: &NodeRef, marker: &str) {
+        if !self.markdown() {
+            self.children(node);
+            return;
+        }
+
+        let content = self.inline(node);
+
+        if content.is_empty() {
+            self.space_before(node);
+            return;
+        }
+
+        self.space_before(node);
+        self.writer.marker(&format!("{marker}{content}{marker}"));
+        self.space_after(node);
+    }
+
+    fn link(&mut self, node: &NodeRef, element: &ElementData) {
+        let href = attribute(element, "href").unwrap_or_default();
+        let href = href.trim();
+
+        // 页内锚点和脚本链接只保留文字
+        if href.is_empty() || href.starts_with('#') || is_script_url(href) {
+            self.children(node);
+            return;
+        }
+
+        let content = self.inline(node);
+
+        self.space_before(node);
+
+        if self.markdown() {
+            let target = href
+                .replace(' ', "%20")
+                .replace('(', "%28")
+                .replace(')', "%29");
+
+            if content.is_empty() {
+                self.writer.marker(&format!("<{}>", target));
+            } else {
+                self.writer.marker(&format!("[{}]({})", content, target));
+            }
+        } else if content.is_empty() || content == href {
+            self.writer.text(href);
+        } else {
+            self.writer.text(&format!("{} ({})", content, href));
+        }
+
+        self.space_after(node);
+    }
+
+    fn image(&mut self, element: &ElementData) {
+        let alt = attribute(element, "alt").unwrap_or_default();
+        let src = attribute(element, "src").unwrap_or_default();
+
+        // 内嵌的 data URI 可能很长，只保留说明文字
+        if !self.markdown() || src.is_empty() || src.starts_with("data:") {
+            self.writer.text(&alt);
+            return;
+        }
+
+        let alt = escape_markdown(&collapse_whitespace(&alt));
+        let src = src.trim().replace(' ', "%20");
+
+        self.writer.marker(&format!("![{}]({})", alt, src));
+    }
+
+    fn table(&mut self, node: &NodeRef) {
+        let mut rows = Vec::new();
+        collect_rows(node, &mut rows);
+
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| {
+                row.children()
+                    .filter(|cell| is_element(cell, &["td", "th"]))
+                    .map(|cell| {
+                        let content = self.inline(&cell);
+
+                        if self.markdown() && !self.inline {
+                            content.replace('|', "\\|")
+                        } else {
+                            content
+                        }
+                    })
+                    .collect()
+            })
+            .filter(|row: &Vec<String>| !row.is_empty())
+            .collect();
+
+        if rows.is_empty() {
+            return;
+        }
+
+        // 单元格中嵌套的表格只保留文字
+        if self.inline {
+            self.writer.text(" ");
+            self.writer.text(&rows.concat().join(" "));
+            self.writer.text(" ");
+            return;
+        }
+
+        self.writer.block(2);
+
+        if self.markdown() {
+            let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+
+            for (index, row) in rows.iter().enumerate() {
+                let cells: Vec<&str> = (0..columns)
+                    .map(|column| row.get(column).map_or("", String::as_str))
+                    .collect();
+
+                self.writer.block(1);
+                self.writer.marker(&format!("| {} |", cells.join(" | ")));
+
+                // 第一行作为表头
+                if index == 0 {
+                    self.writer.block(1);
+                    self.writer
+                        .marker(&format!("|{}", " --- |".repeat(columns)));
+                }
+            }
+        } else {
+            for row in &rows {
+                self.writer.block(1);
+                self.writer.marker(&row.join("\t"));
+            }
+        }
+
+        self.writer.block(2);
+    }
+
+    // 把子节点转为一行文本，用于链接文字、强调和表格单元格
+    fn inline(&self, node: &NodeRef) -> String {
+        let mut converter = Converter::new(self.format);
+        converter.depth = self.depth;
+        converter.inline = true;
+        converter.children(node);
+
+        collapse_whitespace(&converter.writer.finish())
+    }
+
+    // 标记不参与空白合并，需要根据原文补上两侧的空格
+    fn space_before(&mut self, node: &NodeRef) {
+        if node.text_contents().starts_with(char::is_whitespace) {
+            self.writer.text(" ");
+        }
+    }
+
+    fn space_after(&mut self, node: &NodeRef) {
+        if node.text_contents().ends_with(char::is_whitespace) {
+            self.writer.text(" ");
+        }
+    }
+}
+
+fn is_element(node: &NodeRef, names: &[&str]) -> bool {
+    node.as_element()
+        .is_some_and(|element| names.contains(&&*element.name.local))
+}
+
+// 表格的行，不包含嵌套表格中的行
+fn collect_rows(node: &NodeRef, rows: &mut Vec<NodeRef>) {
+    for child in node.children() {
+        if is_element(&child, &["tr"]) {
+            rows.push(child);
+        } else if is_element(&child, &["thead", "tbody", "tfoot"]) {
+            collect_rows(&child, rows);
+        }
+    }
+}
+
+fn code_language(element: &ElementData) -> Option<String> {
+    let class = attribute(element, "class")?;
+
+    class.split_whitespace().find_map(|name| {
+        name.strip_prefix("language-")
+            .or_else(|| name.strip_prefix("lang-"))
+            .filter(|language| !language.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn is_script_url(url: &str) -> bool {
+    let scheme = url.split(':').next().unwrap_or_default();
+
+    url.contains(':')
+        && matches!(
+            scheme.to_ascii_lowercase().as_str(),
+            "javascript" | "vbscript"
+        )
+}
+
+fn longest_run(text: &str, target: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+
+    for c in text.chars() {
+        if c == target {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+
+    longest
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markdown(html: &str) -> String {
+        convert(html, OutputFormat::Markdown)
+    }
+
+    fn text(html: &str) -> String {
+        convert(html, OutputFormat::Text)
+    }
+
+    #[test]
+    fn converts_headings_and_paragraphs() {
+        let html =
+            "<h2>Title</h2><p>First   line<br>second <b>bold</b> and <em>it</em></p><hr><p>end</p>";
+
+        assert_eq!(
+            markdown(html),
+            "## Title\n\nFirst line\nsecond **bold** and *it*\n\n---\n\nend"
+        );
+        assert_eq!(text(html), "Title\n\nFirst line\nsecond bold and it\n\nend");
+    }
+
+    #[test]
+    fn converts_nested_lists() {
+        let html = "<ul><li>one<ol start=\"3\"><li>three</li><li>four<ul><li>deep</li></ul></li></ol></li><li>two</li></ul>";
+
+        assert_eq!(
+            markdown(html),
+            "- one\n  3. three\n  4. four\n     - deep\n- two"
+        );
+        assert_eq!(
+            text(html),
+            "• one\n  3. three\n  4. four\n     • deep\n• two"
+        );
+    }
+
+    #[test]
+    fn converts_blockquotes() {
+        let html = "<blockquote><p>a</p><blockquote>b</blockquote></blockquote>";
+
+        assert_eq!(markdown(html), "> a\n>\n> > b");
+        assert_eq!(text(html), "a\n\nb");
+    }
+
+    #[test]
+    fn converts_tables() {
+        let html = "<table><thead><tr><th>Name</th><th>A|B</th></tr></thead>\
+                    <tbody><tr><td><b>x</b></td><td>1</td><td>extra</td></tr><tr></tr>\
+                    <tr><td>y <table><tr><td>inner</td></tr></table></td></tr></tbody></table>";
+
+        assert_eq!(
+            markdown(html),
+            "| Name | A\\|B |  |\n| --- | --- | --- |\n| **x** | 1 | extra |\n| y inner |  |  |"
+        );
+        assert_eq!(text(html), "Name\tA|B\nx\t1\textra\ny inner");
+    }
+
+    #[test]
+    fn converts_links_and_images() {
+        let html = "<p>See <a href=\"https://a.com/x (1)\">the *docs*</a>, \
+                    <a href=\"https://b.com\">https://b.com</a>, <a href=\"#top\">top</a>, \
+                    <a href=\"JavaScript:alert(1)\">js</a> <a href=\"https://c.com\"></a> \
+                    <img src=\"https://a.com/i.png\" alt=\"logo\"><img src=\"data:image/png;base64,AA\" alt=\"inline\"></p>";
+
+        assert_eq!(
+            markdown(html),
+            "See [the \\*docs\\*](https://a.com/x%20%281%29), [https://b.com](https://b.com), \
+             top, js <https://c.com> ![logo](https://a.com/i.png)inline"
+        );
+        assert_eq!(
+            text(html),
+            "See the *docs* (https://a.com/x (1)), https://b.com, top, js https://c.com logoinline"
+        );
+    }
+
+    #[test]
+    fn converts_code() {
+        let html = "<p>Run <code>a `b`</code></p><pre class=\"language-rust\"><code>fn main() {\n    ```\n}\n</code></pre>";
+
+        assert_eq!(
+            markdown(html),
+            "Run `` a `b` ``\n\n````rust\nfn main() {\n    ```\n}\n````"
+        );
+        assert_eq!(text(html), "Run a `b`\n\nfn main() {\n    ```\n}");
+    }
+
+    #[test]
+    fn skips_scripts_and_styles() {
+        let html = "<head><title>t</title><style>p{}</style></head><script>x()</script><p>only</p>";
+
+        assert_eq!(markdown(html), "only");
+    }
+
+    #[test]
+    fn handles_malformed_html() {
+        let cases = [
+            ("<ul><li>a<li>b</ul>", "- a\n- b"),
+            (
+                "<p>unclosed <b>bold <i>both</p>",
+                "unclosed **bold *both***",
+            ),
+            ("</div></p><td>cell", "cell"),
+            ("<a href=\"https://a.com\">x", "[x](https://a.com)"),
+            ("<table><td>a<td>b", "| a | b |\n| --- | --- |"),
+            ("<h9>x</h9><<>>", "x<<>>"),
+            ("", ""),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(markdown(html), expected, "{}", html);
+        }
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let html = format!(
+            "{}<i>x</i> y{}",
+            "<div>".repeat(3_000),
+            "</div>".repeat(3_000)
+        );
+
+        // 超过限制的部分只保留文字，在较小的栈上也不会溢出
+        let (markdown, text) = std::thread::Builder::new()
+            .stack_size(1024 * 1024)
+            .spawn(move || (markdown(&html), text(&html)))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(markdown, "x y");
+        assert_eq!(text, "x y");
+    }
+}
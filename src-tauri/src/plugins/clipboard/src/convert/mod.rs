@@ -0,0 +1,71 @@
+//! 富文本内容转换
+//!
+//! 把剪贴板中的 HTML 和 RTF 转为 Markdown 或纯文本，
+//! 纯文本用于历史记录的搜索，Markdown 用于「粘贴为 Markdown」。
+//! HTML 在渲染之前还需要经过清理。
+
+use kuchikiki::ElementData;
+use serde::{Deserialize, Serialize};
+
+mod html;
+mod rtf;
+mod sanitize;
+mod writer;
+
+pub use sanitize::sanitize_html;
+
+/// 富文本的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    Html,
+    Rtf,
+}
+
+/// 转换结果的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Markdown,
+    /// 保留换行和列表符号的纯文本
+    Text,
+}
+
+/// 把 `from` 格式的 `value` 转为 `to` 格式的文本
+pub fn convert(value: &str, from: ContentFormat, to: OutputFormat) -> String {
+    match from {
+        ContentFormat::Html => html::convert(value, to),
+        ContentFormat::Rtf => rtf::convert(value, to),
+    }
+}
+
+/// 转义文本中会被当作 Markdown 语法的字符，单词内部的下划线不需要转义
+fn escape_markdown(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+
+    for (index, &c) in chars.iter().enumerate() {
+        let escape = match c {
+            '\\' | '*' | '`' | '[' | ']' => true,
+            '_' => {
+                let before = index > 0 && chars[index - 1].is_alphanumeric();
+                let after = chars.get(index + 1).is_some_and(|c| c.is_alphanumeric());
+
+                !(before && after)
+            }
+            _ => false,
+        };
+
+        if escape {
+            result.push('\\');
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+fn attribute(element: &ElementData, name: &str) -> Option<String> {
+    element.attributes.borrow().get(name).map(str::to_string)
+}
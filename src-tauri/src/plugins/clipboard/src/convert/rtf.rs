@@ -0,0 +1,880 @@
+//! RTF 转 Markdown 和纯文本
+//!
+//! 只解析文字、段落、列表、表格、粗体、斜体、删除线和超链接，
+//! 字体表、样式表、图片等目标组整组跳过。
+//! `\'hh` 按 Windows-1252 解码，其它代码页中的非 ASCII 字节依赖 `\uN` 提供的 Unicode 字符。
+
+use super::writer::TextWriter;
+use super::{escape_markdown, OutputFormat};
+
+// 内容不需要输出的目标组
+const SKIPPED: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "nonshppict",
+    "object",
+    "shpinst",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "footnote",
+    "listtable",
+    "listoverridetable",
+    "revtbl",
+    "rsidtbl",
+    "filetbl",
+    "generator",
+    "xmlnstbl",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "latentstyles",
+    "pn",
+    "bkmkstart",
+    "bkmkend",
+];
+
+/// 把 RTF 转为 `format` 格式的文本
+pub fn convert(rtf: &str, format: OutputFormat) -> String {
+    let mut parser = Parser::new(format);
+    parser.parse(rtf);
+    parser.finish()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    link: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Destination {
+    Text,
+    Skip,
+    // 列表项前的编号，例如 {\listtext 1.\tab}
+    ListText,
+    // 域代码，例如 HYPERLINK "https://example.com"
+    FieldInstruction,
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    destination: Destination,
+    style: Style,
+    // \ucN，\uN 之后需要跳过的替代字符数
+    unicode_skip: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Run {
+    text: String,
+    style: Style,
+}
+
+#[derive(Debug, Default)]
+struct Paragraph {
+    // 按 \line 分隔的行
+    lines: Vec<Vec<Run>>,
+    // 列表项的编号或者项目符号
+    list: Option<String>,
+}
+
+impl Paragraph {
+    fn is_empty(&self) -> bool {
+        self.lines
+            .iter()
+            .flatten()
+            .all(|run| run.text.trim().is_empty())
+    }
+}
+
+struct Parser {
+    format: OutputFormat,
+    writer: TextWriter,
+    groups: Vec<Group>,
+    paragraph: Paragraph,
+    // 上一个段落是列表项
+    in_list: bool,
+    in_table: bool,
+    cell: Vec<String>,
+    row: Vec<String>,
+    rows: Vec<Vec<String>>,
+    list_text: String,
+    instruction: String,
+    // 还需要跳过的替代字符数
+    pending_skip: usize,
+    // 代理对的前半部分
+    high_surrogate: Option<u32>,
+    // 是否可以按 Windows-1252 解码 \'hh
+    ansi: bool,
+}
+
+impl Parser {
+    fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            writer: TextWriter::new(),
+            groups: vec![Group {
+                destination: Destination::Text,
+                style: Style::default(),
+                unicode_skip: 1,
+            }],
+            paragraph: Paragraph::default(),
+            in_list: false,
+            in_table: false,
+            cell: Vec::new(),
+            row: Vec::new(),
+            rows: Vec::new(),
+            list_text: String::new(),
+            instruction: String::new(),
+            pending_skip: 0,
+            high_surrogate: None,
+            ansi: true,
+        }
+    }
+
+    fn markdown(&self) -> bool {
+        self.format == OutputFormat::Markdown
+    }
+
+    fn group(&mut self) -> &mut Group {
+        // 根组不会被弹出
+        self.groups.last_mut().expect("root group")
+    }
+
+    fn parse(&mut self, rtf: &str) {
+        let chars: Vec<char> = rtf.chars().collect();
+        let mut index = 0;
+
+        while index < chars.len() {
+            let c = chars[index];
+            index += 1;
+
+            match c {
+                '{' => {
+                    let group = self.group().clone();
+                    self.groups.push(group);
+                }
+                '}' => self.close_group(),
+                '\\' => index = self.control(&chars, index),
+                // 源文件中的换行没有意义
+                '\r' | '\n' => {}
+                c => self.char(c),
+            }
+        }
+    }
+
+    // 解析 `\` 之后的控制字或控制符号，返回下一个字符的位置
+    fn control(&mut self, chars: &[char], mut index: usize) -> usize {
+        let Some(&c) = chars.get(index) else {
+            return index;
+        };
+
+        if !c.is_ascii_alphabetic() {
+            index += 1;
+
+            match c {
+                '\'' => {
+                    // 最多两位十六进制数字，不完整时忽略
+                    let hex: String = chars[index..]
+                        .iter()
+                        .take(2)
+                        .take_while(|c| c.is_ascii_hexdigit())
+                        .collect();
+                    index += hex.len();
+
+                    if hex.len() == 2 {
+                        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                            self.byte(byte);
+                        }
+                    }
+                }
+                '*' => self.group().destination = Destination::Skip,
+                '~' => self.char(' '),
+                '_' => self.char('-'),
+                // 可选连字符
+                '-' => {}
+                '\r' | '\n' => self.end_paragraph(),
+                c => self.char(c),
+            }
+
+            return index;
+        }
+
+        let start = index;
+
+        while chars.get(index).is_some_and(char::is_ascii_alphabetic) {
+            index += 1;
+        }
+
+        let word: String = chars[start..index].iter().collect();
+
+        let number_start = index;
+
+        if chars.get(index) == Some(&'-') {
+            index += 1;
+        }
+
+        while chars.get(index).is_some_and(char::is_ascii_digit) {
+            index += 1;
+        }
+
+        let param: Option<i32> = chars[number_start..index]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok();
+
+        // 控制字后的一个空格是分隔符
+        if chars.get(index) == Some(&' ') {
+            index += 1;
+        }
+
+        if word == "bin" {
+            // 二进制数据直接跳过
+            return index.saturating_add(param.unwrap_or_default().max(0) as usize);
+        }
+
+        self.word(&word, param);
+
+        index
+    }
+
+    fn word(&mut self, word: &str, param: Option<i32>) {
+        let on = param != Some(0);
+
+        match word {
+            _ if SKIPPED.contains(&word) => self.group().destination = Destination::Skip,
+            "listtext" | "pntext" => {
+                self.group().destination = Destination::ListText;
+                self.list_text.clear();
+            }
+            "fldinst" => {
+                self.group().destination = Destination::FieldInstruction;
+                self.instruction.clear();
+            }
+            "ansicpg" => self.ansi = matches!(param, None | Some(1252)),
+            "mac" | "pc" | "pca" => self.ansi = false,
+            "uc" => self.group().unicode_skip = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                if let Some(code) = param {
+                    // 大于 32767 的字符写作负数
+                    let code = if code < 0 { code + 65536 } else { code };
+
+                    self.unicode(code as u32);
+                    self.pending_skip = self.group().unicode_skip;
+                }
+            }
+            "par" | "sect" | "page" => self.end_paragraph(),
+            "line" => self.paragraph.lines.push(Vec::new()),
+            "tab" => self.char('\t'),
+            "emdash" => self.char('—'),
+            "endash" => self.char('–'),
+            "emspace" | "enspace" | "qmspace" => self.char(' '),
+            "lquote" => self.char('‘'),
+            "rquote" => self.char('’'),
+            "ldblquote" => self.char('“'),
+            "rdblquote" => self.char('”'),
+            "bullet" => self.char('•'),
+            "b" => self.group().style.bold = on,
+            "i" => self.group().style.italic = on,
+            "strike" | "striked" => self.group().style.strike = on,
+            "plain" => {
+                let style = &mut self.group().style;
+                style.bold = false;
+                style.italic = false;
+                style.strike = false;
+            }
+            "pard" => self.in_table = false,
+            "intbl" => self.in_table = true,
+            "cell" => self.end_cell(),
+            "row" => {
+                // 最后一个单元格通常已经由 \cell 结束
+                if !self.paragraph.is_empty() || !self.cell.is_empty() {
+                    self.end_cell();
+                }
+
+                let row = std::mem::take(&mut self.row);
+                self.rows.push(row);
+            }
+            _ => {}
+        }
+    }
+
+    fn close_group(&mut self) {
+        if self.groups.len() <= 1 {
+            return;
+        }
+
+        let Some(group) = self.groups.pop() else {
+            return;
+        };
+
+        match group.destination {
+            Destination::ListText if self.group().destination == Destination::Text => {
+                self.paragraph.list = Some(list_marker(&self.list_text, self.markdown()));
+            }
+            // 之后的 {\fldrslt ...} 是超链接的文字
+            Destination::FieldInstruction => {
+                let link = hyperlink(&self.instruction);
+                self.group().style.link = link;
+            }
+            _ => {}
+        }
+    }
+
+    // BMP 以外的字符写作两个 \uN 组成的代理对
+    fn unicode(&mut self, code: u32) {
+        let code = match (self.high_surrogate.take(), code) {
+            (_, 0xD800..=0xDBFF) => {
+                self.high_surrogate = Some(code);
+                return;
+            }
+            (Some(high), 0xDC00..=0xDFFF) => 0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00),
+            (None, 0xDC00..=0xDFFF) => return,
+            (_, code) => code,
+        };
+
+        if let Some(c) = char::from_u32(code) {
+            self.char(c);
+        }
+    }
+
+    fn byte(&mut self, byte: u8) {
+        if self.pending_skip > 0 {
+            self.pending_skip -= 1;
+            return;
+        }
+
+        if byte < 0x80 || self.ansi {
+            if let Some(c) = decode_cp1252(byte) {
+                self.push(c);
+            }
+        }
+    }
+
+    fn char(&mut self, c: char) {
+        if self.pending_skip > 0 {
+            self.pending_skip -= 1;
+            return;
+        }
+
+        self.push(c);
+    }
+
+    fn push(&mut self, c: char) {
+        let group = self.groups.last().expect("root group");
+
+        match group.destination {
+            Destination::Text => {}
+            Destination::Skip => return,
+            Destination::ListText => {
+                self.list_text.push(c);
+                return;
+            }
+            Destination::FieldInstruction => {
+                self.instruction.push(c);
+                return;
+            }
+        }
+
+        let style = if self.markdown() {
+            group.style.clone()
+        } else {
+            // 纯文本只需要区分超链接
+            Style {
+                link: group.style.link.clone(),
+                ..Style::default()
+            }
+        };
+
+        if self.paragraph.lines.is_empty() {
+            self.paragraph.lines.push(Vec::new());
+        }
+
+        let line = self.paragraph.lines.last_mut().expect("line");
+
+        match line.last_mut() {
+            Some(run) if run.style == style => run.text.push(c),
+            _ => line.push(Run {
+                text: c.to_string(),
+                style,
+            }),
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        let paragraph = std::mem::take(&mut self.paragraph);
+
+        if self.in_table {
+            // 单元格中的多个段落合并为一行
+            if !paragraph.is_empty() {
+                let text = self.render_inline(&paragraph);
+                self.cell.push(text);
+            }
+
+            return;
+        }
+
+        self.flush_table();
+        self.write_paragraph(&paragraph);
+    }
+
+    fn end_cell(&mut self) {
+        let paragraph = std::mem::take(&mut self.paragraph);
+
+        if !paragraph.is_empty() {
+            let text = self.render_inline(&paragraph);
+            self.cell.push(text);
+        }
+
+        let cell = std::mem::take(&mut self.cell).join(" ");
+        self.row.push(cell);
+    }
+
+    fn write_paragraph(&mut self, paragraph: &Paragraph) {
+        if paragraph.is_empty() {
+            // Markdown 中多个空段落和一个没有区别
+            if !self.markdown() {
+                self.writer.newline();
+            }
+
+            return;
+        }
+
+        let markdown = self.markdown();
+
+        match &paragraph.list {
+            Some(marker) => {
+                if !self.in_list && markdown {
+                    self.writer.block(2);
+                }
+
+                self.writer.item(marker);
+            }
+            None if markdown => self.writer.block(2),
+            None => {}
+        }
+
+        let lines: Vec<String> = paragraph
+            .lines
+            .iter()
+            .map(|runs| render_runs(runs, self.format))
+            .collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                // Markdown 中行尾的反斜杠表示换行
+                if markdown {
+                    self.writer.marker("\\");
+                }
+
+                self.writer.newline();
+            }
+
+            if markdown {
+                self.writer.text(line);
+            } else {
+                self.writer.raw(line.trim_end());
+            }
+        }
+
+        if paragraph.list.is_some() {
+            self.writer.pop_prefix();
+        }
+
+        self.in_list = paragraph.list.is_some();
+
+        if markdown && !self.in_list {
+            self.writer.block(2);
+        } else {
+            self.writer.newline();
+        }
+    }
+
+    // 把段落转为一行，用于表格单元格
+    fn render_inline(&self, paragraph: &Paragraph) -> String {
+        let text = paragraph
+            .lines
+            .iter()
+            .map(|runs| render_runs(runs, self.format))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if self.markdown() {
+            text.replace('|', "\\|")
+        } else {
+            text
+        }
+    }
+
+    fn flush_table(&mut self) {
+        if !self.row.is_empty() || !self.cell.is_empty() {
+            self.end_cell();
+            let row = std::mem::take(&mut self.row);
+            self.rows.push(row);
+        }
+
+        let rows: Vec<Vec<String>> = std::mem::take(&mut self.rows)
+            .into_iter()
+            .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+            .collect();
+
+        if rows.is_empty() {
+            return;
+        }
+
+        let markdown = self.markdown();
+
+        self.writer.block(if markdown { 2 } else { 1 });
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+
+        for (index, row) in rows.iter().enumerate() {
+            self.writer.block(1);
+
+            if markdown {
+                let cells: Vec<&str> = (0..columns)
+                    .map(|column| row.get(column).map_or("", String::as_str))
+                    .collect();
+
+                self.writer.marker(&format!("| {} |", cells.join(" | ")));
+
+                // 第一行作为表头
+                if index == 0 {
+                    self.writer.block(1);
+                    self.writer
+                        .marker(&format!("|{}", " --- |".repeat(columns)));
+                }
+            } else {
+                self.writer.marker(&row.join("\t"));
+            }
+        }
+
+        self.in_list = false;
+        self.writer.block(if markdown { 2 } else { 1 });
+    }
+
+    fn finish(mut self) -> String {
+        // 最后一个段落可能没有 \par
+        if self.in_table && !self.paragraph.is_empty() {
+            self.end_cell();
+        }
+
+        self.in_table = false;
+        self.end_paragraph();
+
+        self.writer.finish()
+    }
+}
+
+// 合并样式相同的文字，Markdown 中加上强调符号和链接
+fn render_runs(runs: &[Run], format: OutputFormat) -> String {
+    let mut result = String::new();
+    let mut index = 0;
+
+    while index < runs.len() {
+        let link = &runs[index].style.link;
+        let end = runs[index..]
+            .iter()
+            .position(|run| &run.style.link != link)
+            .map_or(runs.len(), |offset| index + offset);
+
+        let text: String = match format {
+            OutputFormat::Markdown => runs[index..end].iter().map(emphasize).collect(),
+            OutputFormat::Text => runs[index..end]
+                .iter()
+                .map(|run| run.text.as_str())
+                .collect(),
+        };
+
+        match link {
+            Some(url) if !text.trim().is_empty() => {
+                let (leading, content, trailing) = split_whitespace(&text);
+
+                result.push_str(leading);
+
+                match format {
+                    OutputFormat::Markdown => {
+                        let target = url
+                            .replace(' ', "%20")
+                            .replace('(', "%28")
+                            .replace(')', "%29");
+                        result.push_str(&format!("[{}]({})", content, target));
+                    }
+                    OutputFormat::Text if content == url => result.push_str(content),
+                    OutputFormat::Text => result.push_str(&format!("{} ({})", content, url)),
+                }
+
+                result.push_str(trailing);
+            }
+            _ => result.push_str(&text),
+        }
+
+        index = end;
+    }
+
+    result
+}
+
+fn emphasize(run: &Run) -> String {
+    let text = escape_markdown(&run.text);
+    let (leading, content, trailing) = split_whitespace(&text);
+
+    if content.is_empty() {
+        return text;
+    }
+
+    let mut marker = match (run.style.bold, run.style.italic) {
+        (true, true) => "***",
+        (true, false) => "**",
+        (false, true) => "*",
+        (false, false) => "",
+    }
+    .to_string();
+
+    if run.style.strike {
+        marker.insert_str(0, "~~");
+    }
+
+    let closing: String = marker.chars().rev().collect();
+
+    format!("{leading}{marker}{content}{closing}{trailing}")
+}
+
+// 强调符号紧贴文字，两侧的空白放在符号外
+fn split_whitespace(text: &str) -> (&str, &str, &str) {
+    let content = text.trim();
+
+    if content.is_empty() {
+        return (text, "", "");
+    }
+
+    let start = text.len() - text.trim_start().len();
+    let end = start + content.len();
+
+    (&text[..start], content, &text[end..])
+}
+
+fn list_marker(text: &str, markdown: bool) -> String {
+    let text = text.trim();
+    let number = text.trim_end_matches(['.', ')']);
+
+    if !number.is_empty() && number.len() < text.len() && number.chars().all(|c| c.is_ascii_digit())
+    {
+        format!("{}. ", number)
+    } else if markdown {
+        "- ".to_string()
+    } else {
+        "• ".to_string()
+    }
+}
+
+// 解析 HYPERLINK "url" 域代码
+fn hyperlink(instruction: &str) -> Option<String> {
+    let rest = instruction.trim().strip_prefix("HYPERLINK")?;
+    let start = rest.find('"')? + 1;
+    let end = start + rest[start..].find('"')?;
+    let url = rest[start..end].trim();
+
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+// Windows-1252 中 0x80 到 0x9F 对应的字符，未定义的位置为 None
+const CP1252: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+fn decode_cp1252(byte: u8) -> Option<char> {
+    match byte {
+        0x80..=0x9f => CP1252[(byte - 0x80) as usize],
+        _ => Some(byte as char),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str =
+        r"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0 Calibri;}}{\colortbl;\red255\green0\blue0;}";
+
+    fn markdown(body: &str) -> String {
+        convert(&format!("{}{}}}", HEADER, body), OutputFormat::Markdown)
+    }
+
+    fn text(body: &str) -> String {
+        convert(&format!("{}{}}}", HEADER, body), OutputFormat::Text)
+    }
+
+    #[test]
+    fn converts_paragraphs_and_styles() {
+        let body = r"\pard Hello {\b bold} {\i it}\i0  and {\strike gone}\par Line\line two\par";
+
+        assert_eq!(
+            markdown(body),
+            "Hello **bold** *it* and ~~gone~~\n\nLine\\\ntwo"
+        );
+        assert_eq!(text(body), "Hello bold it and gone\nLine\ntwo");
+    }
+
+    #[test]
+    fn decodes_hex_escapes() {
+        assert_eq!(text(r"caf\'e9 \'80 \'93q\'94"), "café € “q”");
+        // 非 Windows-1252 代码页中的字节无法解码
+        assert_eq!(
+            convert(r"{\rtf1\ansi\ansicpg936 a\'c4\'e3b}", OutputFormat::Text),
+            "ab"
+        );
+        // 不完整的十六进制不会吞掉后面的字符
+        assert_eq!(text(r"a\'4 b\'zc\'"), "a bzc");
+        assert_eq!(text(r"a\'é1"), "aé1");
+    }
+
+    #[test]
+    fn decodes_unicode_escapes() {
+        assert_eq!(text(r"\u20320?\u22909?"), "你好");
+        // 大于 32767 的字符写作负数
+        assert_eq!(text(r"\u-3913?"), "\u{F0B7}");
+        // \ucN 指定替代字符的个数，随组恢复
+        assert_eq!(text(r"{\uc2\u20320??\u22909 xx}\u19990?"), "你好世");
+        assert_eq!(text(r"\uc0\u20320 a"), "你a");
+        // 代理对
+        assert_eq!(text(r"\u-10179?\u-8704?!"), "😀!");
+        // 孤立的代理不输出
+        assert_eq!(text(r"a\u-8704?b\u-10179?c"), "abc");
+    }
+
+    #[test]
+    fn skips_binary_data() {
+        assert_eq!(text(r"a{\*\blipuid x}{\pict\bin4 }{\}b}c"), "ac");
+        assert_eq!(text(r"a\bin3 xyzb"), "ab");
+        // 长度超出文件末尾
+        assert_eq!(text(r"a\bin2147483647 xyz"), "a");
+        assert_eq!(text(r"a\bin-5 b"), "ab");
+    }
+
+    #[test]
+    fn converts_lists() {
+        let body = concat!(
+            r"{\listtext 1.\tab}First\par ",
+            r"{\listtext 2.\tab}Second\par ",
+            r"{\pntext\'b7\tab}Bullet\par ",
+            r"After\par"
+        );
+
+        assert_eq!(markdown(body), "1. First\n2. Second\n- Bullet\n\nAfter");
+        assert_eq!(text(body), "1. First\n2. Second\n• Bullet\nAfter");
+    }
+
+    #[test]
+    fn converts_tables() {
+        let body = concat!(
+            r"\trowd\cellx1000\cellx2000",
+            r"\pard\intbl Name\cell A|B\cell\row",
+            r"\trowd\pard\intbl {\b x}\cell 1\par 2\cell\row",
+            r"\pard After\par"
+        );
+
+        assert_eq!(
+            markdown(body),
+            "| Name | A\\|B |\n| --- | --- |\n| **x** | 1 2 |\n\nAfter"
+        );
+        assert_eq!(text(body), "Name\tA|B\nx\t1 2\nAfter");
+    }
+
+    #[test]
+    fn converts_hyperlinks() {
+        let body = concat!(
+            r#"See {\field{\*\fldinst HYPERLINK "https://a.com/x (1)"}{\fldrslt {\ul docs}}} "#,
+            r#"and {\field{\*\fldinst{HYPERLINK "https://b.com"}}{\fldrslt https://b.com}}."#,
+        );
+
+        assert_eq!(
+            markdown(body),
+            "See [docs](https://a.com/x%20%281%29) and [https://b.com](https://b.com)."
+        );
+        assert_eq!(
+            text(body),
+            "See docs (https://a.com/x (1)) and https://b.com."
+        );
+    }
+
+    #[test]
+    fn skips_destinations() {
+        let body =
+            r"{\info{\title T}}{\stylesheet{\s1 Heading;}}{\*\generator Word}{\header H}Body";
+
+        assert_eq!(text(body), "Body");
+    }
+
+    #[test]
+    fn handles_unbalanced_groups() {
+        let cases = [
+            (r"{\rtf1 a}}}}b", "ab"),
+            (r"{\rtf1 {\b a", "a"),
+            (r"{\rtf1 {\*\unknown a}b", "b"),
+            (r"}}{{{\rtf1 x", "x"),
+            (r"{\rtf1 a\", "a"),
+            (r"{\rtf1 \u", ""),
+            (r"{\rtf1 \u99999999 x\u65 abc}d", "Abcd"),
+            ("", ""),
+            ("plain text", "plain text"),
+        ];
+
+        for (rtf, expected) in cases {
+            assert_eq!(convert(rtf, OutputFormat::Text), expected, "{}", rtf);
+            convert(rtf, OutputFormat::Markdown);
+        }
+    }
+
+    #[test]
+    fn handles_deep_nesting() {
+        let rtf = format!("{}a{}", "{\\b ".repeat(100_000), "}".repeat(100_000));
+
+        assert_eq!(convert(&rtf, OutputFormat::Markdown), "**a**");
+    }
+}
@@ -0,0 +1,336 @@
+//! HTML 清理
+//!
+//! 参考 ammonia 的白名单规则：只保留用于展示的标签和属性，
+//! 去掉脚本、事件属性和所有需要联网加载的资源，结果只用于在列表中渲染。
+
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::{ElementData, NodeData, NodeRef};
+
+use super::attribute;
+
+// 连同内容一起删除的元素
+const REMOVED: &[&str] = &[
+    "head", "script", "style", "title", "noscript", "template", "iframe", "frame", "frameset",
+    "object", "embed", "applet", "meta", "link", "base", "svg", "math", "audio", "video", "source",
+    "track", "picture", "canvas", "button", "input", "select", "textarea",
+];
+
+// 保留的元素，其它元素只保留内容
+const ALLOWED: &[&str] = &[
+    "a",
+    "abbr",
+    "acronym",
+    "article",
+    "aside",
+    "b",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "br",
+    "caption",
+    "center",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "font",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "rp",
+    "rt",
+    "rtc",
+    "ruby",
+    "s",
+    "samp",
+    "small",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "tt",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
+
+// 所有元素都可以保留的属性
+const GENERIC_ATTRIBUTES: &[&str] = &["lang", "title", "dir", "style", "align", "valign"];
+
+// 各个元素额外可以保留的属性
+const ELEMENT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("font", &["color", "face", "size"]),
+    ("table", &["border", "cellpadding", "cellspacing", "width"]),
+    ("td", &["colspan", "rowspan", "width", "nowrap"]),
+    ("th", &["colspan", "rowspan", "width", "nowrap"]),
+    ("col", &["span", "width"]),
+    ("colgroup", &["span", "width"]),
+    ("ol", &["start", "type", "reversed"]),
+    ("li", &["value"]),
+    ("time", &["datetime"]),
+    ("data", &["value"]),
+    ("q", &["cite"]),
+    ("blockquote", &["cite"]),
+];
+
+// 链接允许的协议
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+// 包含这些内容的样式声明会被删除
+const UNSAFE_STYLES: &[&str] = &[
+    "url(",
+    "expression(",
+    "@import",
+    "javascript:",
+    "behavior",
+    "-moz-binding",
+    "image-set(",
+];
+
+/// 清理 HTML，返回可以安全渲染的片段
+pub fn sanitize_html(html: &str) -> String {
+    let document = kuchikiki::parse_html().one(html).document_node;
+
+    // 解析后的文档总有 body，片段也会被放到 body 中
+    let Ok(body) = document.select_first("body") else {
+        return String::new();
+    };
+
+    let body = body.as_node();
+    clean(body);
+
+    body.children().map(|child| child.to_string()).collect()
+}
+
+fn clean(node: &NodeRef) {
+    for child in node.children().collect::<Vec<_>>() {
+        match child.data() {
+            NodeData::Text(_) => {}
+            NodeData::Element(element) => {
+                let name = element.name.local.to_string();
+
+                if REMOVED.contains(&name.as_str()) {
+                    child.detach();
+                    continue;
+                }
+
+                clean(&child);
+
+                if !ALLOWED.contains(&name.as_str()) {
+                    unwrap(&child);
+                    continue;
+                }
+
+                if name == "img" && !is_inline_image(element) {
+                    // 远程图片换成说明文字
+                    let alt = attribute(element, "alt").unwrap_or_default();
+
+                    if !alt.trim().is_empty() {
+                        child.insert_before(NodeRef::new_text(alt));
+                    }
+
+                    child.detach();
+                    continue;
+                }
+
+                clean_attributes(&name, element);
+            }
+            // 注释、处理指令等
+            _ => child.detach(),
+        }
+    }
+}
+
+// 用子节点替换元素本身
+fn unwrap(node: &NodeRef) {
+    for child in node.children().collect::<Vec<_>>() {
+        node.insert_before(child);
+    }
+
+    node.detach();
+}
+
+fn clean_attributes(name: &str, element: &ElementData) {
+    let allowed = ELEMENT_ATTRIBUTES
+        .iter()
+        .find(|(element, _)| *element == name)
+        .map_or(&[][..], |(_, attributes)| *attributes);
+
+    let mut attributes = element.attributes.borrow_mut();
+
+    attributes.map.retain(|key, attribute| {
+        let key = &*key.local;
+
+        if !GENERIC_ATTRIBUTES.contains(&key) && !allowed.contains(&key) {
+            return false;
+        }
+
+        match key {
+            "href" | "cite" => is_safe_url(&attribute.value),
+            "style" => {
+                attribute.value = clean_style(&attribute.value);
+
+                !attribute.value.is_empty()
+            }
+            _ => true,
+        }
+    });
+
+    // 链接在外部浏览器中打开，不需要传递来源
+    if name == "a" && attributes.contains("href") {
+        attributes.insert("rel", "noopener noreferrer".to_string());
+    }
+}
+
+fn clean_style(style: &str) -> String {
+    style
+        .split(';')
+        .map(str::trim)
+        .filter(|declaration| !declaration.is_empty() && is_safe_style(declaration))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn is_safe_style(declaration: &str) -> bool {
+    // 转义和注释可以拆开关键字（例如 `\75 rl(`、`position:/**/fixed`），不做解码，直接删除
+    if declaration.contains('\\') || declaration.contains("/*") {
+        return false;
+    }
+
+    // 去掉所有空白后再匹配，避免被制表符、换行等绕过
+    let compact = declaration
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    // 固定定位的元素可能覆盖整个窗口
+    !UNSAFE_STYLES
+        .iter()
+        .any(|unsafe_style| compact.contains(unsafe_style))
+        && !compact.starts_with("position:fixed")
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim();
+
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        URL_SCHEMES
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+// 内嵌在 HTML 中的图片不需要联网
+fn is_inline_image(element: &ElementData) -> bool {
+    attribute(element, "src").is_some_and(|src| {
+        src.trim()
+            .get(..11)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("data:image/"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_plain_styles() {
+        assert_eq!(
+            clean_style("color: red;font-weight:bold ; "),
+            "color: red; font-weight:bold"
+        );
+    }
+
+    #[test]
+    fn removes_remote_resources() {
+        assert_eq!(
+            clean_style("color: red; background: URL(https://tracker/x)"),
+            "color: red"
+        );
+        assert_eq!(clean_style("background-image: image-set(\"a.png\" 1x)"), "");
+        assert_eq!(clean_style("width: expression(alert(1))"), "");
+    }
+
+    #[test]
+    fn removes_escaped_declarations() {
+        assert_eq!(
+            clean_style("color: red; background:\\75 rl(https://tracker/x)"),
+            "color: red"
+        );
+        assert_eq!(clean_style("background: u\\rl(https://tracker/x)"), "");
+        assert_eq!(clean_style("position: \\66ixed"), "");
+        assert_eq!(clean_style("background: u/**/rl(https://tracker/x)"), "");
+    }
+
+    #[test]
+    fn removes_fixed_position() {
+        assert_eq!(clean_style("position:fixed; top: 0"), "top: 0");
+        assert_eq!(clean_style("POSITION :\tFixed"), "");
+        assert_eq!(clean_style("position:\n fixed !important"), "");
+        assert_eq!(clean_style("position:/**/fixed"), "");
+        assert_eq!(clean_style("position: relative"), "position: relative");
+    }
+
+    #[test]
+    fn sanitizes_style_attribute() {
+        let html = sanitize_html(
+            "<p style=\"color:red;background:\\75 rl(https://tracker/x)\">Hi</p>\
+             <span style=\"position:&#9;fixed\">There</span>",
+        );
+
+        assert_eq!(html, "<p style=\"color:red\">Hi</p><span>There</span>");
+    }
+
+    #[test]
+    fn removes_scripts_and_remote_images() {
+        let html = sanitize_html(
+            "<script>alert(1)</script><a href=\"javascript:alert(1)\" onclick=\"x()\">a</a>\
+             <img src=\"https://tracker/x.png\" alt=\"logo\"><img src=\"data:image/png;base64,AA\">",
+        );
+
+        assert_eq!(html, "<a>a</a>logo<img src=\"data:image/png;base64,AA\">");
+    }
+}
@@ -0,0 +1,232 @@
+//! 按块输出文本
+//!
+//! 负责合并空白、段落之间的空行以及引用和列表的行首前缀，
+//! HTML 和 RTF 的转换只需要按顺序写入文本和块的边界。
+
+/// 输出文本
+#[derive(Debug, Default)]
+pub struct TextWriter {
+    output: String,
+    // 每一层引用或列表的行首前缀
+    prefixes: Vec<String>,
+    // 写入下一段内容之前需要的换行数
+    pending_newlines: usize,
+    // 等待中的空行使用前几层前缀，块边界在进入引用之前时不带引用符号
+    blank_depth: usize,
+    // 已经写入过内容
+    started: bool,
+    // 上一个写入的字符是否为空白，用于合并空白
+    last_space: bool,
+    // 刚写入列表符号，列表项的第一个块紧跟在符号后面
+    item_open: bool,
+}
+
+impl TextWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入一段文本，连续的空白合并为一个空格
+    pub fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                // 行首和块边界处的空白没有意义
+                if self.started && !self.last_space && self.pending_newlines == 0 {
+                    self.output.push(' ');
+                    self.last_space = true;
+                }
+            } else {
+                self.flush();
+                self.output.push(c);
+                self.last_space = false;
+            }
+        }
+    }
+
+    /// 原样写入文本，保留空白和换行，用于代码块
+    pub fn raw(&mut self, text: &str) {
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.wrap(1);
+            }
+
+            self.flush();
+            self.output.push_str(line.trim_end_matches('\r'));
+        }
+
+        self.last_space = false;
+    }
+
+    /// 写入不参与空白合并的标记，例如强调符号和链接
+    pub fn marker(&mut self, marker: &str) {
+        self.flush();
+        self.output.push_str(marker);
+        self.last_space = marker.ends_with(' ');
+    }
+
+    /// 写入列表符号，之后的行缩进到符号之后，直到对应的 `pop_prefix`
+    pub fn item(&mut self, marker: &str) {
+        self.marker(marker);
+        self.push_prefix(" ".repeat(marker.chars().count()));
+        self.item_open = true;
+    }
+
+    /// 换行，连续调用时产生空行
+    pub fn newline(&mut self) {
+        if self.started {
+            let lines = self.pending_newlines + 1;
+            self.wrap(lines);
+        }
+    }
+
+    /// 开始新的块，`lines` 为 1 时另起一行，为 2 时和前面的内容之间空一行
+    pub fn block(&mut self, lines: usize) {
+        if self.started && !self.item_open {
+            let lines = self.pending_newlines.max(lines);
+            self.wrap(lines);
+        }
+    }
+
+    /// 之后的每一行都加上前缀，直到对应的 `pop_prefix`
+    pub fn push_prefix(&mut self, prefix: impl Into<String>) {
+        self.prefixes.push(prefix.into());
+    }
+
+    pub fn pop_prefix(&mut self) {
+        self.prefixes.pop();
+        self.blank_depth = self.blank_depth.min(self.prefixes.len());
+        self.item_open = false;
+    }
+
+    /// 去掉末尾空白后的结果
+    pub fn finish(self) -> String {
+        self.output.trim_end().to_string()
+    }
+
+    fn wrap(&mut self, lines: usize) {
+        if self.pending_newlines == 0 {
+            self.blank_depth = self.prefixes.len();
+        }
+
+        self.pending_newlines = lines;
+        self.blank_depth = self.blank_depth.min(self.prefixes.len());
+    }
+
+    // 写入等待中的换行和新一行的前缀
+    fn flush(&mut self) {
+        let prefix = self.prefixes.concat();
+
+        if !self.started {
+            self.output.push_str(&prefix);
+            self.started = true;
+        } else if self.pending_newlines > 0 {
+            let len = self.output.trim_end_matches([' ', '\t']).len();
+            self.output.truncate(len);
+
+            let blank_prefix = self.prefixes[..self.blank_depth].concat();
+
+            for _ in 1..self.pending_newlines {
+                self.output.push('\n');
+                self.output.push_str(blank_prefix.trim_end());
+            }
+
+            self.output.push('\n');
+            self.output.push_str(&prefix);
+        }
+
+        self.pending_newlines = 0;
+        self.item_open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_whitespace() {
+        let mut writer = TextWriter::new();
+        writer.text("  a \n\t b  ");
+        writer.marker("**c**");
+        writer.text(" d ");
+
+        assert_eq!(writer.finish(), "a b **c** d");
+    }
+
+    #[test]
+    fn separates_blocks() {
+        let mut writer = TextWriter::new();
+        writer.block(2);
+        writer.text("a");
+        writer.block(2);
+        writer.block(1);
+        writer.text("b");
+        writer.block(1);
+        writer.text("c");
+        writer.newline();
+        writer.newline();
+        writer.text("d");
+
+        assert_eq!(writer.finish(), "a\n\nb\nc\n\nd");
+    }
+
+    #[test]
+    fn keeps_raw_text() {
+        let mut writer = TextWriter::new();
+        writer.push_prefix("> ");
+        writer.raw("a  b\r\n\n  c");
+
+        assert_eq!(writer.finish(), "> a  b\n>\n>   c");
+    }
+
+    #[test]
+    fn indents_nested_items() {
+        let mut writer = TextWriter::new();
+        writer.item("- ");
+        writer.block(1);
+        writer.text("a");
+        writer.block(1);
+        writer.item("10. ");
+        writer.text("b");
+        writer.block(2);
+        writer.text("c");
+        writer.pop_prefix();
+        writer.pop_prefix();
+        writer.block(1);
+        writer.text("d");
+
+        assert_eq!(writer.finish(), "- a\n  10. b\n\n      c\nd");
+    }
+
+    #[test]
+    fn prefixes_blank_lines_inside_quotes() {
+        let mut writer = TextWriter::new();
+        writer.text("before");
+        writer.block(2);
+        writer.push_prefix("> ");
+        writer.text("a");
+        writer.block(2);
+        writer.text("b");
+        writer.pop_prefix();
+        writer.block(2);
+        writer.text("after");
+
+        // 进入引用之前的空行不带引用符号
+        assert_eq!(writer.finish(), "before\n\n> a\n>\n> b\n\nafter");
+    }
+
+    #[test]
+    fn handles_unbalanced_calls() {
+        let mut writer = TextWriter::new();
+        writer.pop_prefix();
+        writer.newline();
+        writer.block(3);
+        writer.pop_prefix();
+        writer.text("a");
+        writer.pop_prefix();
+        writer.block(2);
+
+        assert_eq!(writer.finish(), "a");
+        assert_eq!(TextWriter::new().finish(), "");
+    }
+}
@@ -0,0 +1,108 @@
+//! 代码高亮
+//!
+//! 使用 syntect 生成只带 class 的 HTML，颜色由 `style` 返回的 CSS 决定。
+//! 这样保存在记录中的 HTML 和主题无关，切换亮色、暗色时不需要重新生成。
+
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+// 亮色和暗色主题
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+
+// 暗色主题的选择器前缀，和 App 中切换主题时添加的 class 一致
+const DARK_SCOPE: &str = "html.dark";
+
+// 超过这个长度的代码不高亮，列表中只显示前几行
+const MAX_LEN: usize = 64 * 1024;
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+// 识别出的语言对应的语法，syntect 没有内置的语言返回 None
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let extension = match language {
+        "rust" => "rs",
+        "python" => "py",
+        // TypeScript 没有内置语法，用 JavaScript 的语法高亮效果也足够
+        "javascript" | "typescript" => "js",
+        "java" => "java",
+        "c" => "c",
+        "cpp" => "cpp",
+        "csharp" => "cs",
+        "go" => "go",
+        "php" => "php",
+        "ruby" => "rb",
+        "sql" => "sql",
+        "html" => "html",
+        "xml" => "xml",
+        "css" => "css",
+        "shell" => "sh",
+        "yaml" => "yaml",
+        "json" => "json",
+        _ => return None,
+    };
+
+    syntax_set().find_syntax_by_extension(extension)
+}
+
+/// 把代码转为高亮后的 HTML 片段，不支持的语言或者过长的代码返回 None
+pub fn highlight(code: &str, language: &str) -> Option<String> {
+    if code.len() > MAX_LEN {
+        return None;
+    }
+
+    let syntax = find_syntax(language)?;
+
+    let mut generator =
+        ClassedHTMLGenerator::new_with_class_style(syntax, syntax_set(), CLASS_STYLE);
+
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+
+    Some(generator.finalize())
+}
+
+/// 高亮使用的 CSS，包含亮色主题和 `html.dark` 下的暗色主题
+pub fn style() -> Result<String, syntect::Error> {
+    let themes = ThemeSet::load_defaults();
+
+    let light = css_for_theme_with_class_style(&themes.themes[LIGHT_THEME], CLASS_STYLE)?;
+    let dark = css_for_theme_with_class_style(&themes.themes[DARK_THEME], CLASS_STYLE)?;
+
+    Ok(format!(
+        "{}\n{}",
+        scope_css(&light, None),
+        scope_css(&dark, Some(DARK_SCOPE))
+    ))
+}
+
+// 给每个选择器加上前缀，并去掉背景色，让代码使用列表项本身的背景
+fn scope_css(css: &str, scope: Option<&str>) -> String {
+    css.lines()
+        .filter(|line| !line.trim_start().starts_with("background-color"))
+        .map(|line| match (scope, line.strip_suffix('{')) {
+            (Some(scope), Some(selectors)) => {
+                let selectors: Vec<String> = selectors
+                    .split(',')
+                    .map(|selector| format!("{scope} {}", selector.trim()))
+                    .collect();
+
+                format!("{} {{", selectors.join(", "))
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,28 @@
+use tauri::{
+    generate_handler,
+    plugin::{Builder, TauriPlugin},
+    Runtime,
+};
+
+mod classify;
+mod commands;
+mod convert;
+mod highlight;
+mod secret;
+
+pub use classify::{classify, classify_all, Classification, Subtype};
+pub use commands::*;
+pub use convert::{convert, ContentFormat, OutputFormat};
+pub use highlight::highlight;
+pub use secret::detect_secret;
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("eco-clipboard")
+        .invoke_handler(generate_handler![
+            commands::convert_content,
+            commands::sanitize_html,
+            commands::classify_texts,
+            commands::highlight_style,
+        ])
+        .build()
+}
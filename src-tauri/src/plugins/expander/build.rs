@@ -0,0 +1,5 @@
+const COMMANDS: &[&str] = &["start_expander", "stop_expander", "set_expander_config"];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,38 @@
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+use crate::detector::ExpanderConfig;
+use crate::listener::SharedDetector;
+use crate::monitor::KeyboardMonitor;
+
+const UNSUPPORTED_MESSAGE: &str = "Abbreviation expansion is only supported on Windows and X11";
+
+/// 启动键盘监控，开始展开缩写
+#[command]
+pub async fn start_expander<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    match app.try_state::<KeyboardMonitor>() {
+        Some(monitor) => monitor.start(),
+        None => Err(UNSUPPORTED_MESSAGE.to_string()),
+    }
+}
+
+/// 停止键盘监控，等待监控线程退出
+#[command]
+pub async fn stop_expander<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    match app.try_state::<KeyboardMonitor>() {
+        Some(monitor) => monitor.stop(),
+        None => Ok(()),
+    }
+}
+
+/// 更新需要展开的缩写和排除的应用
+#[command]
+pub async fn set_expander_config(
+    detector: State<'_, SharedDetector>,
+    config: ExpanderConfig,
+) -> Result<(), String> {
+    let mut detector = detector.lock().map_err(|e| e.to_string())?;
+
+    detector.set_config(config);
+
+    Ok(())
+}
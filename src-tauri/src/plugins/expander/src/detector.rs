@@ -0,0 +1,397 @@
+//! 与平台无关的缩写识别状态机
+//!
+//! 输入为各平台 [`MonitorInput`](crate::MonitorInput) 产生的按键事件，缓存最近输入的字符，
+//! 末尾和某个缩写相同时输出需要展开的缩写。状态机不依赖系统钩子和全局变量，
+//! 可以直接回放按键序列来验证输出。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::monitor::KeyEvent;
+
+/// 展开缩写的参数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExpanderConfig {
+    /// 需要展开的缩写，区分大小写
+    pub abbreviations: Vec<String>,
+    /// 不展开缩写的应用，Windows 上为可执行文件名，Linux 上为窗口的 WM_CLASS，不区分大小写
+    pub excluded_apps: Vec<String>,
+}
+
+impl ExpanderConfig {
+    /// 应用是否被排除
+    pub fn is_excluded(&self, app: &str) -> bool {
+        let app = app.trim();
+
+        !app.is_empty()
+            && self
+                .excluded_apps
+                .iter()
+                .any(|excluded| excluded.trim().eq_ignore_ascii_case(app))
+    }
+}
+
+/// 识别出的缩写
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expansion {
+    pub abbreviation: String,
+    /// 需要删除的字符数，即缩写的长度
+    pub erase: usize,
+}
+
+/// 缩写识别状态机
+#[derive(Debug, Clone, Default)]
+pub struct ExpansionDetector {
+    config: ExpanderConfig,
+    // 最近输入的字符，比最长的缩写多保留一个，用于判断缩写前面是否是单词的边界
+    buffer: VecDeque<char>,
+    capacity: usize,
+}
+
+impl ExpansionDetector {
+    pub fn new(config: ExpanderConfig) -> Self {
+        let mut detector = Self::default();
+
+        detector.set_config(config);
+
+        detector
+    }
+
+    pub fn config(&self) -> &ExpanderConfig {
+        &self.config
+    }
+
+    /// 更新参数，忽略空白的缩写并清空已经输入的字符
+    pub fn set_config(&mut self, mut config: ExpanderConfig) {
+        config
+            .abbreviations
+            .retain(|abbreviation| !abbreviation.trim().is_empty());
+
+        self.capacity = config
+            .abbreviations
+            .iter()
+            .map(|abbreviation| abbreviation.chars().count() + 1)
+            .max()
+            .unwrap_or_default();
+
+        self.config = config;
+        self.buffer.clear();
+    }
+
+    /// 处理一个按键事件，末尾输入了缩写时返回需要展开的缩写
+    pub fn handle(&mut self, event: KeyEvent) -> Option<Expansion> {
+        match event {
+            KeyEvent::Char(char) => {
+                if self.capacity == 0 {
+                    return None;
+                }
+
+                if self.buffer.len() == self.capacity {
+                    self.buffer.pop_front();
+                }
+
+                self.buffer.push_back(char);
+
+                let expansion = self.matched()?;
+
+                // 展开后重新开始，删除缩写时发送的退格键不会影响之前的字符
+                self.buffer.clear();
+
+                Some(expansion)
+            }
+            KeyEvent::Backspace => {
+                self.buffer.pop_back();
+
+                None
+            }
+            KeyEvent::Interrupt => {
+                self.buffer.clear();
+
+                None
+            }
+        }
+    }
+
+    /// 已经输入的字符
+    pub fn pending(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    // 末尾相同的缩写中最长的一个，以字母或数字开头的缩写前面不能紧接着字母或数字
+    fn matched(&self) -> Option<Expansion> {
+        let typed: Vec<char> = self.buffer.iter().copied().collect();
+
+        self.config
+            .abbreviations
+            .iter()
+            .filter_map(|abbreviation| {
+                let chars: Vec<char> = abbreviation.chars().collect();
+
+                if !typed.ends_with(&chars) {
+                    return None;
+                }
+
+                let start = typed.len() - chars.len();
+
+                let bounded = !chars[0].is_alphanumeric()
+                    || start == 0
+                    || !typed[start - 1].is_alphanumeric();
+
+                bounded.then(|| Expansion {
+                    abbreviation: abbreviation.clone(),
+                    erase: chars.len(),
+                })
+            })
+            .max_by_key(|expansion| expansion.erase)
+    }
+}
+
+/// 依次处理按键序列，返回识别出的缩写
+pub fn replay(
+    config: ExpanderConfig,
+    events: impl IntoIterator<Item = KeyEvent>,
+) -> Vec<Expansion> {
+    let mut detector = ExpansionDetector::new(config);
+
+    events
+        .into_iter()
+        .filter_map(|event| detector.handle(event))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use KeyEvent::{Backspace, Char, Interrupt};
+
+    fn config_with(abbreviations: &[&str]) -> ExpanderConfig {
+        ExpanderConfig {
+            abbreviations: abbreviations
+                .iter()
+                .map(|abbreviation| abbreviation.to_string())
+                .collect(),
+            excluded_apps: Vec::new(),
+        }
+    }
+
+    fn typed(text: &str) -> impl Iterator<Item = KeyEvent> + '_ {
+        text.chars().map(Char)
+    }
+
+    fn expansion(abbreviation: &str) -> Expansion {
+        Expansion {
+            abbreviation: abbreviation.to_string(),
+            erase: abbreviation.chars().count(),
+        }
+    }
+
+    #[test]
+    fn expands_at_start_and_after_space() {
+        let config = config_with(&[";sig"]);
+
+        assert_eq!(replay(config.clone(), typed(";sig")), [expansion(";sig")]);
+        assert_eq!(
+            replay(config.clone(), typed("hello ;sig")),
+            [expansion(";sig")]
+        );
+
+        // 以符号开头的缩写不要求前面是单词的边界
+        assert_eq!(
+            replay(config.clone(), typed("hello;sig")),
+            [expansion(";sig")]
+        );
+
+        assert!(replay(config, typed(";si g")).is_empty());
+    }
+
+    #[test]
+    fn requires_word_boundary() {
+        let config = config_with(&["sig"]);
+
+        assert!(replay(config.clone(), typed("xsig")).is_empty());
+        assert!(replay(config.clone(), typed("1sig")).is_empty());
+        assert!(replay(config.clone(), typed("résig")).is_empty());
+
+        assert_eq!(replay(config.clone(), typed("sig")), [expansion("sig")]);
+        assert_eq!(replay(config.clone(), typed("x sig")), [expansion("sig")]);
+        assert_eq!(replay(config.clone(), typed("x.sig")), [expansion("sig")]);
+        assert_eq!(replay(config, typed("x\tsig")), [expansion("sig")]);
+    }
+
+    #[test]
+    fn prefers_longest_match() {
+        let config = config_with(&["ig", "sig", ";sig"]);
+
+        assert_eq!(replay(config.clone(), typed(";sig")), [expansion(";sig")]);
+        assert_eq!(replay(config.clone(), typed(" sig")), [expansion("sig")]);
+
+        // 较长的缩写不在单词的边界上时使用较短的，较短的也不在边界上时不展开
+        assert!(replay(config.clone(), typed("xsig")).is_empty());
+        assert_eq!(
+            replay(config_with(&["ig", ";;ig"]), typed(";ig")),
+            [expansion("ig")]
+        );
+    }
+
+    #[test]
+    fn edits_buffer_with_backspace() {
+        let config = config_with(&["sig"]);
+        let mut detector = ExpansionDetector::new(config.clone());
+
+        for event in typed("six") {
+            detector.handle(event);
+        }
+        assert_eq!(detector.pending(), "six");
+
+        assert_eq!(detector.handle(Backspace), None);
+        assert_eq!(detector.pending(), "si");
+        assert_eq!(detector.handle(Char('g')), Some(expansion("sig")));
+
+        // 删除前面的字母后缩写位于边界上
+        let events = typed("xs")
+            .chain([Backspace, Backspace])
+            .chain(typed("sig"));
+        assert_eq!(replay(config.clone(), events), [expansion("sig")]);
+
+        // 缓存为空时的退格键被忽略
+        let events = [Backspace, Backspace].into_iter().chain(typed("sig"));
+        assert_eq!(replay(config, events), [expansion("sig")]);
+    }
+
+    #[test]
+    fn clears_buffer_on_interrupt() {
+        let config = config_with(&["sig"]);
+        let mut detector = ExpansionDetector::new(config.clone());
+
+        for event in typed("si") {
+            detector.handle(event);
+        }
+
+        assert_eq!(detector.handle(Interrupt), None);
+        assert_eq!(detector.pending(), "");
+        assert_eq!(detector.handle(Char('g')), None);
+
+        // 中断后前面的字符不再影响单词的边界，例如移动光标后输入
+        let events = typed("x").chain([Interrupt]).chain(typed("sig"));
+        assert_eq!(replay(config, events), [expansion("sig")]);
+    }
+
+    #[test]
+    fn evicts_oldest_char_at_capacity() {
+        let mut detector = ExpansionDetector::new(config_with(&["ab", "sig"]));
+
+        for event in typed("hello world") {
+            detector.handle(event);
+        }
+
+        // 最长的缩写加上前面的一个字符
+        assert_eq!(detector.pending(), "orld");
+
+        // 缓存已满时仍然保留缩写前面的字符，用来判断单词的边界
+        assert_eq!(detector.handle(Char(' ')), None);
+        assert_eq!(detector.pending(), "rld ");
+        assert!(replay(config_with(&["sig"]), typed("xxxxsig")).is_empty());
+        assert_eq!(
+            replay(config_with(&["sig"]), typed("xxx sig")),
+            [expansion("sig")]
+        );
+    }
+
+    #[test]
+    fn clears_buffer_after_expansion() {
+        let config = config_with(&["sig", "gg"]);
+        let mut detector = ExpansionDetector::new(config.clone());
+
+        for event in typed(" si") {
+            detector.handle(event);
+        }
+
+        assert_eq!(detector.handle(Char('g')), Some(expansion("sig")));
+        assert_eq!(detector.pending(), "");
+
+        // 展开后的字符不会和之后的输入组成新的缩写
+        assert_eq!(detector.handle(Char('g')), None);
+        assert_eq!(detector.pending(), "g");
+
+        // 删除缩写的退格键不会影响之后的输入
+        assert_eq!(detector.handle(Backspace), None);
+        assert_eq!(detector.handle(Backspace), None);
+
+        assert_eq!(
+            replay(config, typed("sigsig sig")),
+            [expansion("sig"), expansion("sig"), expansion("sig")]
+        );
+    }
+
+    #[test]
+    fn ignores_blank_abbreviations() {
+        let mut detector = ExpansionDetector::new(config_with(&["", " ", "\t\n", "sig"]));
+
+        assert_eq!(detector.config().abbreviations, ["sig"]);
+
+        for event in typed(" \t\n ") {
+            assert_eq!(detector.handle(event), None);
+        }
+
+        assert_eq!(detector.handle(Interrupt), None);
+        assert_eq!(detector.handle(Char('s')), None);
+        assert_eq!(detector.handle(Char('i')), None);
+        assert_eq!(detector.handle(Char('g')), Some(expansion("sig")));
+
+        // 只有空白的缩写时不缓存任何字符
+        let mut detector = ExpansionDetector::new(config_with(&[" ", ""]));
+
+        assert!(detector.config().abbreviations.is_empty());
+        assert_eq!(detector.handle(Char(' ')), None);
+        assert_eq!(detector.pending(), "");
+    }
+
+    #[test]
+    fn resets_buffer_on_new_config() {
+        let mut detector = ExpansionDetector::new(config_with(&["sig"]));
+
+        for event in typed("si") {
+            detector.handle(event);
+        }
+
+        detector.set_config(config_with(&["sig", "signature"]));
+
+        assert_eq!(detector.pending(), "");
+        assert_eq!(detector.handle(Char('g')), None);
+    }
+
+    #[test]
+    fn counts_chars_not_bytes() {
+        let config = config_with(&["→签名"]);
+
+        assert_eq!(
+            replay(config, typed("好的→签名")),
+            [Expansion {
+                abbreviation: "→签名".into(),
+                erase: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn matches_excluded_apps_case_insensitively() {
+        let config = ExpanderConfig {
+            abbreviations: Vec::new(),
+            excluded_apps: vec!["KeePassXC.exe".into(), " code ".into(), "".into()],
+        };
+
+        assert!(config.is_excluded("keepassxc.exe"));
+        assert!(config.is_excluded("KEEPASSXC.EXE"));
+        assert!(config.is_excluded("Code"));
+        assert!(config.is_excluded(" code\n"));
+
+        assert!(!config.is_excluded("keepassxc"));
+        assert!(!config.is_excluded("vscode"));
+
+        // 读取不到前台应用时不排除
+        assert!(!config.is_excluded(""));
+        assert!(!config.is_excluded("  "));
+    }
+}
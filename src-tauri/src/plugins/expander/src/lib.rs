@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+use tauri::{
+    generate_handler,
+    plugin::{Builder, TauriPlugin},
+    Manager, RunEvent, Runtime,
+};
+
+mod commands;
+mod detector;
+mod listener;
+mod monitor;
+
+pub use commands::*;
+pub use detector::{replay, ExpanderConfig, Expansion, ExpansionDetector};
+pub use listener::{SharedDetector, EXPAND_EVENT};
+pub use monitor::{InputFactory, KeyEvent, KeySink, KeyboardMonitor, MonitorInput, StopHandle};
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("eco-expander")
+        .invoke_handler(generate_handler![
+            commands::start_expander,
+            commands::stop_expander,
+            commands::set_expander_config,
+        ])
+        .setup(|app, _api| {
+            let detector: SharedDetector = Arc::new(Mutex::new(ExpansionDetector::default()));
+
+            // 只在支持系统钩子的平台上创建监控，默认关闭，由前端在用户开启后启动
+            if let Some(factory) = monitor::default_input_factory() {
+                let sink = listener::create_sink(app.clone(), detector.clone());
+
+                app.manage(KeyboardMonitor::new("keyboard", factory, sink));
+            }
+
+            app.manage(detector);
+
+            Ok(())
+        })
+        .on_event(|app, event| {
+            // 退出时卸载钩子并结束监控线程
+            if let RunEvent::Exit = event {
+                if let Some(monitor) = app.try_state::<KeyboardMonitor>() {
+                    let _ = monitor.stop();
+                }
+            }
+        })
+        .build()
+}
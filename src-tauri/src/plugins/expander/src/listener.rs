@@ -0,0 +1,70 @@
+//! 处理监控产生的按键事件
+//!
+//! 按键交给 [`ExpansionDetector`] 识别，识别出缩写后删除缩写，再通知前端粘贴对应的片段。
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_eco_selection::foreground_app;
+
+use crate::detector::{Expansion, ExpansionDetector};
+use crate::monitor::{self, KeySink};
+
+/// 展开缩写的事件名，前端收到后粘贴对应的片段
+pub const EXPAND_EVENT: &str = "expander:expand";
+
+/// 修改参数的命令和监控线程共用的状态机
+pub type SharedDetector = Arc<Mutex<ExpansionDetector>>;
+
+/// 创建处理按键事件的回调
+pub fn create_sink<R: Runtime>(app: AppHandle<R>, detector: SharedDetector) -> KeySink {
+    Arc::new(move |event| {
+        let expansion = match detector.lock() {
+            Ok(mut detector) => detector.handle(event),
+            Err(_) => return,
+        };
+
+        if let Some(expansion) = expansion {
+            // 钩子回调需要尽快返回，模拟按键也不能在回调中进行
+            let app = app.clone();
+            let detector = detector.clone();
+
+            thread::spawn(move || expand(&app, &detector, expansion));
+        }
+    })
+}
+
+// 删除输入的缩写后通知前端，在排除的应用和本应用的窗口中不展开
+fn expand<R: Runtime>(app: &AppHandle<R>, detector: &SharedDetector, expansion: Expansion) {
+    let focused = app
+        .webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or_default());
+
+    if focused {
+        return;
+    }
+
+    let foreground = foreground_app().unwrap_or_default();
+
+    let excluded = detector
+        .lock()
+        .map(|detector| detector.config().is_excluded(&foreground))
+        .unwrap_or(true);
+
+    if excluded {
+        log::info!("Skip expanding in excluded app {}", foreground);
+
+        return;
+    }
+
+    // 等待前台应用处理完缩写的最后一个字符
+    thread::sleep(Duration::from_millis(20));
+
+    monitor::erase(expansion.erase);
+
+    if let Err(e) = app.emit(EXPAND_EVENT, &expansion) {
+        log::error!("Failed to emit expand event: {}", e);
+    }
+}
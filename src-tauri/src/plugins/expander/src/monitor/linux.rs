@@ -0,0 +1,341 @@
+//! X11 系统级输入源
+//!
+//! 使用 XRecord 扩展录制所有客户端收到的按键和鼠标点击，按当前的键盘布局把按键转换为字符。
+//! 录制在数据连接上阻塞运行，停止时在控制连接上调用 `XRecordDisableContext` 结束录制。
+//! Wayland 下只能录制到通过 XWayland 运行的应用。
+
+use super::{KeyEvent, KeySink, MonitorInput, StopHandle};
+use rdev::{simulate, EventType, Key};
+use std::os::raw::{c_char, c_int, c_uint, c_ulong};
+use std::{ptr, thread, time::Duration};
+use x11::keysym;
+use x11::xlib::{
+    self, Display, LockMask, Mod5Mask, ShiftMask, XCloseDisplay, XFlush, XOpenDisplay, XSync,
+    XkbGetState, XkbKeycodeToKeysym, XkbLookupKeySym, XkbStateRec,
+};
+use x11::xrecord::{
+    XRecordAllClients, XRecordAllocRange, XRecordClientSpec, XRecordContext, XRecordCreateContext,
+    XRecordDisableContext, XRecordEnableContext, XRecordFreeContext, XRecordFreeData,
+    XRecordFromServer, XRecordInterceptData, XRecordQueryVersion,
+};
+
+// 使用核心键盘设备
+const XKB_USE_CORE_KBD: c_uint = 0x0100;
+
+/// X11 XRecord 输入源
+pub struct XRecordInput {
+    // 创建和结束录制的连接
+    control: *mut Display,
+    // 阻塞录制的连接，同一个连接不能同时用于其它请求
+    data: *mut Display,
+    context: XRecordContext,
+    state: Option<Box<RecordState>>,
+}
+
+// 连接只在监控线程中使用，控制连接在停止时才交给其它线程
+unsafe impl Send for XRecordInput {}
+
+// 录制回调中使用的状态
+struct RecordState {
+    // 查询键盘布局的连接，只在录制回调中使用
+    lookup: *mut Display,
+    sink: KeySink,
+    modifiers: Modifiers,
+}
+
+#[derive(Default)]
+struct Modifiers {
+    shift: bool,
+    control: bool,
+    alt: bool,
+    super_key: bool,
+    level3: bool,
+}
+
+struct SendDisplay(*mut Display);
+
+unsafe impl Send for SendDisplay {}
+
+impl XRecordInput {
+    pub fn new() -> Self {
+        Self {
+            control: ptr::null_mut(),
+            data: ptr::null_mut(),
+            context: 0,
+            state: None,
+        }
+    }
+}
+
+impl Default for XRecordInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonitorInput<KeyEvent> for XRecordInput {
+    fn name(&self) -> &'static str {
+        "x11-record"
+    }
+
+    fn prepare(&mut self, sink: KeySink) -> Result<StopHandle, String> {
+        unsafe {
+            self.control = XOpenDisplay(ptr::null());
+            self.data = XOpenDisplay(ptr::null());
+
+            let lookup = XOpenDisplay(ptr::null());
+
+            self.state = Some(Box::new(RecordState {
+                lookup,
+                sink,
+                modifiers: Modifiers::default(),
+            }));
+
+            if self.control.is_null() || self.data.is_null() || lookup.is_null() {
+                return Err("Could not open display".to_string());
+            }
+
+            let (mut major, mut minor) = (0, 0);
+
+            if XRecordQueryVersion(self.control, &mut major, &mut minor) == 0 {
+                return Err("XRecord extension is not available".to_string());
+            }
+
+            let range = XRecordAllocRange();
+
+            if range.is_null() {
+                return Err("Failed to allocate XRecord range".to_string());
+            }
+
+            (*range).device_events.first = xlib::KeyPress as u8;
+            (*range).device_events.last = xlib::ButtonPress as u8;
+
+            let mut clients: XRecordClientSpec = XRecordAllClients;
+            let mut ranges = range;
+
+            self.context = XRecordCreateContext(self.control, 0, &mut clients, 1, &mut ranges, 1);
+
+            xlib::XFree(range as _);
+
+            if self.context == 0 {
+                return Err("Failed to create XRecord context".to_string());
+            }
+
+            // 录制之前确保控制连接上的请求已经发送
+            XSync(self.control, xlib::False);
+
+            log::info!("XRecord context created with version {}.{}", major, minor);
+
+            let control = SendDisplay(self.control);
+            let context = self.context;
+
+            Ok(Box::new(move || {
+                let control = control;
+
+                XRecordDisableContext(control.0, context);
+                XFlush(control.0);
+            }))
+        }
+    }
+
+    fn run(&mut self) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        unsafe {
+            // 阻塞直到录制被停止
+            let closure = state.as_mut() as *mut RecordState as *mut c_char;
+
+            if XRecordEnableContext(self.data, self.context, Some(record_callback), closure) == 0 {
+                log::error!("Failed to enable XRecord context");
+            }
+        }
+    }
+
+    fn cleanup(&mut self) {
+        unsafe {
+            if self.context != 0 && !self.control.is_null() {
+                XRecordFreeContext(self.control, self.context);
+
+                self.context = 0;
+            }
+
+            if let Some(state) = self.state.take() {
+                if !state.lookup.is_null() {
+                    XCloseDisplay(state.lookup);
+                }
+            }
+
+            for display in [&mut self.data, &mut self.control] {
+                if !display.is_null() {
+                    XCloseDisplay(*display);
+
+                    *display = ptr::null_mut();
+                }
+            }
+        }
+    }
+}
+
+/// 模拟按下退格键，删除已经输入的缩写
+pub fn erase(count: usize) {
+    for _ in 0..count {
+        for event_type in [
+            EventType::KeyPress(Key::Backspace),
+            EventType::KeyRelease(Key::Backspace),
+        ] {
+            if let Err(error) = simulate(&event_type) {
+                log::error!("Failed to simulate backspace: {:?}", error);
+
+                return;
+            }
+
+            // 连续发送时部分应用会丢失按键
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+/// 把 keysym 转换为字符，不输入字符的 keysym 返回 None
+///
+/// 只转换 Latin-1、Unicode 和小键盘的 keysym，其它旧的 keysym 按不输入字符处理。
+pub fn keysym_to_char(keysym: c_uint) -> Option<char> {
+    let char = match keysym {
+        0x20..=0x7e | 0xa0..=0xff => char::from_u32(keysym),
+        0x0100_00a0..=0x0110_ffff => char::from_u32(keysym - 0x0100_0000),
+        keysym::XK_KP_Space => Some(' '),
+        keysym::XK_KP_0..=keysym::XK_KP_9 => char::from_u32(keysym - keysym::XK_KP_0 + '0' as u32),
+        keysym::XK_KP_Multiply => Some('*'),
+        keysym::XK_KP_Add => Some('+'),
+        keysym::XK_KP_Separator => Some(','),
+        keysym::XK_KP_Subtract => Some('-'),
+        keysym::XK_KP_Decimal => Some('.'),
+        keysym::XK_KP_Divide => Some('/'),
+        keysym::XK_KP_Equal => Some('='),
+        _ => None,
+    };
+
+    char.filter(|char| !char.is_control())
+}
+
+// 死键和下一个按键组合成一个字符，不影响已经输入的字符
+fn is_dead_key(keysym: c_uint) -> bool {
+    (keysym::XK_dead_grave..=keysym::XK_dead_greek).contains(&keysym)
+}
+
+impl RecordState {
+    // 记录修饰键的状态，返回是否是修饰键
+    fn update_modifiers(&mut self, keysym: c_uint, pressed: bool) -> bool {
+        let modifiers = &mut self.modifiers;
+
+        let modifier = match keysym {
+            keysym::XK_Shift_L | keysym::XK_Shift_R => &mut modifiers.shift,
+            keysym::XK_Control_L | keysym::XK_Control_R => &mut modifiers.control,
+            keysym::XK_Alt_L | keysym::XK_Alt_R | keysym::XK_Meta_L | keysym::XK_Meta_R => {
+                &mut modifiers.alt
+            }
+            keysym::XK_Super_L | keysym::XK_Super_R | keysym::XK_Hyper_L | keysym::XK_Hyper_R => {
+                &mut modifiers.super_key
+            }
+            keysym::XK_ISO_Level3_Shift | keysym::XK_Mode_switch => &mut modifiers.level3,
+            keysym::XK_Caps_Lock | keysym::XK_Shift_Lock | keysym::XK_Num_Lock => return true,
+            _ => return false,
+        };
+
+        *modifier = pressed;
+
+        true
+    }
+
+    // 按当前的键盘布局和修饰键把按键转换为事件
+    unsafe fn translate(&mut self, keycode: u8) -> Option<KeyEvent> {
+        let modifiers = &self.modifiers;
+
+        // 组合键通常是快捷键，可能移动光标或者修改内容
+        if modifiers.control || modifiers.alt || modifiers.super_key {
+            return Some(KeyEvent::Interrupt);
+        }
+
+        // 大写锁定和键盘布局的分组由服务器记录
+        let mut xkb_state: XkbStateRec = std::mem::zeroed();
+
+        XkbGetState(self.lookup, XKB_USE_CORE_KBD, &mut xkb_state);
+
+        let mut mask =
+            (xkb_state.locked_mods as c_uint & LockMask) | ((xkb_state.group as c_uint) << 13);
+
+        if modifiers.shift {
+            mask |= ShiftMask;
+        }
+
+        if modifiers.level3 {
+            mask |= Mod5Mask;
+        }
+
+        let mut consumed = 0;
+        let mut keysym: c_ulong = 0;
+
+        if XkbLookupKeySym(self.lookup, keycode, mask, &mut consumed, &mut keysym) == 0 {
+            return Some(KeyEvent::Interrupt);
+        }
+
+        let keysym = keysym as c_uint;
+
+        if keysym == keysym::XK_BackSpace {
+            return Some(KeyEvent::Backspace);
+        }
+
+        if is_dead_key(keysym) {
+            return None;
+        }
+
+        // 方向键、回车、Tab、Esc 等不输入字符的按键
+        Some(keysym_to_char(keysym).map_or(KeyEvent::Interrupt, KeyEvent::Char))
+    }
+
+    unsafe fn handle(&mut self, kind: c_int, detail: u8) -> Option<KeyEvent> {
+        match kind {
+            xlib::KeyPress | xlib::KeyRelease => {
+                // 按没有修饰键时的 keysym 判断是否是修饰键
+                let keysym = XkbKeycodeToKeysym(self.lookup, detail, 0, 0) as c_uint;
+
+                if self.update_modifiers(keysym, kind == xlib::KeyPress) || kind == xlib::KeyRelease
+                {
+                    return None;
+                }
+
+                self.translate(detail)
+            }
+            // 点击可能移动光标
+            xlib::ButtonPress => Some(KeyEvent::Interrupt),
+            _ => None,
+        }
+    }
+}
+
+/// 录制回调，数据的前两个字节是事件的类型和按键
+unsafe extern "C" fn record_callback(closure: *mut c_char, data: *mut XRecordInterceptData) {
+    if data.is_null() {
+        return;
+    }
+
+    let intercepted = &*data;
+
+    if intercepted.category == XRecordFromServer
+        && !closure.is_null()
+        && !intercepted.data.is_null()
+        && intercepted.data_len > 0
+    {
+        let state = &mut *(closure as *mut RecordState);
+
+        let kind = *intercepted.data as c_int & 0x7f;
+        let detail = *intercepted.data.add(1);
+
+        if let Some(event) = state.handle(kind, detail) {
+            (state.sink)(event);
+        }
+    }
+
+    XRecordFreeData(data);
+}
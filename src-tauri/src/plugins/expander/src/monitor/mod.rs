@@ -0,0 +1,317 @@
+//! 键盘监控
+//!
+//! 监控运行在独立的线程中，由具体平台的 [`MonitorInput`] 产生按键事件。
+//! 启动、停止和停止超时后的处理与划词监控共用 [`Monitor`]，旧的线程没有退出前不会重复安装钩子。
+
+use serde::{Deserialize, Serialize};
+use tauri_plugin_eco_selection::{EventSink, Monitor};
+
+pub use tauri_plugin_eco_selection::{MonitorInput, StopHandle};
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "windows")]
+pub use windows::{erase, WindowsHookInput};
+
+#[cfg(target_os = "linux")]
+pub use linux::{erase, keysym_to_char, XRecordInput};
+
+/// 输入源产生的按键事件，模拟的按键不会产生事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum KeyEvent {
+    /// 输入了一个字符
+    Char(char),
+    /// 按下退格键
+    Backspace,
+    /// 光标可能已经移动，例如方向键、回车、组合键、鼠标点击和切换窗口
+    Interrupt,
+}
+
+/// 接收按键事件的回调，会在监控线程中被调用
+pub type KeySink = EventSink<KeyEvent>;
+
+/// 创建输入源的函数，每次启动监控都会创建新的输入源
+pub type InputFactory = tauri_plugin_eco_selection::InputFactory<KeyEvent>;
+
+/// 键盘监控的句柄
+pub type KeyboardMonitor = Monitor<KeyEvent>;
+
+/// 当前平台默认的输入源，不支持的平台返回 None
+pub fn default_input_factory() -> Option<InputFactory> {
+    #[cfg(target_os = "windows")]
+    {
+        Some(Box::new(|| Box::new(WindowsHookInput::new())))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Some(Box::new(|| Box::new(XRecordInput::new())))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tauri_plugin_eco_selection::{ChannelInput, ChannelInputSender, MonitorState};
+
+    // 与监控等待线程启动或退出的超时时间一致
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    type Events = Arc<Mutex<Vec<KeyEvent>>>;
+    type Senders = Arc<Mutex<Vec<ChannelInputSender<KeyEvent>>>>;
+
+    fn collect_sink() -> (KeySink, Events) {
+        let events: Events = Arc::default();
+        let cloned = events.clone();
+
+        let sink: KeySink = Arc::new(move |event| cloned.lock().unwrap().push(event));
+
+        (sink, events)
+    }
+
+    // 每次启动都创建新的通道输入源，并保存发送事件的句柄
+    fn channel_monitor() -> (KeyboardMonitor, Events, Senders) {
+        let (sink, events) = collect_sink();
+        let senders: Senders = Arc::default();
+        let cloned = senders.clone();
+
+        let factory: InputFactory = Box::new(move || {
+            let input = ChannelInput::new();
+
+            cloned.lock().unwrap().push(input.sender());
+
+            Box::new(input)
+        });
+
+        (
+            KeyboardMonitor::new("keyboard", factory, sink),
+            events,
+            senders,
+        )
+    }
+
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + TIMEOUT;
+
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        condition()
+    }
+
+    struct FailingInput;
+
+    impl MonitorInput<KeyEvent> for FailingInput {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn prepare(&mut self, _sink: KeySink) -> Result<StopHandle, String> {
+            Err("hook unavailable".to_string())
+        }
+
+        fn run(&mut self) {}
+    }
+
+    // 忽略停止请求的输入源，直到测试放行才退出，并记录钩子的安装次数
+    struct HangingInput {
+        release: Arc<Mutex<Receiver<()>>>,
+        hooks: Arc<Mutex<u32>>,
+    }
+
+    impl MonitorInput<KeyEvent> for HangingInput {
+        fn name(&self) -> &'static str {
+            "hanging"
+        }
+
+        fn prepare(&mut self, _sink: KeySink) -> Result<StopHandle, String> {
+            *self.hooks.lock().unwrap() += 1;
+
+            Ok(Box::new(|| {}))
+        }
+
+        fn run(&mut self) {
+            let _ = self.release.lock().unwrap().recv();
+        }
+
+        fn cleanup(&mut self) {
+            *self.hooks.lock().unwrap() -= 1;
+        }
+    }
+
+    #[test]
+    fn starts_once() {
+        let (monitor, _, senders) = channel_monitor();
+
+        assert!(!monitor.is_running());
+
+        monitor.start().unwrap();
+        monitor.start().unwrap();
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Running);
+        assert_eq!(status.input.as_deref(), Some("channel"));
+        assert_eq!(status.start_count, 1);
+        assert_eq!(senders.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stop_joins_worker() {
+        let (monitor, _, senders) = channel_monitor();
+
+        monitor.start().unwrap();
+
+        let start = Instant::now();
+
+        monitor.stop().unwrap();
+
+        assert!(start.elapsed() < TIMEOUT);
+        assert!(!monitor.is_running());
+
+        // 输入源已经结束，已经停止时再次停止没有影响
+        assert!(!senders.lock().unwrap()[0].send(KeyEvent::Backspace));
+
+        monitor.stop().unwrap();
+    }
+
+    #[test]
+    fn restarts_with_new_input() {
+        let (monitor, events, senders) = channel_monitor();
+
+        monitor.start().unwrap();
+        monitor.stop().unwrap();
+        monitor.start().unwrap();
+
+        assert_eq!(monitor.status().start_count, 2);
+        assert_eq!(senders.lock().unwrap().len(), 2);
+
+        let sender = senders.lock().unwrap()[1].clone();
+
+        assert!(sender.send(KeyEvent::Char('a')));
+        assert!(wait_for(|| events.lock().unwrap().len() == 1));
+
+        monitor.stop().unwrap();
+    }
+
+    #[test]
+    fn forwards_events_to_sink() {
+        let (monitor, events, senders) = channel_monitor();
+
+        monitor.start().unwrap();
+
+        let sender = senders.lock().unwrap()[0].clone();
+        let sent = [
+            KeyEvent::Char(';'),
+            KeyEvent::Char('中'),
+            KeyEvent::Backspace,
+            KeyEvent::Interrupt,
+        ];
+
+        for event in sent {
+            assert!(sender.send(event));
+        }
+
+        assert!(wait_for(|| events.lock().unwrap().len() == sent.len()));
+        assert_eq!(*events.lock().unwrap(), sent);
+
+        monitor.stop().unwrap();
+    }
+
+    #[test]
+    fn replays_recorded_events() {
+        let (sink, events) = collect_sink();
+        let recorded = vec![KeyEvent::Char('x'), KeyEvent::Interrupt];
+        let cloned = recorded.clone();
+
+        let monitor = KeyboardMonitor::new(
+            "keyboard",
+            Box::new(move || Box::new(ChannelInput::with_records(cloned.clone()))),
+            sink,
+        );
+
+        monitor.start().unwrap();
+
+        assert!(wait_for(|| events.lock().unwrap().len() == recorded.len()));
+        assert_eq!(*events.lock().unwrap(), recorded);
+    }
+
+    #[test]
+    fn reports_prepare_error() {
+        let (sink, _) = collect_sink();
+        let monitor = KeyboardMonitor::new("keyboard", Box::new(|| Box::new(FailingInput)), sink);
+
+        assert_eq!(monitor.start(), Err("hook unavailable".to_string()));
+        assert!(!monitor.is_running());
+        assert_eq!(
+            monitor.status().last_error.as_deref(),
+            Some("hook unavailable")
+        );
+    }
+
+    #[test]
+    fn hanging_worker_is_not_hooked_twice() {
+        let (sink, _) = collect_sink();
+        let (release_tx, release_rx) = mpsc::channel();
+        let release = Arc::new(Mutex::new(release_rx));
+        let hooks = Arc::new(Mutex::new(0));
+        let hang = AtomicBool::new(true);
+        let cloned = hooks.clone();
+
+        let factory: InputFactory = Box::new(move || {
+            if hang.swap(false, Ordering::SeqCst) {
+                Box::new(HangingInput {
+                    release: release.clone(),
+                    hooks: cloned.clone(),
+                })
+            } else {
+                Box::new(ChannelInput::new())
+            }
+        });
+
+        let monitor = KeyboardMonitor::new("keyboard", factory, sink);
+
+        monitor.start().unwrap();
+
+        assert!(monitor.stop().is_err());
+        assert_eq!(monitor.status().state, MonitorState::Stopping);
+
+        // 旧的线程还没有卸载钩子，不能再安装一次
+        assert!(monitor.start().is_err());
+        assert_eq!(*hooks.lock().unwrap(), 1);
+        assert_eq!(monitor.status().start_count, 1);
+
+        release_tx.send(()).unwrap();
+
+        assert!(wait_for(|| monitor.status().state == MonitorState::Stopped));
+        assert_eq!(*hooks.lock().unwrap(), 0);
+
+        monitor.start().unwrap();
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Running);
+        assert_eq!(status.input.as_deref(), Some("channel"));
+        assert_eq!(status.start_count, 2);
+    }
+}
@@ -0,0 +1,303 @@
+//! Windows 系统级输入源
+//!
+//! 使用 WH_KEYBOARD_LL 低级键盘钩子监听按键，按前台窗口的键盘布局把按键转换为字符，
+//! 使用 WH_MOUSE_LL 低级鼠标钩子和前台窗口事件钩子监听可能移动光标的点击和窗口切换。
+//! 钩子安装在监控线程上，停止时通过 `PostThreadMessageW(WM_QUIT)` 唤醒消息循环。
+
+use super::{KeyEvent, KeySink, MonitorInput, StopHandle};
+use std::sync::Mutex;
+
+use windows::Win32::Foundation::{HMODULE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, GetKeyState, GetKeyboardLayout, SendInput, ToUnicodeEx, INPUT, INPUT_0,
+    INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, VIRTUAL_KEY, VK_BACK,
+    VK_CAPITAL, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_RCONTROL,
+    VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetForegroundWindow, GetMessageW, GetWindowThreadProcessId,
+    PeekMessageW, PostThreadMessageW, SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx,
+    EVENT_SYSTEM_FOREGROUND, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, PM_NOREMOVE,
+    WH_KEYBOARD_LL, WH_MOUSE_LL, WINEVENT_OUTOFCONTEXT, WM_KEYDOWN, WM_LBUTTONDOWN, WM_MBUTTONDOWN,
+    WM_QUIT, WM_RBUTTONDOWN, WM_SYSKEYDOWN,
+};
+
+// 转换字符时不修改键盘的状态，避免影响前台应用中的死键，需要 Windows 10 1607 及以上
+const TO_UNICODE_NO_STATE: u32 = 0x4;
+
+// 只记录状态、不会输入字符的按键
+const MODIFIER_KEYS: [VIRTUAL_KEY; 12] = [
+    VK_SHIFT,
+    VK_LSHIFT,
+    VK_RSHIFT,
+    VK_CONTROL,
+    VK_LCONTROL,
+    VK_RCONTROL,
+    VK_MENU,
+    VK_LMENU,
+    VK_RMENU,
+    VK_LWIN,
+    VK_RWIN,
+    VK_CAPITAL,
+];
+
+// 钩子回调无法携带上下文，只能通过全局变量把事件交给当前的监控
+static KEY_SINK: Mutex<Option<KeySink>> = Mutex::new(None);
+
+/// Windows 钩子输入源
+#[derive(Default)]
+pub struct WindowsHookInput {
+    keyboard_hook: Option<HHOOK>,
+    mouse_hook: Option<HHOOK>,
+    foreground_hook: Option<HWINEVENTHOOK>,
+}
+
+// 钩子句柄只在监控线程中使用
+unsafe impl Send for WindowsHookInput {}
+
+impl WindowsHookInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MonitorInput<KeyEvent> for WindowsHookInput {
+    fn name(&self) -> &'static str {
+        "windows-hook"
+    }
+
+    fn prepare(&mut self, sink: KeySink) -> Result<StopHandle, String> {
+        if let Ok(mut guard) = KEY_SINK.lock() {
+            *guard = Some(sink);
+        }
+
+        unsafe {
+            let h_instance: HMODULE = GetModuleHandleW(None).unwrap_or_default();
+
+            let keyboard_hook =
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), h_instance, 0)
+                    .map_err(|e| format!("Failed to set keyboard hook: {:?}", e))?;
+
+            self.keyboard_hook = Some(keyboard_hook);
+
+            log::info!("Keyboard hook installed successfully");
+
+            // 鼠标钩子和前台窗口事件钩子只用于清空已经输入的字符，安装失败不影响展开
+            self.mouse_hook = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), h_instance, 0)
+                .map_err(|e| log::error!("Failed to set mouse hook: {:?}", e))
+                .ok();
+
+            let foreground_hook = SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                HMODULE::default(),
+                Some(foreground_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+
+            if !foreground_hook.is_invalid() {
+                self.foreground_hook = Some(foreground_hook);
+            }
+
+            // 确保线程已经创建消息队列，否则在进入消息循环之前投递的 WM_QUIT 会丢失
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+
+            let thread_id = GetCurrentThreadId();
+
+            Ok(Box::new(move || {
+                let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            }))
+        }
+    }
+
+    fn run(&mut self) {
+        unsafe {
+            let mut msg = MSG::default();
+
+            // GetMessageW 收到 WM_QUIT 时返回 0，出错时返回 -1
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) {
+        unsafe {
+            if let Some(hook) = self.keyboard_hook.take() {
+                let _ = UnhookWindowsHookEx(hook);
+
+                log::info!("Keyboard hook uninstalled");
+            }
+
+            if let Some(hook) = self.mouse_hook.take() {
+                let _ = UnhookWindowsHookEx(hook);
+            }
+
+            if let Some(hook) = self.foreground_hook.take() {
+                let _ = UnhookWinEvent(hook);
+            }
+        }
+
+        if let Ok(mut guard) = KEY_SINK.lock() {
+            *guard = None;
+        }
+    }
+}
+
+/// 模拟按下退格键，删除已经输入的缩写
+pub fn erase(count: usize) {
+    let key = |flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VK_BACK,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+
+    let inputs: Vec<INPUT> = (0..count)
+        .flat_map(|_| [key(KEYBD_EVENT_FLAGS(0)), key(KEYEVENTF_KEYUP)])
+        .collect();
+
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
+/// 把事件交给当前的监控
+fn dispatch(event: KeyEvent) {
+    let sink = KEY_SINK.lock().ok().and_then(|guard| guard.clone());
+
+    if let Some(sink) = sink {
+        sink(event);
+    }
+}
+
+fn is_pressed(key: VIRTUAL_KEY) -> bool {
+    unsafe { GetAsyncKeyState(key.0 as i32) as u16 & 0x8000 != 0 }
+}
+
+// 按前台窗口的键盘布局把按键转换为事件，AltGr 等同于同时按下 Ctrl 和 Alt
+unsafe fn translate(keyboard_info: &KBDLLHOOKSTRUCT) -> Vec<KeyEvent> {
+    let key = VIRTUAL_KEY(keyboard_info.vkCode as u16);
+
+    if MODIFIER_KEYS.contains(&key) {
+        return Vec::new();
+    }
+
+    if key == VK_BACK {
+        return vec![KeyEvent::Backspace];
+    }
+
+    let control = is_pressed(VK_CONTROL);
+    let alt = is_pressed(VK_MENU);
+
+    // 组合键通常是快捷键，可能移动光标或者修改内容
+    if control != alt || is_pressed(VK_LWIN) || is_pressed(VK_RWIN) {
+        return vec![KeyEvent::Interrupt];
+    }
+
+    let mut state = [0u8; 256];
+
+    for modifier in [VK_SHIFT, VK_CONTROL, VK_MENU] {
+        if is_pressed(modifier) {
+            state[modifier.0 as usize] = 0x80;
+        }
+    }
+
+    state[VK_CAPITAL.0 as usize] = (GetKeyState(VK_CAPITAL.0 as i32) & 1) as u8;
+
+    let thread_id = GetWindowThreadProcessId(GetForegroundWindow(), None);
+    let layout = GetKeyboardLayout(thread_id);
+
+    let mut buffer = [0u16; 8];
+
+    let length = ToUnicodeEx(
+        keyboard_info.vkCode,
+        keyboard_info.scanCode,
+        &state,
+        &mut buffer,
+        TO_UNICODE_NO_STATE,
+        layout,
+    );
+
+    match length {
+        // 死键和下一个按键组合成一个字符，先不处理
+        length if length < 0 => Vec::new(),
+        // 方向键、Home、Delete 等不输入字符的按键
+        0 => vec![KeyEvent::Interrupt],
+        length => char::decode_utf16(buffer[..length as usize].iter().copied())
+            .map(|char| match char {
+                Ok(char) if !char.is_control() => KeyEvent::Char(char),
+                // 回车、Tab、Esc 等
+                _ => KeyEvent::Interrupt,
+            })
+            .collect(),
+    }
+}
+
+/// 键盘钩子回调
+unsafe extern "system" fn keyboard_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0 {
+        let keyboard_info = &*(l_param.0 as *const KBDLLHOOKSTRUCT);
+
+        // 忽略模拟的按键，例如删除缩写时的退格键和粘贴时的组合键
+        let injected = keyboard_info.flags.0 & LLKHF_INJECTED.0 != 0;
+
+        if !injected && matches!(w_param.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN) {
+            for event in translate(keyboard_info) {
+                dispatch(event);
+            }
+        }
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 鼠标钩子回调，点击可能移动光标
+unsafe extern "system" fn mouse_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0
+        && matches!(
+            w_param.0 as u32,
+            WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN
+        )
+    {
+        dispatch(KeyEvent::Interrupt);
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 前台窗口变化回调
+unsafe extern "system" fn foreground_event_proc(
+    _h_win_event_hook: HWINEVENTHOOK,
+    event: u32,
+    _hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _dw_event_thread: u32,
+    _dwms_event_time: u32,
+) {
+    if event == EVENT_SYSTEM_FOREGROUND {
+        dispatch(KeyEvent::Interrupt);
+    }
+}
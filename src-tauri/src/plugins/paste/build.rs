@@ -1,4 +1,10 @@
-const COMMANDS: &[&str] = &["paste"];
+const COMMANDS: &[&str] = &[
+    "paste",
+    "transform_text",
+    "snippet_inputs",
+    "expand_snippet",
+    "move_cursor_back",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
@@ -1,4 +1,5 @@
 use super::wait;
+use crate::snippet::MAX_CURSOR_BACK;
 use rdev::{simulate, EventType, Key};
 use std::sync::Mutex;
 use tauri::command;
@@ -11,6 +12,9 @@ use x11::xlib::{
 
 static PREVIOUS_WINDOW: Mutex<Option<u64>> = Mutex::new(None);
 
+// 移动光标时每个按键事件之间的间隔（毫秒），比粘贴时短，移动较远时不会等待太久
+const CURSOR_KEY_INTERVAL: u64 = 2;
+
 // 获取窗口标题
 fn get_net_wm_name(display: *mut Display, window: u64) -> std::result::Result<String, String> {
     let mut actual_type: Atom = 0;
@@ -116,15 +120,15 @@ fn focus_previous_window() {
     }
 }
 
+fn dispatch(event_type: &EventType) {
+    wait(20);
+
+    simulate(event_type).unwrap();
+}
+
 // 粘贴
 #[command]
 pub async fn paste() {
-    fn dispatch(event_type: &EventType) {
-        wait(20);
-
-        simulate(event_type).unwrap();
-    }
-
     focus_previous_window();
 
     wait(100);
@@ -134,3 +138,18 @@ pub async fn paste() {
     dispatch(&EventType::KeyRelease(Key::Insert));
     dispatch(&EventType::KeyRelease(Key::ShiftLeft));
 }
+
+// 光标向左移动，最多移动 MAX_CURSOR_BACK 次
+#[command]
+pub async fn move_cursor_back(count: usize) {
+    for _ in 0..count.min(MAX_CURSOR_BACK) {
+        for event_type in [
+            EventType::KeyPress(Key::LeftArrow),
+            EventType::KeyRelease(Key::LeftArrow),
+        ] {
+            wait(CURSOR_KEY_INTERVAL);
+
+            simulate(&event_type).unwrap();
+        }
+    }
+}
@@ -11,6 +11,8 @@ use std::thread;
 use tauri::{command, AppHandle, Runtime, WebviewWindow};
 use tauri_plugin_eco_window::{set_macos_panel, MacOSPanelStatus, MAIN_WINDOW_TITLE};
 
+use crate::snippet::MAX_CURSOR_BACK;
+
 static PREVIOUS_WINDOW: Mutex<Option<i32>> = Mutex::new(None);
 
 extern "C" fn application_did_activate(_self: &Object, _cmd: Sel, notification: id) {
@@ -91,3 +93,25 @@ pub async fn paste<R: Runtime>(app_handle: AppHandle<R>, window: WebviewWindow<R
         .output()
         .expect("failed to execute process");
 }
+
+// 光标向左移动，最多移动 MAX_CURSOR_BACK 次，123 是左方向键的虚拟键码
+#[command]
+pub async fn move_cursor_back(count: usize) {
+    let count = count.min(MAX_CURSOR_BACK);
+
+    if count == 0 {
+        return;
+    }
+
+    let script = format!(
+        r#"tell application "System Events" to repeat {} times
+key code 123
+end repeat"#,
+        count
+    );
+
+    Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .expect("failed to execute process");
+}
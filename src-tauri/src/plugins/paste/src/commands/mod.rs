@@ -1,3 +1,9 @@
+use chrono::Local;
+use tauri::command;
+
+use crate::snippet::{self, ExpandedSnippet, SnippetValues};
+use crate::transform::{self, PasteTransform};
+
 #[cfg(target_os = "macos")]
 mod macos;
 
@@ -22,3 +28,24 @@ pub fn wait(millis: u64) {
 
     thread::sleep(time::Duration::from_millis(millis));
 }
+
+/// 依次执行粘贴前的转换，返回转换后的文本
+#[command]
+pub async fn transform_text(
+    text: String,
+    transforms: Vec<PasteTransform>,
+) -> Result<String, String> {
+    transform::apply_all(&text, &transforms).map_err(|e| e.to_string())
+}
+
+/// 片段中需要用户输入的名称
+#[command]
+pub async fn snippet_inputs(template: String) -> Vec<String> {
+    snippet::inputs(&template)
+}
+
+/// 展开片段中的占位符
+#[command]
+pub async fn expand_snippet(template: String, values: SnippetValues) -> ExpandedSnippet {
+    snippet::expand(&template, &values, Local::now())
+}
@@ -1,4 +1,5 @@
 use super::wait;
+use crate::snippet::MAX_CURSOR_BACK;
 use enigo::{
     Direction::{Click, Press, Release},
     Enigo, Key, Keyboard, Settings,
@@ -113,3 +114,13 @@ pub async fn paste() {
     enigo.key(Key::Other(0x2D), Click).unwrap();
     enigo.key(Key::Shift, Release).unwrap();
 }
+
+// 光标向左移动，最多移动 MAX_CURSOR_BACK 次
+#[command]
+pub async fn move_cursor_back(count: usize) {
+    let mut enigo = Enigo::new(&Settings::default()).unwrap();
+
+    for _ in 0..count.min(MAX_CURSOR_BACK) {
+        enigo.key(Key::LeftArrow, Click).unwrap();
+    }
+}
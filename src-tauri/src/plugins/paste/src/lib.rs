@@ -5,8 +5,12 @@ use tauri::{
 };
 
 mod commands;
+mod snippet;
+mod transform;
 
 pub use commands::*;
+pub use snippet::{ExpandedSnippet, SnippetValues};
+pub use transform::{apply_all, PasteTransform, TransformError};
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("eco-paste")
@@ -15,6 +19,12 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 
             Ok(())
         })
-        .invoke_handler(generate_handler![commands::paste])
+        .invoke_handler(generate_handler![
+            commands::paste,
+            commands::transform_text,
+            commands::snippet_inputs,
+            commands::expand_snippet,
+            commands::move_cursor_back,
+        ])
         .build()
 }
@@ -0,0 +1,303 @@
+//! 片段的占位符
+//!
+//! 语法：
+//! - `{date}` 当前日期，`{date:%Y/%m/%d}` 按 strftime 的格式输出，`{time}` 当前时间
+//! - `{clipboard}` 剪贴板中的文本
+//! - `{cursor}` 粘贴后光标停留的位置，只有第一个生效
+//! - `{input:Name}` 粘贴前由用户输入，名称相同的占位符使用同一个值
+//! - `\{` 输出字面量 `{`
+//!
+//! 不认识的占位符按原样输出。展开不依赖系统环境，剪贴板和输入的内容由调用方传入。
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+const TIME_FORMAT: &str = "%H:%M:%S";
+
+/// 光标最多向左移动的次数，`{cursor}` 之后的内容更长时光标停在末尾
+pub const MAX_CURSOR_BACK: usize = 500;
+
+/// 展开片段时使用的内容
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnippetValues {
+    /// 剪贴板中的文本
+    pub clipboard: String,
+    /// `{input:Name}` 的值，按名称对应
+    pub inputs: HashMap<String, String>,
+}
+
+/// 展开后的片段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpandedSnippet {
+    pub text: String,
+    /// 粘贴后光标需要向左移动的字符数，没有 `{cursor}` 或超过 [`MAX_CURSOR_BACK`] 时为空
+    pub cursor_back: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Date(Option<String>),
+    Time(Option<String>),
+    Clipboard,
+    Cursor,
+    Input(String),
+}
+
+/// 片段中需要用户输入的名称，按第一次出现的顺序排列
+pub fn inputs(source: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for part in parse(source) {
+        if let Part::Input(name) = part {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    names
+}
+
+/// 展开片段中的占位符，没有提供的输入替换为空字符串
+pub fn expand(source: &str, values: &SnippetValues, now: DateTime<Local>) -> ExpandedSnippet {
+    let mut text = String::new();
+    let mut cursor = None;
+
+    for part in parse(source) {
+        match part {
+            Part::Text(value) => text.push_str(&value),
+            Part::Date(format) => text.push_str(&format_time(&now, format, DATE_FORMAT)),
+            Part::Time(format) => text.push_str(&format_time(&now, format, TIME_FORMAT)),
+            Part::Clipboard => text.push_str(&values.clipboard),
+            Part::Cursor => {
+                cursor.get_or_insert(text.len());
+            }
+            Part::Input(name) => {
+                if let Some(value) = values.inputs.get(&name) {
+                    text.push_str(value);
+                }
+            }
+        }
+    }
+
+    let cursor_back = cursor
+        .map(|offset| count_keys(&text[offset..]))
+        .filter(|count| *count <= MAX_CURSOR_BACK);
+
+    ExpandedSnippet { text, cursor_back }
+}
+
+fn parse(source: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = source;
+
+    while let Some(index) = rest.find(['\\', '{']) {
+        text.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if let Some(after) = rest.strip_prefix("\\{") {
+            text.push('{');
+            rest = after;
+
+            continue;
+        }
+
+        if rest.starts_with('\\') {
+            text.push('\\');
+            rest = &rest[1..];
+
+            continue;
+        }
+
+        let placeholder = rest[1..]
+            .find(['{', '}'])
+            .filter(|end| rest[1 + end..].starts_with('}'))
+            .and_then(|end| placeholder(&rest[1..1 + end]).map(|part| (part, end + 2)));
+
+        match placeholder {
+            Some((part, length)) => {
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+
+                parts.push(part);
+                rest = &rest[length..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+
+    parts
+}
+
+fn placeholder(content: &str) -> Option<Part> {
+    let (name, argument) = match content.split_once(':') {
+        Some((name, argument)) => (name.trim(), Some(argument)),
+        None => (content.trim(), None),
+    };
+
+    let format = || {
+        argument
+            .filter(|format| !format.is_empty())
+            .map(String::from)
+    };
+
+    match (name, argument) {
+        ("date", _) => Some(Part::Date(format())),
+        ("time", _) => Some(Part::Time(format())),
+        ("clipboard", None) => Some(Part::Clipboard),
+        ("cursor", None) => Some(Part::Cursor),
+        ("input", Some(argument)) if !argument.trim().is_empty() => {
+            Some(Part::Input(argument.trim().to_string()))
+        }
+        _ => None,
+    }
+}
+
+// 格式不正确时使用默认的格式，避免格式化时出错
+fn format_time(now: &DateTime<Local>, format: Option<String>, default: &str) -> String {
+    let format = format
+        .filter(|format| !StrftimeItems::new(format).any(|item| matches!(item, Item::Error)))
+        .unwrap_or_else(|| default.to_string());
+
+    now.format(&format).to_string()
+}
+
+// 移动光标时按一次方向键跳过一个字素簇，组合字符、emoji 序列和换行符 `\r\n` 都只需要一次
+fn count_keys(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 5, 7, 8, 9).unwrap()
+    }
+
+    fn values(clipboard: &str, inputs: &[(&str, &str)]) -> SnippetValues {
+        SnippetValues {
+            clipboard: clipboard.to_string(),
+            inputs: inputs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn text(source: &str) -> String {
+        expand(source, &values("copied", &[("Name", "Tom")]), now()).text
+    }
+
+    #[test]
+    fn parses_placeholders() {
+        assert_eq!(
+            parse("Hi {input: Name }, {date}{time:%H}{clipboard}{cursor}!"),
+            [
+                Part::Text("Hi ".into()),
+                Part::Input("Name".into()),
+                Part::Text(", ".into()),
+                Part::Date(None),
+                Part::Time(Some("%H".into())),
+                Part::Clipboard,
+                Part::Cursor,
+                Part::Text("!".into()),
+            ]
+        );
+
+        assert_eq!(parse(""), []);
+        assert_eq!(parse("{date:}"), [Part::Date(None)]);
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_placeholders() {
+        for source in [
+            "{unknown}",
+            "{clipboard:x}",
+            "{cursor:1}",
+            "{input}",
+            "{input: }",
+            "{date",
+            "}{",
+            "{}",
+            "a { b",
+        ] {
+            assert_eq!(text(source), source);
+        }
+
+        // 只有最里面的一层是占位符
+        assert_eq!(text("{{date}}"), "{2024-03-05}");
+        assert_eq!(text("{a{clipboard}"), "{acopied");
+    }
+
+    #[test]
+    fn escapes_braces() {
+        assert_eq!(text("\\{date}"), "{date}");
+        assert_eq!(text("\\\\{date}"), "\\{date}");
+        assert_eq!(text("a\\b\\"), "a\\b\\");
+        assert_eq!(text("\\{{date}"), "{2024-03-05");
+    }
+
+    #[test]
+    fn formats_date_and_time() {
+        assert_eq!(text("{date} {time}"), "2024-03-05 07:08:09");
+        assert_eq!(text("{date:%Y/%m/%d} {time:%H.%M}"), "2024/03/05 07.08");
+
+        // 格式中可以有冒号，格式不正确时使用默认的格式
+        assert_eq!(text("{time:%H:%M}"), "07:08");
+        assert_eq!(text("{date:%Q}"), "2024-03-05");
+    }
+
+    #[test]
+    fn collects_inputs_in_order() {
+        assert_eq!(
+            inputs("{input:B} {input:A} {input: B } {input:} {clipboard}"),
+            ["B", "A"]
+        );
+        assert!(inputs("\\{input:A}").is_empty());
+
+        assert_eq!(text("{input:Name}/{input:Name}/{input:Other}"), "Tom/Tom/");
+    }
+
+    #[test]
+    fn counts_cursor_moves() {
+        let cursor_back = |source: &str| expand(source, &values("", &[]), now()).cursor_back;
+
+        assert_eq!(cursor_back("abc"), None);
+        assert_eq!(cursor_back("ab{cursor}cd{cursor}e"), Some(3));
+        assert_eq!(cursor_back("abc{cursor}"), Some(0));
+
+        // 换行符、组合字符和 emoji 序列都只需要按一次方向键
+        assert_eq!(cursor_back("{cursor}a\r\nb\n"), Some(4));
+        assert_eq!(cursor_back("{cursor}e\u{301}"), Some(1));
+        assert_eq!(cursor_back("{cursor}👨‍👩‍👧🇨🇳中"), Some(3));
+
+        // 太远时不移动光标
+        let far = "a".repeat(MAX_CURSOR_BACK);
+        assert_eq!(
+            cursor_back(&format!("{{cursor}}{}", far)),
+            Some(MAX_CURSOR_BACK)
+        );
+        assert_eq!(cursor_back(&format!("{{cursor}}{}b", far)), None);
+    }
+}
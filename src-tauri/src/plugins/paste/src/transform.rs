@@ -0,0 +1,270 @@
+//! 粘贴前对文本的转换
+//!
+//! 多个转换按顺序依次执行，例如先去掉首尾空白再转为单行。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tauri_plugin_eco_selection::title_case;
+
+/// 粘贴时可用的转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteTransform {
+    /// 去掉首尾空白
+    Trimmed,
+    /// 合并为一行，去掉空行
+    SingleLine,
+    Upper,
+    Lower,
+    /// 每个单词首字母大写
+    Title,
+    /// 用双引号包裹
+    Quoted,
+    /// 转义为 JSON 字符串的内容，不含两侧的引号
+    JsonEscape,
+    /// 用单引号包裹，可以直接作为 POSIX shell 的参数
+    ShellEscape,
+    /// 转义正则表达式的元字符
+    RegexEscape,
+    /// 把 URL 转为 Markdown 链接
+    MarkdownLink,
+}
+
+/// 转换失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// 转为 Markdown 链接时文本不是 URL
+    NotUrl,
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotUrl => write!(f, "Text is not a URL"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+impl PasteTransform {
+    pub fn apply(self, text: &str) -> Result<String, TransformError> {
+        let result = match self {
+            Self::Trimmed => text.trim().to_string(),
+            Self::SingleLine => single_line(text),
+            Self::Upper => text.to_uppercase(),
+            Self::Lower => text.to_lowercase(),
+            Self::Title => title_case(text),
+            Self::Quoted => format!("\"{}\"", text),
+            Self::JsonEscape => json_escape(text),
+            Self::ShellEscape => format!("'{}'", text.replace('\'', "'\\''")),
+            Self::RegexEscape => regex_escape(text),
+            Self::MarkdownLink => markdown_link(text)?,
+        };
+
+        Ok(result)
+    }
+}
+
+/// 依次执行 `transforms` 中的转换
+pub fn apply_all(text: &str, transforms: &[PasteTransform]) -> Result<String, TransformError> {
+    transforms
+        .iter()
+        .try_fold(text.to_string(), |text, transform| transform.apply(&text))
+}
+
+fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn json_escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\u{08}' => result.push_str("\\b"),
+            '\u{0c}' => result.push_str("\\f"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+// 需要转义的正则表达式元字符，`/` 是 JavaScript 正则字面量的分隔符。
+// 只转义语法上有意义的字符，转义 `-`、`#` 之类的字符在 JavaScript 的 `u` 模式下会报错
+const REGEX_META: &str = "^$\\.*+?()[]{}|/";
+
+fn regex_escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if REGEX_META.contains(c) {
+            result.push('\\');
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+// 链接文字为去掉协议和末尾斜杠的地址
+fn markdown_link(text: &str) -> Result<String, TransformError> {
+    let url = text.trim();
+
+    let rest = ["https://", "http://", "ftp://", "mailto:"]
+        .iter()
+        .find_map(|scheme| {
+            url.get(..scheme.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+                .map(|_| &url[scheme.len()..])
+        })
+        .filter(|rest| !rest.is_empty() && !url.chars().any(char::is_whitespace))
+        .ok_or(TransformError::NotUrl)?;
+
+    let label = rest
+        .trim_end_matches('/')
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+    let target = url.replace('(', "%28").replace(')', "%29");
+
+    Ok(format!("[{}]({})", label, target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PasteTransform::*;
+
+    #[test]
+    fn applies_each_transform() {
+        let cases = [
+            (Trimmed, " \n a b \t", "a b"),
+            (SingleLine, "  a\n\n  b \r\n c\n", "a b c"),
+            (Upper, "ßtraße ü", "SSTRASSE Ü"),
+            (Lower, "ÄB", "äb"),
+            (
+                Title,
+                "hello wide-world (again) \"x\"",
+                "Hello Wide-World (Again) \"X\"",
+            ),
+            (Quoted, "a\"b", "\"a\"b\""),
+            (
+                JsonEscape,
+                "a\"\\\n\r\t\u{08}\u{0c}\u{01}你",
+                "a\\\"\\\\\\n\\r\\t\\b\\f\\u0001你",
+            ),
+            (ShellEscape, "it's", "'it'\\''s'"),
+            (RegexEscape, "a.b*c", "a\\.b\\*c"),
+            (
+                MarkdownLink,
+                " https://example.com/ ",
+                "[example.com](https://example.com/)",
+            ),
+        ];
+
+        for (transform, input, expected) in cases {
+            assert_eq!(
+                transform.apply(input).as_deref(),
+                Ok(expected),
+                "{:?}",
+                transform
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_only_regex_syntax() {
+        assert_eq!(
+            regex_escape("^$\\.*+?()[]{}|/"),
+            "\\^\\$\\\\\\.\\*\\+\\?\\(\\)\\[\\]\\{\\}\\|\\/"
+        );
+        // 这些字符在正则中不需要转义，转义后在 `u` 模式下无效
+        assert_eq!(regex_escape("a-b #c &d ~e =f 你好"), "a-b #c &d ~e =f 你好");
+    }
+
+    #[test]
+    fn builds_markdown_links() {
+        assert_eq!(
+            markdown_link("HTTP://a.com/x_(y)[z]"),
+            Ok("[a.com/x_(y)\\[z\\]](HTTP://a.com/x_%28y%29[z])".to_string())
+        );
+        assert_eq!(
+            markdown_link("mailto:me@a.com"),
+            Ok("[me@a.com](mailto:me@a.com)".to_string())
+        );
+
+        for text in [
+            "",
+            "example.com",
+            "https://",
+            "https://a.com b",
+            "ftp:/x",
+            "网址",
+        ] {
+            assert_eq!(
+                markdown_link(text),
+                Err(TransformError::NotUrl),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn applies_transforms_in_order() {
+        let text = "  hello\n  world  \n";
+
+        assert_eq!(apply_all(text, &[]).as_deref(), Ok(text));
+        assert_eq!(
+            apply_all(text, &[SingleLine, Title, Quoted]).as_deref(),
+            Ok("\"Hello World\"")
+        );
+        // 顺序不同结果不同
+        assert_eq!(
+            apply_all(text, &[Quoted, JsonEscape]).as_deref(),
+            Ok("\\\"  hello\\n  world  \\n\\\"")
+        );
+        assert_eq!(
+            apply_all(text, &[JsonEscape, Quoted]).as_deref(),
+            Ok("\"  hello\\n  world  \\n\"")
+        );
+        assert_eq!(
+            apply_all("https://a.com", &[Upper, MarkdownLink]).as_deref(),
+            Ok("[A.COM](HTTPS://A.COM)")
+        );
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        assert_eq!(
+            apply_all("not a url", &[Trimmed, MarkdownLink, Upper]),
+            Err(TransformError::NotUrl)
+        );
+        // 前面的转换产生了 URL 时不报错
+        assert_eq!(
+            apply_all(" https://a.com \n", &[Trimmed, MarkdownLink]).as_deref(),
+            Ok("[a.com](https://a.com)")
+        );
+    }
+
+    #[test]
+    fn deserializes_camel_case_names() {
+        let transforms: Vec<PasteTransform> =
+            serde_json::from_str(r#"["trimmed","singleLine","jsonEscape","markdownLink"]"#)
+                .unwrap();
+
+        assert_eq!(transforms, [Trimmed, SingleLine, JsonEscape, MarkdownLink]);
+    }
+}
@@ -0,0 +1,5 @@
+const COMMANDS: &[&str] = &["set_secret", "delete_secret", "has_secret"];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
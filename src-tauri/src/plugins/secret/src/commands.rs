@@ -0,0 +1,87 @@
+use tauri::{async_runtime::spawn_blocking, command, AppHandle, Manager, Runtime};
+
+use crate::store::{SecretBackend, SecretError, SecretStore};
+
+// 前端可以管理的密钥，其它插件在 Rust 端使用的密钥（例如同步的设备私钥）不允许前端覆盖或删除
+const FRONTEND_PREFIXES: &[&str] = &["selection-assistant.api-key", "sync.remote."];
+
+// 访问钥匙串会阻塞，放到单独的线程中执行
+async fn with_store<R, T, F>(app: AppHandle<R>, f: F) -> Result<T, String>
+where
+    R: Runtime,
+    T: Send + 'static,
+    F: FnOnce(&SecretStore) -> Result<T, SecretError> + Send + 'static,
+{
+    spawn_blocking(move || f(&app.state::<SecretStore>()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 保存密钥，返回实际保存的位置
+///
+/// 前端只能写入不能读取，需要使用密钥的操作都在 Rust 端通过 [`read_secret`] 完成
+#[command]
+pub async fn set_secret<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    value: String,
+) -> Result<SecretBackend, String> {
+    check_frontend_name(&name)?;
+
+    with_store(app, move |store| store.set(&name, &value)).await
+}
+
+/// 删除密钥，返回是否存在
+#[command]
+pub async fn delete_secret<R: Runtime>(app: AppHandle<R>, name: String) -> Result<bool, String> {
+    check_frontend_name(&name)?;
+
+    with_store(app, move |store| store.delete(&name)).await
+}
+
+/// 是否保存了密钥，不会把密钥返回给前端
+#[command]
+pub async fn has_secret<R: Runtime>(app: AppHandle<R>, name: String) -> Result<bool, String> {
+    check_frontend_name(&name)?;
+
+    with_store(app, move |store| store.contains(&name)).await
+}
+
+/// 在单独的线程中读取密钥，供其它插件在 Rust 端使用
+pub async fn read_secret<R: Runtime>(
+    app: &AppHandle<R>,
+    name: impl Into<String>,
+) -> Result<Option<String>, String> {
+    let name = name.into();
+
+    with_store(app.clone(), move |store| store.get(&name)).await
+}
+
+fn check_frontend_name(name: &str) -> Result<(), String> {
+    if FRONTEND_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidName(name.to_string()).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_only_frontend_names() {
+        assert!(check_frontend_name("selection-assistant.api-key").is_ok());
+        assert!(check_frontend_name("selection-assistant.api-key.anthropic").is_ok());
+        assert!(check_frontend_name("sync.remote.passphrase").is_ok());
+        assert!(check_frontend_name("sync.remote.credential").is_ok());
+
+        assert!(check_frontend_name("sync.keypair").is_err());
+        assert!(check_frontend_name("sync.remote").is_err());
+        assert!(check_frontend_name("").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use tauri::{
+    generate_handler,
+    plugin::{Builder, TauriPlugin},
+    Manager, Runtime,
+};
+
+mod commands;
+mod store;
+mod vault;
+
+pub use commands::*;
+pub use store::{validate_name, SecretBackend, SecretError, SecretStore, VAULT_FILE_NAME};
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("eco-secret")
+        .invoke_handler(generate_handler![
+            commands::set_secret,
+            commands::delete_secret,
+            commands::has_secret,
+        ])
+        .setup(|app, _api| {
+            // 加密文件放在应用配置目录，不包含在数据备份中
+            let vault_path = app.path().app_config_dir()?.join(VAULT_FILE_NAME);
+
+            app.manage(SecretStore::new(&app.config().identifier, vault_path));
+
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,146 @@
+//! 密钥存储
+//!
+//! 优先保存到系统钥匙串（Windows 凭据管理器、macOS 钥匙串、Linux Secret Service），
+//! 钥匙串不可用时（例如没有运行 Secret Service 的 Linux 桌面）退回到加密文件。
+
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::vault::FileVault;
+
+/// 加密文件的文件名
+pub const VAULT_FILE_NAME: &str = "secrets.vault";
+
+/// 名称的最大长度
+const MAX_NAME_LEN: usize = 128;
+
+/// 密钥实际保存的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretBackend {
+    Keyring,
+    File,
+}
+
+/// 读写密钥失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretError {
+    /// 名称为空、过长或包含不支持的字符
+    InvalidName(String),
+    /// 系统钥匙串返回了错误
+    Keyring(String),
+    /// 读写加密文件失败
+    Vault(String),
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "Invalid secret name: {:?}", name),
+            Self::Keyring(message) => write!(f, "Keyring error: {}", message),
+            Self::Vault(message) => write!(f, "Secret vault error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {}
+
+/// 密钥存储
+pub struct SecretStore {
+    service: String,
+    vault: FileVault,
+}
+
+impl SecretStore {
+    /// `service` 为钥匙串中的服务名，`vault_path` 为加密文件的路径
+    pub fn new(service: impl Into<String>, vault_path: impl Into<PathBuf>) -> Self {
+        Self {
+            service: service.into(),
+            vault: FileVault::new(vault_path),
+        }
+    }
+
+    /// 保存密钥，返回实际保存的位置
+    pub fn set(&self, name: &str, value: &str) -> Result<SecretBackend, SecretError> {
+        validate_name(name)?;
+
+        match self.entry(name).and_then(|entry| entry.set_password(value)) {
+            Ok(()) => {
+                // 钥匙串恢复可用后清除加密文件中的旧值
+                let _ = self.vault.delete(name);
+
+                Ok(SecretBackend::Keyring)
+            }
+            Err(e) if is_unavailable(&e) => {
+                log::warn!("Keyring unavailable, falling back to vault: {}", e);
+
+                self.vault.set(name, value)?;
+
+                Ok(SecretBackend::File)
+            }
+            Err(e) => Err(SecretError::Keyring(e.to_string())),
+        }
+    }
+
+    /// 读取密钥，不存在时返回 None
+    pub fn get(&self, name: &str) -> Result<Option<String>, SecretError> {
+        validate_name(name)?;
+
+        match self.entry(name).and_then(|entry| entry.get_password()) {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => self.vault.get(name),
+            Err(e) if is_unavailable(&e) => self.vault.get(name),
+            Err(e) => Err(SecretError::Keyring(e.to_string())),
+        }
+    }
+
+    /// 删除密钥，返回是否存在
+    pub fn delete(&self, name: &str) -> Result<bool, SecretError> {
+        validate_name(name)?;
+
+        let in_keyring = match self.entry(name).and_then(|entry| entry.delete_credential()) {
+            Ok(()) => true,
+            Err(keyring::Error::NoEntry) => false,
+            Err(e) if is_unavailable(&e) => false,
+            Err(e) => return Err(SecretError::Keyring(e.to_string())),
+        };
+
+        let in_vault = self.vault.delete(name)?;
+
+        Ok(in_keyring || in_vault)
+    }
+
+    /// 是否保存了密钥
+    pub fn contains(&self, name: &str) -> Result<bool, SecretError> {
+        self.get(name).map(|value| value.is_some())
+    }
+
+    fn entry(&self, name: &str) -> keyring::Result<Entry> {
+        Entry::new(&self.service, name)
+    }
+}
+
+// 钥匙串服务不存在或无法访问，此时使用加密文件
+fn is_unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+/// 名称只能包含字母、数字、`.`、`-` 和 `_`
+pub fn validate_name(name: &str) -> Result<(), SecretError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidName(name.to_string()))
+    }
+}
@@ -0,0 +1,236 @@
+//! 系统钥匙串不可用时使用的加密文件
+//!
+//! 每一项用 ChaCha20-Poly1305 单独加密，名称作为附加数据参与校验。
+//! 密钥由固定的字符串、本机标识（machine-id 或主机名和用户名）和文件中的随机盐派生，
+//! 这些内容都不是秘密，拿到文件并知道本机标识的人可以解密。
+//! 这只是混淆，避免密钥以明文出现在磁盘和备份中，不能防止有意的读取，安全性远不如系统钥匙串。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::store::SecretError;
+
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    entries: BTreeMap<String, SealedEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedEntry {
+    nonce: String,
+    data: String,
+}
+
+/// 加密文件
+pub struct FileVault {
+    path: PathBuf,
+    // 读改写整个文件，需要串行执行
+    lock: Mutex<()>,
+}
+
+impl FileVault {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 读取一项，不存在时返回 None
+    pub fn get(&self, name: &str) -> Result<Option<String>, SecretError> {
+        let _guard = self.lock.lock().map_err(|e| vault_error(e.to_string()))?;
+
+        let Some(file) = self.read()? else {
+            return Ok(None);
+        };
+
+        let Some(entry) = file.entries.get(name) else {
+            return Ok(None);
+        };
+
+        let cipher = cipher(&file.salt)?;
+        let nonce = decode(&entry.nonce)?;
+        let data = decode(&entry.data)?;
+
+        if nonce.len() != NONCE_LEN {
+            return Err(vault_error("invalid nonce"));
+        }
+
+        let plain = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &data,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                vault_error("failed to decrypt, the vault may come from another device")
+            })?;
+
+        String::from_utf8(plain)
+            .map(Some)
+            .map_err(|e| vault_error(e.to_string()))
+    }
+
+    /// 写入一项，已存在时覆盖
+    pub fn set(&self, name: &str, value: &str) -> Result<(), SecretError> {
+        let _guard = self.lock.lock().map_err(|e| vault_error(e.to_string()))?;
+
+        let mut file = match self.read()? {
+            Some(file) => file,
+            None => VaultFile {
+                version: VAULT_VERSION,
+                salt: STANDARD.encode(random_bytes::<SALT_LEN>()?),
+                entries: BTreeMap::new(),
+            },
+        };
+
+        let cipher = cipher(&file.salt)?;
+        let nonce = random_bytes::<NONCE_LEN>()?;
+
+        let data = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| vault_error("failed to encrypt"))?;
+
+        file.entries.insert(
+            name.to_string(),
+            SealedEntry {
+                nonce: STANDARD.encode(nonce),
+                data: STANDARD.encode(data),
+            },
+        );
+
+        self.write(&file)
+    }
+
+    /// 删除一项，返回是否存在
+    pub fn delete(&self, name: &str) -> Result<bool, SecretError> {
+        let _guard = self.lock.lock().map_err(|e| vault_error(e.to_string()))?;
+
+        let Some(mut file) = self.read()? else {
+            return Ok(false);
+        };
+
+        if file.entries.remove(name).is_none() {
+            return Ok(false);
+        }
+
+        if file.entries.is_empty() {
+            fs::remove_file(&self.path).map_err(|e| vault_error(e.to_string()))?;
+        } else {
+            self.write(&file)?;
+        }
+
+        Ok(true)
+    }
+
+    fn read(&self) -> Result<Option<VaultFile>, SecretError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read(&self.path).map_err(|e| vault_error(e.to_string()))?;
+
+        let file: VaultFile =
+            serde_json::from_slice(&content).map_err(|e| vault_error(e.to_string()))?;
+
+        if file.version != VAULT_VERSION {
+            return Err(vault_error(format!(
+                "unsupported vault version {}",
+                file.version
+            )));
+        }
+
+        Ok(Some(file))
+    }
+
+    // 先写临时文件再重命名，避免写入中断时损坏已有内容
+    fn write(&self, file: &VaultFile) -> Result<(), SecretError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| vault_error(e.to_string()))?;
+        }
+
+        let content = serde_json::to_vec(file).map_err(|e| vault_error(e.to_string()))?;
+        let temp_path = self.path.with_extension("tmp");
+
+        fs::write(&temp_path, content).map_err(|e| vault_error(e.to_string()))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let _ = fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600));
+        }
+
+        fs::rename(&temp_path, &self.path).map_err(|e| vault_error(e.to_string()))
+    }
+}
+
+fn cipher(salt: &str) -> Result<ChaCha20Poly1305, SecretError> {
+    let salt = decode(salt)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"eco-secret-vault");
+    hasher.update(machine_id().as_bytes());
+    hasher.update(&salt);
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&hasher.finalize())))
+}
+
+// 本机标识，读取不到时退回到主机名和用户名
+fn machine_id() -> String {
+    #[cfg(target_os = "linux")]
+    for path in ["/etc/machine-id", "/var/lib/dbus/machine-id"] {
+        if let Ok(id) = fs::read_to_string(path) {
+            let id = id.trim();
+
+            if !id.is_empty() {
+                return id.to_string();
+            }
+        }
+    }
+
+    ["COMPUTERNAME", "HOSTNAME", "USERNAME", "USER"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], SecretError> {
+    let mut bytes = [0u8; N];
+
+    getrandom::getrandom(&mut bytes).map_err(|e| vault_error(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, SecretError> {
+    STANDARD
+        .decode(value)
+        .map_err(|e| vault_error(e.to_string()))
+}
+
+fn vault_error(message: impl Into<String>) -> SecretError {
+    SecretError::Vault(message.into())
+}
@@ -1,7 +1,18 @@
 const COMMANDS: &[&str] = &[
     "start_selection_monitor",
     "stop_selection_monitor",
+    "restart_selection_monitor",
+    "selection_monitor_status",
     "get_selected_text",
+    "show_selection_toolbar",
+    "hide_selection_toolbar",
+    "set_selection_hide_config",
+    "ai_chat_stream",
+    "cancel_ai_request",
+    "render_prompt",
+    "validate_prompt",
+    "list_transforms",
+    "run_transform",
 ];
 
 fn main() {
@@ -0,0 +1,454 @@
+//! 流式对话请求
+//!
+//! 请求格式由 [`ProviderKind`] 对应的 [`ChatProvider`] 决定。
+//! 不依赖 Tauri，可以直接指向本地的模拟服务器验证解析、超时和错误处理。
+
+use futures_util::StreamExt;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::timeout;
+
+use super::ndjson::NdjsonDecoder;
+use super::provider::{error_message, ChatProvider, ProviderKind, StreamFormat};
+use super::sse::SseDecoder;
+
+/// 对话消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// 请求的超时时间（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatTimeouts {
+    /// 建立连接的超时时间
+    pub connect: u64,
+    /// 等待响应头和两段数据之间的最长间隔
+    pub idle: u64,
+    /// 整个请求的超时时间，为 0 时不限制
+    pub total: u64,
+}
+
+impl Default for ChatTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: 10_000,
+            idle: 30_000,
+            total: 180_000,
+        }
+    }
+}
+
+/// 对话请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRequest {
+    /// 服务的类型
+    #[serde(default)]
+    pub provider: ProviderKind,
+    /// 接口地址，例如 `https://api.openai.com`
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// 保存 API Key 的密钥名称，没有传入 `api_key` 时在 Rust 端读取，避免密钥经过前端
+    #[serde(default)]
+    pub api_key_secret: Option<String>,
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// 最大输出长度，部分服务要求必须指定
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub timeouts: ChatTimeouts,
+}
+
+/// 对话结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatCompletion {
+    /// 完整的回复内容，取消时为已经收到的部分
+    pub content: String,
+    pub finish_reason: Option<String>,
+    pub cancelled: bool,
+}
+
+/// 请求失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatError {
+    /// 配置错误，例如缺少模型或地址无效
+    InvalidRequest(String),
+    /// 建立连接或发送请求失败
+    Connect(String),
+    /// 连接超时或长时间没有收到数据
+    Timeout,
+    /// 接口返回了错误状态码
+    Status { status: u16, message: String },
+    /// 读取或解析响应失败
+    Stream(String),
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRequest(message) => write!(f, "Invalid request: {}", message),
+            Self::Connect(message) => write!(f, "Failed to connect: {}", message),
+            Self::Timeout => write!(f, "Request timed out"),
+            Self::Status { status, message } => write!(f, "HTTP {}: {}", status, message),
+            Self::Stream(message) => write!(f, "Failed to read response: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ChatError {}
+
+impl From<reqwest::Error> for ChatError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_connect() || error.is_request() || error.is_builder() {
+            Self::Connect(error.to_string())
+        } else {
+            Self::Stream(error.to_string())
+        }
+    }
+}
+
+/// 发送流式对话请求，每收到一段回复就调用一次 `on_delta`
+///
+/// 整个请求的超时由调用方控制，这里只处理连接超时和数据间隔超时。
+pub async fn stream_chat<F>(
+    request: &ChatRequest,
+    mut on_delta: F,
+) -> Result<ChatCompletion, ChatError>
+where
+    F: FnMut(&str),
+{
+    if request.model.trim().is_empty() {
+        return Err(ChatError::InvalidRequest("model is empty".to_string()));
+    }
+
+    if request.base_url.trim().is_empty() {
+        return Err(ChatError::InvalidRequest("base URL is empty".to_string()));
+    }
+
+    let timeouts = request.timeouts;
+    let idle = Duration::from_millis(timeouts.idle.max(1));
+
+    let client = Client::builder()
+        .connect_timeout(Duration::from_millis(timeouts.connect.max(1)))
+        .build()
+        .map_err(|e| ChatError::InvalidRequest(e.to_string()))?;
+
+    let provider = request.provider.provider();
+    let api_key = request.api_key.as_deref().filter(|key| !key.is_empty());
+
+    let accept = match provider.stream_format() {
+        StreamFormat::Sse => "text/event-stream",
+        StreamFormat::Ndjson => "application/x-ndjson",
+    };
+
+    let mut builder = client
+        .post(provider.endpoint(request))
+        .header("Accept", accept)
+        .json(&provider.body(request));
+
+    for (name, value) in provider.headers(api_key) {
+        builder = builder.header(name, value);
+    }
+
+    let response = timeout(idle, builder.send())
+        .await
+        .map_err(|_| ChatError::Timeout)??;
+
+    let status = response.status();
+
+    if !status.is_success() {
+        let text = timeout(idle, response.text())
+            .await
+            .map_err(|_| ChatError::Timeout)?
+            .unwrap_or_default();
+
+        return Err(status_error(status, &text));
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut decoder = Decoder::new(provider.stream_format());
+    let mut completion = ChatCompletion::default();
+
+    loop {
+        let chunk = match timeout(idle, stream.next()).await {
+            Err(_) => return Err(ChatError::Timeout),
+            Ok(None) => break,
+            Ok(Some(chunk)) => chunk?,
+        };
+
+        for data in decoder.push(&chunk) {
+            if handle_data(provider, &data, &mut completion, &mut on_delta)? {
+                return Ok(completion);
+            }
+        }
+    }
+
+    if let Some(data) = decoder.finish() {
+        handle_data(provider, &data, &mut completion, &mut on_delta)?;
+    }
+
+    Ok(completion)
+}
+
+// 按照服务的格式拆分响应
+enum Decoder {
+    Sse(SseDecoder),
+    Ndjson(NdjsonDecoder),
+}
+
+impl Decoder {
+    fn new(format: StreamFormat) -> Self {
+        match format {
+            StreamFormat::Sse => Self::Sse(SseDecoder::new()),
+            StreamFormat::Ndjson => Self::Ndjson(NdjsonDecoder::new()),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        match self {
+            Self::Sse(decoder) => decoder.push(chunk),
+            Self::Ndjson(decoder) => decoder.push(chunk),
+        }
+    }
+
+    fn finish(&mut self) -> Option<String> {
+        match self {
+            Self::Sse(decoder) => decoder.finish(),
+            Self::Ndjson(decoder) => decoder.finish(),
+        }
+    }
+}
+
+// 处理一条数据，已经结束时返回 true
+fn handle_data<F>(
+    provider: &dyn ChatProvider,
+    data: &str,
+    completion: &mut ChatCompletion,
+    on_delta: &mut F,
+) -> Result<bool, ChatError>
+where
+    F: FnMut(&str),
+{
+    let data = data.trim();
+
+    if data.is_empty() {
+        return Ok(false);
+    }
+
+    let chunk = provider.parse_chunk(data)?;
+
+    if let Some(delta) = chunk.delta.filter(|delta| !delta.is_empty()) {
+        completion.content.push_str(&delta);
+        on_delta(&delta);
+    }
+
+    if chunk.finish_reason.is_some() {
+        completion.finish_reason = chunk.finish_reason;
+    }
+
+    Ok(chunk.done)
+}
+
+fn status_error(status: StatusCode, text: &str) -> ChatError {
+    let message = serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|value| error_message(&value))
+        .unwrap_or_else(|| {
+            let text = text.trim();
+
+            if text.is_empty() {
+                status.canonical_reason().unwrap_or_default().to_string()
+            } else {
+                text.to_string()
+            }
+        });
+
+    ChatError::Status {
+        status: status.as_u16(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock::{MockServer, Step};
+
+    const SSE: &str = "text/event-stream";
+
+    fn request(url: &str) -> ChatRequest {
+        ChatRequest {
+            provider: ProviderKind::OpenAi,
+            base_url: url.to_string(),
+            api_key: Some("sk-test".to_string()),
+            api_key_secret: None,
+            model: "test-model".to_string(),
+            messages: vec![ChatMessage::user("hi")],
+            temperature: None,
+            max_tokens: None,
+            timeouts: ChatTimeouts {
+                connect: 1_000,
+                idle: 2_000,
+                total: 0,
+            },
+        }
+    }
+
+    fn with_idle(url: &str, idle: u64) -> ChatRequest {
+        let mut request = request(url);
+
+        request.timeouts.idle = idle;
+
+        request
+    }
+
+    async fn collect(request: &ChatRequest) -> (Vec<String>, Result<ChatCompletion, ChatError>) {
+        let mut deltas = Vec::new();
+
+        let result = stream_chat(request, |delta| deltas.push(delta.to_string())).await;
+
+        (deltas, result)
+    }
+
+    #[tokio::test]
+    async fn streams_completion() {
+        let server = MockServer::stream(
+            SSE,
+            &[
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\ndata: {\"choi",
+                "ces\":[{\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: [DONE]\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"ignored\"}}]}\n\n",
+            ],
+        );
+
+        let (deltas, result) = collect(&request(&server.url)).await;
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(
+            result.unwrap(),
+            ChatCompletion {
+                content: "Hello".to_string(),
+                finish_reason: Some("stop".to_string()),
+                cancelled: false,
+            }
+        );
+
+        let received = server.request();
+
+        assert_eq!(received.path, "/v1/chat/completions");
+        assert_eq!(received.header("accept"), Some(SSE));
+        assert_eq!(received.header("authorization"), Some("Bearer sk-test"));
+    }
+
+    #[tokio::test]
+    async fn completes_without_done_marker() {
+        let server = MockServer::stream(
+            SSE,
+            &["data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}"],
+        );
+
+        let (deltas, result) = collect(&request(&server.url)).await;
+
+        assert_eq!(deltas, ["Hi"]);
+        assert_eq!(result.unwrap().content, "Hi");
+    }
+
+    #[tokio::test]
+    async fn times_out_waiting_for_first_byte() {
+        let server = MockServer::start(vec![Step::Sleep(Duration::from_secs(2))]);
+
+        let (deltas, result) = collect(&with_idle(&server.url, 200)).await;
+
+        assert!(deltas.is_empty());
+        assert_eq!(result, Err(ChatError::Timeout));
+    }
+
+    #[tokio::test]
+    async fn times_out_when_stream_stalls() {
+        let server = MockServer::start(vec![
+            Step::Head {
+                status: 200,
+                content_type: SSE,
+            },
+            Step::Send("data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n"),
+            Step::Sleep(Duration::from_secs(2)),
+            Step::Send("data: [DONE]\n\n"),
+        ]);
+
+        let (deltas, result) = collect(&with_idle(&server.url, 200)).await;
+
+        assert_eq!(deltas, ["Hel"]);
+        assert_eq!(result, Err(ChatError::Timeout));
+    }
+
+    #[tokio::test]
+    async fn reports_error_status() {
+        let server = MockServer::start(vec![
+            Step::Head {
+                status: 401,
+                content_type: "application/json",
+            },
+            Step::Send("{\"error\":{\"message\":\"Invalid API key\"}}"),
+        ]);
+
+        let (_, result) = collect(&request(&server.url)).await;
+
+        assert_eq!(
+            result,
+            Err(ChatError::Status {
+                status: 401,
+                message: "Invalid API key".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_error_in_stream() {
+        let server = MockServer::stream(
+            SSE,
+            &[
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+                "data: {\"error\":{\"message\":\"Overloaded\"}}\n\n",
+            ],
+        );
+
+        let (deltas, result) = collect(&request(&server.url)).await;
+
+        assert_eq!(deltas, ["Hel"]);
+        assert_eq!(result, Err(ChatError::Stream("Overloaded".to_string())));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_request() {
+        let mut invalid = request("http://127.0.0.1:1");
+
+        invalid.model = " ".to_string();
+
+        let (_, result) = collect(&invalid).await;
+
+        assert!(matches!(result, Err(ChatError::InvalidRequest(_))));
+    }
+}
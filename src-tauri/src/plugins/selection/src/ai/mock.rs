@@ -0,0 +1,152 @@
+//! 测试用的本地 HTTP 服务器
+//!
+//! 每个服务器只处理一次请求，按顺序写出响应的各个分段，分段之间可以停顿，
+//! 用来模拟流式回复、网络分块和长时间没有数据的情况。
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// 响应的一个步骤
+pub enum Step {
+    /// 写出响应头
+    Head {
+        status: u16,
+        content_type: &'static str,
+    },
+    /// 写出一段数据
+    Send(&'static str),
+    /// 停顿一段时间
+    Sleep(Duration),
+}
+
+/// 服务器收到的请求
+#[derive(Debug)]
+pub struct MockRequest {
+    pub path: String,
+    /// 小写的请求头名称和对应的值
+    pub headers: Vec<(String, String)>,
+    pub body: Value,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub struct MockServer {
+    pub url: String,
+    request: Receiver<MockRequest>,
+}
+
+impl MockServer {
+    /// 启动服务器，收到请求后依次执行 `steps`，结束后关闭连接
+    pub fn start(steps: Vec<Step>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, request) = mpsc::channel();
+
+        thread::spawn(move || {
+            let Ok((stream, _)) = listener.accept() else {
+                return;
+            };
+
+            let _ = sender.send(read_request(&stream));
+
+            respond(stream, steps);
+        });
+
+        Self { url, request }
+    }
+
+    /// 以指定的类型返回流式数据，每个分段单独写出
+    pub fn stream(content_type: &'static str, chunks: &[&'static str]) -> Self {
+        let mut steps = vec![Step::Head {
+            status: 200,
+            content_type,
+        }];
+
+        steps.extend(chunks.iter().map(|chunk| Step::Send(chunk)));
+
+        Self::start(steps)
+    }
+
+    /// 服务器收到的请求
+    pub fn request(&self) -> MockRequest {
+        self.request.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+}
+
+fn read_request(stream: &TcpStream) -> MockRequest {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+
+    reader.read_line(&mut line).unwrap();
+
+    let path = line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let mut headers = Vec::new();
+
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+
+        headers.push((name.to_lowercase(), value.trim().to_string()));
+    }
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+
+    let mut body = vec![0; length];
+
+    reader.read_exact(&mut body).unwrap();
+
+    MockRequest {
+        path,
+        headers,
+        body: serde_json::from_slice(&body).unwrap_or_default(),
+    }
+}
+
+fn respond(mut stream: TcpStream, steps: Vec<Step>) {
+    for step in steps {
+        let result = match step {
+            Step::Head {
+                status,
+                content_type,
+            } => write!(
+                stream,
+                "HTTP/1.1 {} Mock\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+                status, content_type
+            ),
+            Step::Send(data) => stream.write_all(data.as_bytes()),
+            Step::Sleep(duration) => {
+                thread::sleep(duration);
+
+                Ok(())
+            }
+        };
+
+        // 客户端已经断开
+        if result.and_then(|_| stream.flush()).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! 划词助手 Agent 的 AI 请求
+//!
+//! 请求在 Rust 端发出，流式回复通过 [`Channel`](tauri::ipc::Channel) 逐段发送给前端，
+//! 每个请求由前端生成的 id 标识，可以随时取消。
+
+mod client;
+#[cfg(test)]
+mod mock;
+mod ndjson;
+mod provider;
+mod sse;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub use client::{stream_chat, ChatCompletion, ChatError, ChatMessage, ChatRequest, ChatTimeouts};
+pub use ndjson::NdjsonDecoder;
+pub use provider::{
+    AnthropicProvider, ChatProvider, GeminiProvider, OllamaProvider, OpenAiProvider, ProviderKind,
+    StreamChunk, StreamFormat,
+};
+pub use sse::SseDecoder;
+
+/// 正在进行的 AI 请求，用于取消
+#[derive(Default)]
+pub struct AiRequests {
+    pending: Mutex<HashMap<String, Pending>>,
+    next_token: AtomicU64,
+}
+
+struct Pending {
+    // 区分同 id 的先后两次请求
+    token: u64,
+    cancel: oneshot::Sender<()>,
+}
+
+impl AiRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记请求，已有相同 id 的请求会被取消
+    fn register(&self, id: &str) -> (u64, oneshot::Receiver<()>) {
+        let (cancel, receiver) = oneshot::channel();
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(previous) = pending.insert(id.to_string(), Pending { token, cancel }) {
+                let _ = previous.cancel.send(());
+            }
+        }
+
+        (token, receiver)
+    }
+
+    fn finish(&self, id: &str, token: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            // 被同 id 的新请求替换时不能移除新请求的登记
+            if pending
+                .get(id)
+                .is_some_and(|pending| pending.token == token)
+            {
+                pending.remove(id);
+            }
+        }
+    }
+
+    /// 取消请求，请求不存在或已经结束时返回 false
+    pub fn cancel(&self, id: &str) -> bool {
+        let pending = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(id));
+
+        match pending {
+            Some(pending) => pending.cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// 取消所有请求
+    pub fn cancel_all(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            for (_, pending) in pending.drain() {
+                let _ = pending.cancel.send(());
+            }
+        }
+    }
+
+    /// 执行可取消的流式请求
+    ///
+    /// 取消时返回已经收到的内容，并把 `cancelled` 标记为 true。
+    pub async fn run<F>(
+        &self,
+        id: &str,
+        request: ChatRequest,
+        mut on_delta: F,
+    ) -> Result<ChatCompletion, ChatError>
+    where
+        F: FnMut(&str),
+    {
+        let (token, mut cancel) = self.register(id);
+        let mut partial = String::new();
+
+        let total = request.timeouts.total;
+
+        let result = {
+            let chat = stream_chat(&request, |delta| {
+                partial.push_str(delta);
+                on_delta(delta);
+            });
+
+            let chat = async {
+                if total == 0 {
+                    chat.await
+                } else {
+                    tokio::time::timeout(Duration::from_millis(total), chat)
+                        .await
+                        .unwrap_or(Err(ChatError::Timeout))
+                }
+            };
+
+            tokio::select! {
+                result = chat => Some(result),
+                _ = &mut cancel => None,
+            }
+        };
+
+        self.finish(id, token);
+
+        match result {
+            Some(result) => result,
+            None => Ok(ChatCompletion {
+                content: partial,
+                finish_reason: None,
+                cancelled: true,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{MockServer, Step};
+    use super::*;
+    use std::time::Instant;
+
+    const SSE: &str = "text/event-stream";
+    const FIRST: &str = "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n";
+    const SECOND: &str =
+        "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n";
+
+    fn request(url: &str) -> ChatRequest {
+        ChatRequest {
+            provider: ProviderKind::OpenAi,
+            base_url: url.to_string(),
+            api_key: None,
+            api_key_secret: None,
+            model: "test-model".to_string(),
+            messages: vec![ChatMessage::user("hi")],
+            temperature: None,
+            max_tokens: None,
+            timeouts: ChatTimeouts {
+                connect: 1_000,
+                idle: 10_000,
+                total: 0,
+            },
+        }
+    }
+
+    // 第一段数据之后停顿，停顿期间连接保持打开
+    fn stalled_server(pause: Duration) -> MockServer {
+        MockServer::start(vec![
+            Step::Head {
+                status: 200,
+                content_type: SSE,
+            },
+            Step::Send(FIRST),
+            Step::Sleep(pause),
+            Step::Send(SECOND),
+            Step::Send("data: [DONE]\n\n"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn run_returns_completion() {
+        let server = MockServer::stream(SSE, &[FIRST, SECOND, "data: [DONE]\n\n"]);
+        let requests = AiRequests::new();
+        let mut deltas = Vec::new();
+
+        let completion = requests
+            .run("a", request(&server.url), |delta| {
+                deltas.push(delta.to_string())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(completion.content, "Hello");
+        assert_eq!(completion.finish_reason.as_deref(), Some("stop"));
+        assert!(!completion.cancelled);
+
+        // 结束后登记已经移除
+        assert!(!requests.cancel("a"));
+    }
+
+    #[tokio::test]
+    async fn cancel_returns_partial_content() {
+        let server = stalled_server(Duration::from_secs(5));
+        let requests = AiRequests::new();
+        let mut cancelled = false;
+        let start = Instant::now();
+
+        let completion = requests
+            .run("a", request(&server.url), |_| {
+                // 收到第一段后取消，不等待服务器的停顿结束
+                cancelled = requests.cancel("a");
+            })
+            .await
+            .unwrap();
+
+        assert!(cancelled);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            completion,
+            ChatCompletion {
+                content: "Hel".to_string(),
+                finish_reason: None,
+                cancelled: true,
+            }
+        );
+        assert!(!requests.cancel("a"));
+    }
+
+    #[tokio::test]
+    async fn cancel_from_another_task() {
+        let server = stalled_server(Duration::from_secs(5));
+        let requests = AiRequests::new();
+
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+
+            requests.cancel("a")
+        };
+
+        let (completion, cancelled) =
+            tokio::join!(requests.run("a", request(&server.url), |_| {}), cancel);
+
+        assert!(cancelled);
+
+        let completion = completion.unwrap();
+
+        assert!(completion.cancelled);
+        assert_eq!(completion.content, "Hel");
+    }
+
+    #[tokio::test]
+    async fn total_timeout_stops_slow_stream() {
+        let server = stalled_server(Duration::from_secs(5));
+        let requests = AiRequests::new();
+        let mut request = request(&server.url);
+
+        request.timeouts.total = 300;
+
+        let result = requests.run("a", request, |_| {}).await;
+
+        assert_eq!(result, Err(ChatError::Timeout));
+        assert!(!requests.cancel("a"));
+    }
+}
@@ -0,0 +1,83 @@
+//! 换行分隔的 JSON 流（Ollama 使用的格式）的增量解析
+
+/// NDJSON 解析器
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入一段数据，返回其中已经完整的非空行
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+
+        while let Some(index) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=index).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+
+        lines
+    }
+
+    /// 数据结束时输出缓存中剩余的一行
+    pub fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&self.buffer).trim().to_string();
+
+        self.buffer.clear();
+
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_lines_split_across_chunks() {
+        let mut decoder = NdjsonDecoder::new();
+
+        assert!(decoder.push(b"{\"done\":").is_empty());
+        assert_eq!(
+            decoder.push(b"false}\n{\"done\":true}\n"),
+            ["{\"done\":false}", "{\"done\":true}"]
+        );
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn skips_blank_lines_and_trims_crlf() {
+        let mut decoder = NdjsonDecoder::new();
+
+        assert_eq!(decoder.push(b"\n{\"a\":1}\r\n\r\n  \n"), ["{\"a\":1}"]);
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let bytes = "{\"text\":\"你好\"}\n".as_bytes();
+        let mut decoder = NdjsonDecoder::new();
+
+        assert!(decoder.push(&bytes[..10]).is_empty());
+        assert_eq!(decoder.push(&bytes[10..]), ["{\"text\":\"你好\"}"]);
+    }
+
+    #[test]
+    fn flushes_last_line_on_finish() {
+        let mut decoder = NdjsonDecoder::new();
+
+        assert!(decoder.push(b"{\"done\":true}").is_empty());
+        assert_eq!(decoder.finish().as_deref(), Some("{\"done\":true}"));
+        assert_eq!(decoder.finish(), None);
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::{json, Value};
+
+use super::{join_url, parse_json, ChatProvider, StreamChunk};
+use crate::ai::client::{ChatError, ChatRequest};
+
+const API_VERSION: &str = "2023-06-01";
+
+/// 接口要求必须指定最大输出长度
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Anthropic 的 `/v1/messages`
+pub struct AnthropicProvider;
+
+impl ChatProvider for AnthropicProvider {
+    fn endpoint(&self, request: &ChatRequest) -> String {
+        join_url(&request.base_url, "/v1", "/messages")
+    }
+
+    fn headers(&self, api_key: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("anthropic-version", API_VERSION.to_string())];
+
+        if let Some(key) = api_key {
+            headers.push(("x-api-key", key.to_string()));
+        }
+
+        headers
+    }
+
+    // system 消息放到单独的字段中
+    fn body(&self, request: &ChatRequest) -> Value {
+        let system: Vec<&str> = request
+            .messages
+            .iter()
+            .filter(|message| message.role == "system")
+            .map(|message| message.content.as_str())
+            .collect();
+
+        let messages: Vec<Value> = request
+            .messages
+            .iter()
+            .filter(|message| message.role != "system")
+            .map(|message| json!({ "role": message.role, "content": message.content }))
+            .collect();
+
+        let mut body = json!({
+            "model": request.model,
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "stream": true,
+        });
+
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        body
+    }
+
+    fn parse_chunk(&self, data: &str) -> Result<StreamChunk, ChatError> {
+        let value = parse_json(data)?;
+
+        let chunk = match value["type"].as_str() {
+            Some("content_block_delta") => StreamChunk {
+                delta: value["delta"]["text"].as_str().map(str::to_string),
+                ..Default::default()
+            },
+            Some("message_delta") => StreamChunk {
+                finish_reason: value["delta"]["stop_reason"].as_str().map(str::to_string),
+                ..Default::default()
+            },
+            Some("message_stop") => StreamChunk {
+                done: true,
+                ..Default::default()
+            },
+            _ => StreamChunk::default(),
+        };
+
+        Ok(chunk)
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+
+use super::{join_url, parse_json, ChatProvider, StreamChunk};
+use crate::ai::client::{ChatError, ChatRequest};
+
+/// Google Gemini 的 `streamGenerateContent`，默认地址为 `https://generativelanguage.googleapis.com`
+pub struct GeminiProvider;
+
+impl ChatProvider for GeminiProvider {
+    fn endpoint(&self, request: &ChatRequest) -> String {
+        let model = request.model.trim();
+        let model = model.strip_prefix("models/").unwrap_or(model);
+
+        let path = format!("/models/{}:streamGenerateContent", model);
+
+        format!("{}?alt=sse", join_url(&request.base_url, "/v1beta", &path))
+    }
+
+    fn headers(&self, api_key: Option<&str>) -> Vec<(&'static str, String)> {
+        api_key
+            .map(|key| vec![("x-goog-api-key", key.to_string())])
+            .unwrap_or_default()
+    }
+
+    // assistant 对应 model，system 消息放到 systemInstruction 中
+    fn body(&self, request: &ChatRequest) -> Value {
+        let system: Vec<Value> = request
+            .messages
+            .iter()
+            .filter(|message| message.role == "system")
+            .map(|message| json!({ "text": message.content }))
+            .collect();
+
+        let contents: Vec<Value> = request
+            .messages
+            .iter()
+            .filter(|message| message.role != "system")
+            .map(|message| {
+                let role = if message.role == "assistant" {
+                    "model"
+                } else {
+                    "user"
+                };
+
+                json!({ "role": role, "parts": [{ "text": message.content }] })
+            })
+            .collect();
+
+        let mut config = json!({});
+
+        if let Some(temperature) = request.temperature {
+            config["temperature"] = json!(temperature);
+        }
+
+        if let Some(max_tokens) = request.max_tokens {
+            config["maxOutputTokens"] = json!(max_tokens);
+        }
+
+        let mut body = json!({
+            "contents": contents,
+            "generationConfig": config,
+        });
+
+        if !system.is_empty() {
+            body["systemInstruction"] = json!({ "parts": system });
+        }
+
+        body
+    }
+
+    fn parse_chunk(&self, data: &str) -> Result<StreamChunk, ChatError> {
+        let value = parse_json(data)?;
+
+        let Some(candidate) = value["candidates"].get(0) else {
+            return Ok(StreamChunk::default());
+        };
+
+        let text: String = candidate["content"]["parts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|part| part["text"].as_str())
+            .collect();
+
+        Ok(StreamChunk {
+            delta: (!text.is_empty()).then_some(text),
+            finish_reason: candidate["finishReason"].as_str().map(str::to_string),
+            done: false,
+        })
+    }
+}
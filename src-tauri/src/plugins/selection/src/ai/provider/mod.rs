@@ -0,0 +1,327 @@
+//! 不同 AI 服务的请求和响应格式
+//!
+//! 每种服务实现 [`ChatProvider`]，负责拼接地址、请求头和请求体，以及解析流式响应中的一条数据。
+//! 发送请求、超时和取消由 [`stream_chat`](super::stream_chat) 统一处理。
+
+mod anthropic;
+mod gemini;
+mod ollama;
+mod openai;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::client::{ChatError, ChatRequest};
+
+pub use anthropic::AnthropicProvider;
+pub use gemini::GeminiProvider;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiProvider;
+
+/// AI 服务的类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// OpenAI 兼容的 `/v1/chat/completions`
+    #[default]
+    OpenAi,
+    /// 本地 Ollama 的 `/api/chat`
+    Ollama,
+    /// Anthropic 的 `/v1/messages`
+    Anthropic,
+    /// Google Gemini 的 `streamGenerateContent`
+    Gemini,
+}
+
+impl ProviderKind {
+    /// 对应的请求格式
+    pub fn provider(self) -> &'static dyn ChatProvider {
+        match self {
+            Self::OpenAi => &OpenAiProvider,
+            Self::Ollama => &OllamaProvider,
+            Self::Anthropic => &AnthropicProvider,
+            Self::Gemini => &GeminiProvider,
+        }
+    }
+}
+
+/// 流式响应的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-Sent Events
+    Sse,
+    /// 每行一个 JSON 对象
+    Ndjson,
+}
+
+/// 从一条流式数据中解析出的内容
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamChunk {
+    /// 新增的回复内容
+    pub delta: Option<String>,
+    /// 结束的原因
+    pub finish_reason: Option<String>,
+    /// 是否已经结束，之后的数据会被忽略
+    pub done: bool,
+}
+
+/// AI 服务的请求格式
+pub trait ChatProvider: Send + Sync {
+    /// 请求的完整地址
+    fn endpoint(&self, request: &ChatRequest) -> String;
+
+    /// 鉴权等额外的请求头
+    fn headers(&self, api_key: Option<&str>) -> Vec<(&'static str, String)>;
+
+    /// 请求体
+    fn body(&self, request: &ChatRequest) -> Value;
+
+    /// 流式响应的格式
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Sse
+    }
+
+    /// 解析一条流式数据
+    fn parse_chunk(&self, data: &str) -> Result<StreamChunk, ChatError>;
+}
+
+/// 拼接地址，`base_url` 已经带有 `version` 时不再重复添加
+pub(crate) fn join_url(base_url: &str, version: &str, path: &str) -> String {
+    let base_url = base_url.trim().trim_end_matches('/');
+
+    if base_url.ends_with(path) {
+        return base_url.to_string();
+    }
+
+    if version.is_empty() || base_url.ends_with(version) {
+        format!("{}{}", base_url, path)
+    } else {
+        format!("{}{}{}", base_url, version, path)
+    }
+}
+
+/// 解析一条 JSON 数据，数据中包含错误对象时返回错误
+pub(crate) fn parse_json(data: &str) -> Result<Value, ChatError> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|e| ChatError::Stream(format!("{}: {}", e, data)))?;
+
+    // 部分服务在流中返回错误对象而不是错误状态码
+    match error_message(&value) {
+        Some(message) => Err(ChatError::Stream(message)),
+        None => Ok(value),
+    }
+}
+
+/// 从响应中取出错误信息，兼容 `{"error": {"message": ...}}` 和 `{"error": "..."}`
+pub(crate) fn error_message(value: &Value) -> Option<String> {
+    let error = value.get("error").filter(|error| !error.is_null())?;
+
+    error["message"]
+        .as_str()
+        .or_else(|| error.as_str())
+        .map(str::to_string)
+        .or_else(|| Some(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::client::{stream_chat, ChatCompletion, ChatMessage, ChatTimeouts};
+    use crate::ai::mock::MockServer;
+    use serde_json::json;
+
+    fn request(provider: ProviderKind, base_url: &str, model: &str) -> ChatRequest {
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+
+        ChatRequest {
+            provider,
+            base_url: base_url.to_string(),
+            api_key: Some("secret".to_string()),
+            api_key_secret: None,
+            model: model.to_string(),
+            messages: vec![
+                message("system", "Be brief"),
+                message("user", "hi"),
+                message("assistant", "hello"),
+                message("user", "again"),
+            ],
+            temperature: Some(0.5),
+            max_tokens: Some(256),
+            timeouts: ChatTimeouts {
+                connect: 1_000,
+                idle: 2_000,
+                total: 0,
+            },
+        }
+    }
+
+    async fn chat(request: &ChatRequest) -> (Vec<String>, ChatCompletion) {
+        let mut deltas = Vec::new();
+
+        let completion = stream_chat(request, |delta| deltas.push(delta.to_string()))
+            .await
+            .unwrap();
+
+        (deltas, completion)
+    }
+
+    fn completion(content: &str, finish_reason: &str) -> ChatCompletion {
+        ChatCompletion {
+            content: content.to_string(),
+            finish_reason: Some(finish_reason.to_string()),
+            cancelled: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn openai_stream() {
+        let server = MockServer::stream(
+            "text/event-stream",
+            &[
+                "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\ndata: {\"choices\":[{\"de",
+                "lta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: [DONE]\n\n",
+            ],
+        );
+
+        let base_url = format!("{}/v1/", server.url);
+        let request = request(ProviderKind::OpenAi, &base_url, "gpt-4o-mini");
+        let (deltas, completion) = chat(&request).await;
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(completion, self::completion("Hello", "stop"));
+
+        let received = server.request();
+
+        assert_eq!(received.path, "/v1/chat/completions");
+        assert_eq!(received.header("authorization"), Some("Bearer secret"));
+        assert_eq!(received.body["model"], "gpt-4o-mini");
+        assert_eq!(received.body["stream"], true);
+        assert_eq!(received.body["max_tokens"], 256);
+        assert_eq!(received.body["temperature"], 0.5);
+        assert_eq!(received.body["messages"][0]["role"], "system");
+        assert_eq!(received.body["messages"].as_array().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn ollama_stream() {
+        let server = MockServer::stream(
+            "application/x-ndjson",
+            &[
+                "{\"model\":\"llama3\",\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n{\"model\":\"lla",
+                "ma3\",\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n",
+                "{\"model\":\"llama3\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"done_reason\":\"stop\"}\n",
+            ],
+        );
+
+        let request = request(ProviderKind::Ollama, &server.url, "llama3");
+        let (deltas, completion) = chat(&request).await;
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(completion, self::completion("Hello", "stop"));
+
+        let received = server.request();
+
+        assert_eq!(received.path, "/api/chat");
+        assert_eq!(received.header("accept"), Some("application/x-ndjson"));
+        assert_eq!(received.body["model"], "llama3");
+        assert_eq!(received.body["options"]["num_predict"], 256);
+        assert_eq!(received.body["messages"][0]["role"], "system");
+    }
+
+    #[tokio::test]
+    async fn anthropic_stream() {
+        let server = MockServer::stream(
+            "text/event-stream",
+            &[
+                "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"content\":[]}}\n\n",
+                "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+                "event: ping\ndata: {\"type\":\"ping\"}\n\n",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\nevent: content_block_delta\ndata: {\"type\":\"content_block_del",
+                "ta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n",
+                "event: content_block_stop\ndata: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+                "event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":2}}\n\n",
+                "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+            ],
+        );
+
+        let request = request(ProviderKind::Anthropic, &server.url, "claude-3-5-haiku");
+        let (deltas, completion) = chat(&request).await;
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(completion, self::completion("Hello", "end_turn"));
+
+        let received = server.request();
+
+        assert_eq!(received.path, "/v1/messages");
+        assert_eq!(received.header("x-api-key"), Some("secret"));
+        assert_eq!(received.header("anthropic-version"), Some("2023-06-01"));
+        assert_eq!(received.header("authorization"), None);
+        assert_eq!(received.body["system"], "Be brief");
+        assert_eq!(received.body["max_tokens"], 256);
+        assert_eq!(
+            received.body["messages"],
+            json!([
+                { "role": "user", "content": "hi" },
+                { "role": "assistant", "content": "hello" },
+                { "role": "user", "content": "again" },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn gemini_stream() {
+        let server = MockServer::stream(
+            "text/event-stream",
+            &[
+                "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hel\"}],\"role\":\"model\"}}]}\r\n\r\n",
+                "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"l\"},{\"text\":\"o\"}],\"role\":\"model\"},",
+                "\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"totalTokenCount\":5}}\r\n\r\n",
+            ],
+        );
+
+        let request = request(ProviderKind::Gemini, &server.url, "models/gemini-1.5-flash");
+        let (deltas, completion) = chat(&request).await;
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(completion, self::completion("Hello", "STOP"));
+
+        let received = server.request();
+
+        assert_eq!(
+            received.path,
+            "/v1beta/models/gemini-1.5-flash:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(received.header("x-goog-api-key"), Some("secret"));
+        assert_eq!(
+            received.body["systemInstruction"],
+            json!({ "parts": [{ "text": "Be brief" }] })
+        );
+        assert_eq!(received.body["contents"][1]["role"], "model");
+        assert_eq!(received.body["generationConfig"]["maxOutputTokens"], 256);
+    }
+
+    #[test]
+    fn joins_url_without_duplicating_version() {
+        assert_eq!(
+            join_url("https://api.example.com", "/v1", "/chat/completions"),
+            "https://api.example.com/v1/chat/completions"
+        );
+        assert_eq!(
+            join_url("https://api.example.com/v1/", "/v1", "/chat/completions"),
+            "https://api.example.com/v1/chat/completions"
+        );
+        assert_eq!(
+            join_url(
+                "https://proxy/v1/chat/completions",
+                "/v1",
+                "/chat/completions"
+            ),
+            "https://proxy/v1/chat/completions"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use serde_json::{json, Value};
+
+use super::{join_url, parse_json, ChatProvider, StreamChunk, StreamFormat};
+use crate::ai::client::{ChatError, ChatRequest};
+
+/// 本地 Ollama 的 `/api/chat`，默认地址为 `http://localhost:11434`
+pub struct OllamaProvider;
+
+impl ChatProvider for OllamaProvider {
+    fn endpoint(&self, request: &ChatRequest) -> String {
+        join_url(&request.base_url, "", "/api/chat")
+    }
+
+    // Ollama 本身不需要鉴权，配置了密钥时按反向代理常用的方式传递
+    fn headers(&self, api_key: Option<&str>) -> Vec<(&'static str, String)> {
+        api_key
+            .map(|key| vec![("Authorization", format!("Bearer {}", key))])
+            .unwrap_or_default()
+    }
+
+    fn body(&self, request: &ChatRequest) -> Value {
+        let mut options = json!({});
+
+        if let Some(temperature) = request.temperature {
+            options["temperature"] = json!(temperature);
+        }
+
+        if let Some(max_tokens) = request.max_tokens {
+            options["num_predict"] = json!(max_tokens);
+        }
+
+        json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": true,
+            "options": options,
+        })
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Ndjson
+    }
+
+    fn parse_chunk(&self, data: &str) -> Result<StreamChunk, ChatError> {
+        let value = parse_json(data)?;
+
+        let done = value["done"].as_bool().unwrap_or(false);
+
+        Ok(StreamChunk {
+            delta: value["message"]["content"].as_str().map(str::to_string),
+            finish_reason: value["done_reason"]
+                .as_str()
+                .map(str::to_string)
+                .filter(|_| done),
+            done,
+        })
+    }
+}
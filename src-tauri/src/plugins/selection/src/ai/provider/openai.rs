@@ -0,0 +1,61 @@
+use serde_json::{json, Value};
+
+use super::{join_url, parse_json, ChatProvider, StreamChunk};
+use crate::ai::client::{ChatError, ChatRequest};
+
+/// 流式响应结束的标记
+const DONE_MARKER: &str = "[DONE]";
+
+/// OpenAI 兼容的 `/v1/chat/completions`
+pub struct OpenAiProvider;
+
+impl ChatProvider for OpenAiProvider {
+    fn endpoint(&self, request: &ChatRequest) -> String {
+        join_url(&request.base_url, "/v1", "/chat/completions")
+    }
+
+    fn headers(&self, api_key: Option<&str>) -> Vec<(&'static str, String)> {
+        api_key
+            .map(|key| vec![("Authorization", format!("Bearer {}", key))])
+            .unwrap_or_default()
+    }
+
+    fn body(&self, request: &ChatRequest) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": true,
+        });
+
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+
+        body
+    }
+
+    fn parse_chunk(&self, data: &str) -> Result<StreamChunk, ChatError> {
+        if data == DONE_MARKER {
+            return Ok(StreamChunk {
+                done: true,
+                ..Default::default()
+            });
+        }
+
+        let value = parse_json(data)?;
+
+        let Some(choice) = value["choices"].get(0) else {
+            return Ok(StreamChunk::default());
+        };
+
+        Ok(StreamChunk {
+            delta: choice["delta"]["content"].as_str().map(str::to_string),
+            finish_reason: choice["finish_reason"].as_str().map(str::to_string),
+            done: false,
+        })
+    }
+}
@@ -0,0 +1,147 @@
+//! Server-Sent Events 的增量解析
+//!
+//! 网络分块可能在任意位置截断，解析器缓存不完整的行，
+//! 每遇到一个空行就输出一条完整事件的 data 内容。
+
+/// SSE 解析器
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入一段数据，返回其中已经完整的事件
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+
+        while let Some(index) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=index).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if let Some(event) = self.handle_line(line) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    /// 数据结束时输出缓存中剩余的事件
+    pub fn finish(&mut self) -> Option<String> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+
+            self.buffer.clear();
+            self.handle_line(line.trim_end_matches('\r'));
+        }
+
+        self.flush()
+    }
+
+    fn handle_line(&mut self, line: &str) -> Option<String> {
+        if line.is_empty() {
+            return self.flush();
+        }
+
+        // 以冒号开头的是注释，常用作心跳
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        if field == "data" {
+            self.data.push(value.to_string());
+        }
+
+        None
+    }
+
+    fn flush(&mut self) -> Option<String> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let data = self.data.join("\n");
+
+        self.data.clear();
+
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(chunks: &[&[u8]]) -> Vec<String> {
+        let mut decoder = SseDecoder::new();
+        let mut events: Vec<String> = chunks
+            .iter()
+            .flat_map(|chunk| decoder.push(chunk))
+            .collect();
+
+        events.extend(decoder.finish());
+
+        events
+    }
+
+    #[test]
+    fn splits_events() {
+        assert_eq!(
+            decode(&[b"data: first\n\ndata: second\n\n"]),
+            ["first", "second"]
+        );
+    }
+
+    #[test]
+    fn joins_events_split_across_chunks() {
+        let chunks: [&[u8]; 5] = [b"da", b"ta: {\"a\":", b"1}\n", b"\n", b"data: [DONE]\n\n"];
+
+        assert_eq!(decode(&chunks), ["{\"a\":1}", "[DONE]"]);
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let bytes = "data: 你好\n\n".as_bytes();
+
+        // 在“你”的 UTF-8 编码中间截断
+        assert_eq!(decode(&[&bytes[..7], &bytes[7..]]), ["你好"]);
+    }
+
+    #[test]
+    fn handles_crlf_and_multiline_data() {
+        assert_eq!(
+            decode(&[b"data: line 1\r\ndata:line 2\r\n\r\n"]),
+            ["line 1\nline 2"]
+        );
+    }
+
+    #[test]
+    fn ignores_comments_and_other_fields() {
+        let chunks: [&[u8]; 1] =
+            [b": ping\n\nevent: message_stop\nid: 1\nretry: 10\ndata: done\n\n"];
+
+        assert_eq!(decode(&chunks), ["done"]);
+    }
+
+    #[test]
+    fn flushes_last_event_on_finish() {
+        let mut decoder = SseDecoder::new();
+
+        assert!(decoder.push(b"data: tail").is_empty());
+        assert_eq!(decoder.finish().as_deref(), Some("tail"));
+        assert_eq!(decoder.finish(), None);
+    }
+}
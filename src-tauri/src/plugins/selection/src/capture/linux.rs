@@ -0,0 +1,128 @@
+use super::{CaptureOptions, SelectionCapture, POLL_INTERVAL};
+use clipboard_rs::{Clipboard, ClipboardContext};
+use rdev::{simulate, EventType, Key};
+use std::thread;
+use std::time::{Duration, Instant};
+use x11::xfixes::{XFixesQueryExtension, XFixesSelectSelectionInput};
+use x11::xlib::{
+    self, XCloseDisplay, XDefaultRootWindow, XInternAtom, XNextEvent, XOpenDisplay, XPending, XSync,
+};
+
+// XFixes 扩展的常量：https://www.x.org/releases/current/doc/fixesproto/fixesproto.txt
+const XFIXES_SELECTION_NOTIFY: i32 = 0;
+const XFIXES_SET_SELECTION_OWNER_NOTIFY_MASK: u64 = 1;
+
+/// 通过模拟 Ctrl+C 获取选中文本（用户点击按钮时调用）
+pub fn get_selected_text_via_clipboard(
+    options: CaptureOptions,
+) -> Result<SelectionCapture, Box<dyn std::error::Error + Send + Sync>> {
+    let context = ClipboardContext::new()?;
+
+    // 1. 保存当前剪贴板内容
+    let saved_text = context.get_text().ok();
+
+    // 2. 模拟 Ctrl+C 并等待 CLIPBOARD 的所有者发生变化
+    if !wait_for_clipboard_change(options, simulate_ctrl_c)? {
+        return Ok(SelectionCapture::NoSelection);
+    }
+
+    // 3. 读取新的剪贴板内容
+    let deadline = Instant::now() + options.timeout;
+    let mut selected_text = context.get_text().ok();
+
+    while selected_text.is_none() && Instant::now() < deadline {
+        thread::sleep(POLL_INTERVAL);
+
+        selected_text = context.get_text().ok();
+    }
+
+    // 4. 恢复原始剪贴板内容
+    if let Some(saved_text) = saved_text {
+        if selected_text.as_ref() != Some(&saved_text) {
+            let _ = context.set_text(saved_text);
+        }
+    }
+
+    Ok(SelectionCapture::from_text(selected_text))
+}
+
+/// 读取剪贴板中的文本
+pub fn read_clipboard_text() -> Option<String> {
+    ClipboardContext::new().ok()?.get_text().ok()
+}
+
+/// 订阅 CLIPBOARD 的 XFixesSelectionNotify 事件，执行 `trigger` 后等待剪贴板变化
+fn wait_for_clipboard_change(
+    options: CaptureOptions,
+    trigger: fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    unsafe {
+        let display = XOpenDisplay(std::ptr::null());
+        if display.is_null() {
+            return Err("Could not open display".into());
+        }
+
+        let mut event_base = 0;
+        let mut error_base = 0;
+        if XFixesQueryExtension(display, &mut event_base, &mut error_base) == xlib::False {
+            XCloseDisplay(display);
+
+            return Err("XFixes extension is not available".into());
+        }
+
+        let clipboard = XInternAtom(display, b"CLIPBOARD\0".as_ptr() as _, xlib::False);
+        XFixesSelectSelectionInput(
+            display,
+            XDefaultRootWindow(display),
+            clipboard,
+            XFIXES_SET_SELECTION_OWNER_NOTIFY_MASK,
+        );
+        // 确保订阅在模拟按键之前生效
+        XSync(display, xlib::False);
+
+        if let Err(error) = trigger() {
+            XCloseDisplay(display);
+
+            return Err(error);
+        }
+
+        let deadline = Instant::now() + options.timeout;
+        let mut changed = false;
+
+        while !changed && Instant::now() < deadline {
+            while XPending(display) > 0 {
+                let mut event = std::mem::zeroed();
+                XNextEvent(display, &mut event);
+
+                if xlib::XEvent::get_type(&event) == event_base + XFIXES_SELECTION_NOTIFY {
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+
+        XCloseDisplay(display);
+
+        Ok(changed)
+    }
+}
+
+/// 模拟 Ctrl+C 按键
+fn simulate_ctrl_c() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for event_type in [
+        EventType::KeyPress(Key::ControlLeft),
+        EventType::KeyPress(Key::KeyC),
+        EventType::KeyRelease(Key::KeyC),
+        EventType::KeyRelease(Key::ControlLeft),
+    ] {
+        // 连续模拟按键需要留出间隔，否则部分应用会丢失事件
+        thread::sleep(Duration::from_millis(20));
+
+        simulate(&event_type).map_err(|_| "Failed to simulate Ctrl+C")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! 通过模拟复制获取选中文本
+//!
+//! 模拟 Ctrl+C 之后不再固定等待，而是等待剪贴板发出变化信号，
+//! 超时仍未变化则认为当前没有选中内容。
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "windows")]
+pub use windows::*;
+
+#[cfg(target_os = "linux")]
+pub use linux::*;
+
+/// 读取剪贴板中的文本
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn read_clipboard_text() -> Option<String> {
+    None
+}
+
+/// 默认等待剪贴板变化的超时时间（毫秒）
+pub const DEFAULT_CAPTURE_TIMEOUT: u64 = 300;
+
+/// 轮询剪贴板变化的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// 获取选中文本的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SelectionCapture {
+    /// 获取到了选中的文本
+    Text { text: String },
+    /// 超时时间内剪贴板没有变化，或者复制的内容不是文本
+    NoSelection,
+}
+
+impl SelectionCapture {
+    fn from_text(text: Option<String>) -> Self {
+        match text {
+            Some(text) if !text.is_empty() => Self::Text { text },
+            _ => Self::NoSelection,
+        }
+    }
+}
+
+/// 获取选中文本的选项
+#[derive(Debug, Clone, Copy)]
+pub struct CaptureOptions {
+    /// 等待剪贴板变化的最长时间
+    pub timeout: Duration,
+}
+
+impl CaptureOptions {
+    pub fn with_timeout(timeout: Option<u64>) -> Self {
+        Self {
+            timeout: Duration::from_millis(timeout.unwrap_or(DEFAULT_CAPTURE_TIMEOUT)),
+        }
+    }
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self::with_timeout(None)
+    }
+}
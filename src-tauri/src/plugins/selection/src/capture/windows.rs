@@ -0,0 +1,154 @@
+use super::{CaptureOptions, SelectionCapture, POLL_INTERVAL};
+use std::thread;
+use std::time::Instant;
+
+use windows::Win32::Foundation::{HANDLE, HGLOBAL, HWND};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, GetClipboardData, GetClipboardSequenceNumber, OpenClipboard,
+    SetClipboardData,
+};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    VIRTUAL_KEY, VK_C, VK_CONTROL,
+};
+
+// CF_UNICODETEXT 常量值
+const CF_UNICODETEXT: u32 = 13;
+
+/// 通过模拟 Ctrl+C 获取选中文本（用户点击按钮时调用）
+pub fn get_selected_text_via_clipboard(
+    options: CaptureOptions,
+) -> Result<SelectionCapture, Box<dyn std::error::Error + Send + Sync>> {
+    unsafe {
+        // 1. 保存当前剪贴板内容和序列号
+        let saved_text = get_clipboard_text();
+        let sequence = GetClipboardSequenceNumber();
+
+        // 2. 模拟 Ctrl+C
+        simulate_ctrl_c()?;
+
+        // 3. 等待剪贴板序列号变化，超时说明没有选中内容
+        let deadline = Instant::now() + options.timeout;
+
+        while GetClipboardSequenceNumber() == sequence {
+            if Instant::now() >= deadline {
+                return Ok(SelectionCapture::NoSelection);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        // 4. 读取新的剪贴板内容，源应用可能还占用着剪贴板，需要重试
+        let mut selected_text = get_clipboard_text();
+
+        while selected_text.is_none() && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+
+            selected_text = get_clipboard_text();
+        }
+
+        // 5. 恢复原始剪贴板内容
+        if let Some(saved_text) = saved_text {
+            if selected_text.as_ref() != Some(&saved_text) {
+                let _ = set_clipboard_text(&saved_text);
+            }
+        }
+
+        Ok(SelectionCapture::from_text(selected_text))
+    }
+}
+
+/// 构造键盘输入事件
+fn keyboard_input(key: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// 模拟 Ctrl+C 按键
+unsafe fn simulate_ctrl_c() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let inputs = [
+        keyboard_input(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        keyboard_input(VK_C, KEYBD_EVENT_FLAGS(0)),
+        keyboard_input(VK_C, KEYEVENTF_KEYUP),
+        keyboard_input(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+
+    let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+
+    if sent as usize != inputs.len() {
+        return Err("Failed to simulate Ctrl+C".into());
+    }
+
+    Ok(())
+}
+
+/// 读取剪贴板中的文本
+pub fn read_clipboard_text() -> Option<String> {
+    unsafe { get_clipboard_text() }
+}
+
+/// 获取剪贴板文本
+unsafe fn get_clipboard_text() -> Option<String> {
+    if OpenClipboard(HWND::default()).is_err() {
+        return None;
+    }
+
+    let result = (|| {
+        let handle = GetClipboardData(CF_UNICODETEXT).ok()?;
+        let hglobal = HGLOBAL(handle.0);
+        let ptr = GlobalLock(hglobal) as *const u16;
+
+        if ptr.is_null() {
+            return None;
+        }
+
+        // 计算字符串长度
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+
+        let slice = std::slice::from_raw_parts(ptr, len);
+        let text = String::from_utf16_lossy(slice);
+
+        let _ = GlobalUnlock(hglobal);
+
+        Some(text)
+    })();
+
+    let _ = CloseClipboard();
+
+    result
+}
+
+/// 设置剪贴板文本
+unsafe fn set_clipboard_text(text: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    let size = wide.len() * 2;
+
+    let mem = GlobalAlloc(GMEM_MOVEABLE, size)?;
+    let ptr = GlobalLock(mem) as *mut u16;
+
+    std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr, wide.len());
+
+    let _ = GlobalUnlock(mem);
+
+    if OpenClipboard(HWND::default()).is_ok() {
+        let _ = EmptyClipboard();
+        let _ = SetClipboardData(CF_UNICODETEXT, HANDLE(mem.0));
+        let _ = CloseClipboard();
+    }
+
+    Ok(())
+}
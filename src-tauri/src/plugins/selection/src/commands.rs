@@ -1,8 +1,19 @@
 use serde::{Deserialize, Serialize};
-use tauri::{command, AppHandle, Runtime};
+use tauri::{command, ipc::Channel, AppHandle, Manager, Runtime, State};
 
-#[cfg(target_os = "windows")]
-use crate::monitor;
+use crate::ai::{AiRequests, ProviderKind};
+use crate::capture::CaptureOptions;
+use crate::monitor::{MonitorState, MonitorStatus, SelectionMonitor};
+use crate::template::{self, TemplateContext, TemplateIssue};
+use crate::transform::{self, TransformInfo};
+use crate::{capture, foreground, hide, toolbar};
+
+pub use crate::ai::{ChatCompletion, ChatMessage, ChatRequest, ChatTimeouts};
+pub use crate::capture::SelectionCapture;
+pub use crate::hide::{HideConfig, HideReason, HideRules, TriggerMode};
+pub use crate::toolbar::ToolbarPlacement;
+
+const UNSUPPORTED_MESSAGE: &str = "Selection monitor is only supported on Windows";
 
 /// 选区事件数据（只包含坐标）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,42 +24,219 @@ pub struct SelectionEvent {
 
 /// 启动选区监控
 #[command]
-pub async fn start_selection_monitor<R: Runtime>(_app: AppHandle<R>) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        monitor::enable_monitor().map_err(|e| e.to_string())?;
+pub async fn start_selection_monitor<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    match app.try_state::<SelectionMonitor>() {
+        Some(monitor) => monitor.start(),
+        None => {
+            log::warn!("{}", UNSUPPORTED_MESSAGE);
+
+            Ok(())
+        }
     }
-    
-    #[cfg(not(target_os = "windows"))]
-    {
-        log::warn!("Selection monitor is only supported on Windows");
+}
+
+/// 停止选区监控，等待监控线程退出
+#[command]
+pub async fn stop_selection_monitor<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    match app.try_state::<SelectionMonitor>() {
+        Some(monitor) => monitor.stop(),
+        None => Ok(()),
     }
-    
-    Ok(())
 }
 
-/// 停止选区监控
+/// 重启选区监控
 #[command]
-pub async fn stop_selection_monitor<R: Runtime>(_app: AppHandle<R>) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        monitor::stop_monitor().map_err(|e| e.to_string())?;
+pub async fn restart_selection_monitor<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    match app.try_state::<SelectionMonitor>() {
+        Some(monitor) => monitor.restart(),
+        None => Err(UNSUPPORTED_MESSAGE.to_string()),
+    }
+}
+
+/// 获取选区监控的状态
+#[command]
+pub async fn selection_monitor_status<R: Runtime>(app: AppHandle<R>) -> MonitorStatus {
+    match app.try_state::<SelectionMonitor>() {
+        Some(monitor) => monitor.status(),
+        None => MonitorStatus {
+            state: MonitorState::Stopped,
+            input: None,
+            started_at: None,
+            start_count: 0,
+            last_error: Some(UNSUPPORTED_MESSAGE.to_string()),
+        },
     }
-    
-    Ok(())
 }
 
 /// 手动获取选中文本（通过模拟 Ctrl+C）
+///
+/// `timeout` 为等待剪贴板变化的毫秒数，超时返回 `NoSelection`
 #[command]
-pub async fn get_selected_text<R: Runtime>(_app: AppHandle<R>) -> Result<String, String> {
-    #[cfg(target_os = "windows")]
+pub async fn get_selected_text<R: Runtime>(
+    _app: AppHandle<R>,
+    timeout: Option<u64>,
+) -> Result<SelectionCapture, String> {
+    let options = CaptureOptions::with_timeout(timeout);
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
-        let text = monitor::get_selected_text_via_clipboard().map_err(|e| e.to_string())?;
-        Ok(text)
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::capture::get_selected_text_via_clipboard(options).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())?
     }
-    
-    #[cfg(not(target_os = "windows"))]
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
-        Err("Selection monitor is only supported on Windows".to_string())
+        let _ = options;
+
+        Err("Getting selected text is only supported on Windows and Linux".to_string())
+    }
+}
+
+/// 在划词位置显示工具栏，返回工具栏最终所在的区域
+///
+/// `x` 和 `y` 为划词位置的物理坐标，工具栏会被限制在对应显示器的工作区内
+#[command]
+pub async fn show_selection_toolbar<R: Runtime>(
+    app: AppHandle<R>,
+    x: i32,
+    y: i32,
+) -> Result<Option<ToolbarPlacement>, String> {
+    let Some(window) = toolbar::get_toolbar_window(&app) else {
+        return Err("Selection toolbar window not found".to_string());
+    };
+
+    toolbar::show_toolbar(&window, x, y).map_err(|e| e.to_string())
+}
+
+/// 隐藏划词工具栏
+#[command]
+pub async fn hide_selection_toolbar<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = toolbar::get_toolbar_window(&app) {
+        toolbar::hide_toolbar(&window).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// 设置工具栏自动隐藏的规则
+#[command]
+pub async fn set_selection_hide_config<R: Runtime>(
+    _app: AppHandle<R>,
+    config: HideConfig,
+) -> Result<(), String> {
+    hide::set_config(config);
+
+    Ok(())
+}
+
+/// 发送流式 AI 请求，回复通过 `on_delta` 逐段发送，结束后返回完整内容
+///
+/// `id` 由调用方生成，用于通过 `cancel_ai_request` 取消请求
+#[command]
+pub async fn ai_chat_stream<R: Runtime>(
+    app: AppHandle<R>,
+    requests: State<'_, AiRequests>,
+    id: String,
+    mut request: ChatRequest,
+    on_delta: Channel<String>,
+) -> Result<ChatCompletion, String> {
+    if request.api_key.is_none() {
+        if let Some(name) = request.api_key_secret.take() {
+            request.api_key = tauri_plugin_eco_secret::read_secret(&app, name).await?;
+
+            // 本地的 Ollama 不需要密钥
+            if request.api_key.is_none() && request.provider != ProviderKind::Ollama {
+                return Err("API key is not configured".to_string());
+            }
+        }
     }
+
+    requests
+        .run(&id, request, |delta| {
+            if let Err(e) = on_delta.send(delta.to_string()) {
+                log::warn!("Failed to send AI delta: {}", e);
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 取消 AI 请求，请求不存在或已经结束时返回 false
+#[command]
+pub async fn cancel_ai_request(
+    requests: State<'_, AiRequests>,
+    id: String,
+) -> Result<bool, String> {
+    Ok(requests.cancel(&id))
+}
+
+/// 用选中文本和当前环境渲染提示词模板
+///
+/// 可用变量见 [`crate::VARIABLES`]，模板语法错误时返回错误。
+/// 系统语言由前端通过 tauri-plugin-locale 获取后传入。
+#[command]
+pub async fn render_prompt<R: Runtime>(
+    _app: AppHandle<R>,
+    template: String,
+    text: String,
+    language: Option<String>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let context = prompt_context(text, language.unwrap_or_default());
+
+        template::render(&template, &context).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 检查提示词模板，返回语法错误、未知变量和未知过滤器
+#[command]
+pub async fn validate_prompt(template: String) -> Vec<TemplateIssue> {
+    template::validate(&template)
+}
+
+/// 列出所有本地文本转换
+#[command]
+pub async fn list_transforms() -> Vec<TransformInfo> {
+    transform::transforms()
+}
+
+/// 在本地执行文本转换，不需要网络
+#[command]
+pub async fn run_transform(id: String, text: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        transform::run(&id, &text).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 收集渲染提示词需要的变量
+fn prompt_context(text: String, language: String) -> TemplateContext {
+    let now = chrono::Local::now();
+
+    let mut context = TemplateContext::new();
+
+    context.insert("text".to_string(), text);
+    context.insert(
+        "app".to_string(),
+        foreground::foreground_app().unwrap_or_default(),
+    );
+    context.insert(
+        "clipboard".to_string(),
+        capture::read_clipboard_text().unwrap_or_default(),
+    );
+    context.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+    context.insert("time".to_string(), now.format("%H:%M").to_string());
+    context.insert(
+        "datetime".to_string(),
+        now.format("%Y-%m-%d %H:%M").to_string(),
+    );
+    context.insert("language".to_string(), language);
+
+    context
 }
@@ -0,0 +1,468 @@
+//! 与平台无关的划词识别状态机
+//!
+//! 输入为各平台 [`MonitorInput`](crate::MonitorInput) 产生的带时间戳的输入事件，
+//! 输出为显示、隐藏工具栏等动作。状态机不依赖系统钩子和全局变量，
+//! 可以直接回放录制的事件序列来验证输出的动作。
+
+use serde::{Deserialize, Serialize};
+
+use crate::hide::HideReason;
+use crate::monitor::{InputEvent, InputRecord, MouseButton};
+
+/// 识别划词的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DetectorConfig {
+    /// 判断为拖拽的最小距离（物理像素）
+    pub drag_threshold: i32,
+    /// 两次点击判断为双击的最长间隔（毫秒）
+    pub double_click_time: u64,
+    /// 两次点击判断为双击的最大距离（物理像素）
+    pub double_click_distance: i32,
+}
+
+impl Default for DetectorConfig {
+    fn default() -> Self {
+        Self {
+            drag_threshold: 5,
+            double_click_time: 500,
+            double_click_distance: 4,
+        }
+    }
+}
+
+/// 触发划词的手势
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Gesture {
+    /// 按住左键拖拽选中
+    Drag,
+    /// 双击选中单词
+    DoubleClick,
+    /// 三击选中段落
+    TripleClick,
+}
+
+/// 状态机输出的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SelectionAction {
+    /// 在指定位置显示工具栏
+    ShowToolbar { x: i32, y: i32, gesture: Gesture },
+    /// 鼠标在指定位置按下，是否在工具栏外部由调用方判断
+    PointerDown { x: i32, y: i32 },
+    /// 按照隐藏规则隐藏工具栏
+    HideToolbar { reason: HideReason },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Point {
+    x: i32,
+    y: i32,
+    time: u64,
+}
+
+impl Point {
+    fn distance(&self, x: i32, y: i32) -> i32 {
+        (x - self.x).abs().max((y - self.y).abs())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Click {
+    point: Point,
+    count: u32,
+}
+
+/// 划词识别状态机
+#[derive(Debug, Clone)]
+pub struct SelectionDetector {
+    config: DetectorConfig,
+    // 左键按下的位置，松开后清空
+    pressed: Option<Point>,
+    // 上一次没有拖拽的点击，用于识别双击和三击
+    last_click: Option<Click>,
+}
+
+impl SelectionDetector {
+    pub fn new(config: DetectorConfig) -> Self {
+        Self {
+            config,
+            pressed: None,
+            last_click: None,
+        }
+    }
+
+    pub fn config(&self) -> DetectorConfig {
+        self.config
+    }
+
+    /// 更新参数，不影响正在进行的手势
+    pub fn set_config(&mut self, config: DetectorConfig) {
+        self.config = config;
+    }
+
+    /// 处理一个输入事件，返回需要执行的动作
+    pub fn handle(&mut self, record: InputRecord) -> Vec<SelectionAction> {
+        let InputRecord { time, event } = record;
+
+        match event {
+            InputEvent::MouseDown { button, x, y } => {
+                if button == MouseButton::Left {
+                    self.pressed = Some(Point { x, y, time });
+                } else {
+                    self.reset();
+                }
+
+                vec![SelectionAction::PointerDown { x, y }]
+            }
+            InputEvent::MouseUp {
+                button: MouseButton::Left,
+                x,
+                y,
+            } => self.handle_left_up(x, y, time).into_iter().collect(),
+            InputEvent::MouseUp { .. } => Vec::new(),
+            InputEvent::Wheel => self.hide(HideReason::Scroll),
+            InputEvent::KeyDown => self.hide(HideReason::KeyPress),
+            InputEvent::ForegroundChanged => self.hide(HideReason::ForegroundChange),
+        }
+    }
+
+    /// 清空正在进行的手势
+    pub fn reset(&mut self) {
+        self.pressed = None;
+        self.last_click = None;
+    }
+
+    fn hide(&mut self, reason: HideReason) -> Vec<SelectionAction> {
+        self.reset();
+
+        vec![SelectionAction::HideToolbar { reason }]
+    }
+
+    fn handle_left_up(&mut self, x: i32, y: i32, time: u64) -> Option<SelectionAction> {
+        // 没有记录到按下（例如监控在按住时才启动）时忽略
+        let start = self.pressed.take()?;
+
+        if start.distance(x, y) > self.config.drag_threshold {
+            self.last_click = None;
+
+            return Some(SelectionAction::ShowToolbar {
+                x,
+                y,
+                gesture: Gesture::Drag,
+            });
+        }
+
+        let point = Point { x, y, time };
+
+        let count = match self.last_click {
+            Some(click)
+                if time.saturating_sub(click.point.time) <= self.config.double_click_time
+                    && click.point.distance(x, y) <= self.config.double_click_distance =>
+            {
+                click.count + 1
+            }
+            _ => 1,
+        };
+
+        self.last_click = Some(Click { point, count });
+
+        let gesture = match count {
+            1 => return None,
+            2 => Gesture::DoubleClick,
+            _ => Gesture::TripleClick,
+        };
+
+        Some(SelectionAction::ShowToolbar { x, y, gesture })
+    }
+}
+
+impl Default for SelectionDetector {
+    fn default() -> Self {
+        Self::new(DetectorConfig::default())
+    }
+}
+
+/// 回放录制的事件序列，返回状态机依次输出的动作
+pub fn replay<'a>(
+    config: DetectorConfig,
+    records: impl IntoIterator<Item = &'a InputRecord>,
+) -> Vec<SelectionAction> {
+    let mut detector = SelectionDetector::new(config);
+
+    records
+        .into_iter()
+        .flat_map(|record| detector.handle(*record))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn down(time: u64, button: MouseButton, x: i32, y: i32) -> InputRecord {
+        InputRecord {
+            time,
+            event: InputEvent::MouseDown { button, x, y },
+        }
+    }
+
+    fn up(time: u64, button: MouseButton, x: i32, y: i32) -> InputRecord {
+        InputRecord {
+            time,
+            event: InputEvent::MouseUp { button, x, y },
+        }
+    }
+
+    fn event(time: u64, event: InputEvent) -> InputRecord {
+        InputRecord { time, event }
+    }
+
+    // 在同一位置按下再松开左键
+    fn click(time: u64, x: i32, y: i32) -> [InputRecord; 2] {
+        [
+            down(time, MouseButton::Left, x, y),
+            up(time + 50, MouseButton::Left, x, y),
+        ]
+    }
+
+    fn show(x: i32, y: i32, gesture: Gesture) -> SelectionAction {
+        SelectionAction::ShowToolbar { x, y, gesture }
+    }
+
+    fn pointer(x: i32, y: i32) -> SelectionAction {
+        SelectionAction::PointerDown { x, y }
+    }
+
+    fn shown(actions: &[SelectionAction]) -> Vec<SelectionAction> {
+        actions
+            .iter()
+            .filter(|action| matches!(action, SelectionAction::ShowToolbar { .. }))
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn drag_beyond_threshold_shows_toolbar() {
+        let records = [
+            down(0, MouseButton::Left, 100, 100),
+            up(300, MouseButton::Left, 160, 104),
+        ];
+
+        assert_eq!(
+            replay(DetectorConfig::default(), &records),
+            [pointer(100, 100), show(160, 104, Gesture::Drag)]
+        );
+    }
+
+    #[test]
+    fn drag_within_threshold_is_click() {
+        let config = DetectorConfig::default();
+        let records = [
+            down(0, MouseButton::Left, 100, 100),
+            up(300, MouseButton::Left, 100 + config.drag_threshold, 100),
+        ];
+
+        assert_eq!(replay(config, &records), [pointer(100, 100)]);
+    }
+
+    #[test]
+    fn custom_drag_threshold() {
+        let config = DetectorConfig {
+            drag_threshold: 20,
+            ..DetectorConfig::default()
+        };
+        let records = [
+            down(0, MouseButton::Left, 0, 0),
+            up(300, MouseButton::Left, 15, 15),
+        ];
+
+        assert!(shown(&replay(config, &records)).is_empty());
+
+        let records = [
+            down(0, MouseButton::Left, 0, 0),
+            up(300, MouseButton::Left, 0, 21),
+        ];
+
+        assert_eq!(
+            shown(&replay(config, &records)),
+            [show(0, 21, Gesture::Drag)]
+        );
+    }
+
+    #[test]
+    fn double_and_triple_click() {
+        let records: Vec<_> = [click(0, 50, 50), click(200, 51, 50), click(400, 52, 51)].concat();
+
+        assert_eq!(
+            replay(DetectorConfig::default(), &records),
+            [
+                pointer(50, 50),
+                pointer(51, 50),
+                show(51, 50, Gesture::DoubleClick),
+                pointer(52, 51),
+                show(52, 51, Gesture::TripleClick),
+            ]
+        );
+    }
+
+    #[test]
+    fn double_click_time_is_inclusive() {
+        let config = DetectorConfig::default();
+
+        // 两次松开的间隔刚好等于双击时间
+        let records = [click(0, 50, 50), click(config.double_click_time, 50, 50)].concat();
+
+        assert_eq!(
+            shown(&replay(config, &records)),
+            [show(50, 50, Gesture::DoubleClick)]
+        );
+
+        let records = [
+            click(0, 50, 50),
+            click(config.double_click_time + 1, 50, 50),
+        ]
+        .concat();
+
+        assert!(shown(&replay(config, &records)).is_empty());
+    }
+
+    #[test]
+    fn slow_third_click_starts_over() {
+        let config = DetectorConfig::default();
+        let late = 200 + config.double_click_time + 1;
+        let records = [click(0, 50, 50), click(200, 50, 50), click(late, 50, 50)].concat();
+
+        assert_eq!(
+            shown(&replay(config, &records)),
+            [show(50, 50, Gesture::DoubleClick)]
+        );
+    }
+
+    #[test]
+    fn double_click_distance() {
+        let config = DetectorConfig::default();
+        let distance = config.double_click_distance;
+
+        let records = [click(0, 50, 50), click(200, 50 + distance, 50 - distance)].concat();
+
+        assert_eq!(
+            shown(&replay(config, &records)),
+            [show(50 + distance, 50 - distance, Gesture::DoubleClick)]
+        );
+
+        let records = [click(0, 50, 50), click(200, 50 + distance + 1, 50)].concat();
+
+        assert!(shown(&replay(config, &records)).is_empty());
+    }
+
+    #[test]
+    fn drag_resets_click_count() {
+        let records = [
+            click(0, 50, 50).as_slice(),
+            &[
+                down(100, MouseButton::Left, 50, 50),
+                up(200, MouseButton::Left, 150, 50),
+            ],
+            &click(300, 150, 50),
+        ]
+        .concat();
+
+        assert_eq!(
+            shown(&replay(DetectorConfig::default(), &records)),
+            [show(150, 50, Gesture::Drag)]
+        );
+    }
+
+    #[test]
+    fn right_click_resets_gesture() {
+        let records = [
+            click(0, 50, 50).as_slice(),
+            &[
+                down(100, MouseButton::Right, 50, 50),
+                up(150, MouseButton::Right, 50, 50),
+            ],
+            &click(200, 50, 50),
+        ]
+        .concat();
+
+        assert_eq!(
+            replay(DetectorConfig::default(), &records),
+            [pointer(50, 50), pointer(50, 50), pointer(50, 50)]
+        );
+
+        // 按住左键时按下右键，松开左键不再识别为拖拽
+        let records = [
+            down(0, MouseButton::Left, 0, 0),
+            down(100, MouseButton::Right, 100, 0),
+            up(200, MouseButton::Left, 100, 0),
+        ];
+
+        assert!(shown(&replay(DetectorConfig::default(), &records)).is_empty());
+    }
+
+    #[test]
+    fn other_events_hide_toolbar() {
+        let cases = [
+            (InputEvent::Wheel, HideReason::Scroll),
+            (InputEvent::KeyDown, HideReason::KeyPress),
+            (InputEvent::ForegroundChanged, HideReason::ForegroundChange),
+        ];
+
+        for (input, reason) in cases {
+            // 隐藏后之前的单击不再参与双击的识别
+            let records = [
+                click(0, 50, 50).as_slice(),
+                &[event(100, input)],
+                &click(200, 50, 50),
+            ]
+            .concat();
+
+            assert_eq!(
+                replay(DetectorConfig::default(), &records),
+                [
+                    pointer(50, 50),
+                    SelectionAction::HideToolbar { reason },
+                    pointer(50, 50),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn mouse_up_without_press_is_ignored() {
+        let records = [
+            up(0, MouseButton::Left, 50, 50),
+            up(100, MouseButton::Right, 50, 50),
+            up(200, MouseButton::Left, 50, 50),
+        ];
+
+        assert!(replay(DetectorConfig::default(), &records).is_empty());
+    }
+
+    #[test]
+    fn set_config_keeps_gesture_in_progress() {
+        let mut detector = SelectionDetector::default();
+
+        detector.handle(down(0, MouseButton::Left, 0, 0));
+        detector.set_config(DetectorConfig {
+            drag_threshold: 100,
+            ..DetectorConfig::default()
+        });
+
+        // 按下的位置保留，松开时按新的参数判断
+        assert!(detector
+            .handle(up(100, MouseButton::Left, 50, 0))
+            .is_empty());
+        assert_eq!(detector.config().drag_threshold, 100);
+
+        detector.handle(down(200, MouseButton::Left, 0, 0));
+        detector.set_config(DetectorConfig::default());
+
+        assert_eq!(
+            detector.handle(up(300, MouseButton::Left, 50, 0)),
+            [show(50, 0, Gesture::Drag)]
+        );
+    }
+}
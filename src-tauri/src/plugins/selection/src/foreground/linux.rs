@@ -0,0 +1,86 @@
+use std::ffi::CStr;
+use std::ptr;
+use x11::xlib::{
+    self, XClassHint, XCloseDisplay, XDefaultRootWindow, XFree, XGetClassHint, XGetWindowProperty,
+    XInternAtom, XOpenDisplay,
+};
+
+/// 当前前台应用的名称（窗口的 WM_CLASS）
+pub fn foreground_app() -> Option<String> {
+    unsafe {
+        let display = XOpenDisplay(ptr::null());
+
+        if display.is_null() {
+            return None;
+        }
+
+        let name = active_window(display).and_then(|window| class_name(display, window));
+
+        XCloseDisplay(display);
+
+        name
+    }
+}
+
+// 读取根窗口的 _NET_ACTIVE_WINDOW 属性
+unsafe fn active_window(display: *mut xlib::Display) -> Option<xlib::Window> {
+    let atom = XInternAtom(display, c"_NET_ACTIVE_WINDOW".as_ptr(), xlib::False);
+
+    let mut actual_type = 0;
+    let mut actual_format = 0;
+    let mut items = 0;
+    let mut bytes_after = 0;
+    let mut property: *mut u8 = ptr::null_mut();
+
+    let status = XGetWindowProperty(
+        display,
+        XDefaultRootWindow(display),
+        atom,
+        0,
+        1,
+        xlib::False,
+        xlib::XA_WINDOW,
+        &mut actual_type,
+        &mut actual_format,
+        &mut items,
+        &mut bytes_after,
+        &mut property,
+    );
+
+    if status != xlib::Success as i32 || property.is_null() {
+        return None;
+    }
+
+    let window = (items > 0).then(|| *(property as *const xlib::Window));
+
+    XFree(property as _);
+
+    window.filter(|window| *window != 0)
+}
+
+unsafe fn class_name(display: *mut xlib::Display, window: xlib::Window) -> Option<String> {
+    let mut hint = XClassHint {
+        res_name: ptr::null_mut(),
+        res_class: ptr::null_mut(),
+    };
+
+    if XGetClassHint(display, window, &mut hint) == 0 {
+        return None;
+    }
+
+    let name = (!hint.res_class.is_null()).then(|| {
+        CStr::from_ptr(hint.res_class)
+            .to_string_lossy()
+            .into_owned()
+    });
+
+    if !hint.res_name.is_null() {
+        XFree(hint.res_name as _);
+    }
+
+    if !hint.res_class.is_null() {
+        XFree(hint.res_class as _);
+    }
+
+    name
+}
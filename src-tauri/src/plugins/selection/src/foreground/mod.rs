@@ -0,0 +1,21 @@
+//! 获取当前前台应用的名称
+//!
+//! 划词工具栏不接收焦点，显示工具栏时前台应用仍然是用户划词的应用。
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "windows")]
+pub use windows::foreground_app;
+
+#[cfg(target_os = "linux")]
+pub use linux::foreground_app;
+
+/// 当前前台应用的名称
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn foreground_app() -> Option<String> {
+    None
+}
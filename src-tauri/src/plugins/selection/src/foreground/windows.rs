@@ -0,0 +1,47 @@
+use std::path::Path;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+/// 当前前台应用的名称（可执行文件名，不含扩展名）
+pub fn foreground_app() -> Option<String> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let mut process_id = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+
+        if process_id == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+
+        let _ = CloseHandle(process);
+
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+
+        Path::new(&path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,163 @@
+//! 划词工具栏的自动隐藏
+//!
+//! 监听到点击工具栏外部、滚动滚轮、按下按键或切换前台窗口时，
+//! 按照当前触发方式对应的规则决定是否发送 `selection:hide-toolbar` 事件。
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::toolbar;
+
+/// 隐藏工具栏的事件名
+pub const HIDE_TOOLBAR_EVENT: &str = "selection:hide-toolbar";
+
+static HIDE_CONFIG: Mutex<HideConfig> = Mutex::new(HideConfig::new());
+
+/// 划词助手的触发方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerMode {
+    #[default]
+    Selection,
+    Ctrl,
+    Shortcut,
+}
+
+/// 隐藏工具栏的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HideReason {
+    /// 在工具栏外部按下了鼠标
+    OutsideClick,
+    /// 滚动了鼠标滚轮
+    Scroll,
+    /// 按下了键盘按键
+    KeyPress,
+    /// 前台窗口发生了变化
+    ForegroundChange,
+}
+
+/// 隐藏工具栏的规则，每一项对应一种隐藏原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HideRules {
+    pub outside_click: bool,
+    pub scroll: bool,
+    pub key_press: bool,
+    pub foreground_change: bool,
+}
+
+impl HideRules {
+    const ALL: Self = Self {
+        outside_click: true,
+        scroll: true,
+        key_press: true,
+        foreground_change: true,
+    };
+
+    // 通过按键触发时，触发用的按键本身不应该隐藏工具栏
+    const EXCEPT_KEY_PRESS: Self = Self {
+        key_press: false,
+        ..Self::ALL
+    };
+
+    /// 是否允许因为 `reason` 隐藏工具栏
+    pub fn allows(&self, reason: HideReason) -> bool {
+        match reason {
+            HideReason::OutsideClick => self.outside_click,
+            HideReason::Scroll => self.scroll,
+            HideReason::KeyPress => self.key_press,
+            HideReason::ForegroundChange => self.foreground_change,
+        }
+    }
+}
+
+impl Default for HideRules {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// 自动隐藏的配置，按触发方式分别设置规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HideConfig {
+    /// 当前的触发方式
+    pub mode: TriggerMode,
+    pub selection: HideRules,
+    pub ctrl: HideRules,
+    pub shortcut: HideRules,
+}
+
+impl HideConfig {
+    pub const fn new() -> Self {
+        Self {
+            mode: TriggerMode::Selection,
+            selection: HideRules::ALL,
+            ctrl: HideRules::EXCEPT_KEY_PRESS,
+            shortcut: HideRules::EXCEPT_KEY_PRESS,
+        }
+    }
+
+    /// 当前触发方式对应的规则
+    pub fn rules(&self) -> HideRules {
+        match self.mode {
+            TriggerMode::Selection => self.selection,
+            TriggerMode::Ctrl => self.ctrl,
+            TriggerMode::Shortcut => self.shortcut,
+        }
+    }
+}
+
+impl Default for HideConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 隐藏工具栏事件的数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HideToolbarEvent {
+    pub reason: HideReason,
+}
+
+/// 更新自动隐藏的配置
+pub fn set_config(config: HideConfig) {
+    if let Ok(mut guard) = HIDE_CONFIG.lock() {
+        *guard = config;
+    }
+}
+
+/// 获取自动隐藏的配置
+pub fn get_config() -> HideConfig {
+    HIDE_CONFIG.lock().map(|guard| *guard).unwrap_or_default()
+}
+
+/// 工具栏可见且规则允许时，发送隐藏工具栏的事件
+pub fn request_hide<R: Runtime>(app: &AppHandle<R>, reason: HideReason) -> bool {
+    if !get_config().rules().allows(reason) {
+        return false;
+    }
+
+    // 先清除可见状态，避免前端隐藏窗口之前重复发送事件
+    if toolbar::take_visible_bounds().is_none() {
+        return false;
+    }
+
+    if let Err(e) = app.emit(HIDE_TOOLBAR_EVENT, HideToolbarEvent { reason }) {
+        log::error!("Failed to emit hide-toolbar event: {}", e);
+
+        return false;
+    }
+
+    true
+}
+
+/// 处理鼠标按下，落在工具栏外部时隐藏工具栏
+pub fn handle_mouse_down<R: Runtime>(app: &AppHandle<R>, x: i32, y: i32) -> bool {
+    match toolbar::visible_bounds() {
+        Some(bounds) if !bounds.contains(x, y) => request_hide(app, HideReason::OutsideClick),
+        _ => false,
+    }
+}
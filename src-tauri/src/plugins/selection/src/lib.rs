@@ -1,14 +1,36 @@
 use tauri::{
     generate_handler,
     plugin::{Builder, TauriPlugin},
-    Wry,
+    Manager, RunEvent, Wry,
 };
 
+mod ai;
+mod capture;
 mod commands;
-#[cfg(target_os = "windows")]
+mod detector;
+mod foreground;
+mod hide;
+mod listener;
 mod monitor;
+mod template;
+mod toolbar;
+mod transform;
 
+pub use ai::{
+    AiRequests, ChatError, ChatProvider, NdjsonDecoder, ProviderKind, SseDecoder, StreamChunk,
+    StreamFormat,
+};
 pub use commands::*;
+pub use detector::{replay, DetectorConfig, Gesture, SelectionAction, SelectionDetector};
+pub use foreground::foreground_app;
+pub use monitor::{
+    ChannelInput, ChannelInputSender, EventSink, InputEvent, InputFactory, InputRecord, Monitor,
+    MonitorInput, MonitorState, MonitorStatus, MouseButton, SelectionMonitor, StopHandle,
+};
+pub use template::{
+    Template, TemplateContext, TemplateError, TemplateIssue, TemplateIssueKind, FILTERS, VARIABLES,
+};
+pub use transform::{title_case, TransformCategory, TransformError, TransformInfo};
 
 pub const SELECTION_TOOLBAR_LABEL: &str = "selection-toolbar";
 
@@ -17,16 +39,48 @@ pub fn init() -> TauriPlugin<Wry> {
         .invoke_handler(generate_handler![
             commands::start_selection_monitor,
             commands::stop_selection_monitor,
+            commands::restart_selection_monitor,
+            commands::selection_monitor_status,
             commands::get_selected_text,
+            commands::show_selection_toolbar,
+            commands::hide_selection_toolbar,
+            commands::set_selection_hide_config,
+            commands::ai_chat_stream,
+            commands::cancel_ai_request,
+            commands::render_prompt,
+            commands::validate_prompt,
+            commands::list_transforms,
+            commands::run_transform,
         ])
         .setup(|app, _api| {
-            #[cfg(target_os = "windows")]
-            {
-                // app 是 &AppHandle<Wry>
-                monitor::set_app_handle(app.clone());
+            app.manage(AiRequests::new());
+
+            // 只在支持系统钩子的平台上创建监控
+            if let Some(factory) = monitor::default_input_factory() {
+                let sink = listener::create_sink(app.clone());
+
+                app.manage(SelectionMonitor::new("selection", factory, sink));
             }
+
+            // 工具栏不接收焦点，避免显示时打断用户在其它应用中的选区
+            if let Some(window) = toolbar::get_toolbar_window(app) {
+                let _ = window.set_focusable(false);
+            }
+
             log::info!("Selection monitor plugin initialized");
             Ok(())
         })
+        .on_event(|app, event| {
+            // 退出时卸载钩子并结束监控线程，取消未完成的 AI 请求
+            if let RunEvent::Exit = event {
+                if let Some(monitor) = app.try_state::<SelectionMonitor>() {
+                    let _ = monitor.stop();
+                }
+
+                if let Some(requests) = app.try_state::<AiRequests>() {
+                    requests.cancel_all();
+                }
+            }
+        })
         .build()
 }
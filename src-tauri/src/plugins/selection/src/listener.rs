@@ -0,0 +1,55 @@
+//! 处理监控产生的输入事件
+//!
+//! 输入事件交给 [`SelectionDetector`] 识别，再把识别出的动作转换为前端事件。
+
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::detector::{SelectionAction, SelectionDetector};
+use crate::hide;
+use crate::monitor::{self, EventSink};
+use crate::SelectionEvent;
+
+/// 显示工具栏的事件名
+pub const SHOW_TOOLBAR_EVENT: &str = "selection:show-toolbar";
+
+/// 创建处理输入事件的回调
+pub fn create_sink<R: Runtime>(app: AppHandle<R>) -> EventSink {
+    let detector = Mutex::new(SelectionDetector::new(monitor::default_detector_config()));
+
+    Arc::new(move |record| {
+        let actions = match detector.lock() {
+            Ok(mut detector) => detector.handle(record),
+            Err(_) => return,
+        };
+
+        for action in actions {
+            apply_action(&app, action);
+        }
+    })
+}
+
+/// 执行状态机输出的动作
+pub fn apply_action<R: Runtime>(app: &AppHandle<R>, action: SelectionAction) {
+    match action {
+        SelectionAction::ShowToolbar { x, y, gesture } => {
+            // 直接发送事件（只包含坐标），不获取文本
+            if let Err(e) = app.emit(SHOW_TOOLBAR_EVENT, SelectionEvent { x, y }) {
+                log::error!("Failed to emit selection event: {}", e);
+            } else {
+                log::info!(
+                    "Selection detected at ({}, {}) by {:?}, sent show-toolbar event",
+                    x,
+                    y,
+                    gesture
+                );
+            }
+        }
+        SelectionAction::PointerDown { x, y } => {
+            hide::handle_mouse_down(app, x, y);
+        }
+        SelectionAction::HideToolbar { reason } => {
+            hide::request_hide(app, reason);
+        }
+    }
+}
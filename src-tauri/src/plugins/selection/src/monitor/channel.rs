@@ -0,0 +1,98 @@
+use super::{EventSink, InputRecord, MonitorInput, StopHandle};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+enum Message<E> {
+    Record(E),
+    Stop,
+}
+
+/// 由通道驱动的输入源
+///
+/// 不依赖任何系统钩子，通过 [`ChannelInput::sender`] 发送的事件会原样交给监控处理，
+/// 可以在不支持系统钩子的环境中回放录制的事件序列，或者在测试中模拟用户操作。
+pub struct ChannelInput<E = InputRecord> {
+    sender: Sender<Message<E>>,
+    receiver: Receiver<Message<E>>,
+    sink: Option<EventSink<E>>,
+}
+
+/// 向 [`ChannelInput`] 发送事件的句柄
+pub struct ChannelInputSender<E = InputRecord>(Sender<Message<E>>);
+
+impl<E> ChannelInputSender<E> {
+    /// 发送输入事件，输入源已经结束时返回 false
+    pub fn send(&self, record: E) -> bool {
+        self.0.send(Message::Record(record)).is_ok()
+    }
+}
+
+impl<E> Clone for ChannelInputSender<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E> ChannelInput<E> {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            sender,
+            receiver,
+            sink: None,
+        }
+    }
+
+    /// 创建预先放入录制事件的输入源，监控启动后会依次回放这些事件
+    pub fn with_records(records: impl IntoIterator<Item = E>) -> Self {
+        let input = Self::new();
+        let sender = input.sender();
+
+        for record in records {
+            sender.send(record);
+        }
+
+        input
+    }
+
+    /// 获取发送事件的句柄
+    pub fn sender(&self) -> ChannelInputSender<E> {
+        ChannelInputSender(self.sender.clone())
+    }
+}
+
+impl<E> Default for ChannelInput<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Send + 'static> MonitorInput<E> for ChannelInput<E> {
+    fn name(&self) -> &'static str {
+        "channel"
+    }
+
+    fn prepare(&mut self, sink: EventSink<E>) -> Result<StopHandle, String> {
+        self.sink = Some(sink);
+
+        let sender = self.sender.clone();
+
+        Ok(Box::new(move || {
+            let _ = sender.send(Message::Stop);
+        }))
+    }
+
+    fn run(&mut self) {
+        let Some(sink) = self.sink.as_ref() else {
+            return;
+        };
+
+        while let Ok(Message::Record(record)) = self.receiver.recv() {
+            sink(record);
+        }
+    }
+
+    fn cleanup(&mut self) {
+        self.sink = None;
+    }
+}
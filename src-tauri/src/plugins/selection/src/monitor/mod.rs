@@ -0,0 +1,632 @@
+//! 划词监控的生命周期管理
+//!
+//! 监控运行在独立的线程中，由具体平台的 [`MonitorInput`] 产生输入事件。
+//! [`Monitor`] 负责启动、停止、重启这个线程，停止时会唤醒事件循环，
+//! 并在超时时间内等待线程退出。停止超时的线程退出前不会再次启动，避免重复安装钩子。
+//! 监控对事件的类型是通用的，缩写展开的键盘监控也使用同样的生命周期。
+
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::detector::DetectorConfig;
+
+mod channel;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+pub use channel::{ChannelInput, ChannelInputSender};
+
+#[cfg(target_os = "windows")]
+pub use windows::WindowsHookInput;
+
+/// 等待监控线程启动或退出的超时时间
+const JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 鼠标按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// 输入源产生的输入事件，坐标为物理像素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InputEvent {
+    MouseDown { button: MouseButton, x: i32, y: i32 },
+    MouseUp { button: MouseButton, x: i32, y: i32 },
+    Wheel,
+    KeyDown,
+    ForegroundChanged,
+}
+
+/// 带时间戳的输入事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputRecord {
+    /// 事件发生的时间（毫秒），只用于计算事件之间的间隔
+    pub time: u64,
+    pub event: InputEvent,
+}
+
+/// 接收输入事件的回调，会在监控线程中被调用
+pub type EventSink<E = InputRecord> = Arc<dyn Fn(E) + Send + Sync>;
+
+/// 从其它线程结束事件循环的函数
+pub type StopHandle = Box<dyn FnOnce() + Send>;
+
+/// 创建输入源的函数，每次启动监控都会创建新的输入源
+pub type InputFactory<E = InputRecord> = Box<dyn Fn() -> Box<dyn MonitorInput<E>> + Send + Sync>;
+
+/// 划词的监控
+pub type SelectionMonitor = Monitor<InputRecord>;
+
+/// 监控的输入源，每个平台的后端各自实现
+///
+/// 三个方法都在监控线程中按顺序调用：`prepare` 安装钩子，
+/// `run` 阻塞运行事件循环直到 `prepare` 返回的 [`StopHandle`] 被调用，最后 `cleanup` 卸载钩子。
+pub trait MonitorInput<E = InputRecord>: Send {
+    /// 输入源的名称
+    fn name(&self) -> &'static str;
+
+    /// 准备输入源，返回用于结束事件循环的函数
+    fn prepare(&mut self, sink: EventSink<E>) -> Result<StopHandle, String>;
+
+    /// 运行事件循环
+    fn run(&mut self);
+
+    /// 清理输入源
+    fn cleanup(&mut self) {}
+}
+
+/// 监控的运行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MonitorState {
+    /// 未运行
+    Stopped,
+    /// 正在运行
+    Running,
+    /// 已请求停止，但线程没有在超时时间内退出
+    Stopping,
+}
+
+/// 监控的状态信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorStatus {
+    pub state: MonitorState,
+    /// 当前输入源的名称
+    pub input: Option<String>,
+    /// 本次启动的时间戳（毫秒）
+    pub started_at: Option<u64>,
+    /// 累计启动的次数
+    pub start_count: u32,
+    /// 最近一次的错误信息
+    pub last_error: Option<String>,
+}
+
+struct Worker {
+    input: &'static str,
+    started_at: u64,
+    stop: Option<StopHandle>,
+    done: Receiver<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    worker: Option<Worker>,
+    // 停止超时后遗留的线程，退出前不允许再次启动
+    stale: Option<Worker>,
+    start_count: u32,
+    last_error: Option<String>,
+}
+
+/// 监控的句柄
+pub struct Monitor<E> {
+    // 监控的名称，用于线程名称和日志
+    name: &'static str,
+    factory: InputFactory<E>,
+    sink: EventSink<E>,
+    inner: Mutex<Inner>,
+}
+
+impl<E> Monitor<E> {
+    pub fn new(name: &'static str, factory: InputFactory<E>, sink: EventSink<E>) -> Self {
+        Self {
+            name,
+            factory,
+            sink,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// 停止监控，等待监控线程退出
+    pub fn stop(&self) -> Result<(), String> {
+        let mut inner = self.lock();
+
+        let Some(mut worker) = inner.worker.take() else {
+            return Ok(());
+        };
+
+        if let Some(stop) = worker.stop.take() {
+            stop();
+        }
+
+        match worker.done.recv_timeout(JOIN_TIMEOUT) {
+            Err(RecvTimeoutError::Timeout) => {
+                let error = format!("The {} monitor thread did not exit in time", self.name);
+
+                log::warn!("{}", error);
+
+                inner.stale = Some(worker);
+                inner.last_error = Some(error.clone());
+
+                Err(error)
+            }
+            _ => {
+                join(self.name, worker);
+
+                log::info!("Stopped {} monitor", self.name);
+
+                Ok(())
+            }
+        }
+    }
+
+    /// 获取监控的状态
+    pub fn status(&self) -> MonitorStatus {
+        let mut inner = self.lock();
+
+        // 线程自己退出（例如事件循环出错）时，同步更新状态
+        let exited = inner.worker.as_ref().is_some_and(|worker| {
+            !matches!(worker.done.try_recv(), Err(mpsc::TryRecvError::Empty))
+        });
+
+        if exited {
+            if let Some(worker) = inner.worker.take() {
+                join(self.name, worker);
+            }
+        }
+
+        let stale = inner
+            .stale
+            .as_ref()
+            .is_some_and(|worker| matches!(worker.done.try_recv(), Err(mpsc::TryRecvError::Empty)));
+
+        let (state, worker) = match (&inner.worker, stale) {
+            (Some(worker), _) => (MonitorState::Running, Some(worker)),
+            (None, true) => (MonitorState::Stopping, inner.stale.as_ref()),
+            (None, false) => (MonitorState::Stopped, None),
+        };
+
+        MonitorStatus {
+            state,
+            input: worker.map(|worker| worker.input.to_string()),
+            started_at: worker.map(|worker| worker.started_at),
+            start_count: inner.start_count,
+            last_error: inner.last_error.clone(),
+        }
+    }
+
+    /// 是否正在运行
+    pub fn is_running(&self) -> bool {
+        self.status().state == MonitorState::Running
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl<E: 'static> Monitor<E> {
+    /// 启动监控，已经在运行时直接返回，停止超时的线程还没有退出时返回错误
+    pub fn start(&self) -> Result<(), String> {
+        let mut inner = self.lock();
+
+        if inner.worker.is_some() {
+            return Ok(());
+        }
+
+        if let Some(stale) = inner.stale.take() {
+            match stale.done.try_recv() {
+                Err(mpsc::TryRecvError::Empty) => {
+                    inner.stale = Some(stale);
+
+                    return Err(format!(
+                        "The previous {} monitor thread is still stopping",
+                        self.name
+                    ));
+                }
+                _ => join(self.name, stale),
+            }
+        }
+
+        let result = self.spawn();
+
+        match result {
+            Ok(worker) => {
+                log::info!("Started {} monitor with {} input", self.name, worker.input);
+
+                inner.worker = Some(worker);
+                inner.start_count += 1;
+                inner.last_error = None;
+
+                Ok(())
+            }
+            Err(error) => {
+                log::error!("Failed to start {} monitor: {}", self.name, error);
+
+                inner.last_error = Some(error.clone());
+
+                Err(error)
+            }
+        }
+    }
+
+    /// 重启监控
+    pub fn restart(&self) -> Result<(), String> {
+        self.stop()?;
+
+        self.start()
+    }
+
+    // 创建监控线程，等待输入源准备完成
+    fn spawn(&self) -> Result<Worker, String> {
+        let mut input = (self.factory)();
+        let name = input.name();
+        let sink = self.sink.clone();
+
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel();
+
+        let thread = thread::Builder::new()
+            .name(format!("{}-monitor", self.name))
+            .spawn(move || {
+                match input.prepare(sink) {
+                    Ok(stop) => {
+                        let _ = ready_tx.send(Ok(stop));
+
+                        input.run();
+                    }
+                    Err(error) => {
+                        let _ = ready_tx.send(Err(error));
+                    }
+                }
+
+                input.cleanup();
+
+                let _ = done_tx.send(());
+            })
+            .map_err(|e| e.to_string())?;
+
+        let stop = match ready_rx.recv_timeout(JOIN_TIMEOUT) {
+            Ok(Ok(stop)) => stop,
+            Ok(Err(error)) => {
+                let _ = thread.join();
+
+                return Err(error);
+            }
+            Err(_) => {
+                return Err(format!(
+                    "The {} monitor thread did not start in time",
+                    self.name
+                ))
+            }
+        };
+
+        Ok(Worker {
+            input: name,
+            started_at: now(),
+            stop: Some(stop),
+            done: done_rx,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl<E> Drop for Monitor<E> {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn join(name: &str, mut worker: Worker) {
+    if let Some(thread) = worker.thread.take() {
+        if thread.join().is_err() {
+            log::error!("The {} monitor thread panicked", name);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 当前平台默认的输入源，不支持的平台返回 None
+pub fn default_input_factory() -> Option<InputFactory> {
+    #[cfg(target_os = "windows")]
+    {
+        Some(Box::new(|| Box::new(WindowsHookInput::new())))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        None
+    }
+}
+
+/// 当前平台默认的识别参数，Windows 上使用系统的双击设置
+pub fn default_detector_config() -> DetectorConfig {
+    #[cfg(target_os = "windows")]
+    {
+        windows::system_detector_config()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        DetectorConfig::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Instant;
+
+    type Records = Arc<Mutex<Vec<InputRecord>>>;
+    type Senders = Arc<Mutex<Vec<ChannelInputSender>>>;
+
+    fn record(time: u64, event: InputEvent) -> InputRecord {
+        InputRecord { time, event }
+    }
+
+    fn collect_sink() -> (EventSink, Records) {
+        let records: Records = Arc::default();
+        let cloned = records.clone();
+
+        let sink: EventSink = Arc::new(move |record| cloned.lock().unwrap().push(record));
+
+        (sink, records)
+    }
+
+    // 每次启动都创建新的通道输入源，并保存发送事件的句柄
+    fn channel_monitor() -> (SelectionMonitor, Records, Senders) {
+        let (sink, records) = collect_sink();
+        let senders: Senders = Arc::default();
+        let cloned = senders.clone();
+
+        let factory: InputFactory = Box::new(move || {
+            let input = ChannelInput::new();
+
+            cloned.lock().unwrap().push(input.sender());
+
+            Box::new(input)
+        });
+
+        (
+            SelectionMonitor::new("selection", factory, sink),
+            records,
+            senders,
+        )
+    }
+
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + JOIN_TIMEOUT;
+
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        condition()
+    }
+
+    struct FailingInput;
+
+    impl MonitorInput for FailingInput {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn prepare(&mut self, _sink: EventSink) -> Result<StopHandle, String> {
+            Err("hook unavailable".to_string())
+        }
+
+        fn run(&mut self) {}
+    }
+
+    // 忽略停止请求的输入源，直到测试放行才退出
+    struct HangingInput {
+        release: Arc<Mutex<Receiver<()>>>,
+    }
+
+    impl MonitorInput for HangingInput {
+        fn name(&self) -> &'static str {
+            "hanging"
+        }
+
+        fn prepare(&mut self, _sink: EventSink) -> Result<StopHandle, String> {
+            Ok(Box::new(|| {}))
+        }
+
+        fn run(&mut self) {
+            let _ = self.release.lock().unwrap().recv();
+        }
+    }
+
+    #[test]
+    fn starts_once() {
+        let (monitor, _, senders) = channel_monitor();
+
+        assert_eq!(monitor.status().state, MonitorState::Stopped);
+
+        monitor.start().unwrap();
+        monitor.start().unwrap();
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Running);
+        assert_eq!(status.input.as_deref(), Some("channel"));
+        assert!(status.started_at.is_some());
+        assert_eq!(status.start_count, 1);
+        assert_eq!(senders.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stop_joins_worker() {
+        let (monitor, _, _) = channel_monitor();
+
+        monitor.start().unwrap();
+
+        let start = Instant::now();
+
+        monitor.stop().unwrap();
+
+        assert!(start.elapsed() < JOIN_TIMEOUT);
+        assert!(!monitor.is_running());
+        assert_eq!(monitor.status().input, None);
+
+        // 已经停止时再次停止没有影响
+        monitor.stop().unwrap();
+    }
+
+    #[test]
+    fn restart_counts_starts() {
+        let (monitor, _, senders) = channel_monitor();
+
+        monitor.start().unwrap();
+        monitor.restart().unwrap();
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Running);
+        assert_eq!(status.start_count, 2);
+        assert_eq!(senders.lock().unwrap().len(), 2);
+
+        // 上一次的输入源已经结束
+        let old = senders.lock().unwrap()[0].clone();
+
+        assert!(!old.send(record(0, InputEvent::Wheel)));
+    }
+
+    #[test]
+    fn forwards_events_to_sink() {
+        let (monitor, records, senders) = channel_monitor();
+
+        monitor.start().unwrap();
+
+        let sender = senders.lock().unwrap()[0].clone();
+        let events = [
+            record(1, InputEvent::KeyDown),
+            record(
+                2,
+                InputEvent::MouseDown {
+                    button: MouseButton::Left,
+                    x: 10,
+                    y: 20,
+                },
+            ),
+        ];
+
+        for event in events {
+            assert!(sender.send(event));
+        }
+
+        assert!(wait_for(|| records.lock().unwrap().len() == events.len()));
+        assert_eq!(*records.lock().unwrap(), events);
+
+        monitor.stop().unwrap();
+    }
+
+    #[test]
+    fn replays_recorded_events() {
+        let (sink, records) = collect_sink();
+        let events = vec![record(1, InputEvent::Wheel), record(2, InputEvent::KeyDown)];
+        let cloned = events.clone();
+
+        let monitor = SelectionMonitor::new(
+            "selection",
+            Box::new(move || Box::new(ChannelInput::with_records(cloned.clone()))),
+            sink,
+        );
+
+        monitor.start().unwrap();
+
+        assert!(wait_for(|| records.lock().unwrap().len() == events.len()));
+        assert_eq!(*records.lock().unwrap(), events);
+    }
+
+    #[test]
+    fn records_prepare_error() {
+        let (sink, _) = collect_sink();
+        let monitor = SelectionMonitor::new("selection", Box::new(|| Box::new(FailingInput)), sink);
+
+        assert_eq!(monitor.start(), Err("hook unavailable".to_string()));
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Stopped);
+        assert_eq!(status.start_count, 0);
+        assert_eq!(status.last_error.as_deref(), Some("hook unavailable"));
+    }
+
+    #[test]
+    fn hanging_worker_blocks_restart() {
+        let (sink, _) = collect_sink();
+        let (release_tx, release_rx) = mpsc::channel();
+        let release = Arc::new(Mutex::new(release_rx));
+        let hang = AtomicBool::new(true);
+
+        let factory: InputFactory = Box::new(move || {
+            if hang.swap(false, Ordering::SeqCst) {
+                Box::new(HangingInput {
+                    release: release.clone(),
+                })
+            } else {
+                Box::new(ChannelInput::new())
+            }
+        });
+
+        let monitor = SelectionMonitor::new("selection", factory, sink);
+
+        monitor.start().unwrap();
+
+        assert!(monitor.stop().is_err());
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Stopping);
+        assert_eq!(status.input.as_deref(), Some("hanging"));
+        assert!(status.last_error.is_some());
+        assert!(monitor.start().is_err());
+        assert_eq!(monitor.status().start_count, 1);
+
+        // 遗留的线程退出后可以再次启动
+        release_tx.send(()).unwrap();
+
+        assert!(wait_for(|| monitor.status().state == MonitorState::Stopped));
+
+        monitor.start().unwrap();
+
+        let status = monitor.status();
+
+        assert_eq!(status.state, MonitorState::Running);
+        assert_eq!(status.input.as_deref(), Some("channel"));
+        assert_eq!(status.start_count, 2);
+        assert_eq!(status.last_error, None);
+    }
+}
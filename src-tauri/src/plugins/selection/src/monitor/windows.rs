@@ -0,0 +1,253 @@
+//! Windows 系统级输入源
+//!
+//! 使用 WH_MOUSE_LL 低级鼠标钩子和 WH_KEYBOARD_LL 低级键盘钩子监听输入，
+//! 使用前台窗口事件钩子监听窗口切换。钩子安装在监控线程上，
+//! 停止时通过 `PostThreadMessageW(WM_QUIT)` 唤醒阻塞在 `GetMessageW` 中的消息循环。
+
+use super::{EventSink, InputEvent, InputRecord, MonitorInput, MouseButton, StopHandle};
+use crate::detector::DetectorConfig;
+use std::sync::Mutex;
+
+use windows::Win32::Foundation::{HMODULE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetMessageW, GetSystemMetrics, PeekMessageW,
+    PostThreadMessageW, SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx,
+    EVENT_SYSTEM_FOREGROUND, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, MSLLHOOKSTRUCT,
+    PM_NOREMOVE, SM_CXDOUBLECLK, WH_KEYBOARD_LL, WH_MOUSE_LL, WINEVENT_OUTOFCONTEXT, WM_KEYDOWN,
+    WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSEWHEEL,
+    WM_QUIT, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN,
+};
+
+// 钩子回调无法携带上下文，只能通过全局变量把事件交给当前的监控
+static EVENT_SINK: Mutex<Option<EventSink>> = Mutex::new(None);
+
+/// Windows 钩子输入源
+#[derive(Default)]
+pub struct WindowsHookInput {
+    mouse_hook: Option<HHOOK>,
+    keyboard_hook: Option<HHOOK>,
+    foreground_hook: Option<HWINEVENTHOOK>,
+}
+
+// 钩子句柄只在监控线程中使用
+unsafe impl Send for WindowsHookInput {}
+
+impl WindowsHookInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MonitorInput for WindowsHookInput {
+    fn name(&self) -> &'static str {
+        "windows-hook"
+    }
+
+    fn prepare(&mut self, sink: EventSink) -> Result<StopHandle, String> {
+        if let Ok(mut guard) = EVENT_SINK.lock() {
+            *guard = Some(sink);
+        }
+
+        unsafe {
+            let h_instance: HMODULE = GetModuleHandleW(None).unwrap_or_default();
+
+            let mouse_hook = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), h_instance, 0)
+                .map_err(|e| format!("Failed to set mouse hook: {:?}", e))?;
+
+            self.mouse_hook = Some(mouse_hook);
+
+            log::info!("Mouse hook installed successfully");
+
+            // 键盘钩子和前台窗口事件钩子只用于自动隐藏工具栏，安装失败不影响划词
+            self.keyboard_hook =
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), h_instance, 0)
+                    .map_err(|e| log::error!("Failed to set keyboard hook: {:?}", e))
+                    .ok();
+
+            let foreground_hook = SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                HMODULE::default(),
+                Some(foreground_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+
+            if !foreground_hook.is_invalid() {
+                self.foreground_hook = Some(foreground_hook);
+            }
+
+            // 确保线程已经创建消息队列，否则在进入消息循环之前投递的 WM_QUIT 会丢失
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+
+            let thread_id = GetCurrentThreadId();
+
+            Ok(Box::new(move || {
+                let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            }))
+        }
+    }
+
+    fn run(&mut self) {
+        unsafe {
+            let mut msg = MSG::default();
+
+            // GetMessageW 收到 WM_QUIT 时返回 0，出错时返回 -1
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) {
+        unsafe {
+            if let Some(hook) = self.mouse_hook.take() {
+                let _ = UnhookWindowsHookEx(hook);
+
+                log::info!("Mouse hook uninstalled");
+            }
+
+            if let Some(hook) = self.keyboard_hook.take() {
+                let _ = UnhookWindowsHookEx(hook);
+            }
+
+            if let Some(hook) = self.foreground_hook.take() {
+                let _ = UnhookWinEvent(hook);
+            }
+        }
+
+        if let Ok(mut guard) = EVENT_SINK.lock() {
+            *guard = None;
+        }
+    }
+}
+
+/// 按系统的双击设置生成识别参数
+pub fn system_detector_config() -> DetectorConfig {
+    let default = DetectorConfig::default();
+
+    unsafe {
+        let double_click_time = GetDoubleClickTime();
+        // SM_CXDOUBLECLK 是以第一次点击为中心的矩形宽度
+        let double_click_width = GetSystemMetrics(SM_CXDOUBLECLK);
+
+        DetectorConfig {
+            double_click_time: match double_click_time {
+                0 => default.double_click_time,
+                time => time as u64,
+            },
+            double_click_distance: match double_click_width {
+                0 => default.double_click_distance,
+                width => width / 2,
+            },
+            ..default
+        }
+    }
+}
+
+/// 把事件交给当前的监控，`time` 为系统的毫秒时间戳
+fn dispatch(time: u32, event: InputEvent) {
+    let sink = EVENT_SINK.lock().ok().and_then(|guard| guard.clone());
+
+    if let Some(sink) = sink {
+        sink(InputRecord {
+            time: time as u64,
+            event,
+        });
+    }
+}
+
+/// 鼠标钩子回调
+unsafe extern "system" fn mouse_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0 {
+        let mouse_info = &*(l_param.0 as *const MSLLHOOKSTRUCT);
+        let (x, y) = (mouse_info.pt.x, mouse_info.pt.y);
+
+        let event = match w_param.0 as u32 {
+            WM_LBUTTONDOWN => Some(InputEvent::MouseDown {
+                button: MouseButton::Left,
+                x,
+                y,
+            }),
+            WM_RBUTTONDOWN => Some(InputEvent::MouseDown {
+                button: MouseButton::Right,
+                x,
+                y,
+            }),
+            WM_MBUTTONDOWN => Some(InputEvent::MouseDown {
+                button: MouseButton::Middle,
+                x,
+                y,
+            }),
+            WM_LBUTTONUP => Some(InputEvent::MouseUp {
+                button: MouseButton::Left,
+                x,
+                y,
+            }),
+            WM_RBUTTONUP => Some(InputEvent::MouseUp {
+                button: MouseButton::Right,
+                x,
+                y,
+            }),
+            WM_MBUTTONUP => Some(InputEvent::MouseUp {
+                button: MouseButton::Middle,
+                x,
+                y,
+            }),
+            WM_MOUSEWHEEL | WM_MOUSEHWHEEL => Some(InputEvent::Wheel),
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            dispatch(mouse_info.time, event);
+        }
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 键盘钩子回调
+unsafe extern "system" fn keyboard_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0 {
+        let keyboard_info = &*(l_param.0 as *const KBDLLHOOKSTRUCT);
+
+        // 忽略模拟的按键，例如取词时模拟的 Ctrl+C
+        let injected = keyboard_info.flags.0 & LLKHF_INJECTED.0 != 0;
+
+        if !injected && matches!(w_param.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN) {
+            dispatch(keyboard_info.time, InputEvent::KeyDown);
+        }
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 前台窗口变化回调
+unsafe extern "system" fn foreground_event_proc(
+    _h_win_event_hook: HWINEVENTHOOK,
+    event: u32,
+    _hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _dw_event_thread: u32,
+    dwms_event_time: u32,
+) {
+    if event == EVENT_SYSTEM_FOREGROUND {
+        dispatch(dwms_event_time, InputEvent::ForegroundChanged);
+    }
+}
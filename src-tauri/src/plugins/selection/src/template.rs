@@ -0,0 +1,784 @@
+//! Agent 提示词模板
+//!
+//! 语法：
+//! - `{{text}}` 输出变量，变量不存在时输出空字符串
+//! - `{{text | truncate(4000) | trim}}` 依次应用过滤器
+//! - `{{#if app}}...{{else}}...{{/if}}`、`{{#unless clipboard}}...{{/unless}}` 条件，去掉首尾空白后非空为真
+//! - `\{{` 输出字面量 `{{`
+//!
+//! 解析和渲染不依赖系统环境，变量由调用方通过 [`TemplateContext`] 传入。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// 支持的变量
+pub const VARIABLES: &[&str] = &[
+    "text",
+    "app",
+    "clipboard",
+    "date",
+    "time",
+    "datetime",
+    "language",
+];
+
+/// 支持的过滤器
+pub const FILTERS: &[&str] = &[
+    "truncate", "upper", "lower", "trim", "oneline", "default", "json",
+];
+
+/// 截断时默认追加的后缀
+const TRUNCATE_SUFFIX: &str = "…";
+
+/// 渲染模板时使用的变量
+pub type TemplateContext = HashMap<String, String>;
+
+/// 模板中的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateIssue {
+    pub kind: TemplateIssueKind,
+    /// 相关的变量或过滤器名称
+    pub name: Option<String>,
+    /// 在模板中的位置（UTF-16 偏移，与前端字符串下标一致）
+    pub offset: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemplateIssueKind {
+    /// 语法错误，例如标签未闭合
+    Syntax,
+    /// 不支持的变量
+    UnknownVariable,
+    /// 不支持的过滤器
+    UnknownFilter,
+    /// 过滤器的参数不正确
+    InvalidArgument,
+}
+
+/// 解析或渲染失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    /// UTF-16 偏移
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl From<TemplateError> for TemplateIssue {
+    fn from(error: TemplateError) -> Self {
+        Self {
+            kind: TemplateIssueKind::Syntax,
+            name: None,
+            offset: error.offset,
+            message: error.message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Arg {
+    Number(i64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    name: String,
+    args: Vec<Arg>,
+    offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Expr {
+    variable: String,
+    filters: Vec<Filter>,
+    offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Expr(Expr),
+    If {
+        negate: bool,
+        condition: Expr,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// 解析后的模板
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// 解析模板
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let tokens = tokenize(source)?;
+        let mut tokens = tokens.into_iter();
+
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+
+        if let Some(end) = end {
+            return Err(error(end.offset, format!("Unexpected {{{{{}}}}}", end.tag)));
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// 检查模板中不支持的变量、过滤器和参数
+    pub fn issues(&self) -> Vec<TemplateIssue> {
+        let mut issues = Vec::new();
+
+        collect_issues(&self.nodes, &mut issues);
+
+        issues
+    }
+
+    /// 渲染模板，不存在的变量输出空字符串
+    pub fn render(&self, context: &TemplateContext) -> Result<String, TemplateError> {
+        let mut output = String::new();
+
+        render_nodes(&self.nodes, context, &mut output)?;
+
+        Ok(output)
+    }
+}
+
+/// 解析并检查模板，语法错误时只返回该错误
+pub fn validate(source: &str) -> Vec<TemplateIssue> {
+    match Template::parse(source) {
+        Ok(template) => template.issues(),
+        Err(error) => vec![error.into()],
+    }
+}
+
+/// 解析并渲染模板
+pub fn render(source: &str, context: &TemplateContext) -> Result<String, TemplateError> {
+    Template::parse(source)?.render(context)
+}
+
+fn error(offset: usize, message: impl Into<String>) -> TemplateError {
+    TemplateError {
+        offset,
+        message: message.into(),
+    }
+}
+
+// ---------- 词法分析 ----------
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Tag(TagToken),
+}
+
+#[derive(Debug)]
+struct TagToken {
+    tag: String,
+    offset: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, TemplateError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut index = 0;
+    // 偏移按 UTF-16 计算，前端可直接用于字符串下标
+    let utf16_offset =
+        |index: usize| -> usize { chars[..index].iter().map(|c| c.len_utf16()).sum() };
+
+    while index < chars.len() {
+        let rest = &chars[index..];
+
+        if rest.starts_with(&['\\', '{', '{']) {
+            text.push_str("{{");
+            index += 3;
+            continue;
+        }
+
+        if !rest.starts_with(&['{', '{']) {
+            text.push(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        let start = utf16_offset(index);
+        let close = (index + 2..chars.len().saturating_sub(1))
+            .find(|&i| chars[i] == '}' && chars[i + 1] == '}')
+            .ok_or_else(|| error(start, "Unclosed tag, expected }}"))?;
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+
+        let tag: String = chars[index + 2..close].iter().collect();
+
+        tokens.push(Token::Tag(TagToken {
+            tag: tag.trim().to_string(),
+            offset: start,
+        }));
+
+        index = close + 2;
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+
+    Ok(tokens)
+}
+
+// ---------- 语法分析 ----------
+
+type Tokens = std::vec::IntoIter<Token>;
+
+// 解析到 `{{else}}` 或结束标签为止，返回遇到的结束标签
+fn parse_nodes(tokens: &mut Tokens) -> Result<(Vec<Node>, Option<TagToken>), TemplateError> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+
+        if tag.tag == "else" || tag.tag.starts_with('/') {
+            return Ok((nodes, Some(tag)));
+        }
+
+        let block = tag
+            .tag
+            .strip_prefix("#if ")
+            .map(|condition| ("if", false, condition))
+            .or_else(|| {
+                tag.tag
+                    .strip_prefix("#unless ")
+                    .map(|condition| ("unless", true, condition))
+            });
+
+        let Some((name, negate, condition)) = block else {
+            if tag.tag.starts_with('#') {
+                return Err(error(
+                    tag.offset,
+                    format!("Unknown block {{{{{}}}}}", tag.tag),
+                ));
+            }
+
+            nodes.push(Node::Expr(parse_expr(&tag.tag, tag.offset)?));
+            continue;
+        };
+
+        let condition = parse_expr(condition, tag.offset)?;
+        let closing = format!("/{}", name);
+
+        let (then, end) = parse_nodes(tokens)?;
+
+        let (otherwise, end) = match end {
+            Some(end) if end.tag == "else" => parse_nodes(tokens)?,
+            end => (Vec::new(), end),
+        };
+
+        match end {
+            Some(end) if end.tag == closing => {}
+            Some(end) => {
+                return Err(error(
+                    end.offset,
+                    format!("Expected {{{{{}}}}}, found {{{{{}}}}}", closing, end.tag),
+                ))
+            }
+            None => {
+                return Err(error(
+                    tag.offset,
+                    format!("Unclosed {{{{#{}}}}}, expected {{{{{}}}}}", name, closing),
+                ))
+            }
+        }
+
+        nodes.push(Node::If {
+            negate,
+            condition,
+            then,
+            otherwise,
+        });
+    }
+
+    Ok((nodes, None))
+}
+
+fn parse_expr(source: &str, offset: usize) -> Result<Expr, TemplateError> {
+    let mut parts = split_pipes(source, offset)?.into_iter();
+
+    let variable = parts.next().unwrap_or_default().trim().to_string();
+
+    if !is_identifier(&variable) {
+        return Err(error(
+            offset,
+            format!("Invalid variable name {:?}", variable),
+        ));
+    }
+
+    let filters = parts
+        .map(|part| parse_filter(part.trim(), offset))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Expr {
+        variable,
+        filters,
+        offset,
+    })
+}
+
+// 按 `|` 拆分，忽略字符串参数中的 `|`
+fn split_pipes(source: &str, offset: usize) -> Result<Vec<&str>, TemplateError> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+
+    for (index, c) in source.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '|') => {
+                parts.push(&source[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if quote.is_some() {
+        return Err(error(offset, "Unclosed string"));
+    }
+
+    parts.push(&source[start..]);
+
+    Ok(parts)
+}
+
+fn parse_filter(source: &str, offset: usize) -> Result<Filter, TemplateError> {
+    let (name, args) = match source.find('(') {
+        Some(index) => {
+            let args = source[index + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| error(offset, format!("Expected ) in {:?}", source)))?;
+
+            (source[..index].trim(), parse_args(args, offset)?)
+        }
+        None => (source, Vec::new()),
+    };
+
+    if !is_identifier(name) {
+        return Err(error(offset, format!("Invalid filter name {:?}", name)));
+    }
+
+    Ok(Filter {
+        name: name.to_string(),
+        args,
+        offset,
+    })
+}
+
+fn parse_args(source: &str, offset: usize) -> Result<Vec<Arg>, TemplateError> {
+    let mut args = Vec::new();
+    let mut chars = source.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | ',' => {
+                chars.next();
+            }
+            '"' | '\'' => {
+                chars.next();
+
+                let mut value = String::new();
+                let mut closed = false;
+
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => value.extend(chars.next()),
+                        next if next == c => {
+                            closed = true;
+                            break;
+                        }
+                        next => value.push(next),
+                    }
+                }
+
+                if !closed {
+                    return Err(error(offset, "Unclosed string"));
+                }
+
+                args.push(Arg::String(value));
+            }
+            _ => {
+                let mut value = String::new();
+
+                while let Some(&next) = chars.peek() {
+                    if next == ',' || next == ' ' {
+                        break;
+                    }
+
+                    value.push(next);
+                    chars.next();
+                }
+
+                let number = value
+                    .parse()
+                    .map_err(|_| error(offset, format!("Invalid argument {:?}", value)))?;
+
+                args.push(Arg::Number(number));
+            }
+        }
+    }
+
+    Ok(args)
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// ---------- 检查 ----------
+
+fn collect_issues(nodes: &[Node], issues: &mut Vec<TemplateIssue>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Expr(expr) => check_expr(expr, issues),
+            Node::If {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => {
+                check_expr(condition, issues);
+                collect_issues(then, issues);
+                collect_issues(otherwise, issues);
+            }
+        }
+    }
+}
+
+fn check_expr(expr: &Expr, issues: &mut Vec<TemplateIssue>) {
+    if !VARIABLES.contains(&expr.variable.as_str()) {
+        issues.push(TemplateIssue {
+            kind: TemplateIssueKind::UnknownVariable,
+            name: Some(expr.variable.clone()),
+            offset: expr.offset,
+            message: format!("Unknown variable \"{}\"", expr.variable),
+        });
+    }
+
+    for filter in &expr.filters {
+        if !FILTERS.contains(&filter.name.as_str()) {
+            issues.push(TemplateIssue {
+                kind: TemplateIssueKind::UnknownFilter,
+                name: Some(filter.name.clone()),
+                offset: filter.offset,
+                message: format!("Unknown filter \"{}\"", filter.name),
+            });
+
+            continue;
+        }
+
+        // 用空字符串试运行一次，检查参数
+        if let Err(e) = apply_filter(filter, String::new()) {
+            issues.push(TemplateIssue {
+                kind: TemplateIssueKind::InvalidArgument,
+                name: Some(filter.name.clone()),
+                offset: filter.offset,
+                message: e.message,
+            });
+        }
+    }
+}
+
+// ---------- 渲染 ----------
+
+fn render_nodes(
+    nodes: &[Node],
+    context: &TemplateContext,
+    output: &mut String,
+) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Expr(expr) => output.push_str(&eval(expr, context)?),
+            Node::If {
+                negate,
+                condition,
+                then,
+                otherwise,
+            } => {
+                let truthy = !eval(condition, context)?.trim().is_empty();
+
+                let branch = if truthy != *negate { then } else { otherwise };
+
+                render_nodes(branch, context, output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn eval(expr: &Expr, context: &TemplateContext) -> Result<String, TemplateError> {
+    let value = context.get(&expr.variable).cloned().unwrap_or_default();
+
+    expr.filters.iter().try_fold(value, |value, filter| {
+        if FILTERS.contains(&filter.name.as_str()) {
+            apply_filter(filter, value)
+        } else {
+            // 不支持的过滤器已在检查时报告，渲染时原样保留
+            Ok(value)
+        }
+    })
+}
+
+fn apply_filter(filter: &Filter, value: String) -> Result<String, TemplateError> {
+    let args = &filter.args;
+    let invalid = || {
+        error(
+            filter.offset,
+            format!("Invalid arguments for filter \"{}\"", filter.name),
+        )
+    };
+
+    let value = match filter.name.as_str() {
+        "truncate" => {
+            let (limit, suffix) = match args.as_slice() {
+                [Arg::Number(limit)] => (*limit, TRUNCATE_SUFFIX),
+                [Arg::Number(limit), Arg::String(suffix)] => (*limit, suffix.as_str()),
+                _ => return Err(invalid()),
+            };
+
+            let limit = usize::try_from(limit).map_err(|_| invalid())?;
+
+            if value.chars().count() <= limit {
+                value
+            } else {
+                let mut truncated: String = value.chars().take(limit).collect();
+
+                truncated.push_str(suffix);
+                truncated
+            }
+        }
+        "default" => match args.as_slice() {
+            [Arg::String(fallback)] if value.trim().is_empty() => fallback.clone(),
+            [Arg::String(_)] => value,
+            _ => return Err(invalid()),
+        },
+        name => {
+            if !args.is_empty() {
+                return Err(invalid());
+            }
+
+            match name {
+                "upper" => value.to_uppercase(),
+                "lower" => value.to_lowercase(),
+                "trim" => value.trim().to_string(),
+                "oneline" => value.split_whitespace().collect::<Vec<_>>().join(" "),
+                "json" => serde_json::to_string(&value).unwrap_or_default(),
+                _ => return Err(invalid()),
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(pairs: &[(&str, &str)]) -> TemplateContext {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn render_with(source: &str, pairs: &[(&str, &str)]) -> String {
+        render(source, &context(pairs)).unwrap()
+    }
+
+    #[test]
+    fn renders_variables() {
+        assert_eq!(
+            render_with(
+                "Hi {{ text }}, from {{app}}",
+                &[("text", "a"), ("app", "b")]
+            ),
+            "Hi a, from b"
+        );
+        // 变量不存在时输出空字符串
+        assert_eq!(render_with("[{{clipboard}}]", &[]), "[]");
+    }
+
+    #[test]
+    fn applies_filters_in_order() {
+        let pairs = [("text", "  Hello   World \n again ")];
+
+        assert_eq!(
+            render_with("{{text | trim | upper}}", &pairs),
+            "HELLO   WORLD \n AGAIN"
+        );
+        assert_eq!(
+            render_with("{{text | oneline | lower}}", &pairs),
+            "hello world again"
+        );
+        assert_eq!(
+            render_with("{{text | oneline | truncate(5)}}", &pairs),
+            "Hello…"
+        );
+        assert_eq!(
+            render_with("{{text | oneline | truncate(5, \"...\")}}", &pairs),
+            "Hello..."
+        );
+        assert_eq!(
+            render_with("{{text | truncate(100)}}", &[("text", "短")]),
+            "短"
+        );
+        assert_eq!(
+            render_with("{{app | default(\"未知 | 应用\")}}", &[("app", "  ")]),
+            "未知 | 应用"
+        );
+        assert_eq!(render_with("{{app | default('x')}}", &[("app", "y")]), "y");
+        assert_eq!(
+            render_with("{{text | json}}", &[("text", "a \"b\"\n")]),
+            r#""a \"b\"\n""#
+        );
+    }
+
+    #[test]
+    fn truncate_counts_chars() {
+        assert_eq!(
+            render_with("{{text | truncate(2)}}", &[("text", "你好世界")]),
+            "你好…"
+        );
+    }
+
+    #[test]
+    fn renders_conditions() {
+        let source = "{{#if app}}in {{app}}{{else}}nowhere{{/if}}";
+
+        assert_eq!(render_with(source, &[("app", "Code")]), "in Code");
+        assert_eq!(render_with(source, &[("app", " \n")]), "nowhere");
+        assert_eq!(render_with(source, &[]), "nowhere");
+
+        let source = "{{#unless clipboard}}empty{{else}}{{clipboard}}{{/unless}}";
+
+        assert_eq!(render_with(source, &[]), "empty");
+        assert_eq!(render_with(source, &[("clipboard", "c")]), "c");
+
+        // 条件中的过滤器参与判断，可以嵌套
+        let source = "{{#if text | trim}}{{#unless app}}no app{{/unless}}{{/if}}";
+
+        assert_eq!(render_with(source, &[("text", "x")]), "no app");
+        assert_eq!(render_with(source, &[("text", "x"), ("app", "a")]), "");
+        assert_eq!(render_with(source, &[("text", "  ")]), "");
+    }
+
+    #[test]
+    fn escapes_braces() {
+        assert_eq!(
+            render_with(r"\{{text}} {{text}}", &[("text", "a")]),
+            "{{text}} a"
+        );
+        assert_eq!(render_with(r"\{{#if}}", &[]), "{{#if}}");
+        // 单个花括号原样输出
+        assert_eq!(render_with("{ text } }}", &[]), "{ text } }}");
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let cases = [
+            ("ab{{text", 2),
+            ("{{#if app}}x", 0),
+            ("x{{#if app}}{{/unless}}", 12),
+            ("{{/if}}", 0),
+            ("{{else}}", 0),
+            ("{{#each text}}{{/each}}", 0),
+            ("{{te xt}}", 0),
+            ("{{text | truncate(4}}", 0),
+            ("{{text | default(\"a)}}", 0),
+            ("{{text | truncate(abc)}}", 0),
+        ];
+
+        for (source, offset) in cases {
+            let issues = validate(source);
+
+            assert_eq!(issues.len(), 1, "{}", source);
+            assert_eq!(issues[0].kind, TemplateIssueKind::Syntax, "{}", source);
+            assert_eq!(issues[0].offset, offset, "{}", source);
+        }
+    }
+
+    #[test]
+    fn reports_unknown_names() {
+        let issues = validate("{{text}} {{user | upper}} {{#if app | shout}}{{/if}}");
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, TemplateIssueKind::UnknownVariable);
+        assert_eq!(issues[0].name.as_deref(), Some("user"));
+        assert_eq!(issues[0].offset, 9);
+        assert_eq!(issues[1].kind, TemplateIssueKind::UnknownFilter);
+        assert_eq!(issues[1].name.as_deref(), Some("shout"));
+        assert_eq!(issues[1].offset, 26);
+
+        // 不支持的变量和过滤器不影响渲染
+        assert_eq!(
+            render_with("[{{user}}|{{text | shout}}]", &[("text", "a")]),
+            "[|a]"
+        );
+    }
+
+    #[test]
+    fn reports_invalid_arguments() {
+        let issues = validate("{{text | truncate}}{{text | upper(1)}}{{text | truncate(-1)}}");
+
+        let kinds: Vec<_> = issues.iter().map(|issue| issue.kind).collect();
+
+        assert_eq!(kinds, [TemplateIssueKind::InvalidArgument; 3]);
+        assert_eq!(issues[1].name.as_deref(), Some("upper"));
+        assert!(validate("{{text | truncate(10, '…') | default(\"x\")}}").is_empty());
+    }
+
+    #[test]
+    fn offsets_are_utf16() {
+        // "中" 占 1 个 UTF-16 单元，"😀" 占 2 个
+        let issues = validate("中😀 {{user}}");
+
+        assert_eq!(issues[0].offset, 4);
+
+        let issues = validate("😀😀{{text");
+
+        assert_eq!(issues[0].offset, 4);
+        assert_eq!(
+            "😀😀{{text"
+                .encode_utf16()
+                .skip(4)
+                .take(2)
+                .collect::<Vec<_>>(),
+            "{{".encode_utf16().collect::<Vec<_>>()
+        );
+    }
+}
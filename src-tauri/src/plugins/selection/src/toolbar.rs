@@ -0,0 +1,349 @@
+//! 划词工具栏窗口的定位与显示
+//!
+//! 根据划词位置找到对应的显示器，按该显示器的缩放比例计算工具栏大小，
+//! 并把工具栏限制在显示器的工作区（不含任务栏）内。
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, Runtime, WebviewWindow};
+
+use crate::SELECTION_TOOLBAR_LABEL;
+
+/// 工具栏相对划词位置的偏移量（逻辑像素），避免遮挡选区
+const TOOLBAR_OFFSET: f64 = 10.0;
+
+// 工具栏可见时所在的区域，隐藏时为 None
+static VISIBLE_BOUNDS: Mutex<Option<Rect>> = Mutex::new(None);
+
+/// 矩形区域（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    // 点到矩形的距离的平方，点在矩形内时为 0
+    fn distance_squared(&self, x: i32, y: i32) -> i64 {
+        let dx = (self.x - x).max(0).max(x - self.right()) as i64;
+        let dy = (self.y - y).max(0).max(y - self.bottom()) as i64;
+
+        dx * dx + dy * dy
+    }
+}
+
+/// 显示器的区域信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorArea {
+    /// 显示器的完整区域
+    pub bounds: Rect,
+    /// 显示器的工作区，不包含任务栏和程序坞
+    pub work_area: Rect,
+    /// 显示器的缩放比例
+    pub scale_factor: f64,
+}
+
+impl From<&Monitor> for MonitorArea {
+    fn from(monitor: &Monitor) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        let work_area = monitor.work_area();
+
+        Self {
+            bounds: Rect {
+                x: position.x,
+                y: position.y,
+                width: size.width as i32,
+                height: size.height as i32,
+            },
+            work_area: Rect {
+                x: work_area.position.x,
+                y: work_area.position.y,
+                width: work_area.size.width as i32,
+                height: work_area.size.height as i32,
+            },
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+}
+
+/// 计算后的工具栏位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolbarPlacement {
+    /// 工具栏在目标显示器上的区域（物理像素）
+    pub bounds: Rect,
+    /// 目标显示器的缩放比例
+    pub scale_factor: f64,
+}
+
+/// 根据划词位置计算工具栏的位置
+///
+/// `width` 和 `height` 为工具栏的逻辑大小，`x` 和 `y` 为划词位置的物理坐标。
+/// 优先显示在划词位置的右下方，空间不足时翻转到左侧或上方，最后限制在工作区内。
+pub fn compute_toolbar_placement(
+    x: i32,
+    y: i32,
+    width: f64,
+    height: f64,
+    monitors: &[MonitorArea],
+) -> Option<ToolbarPlacement> {
+    let monitor = monitors
+        .iter()
+        .find(|monitor| monitor.bounds.contains(x, y))
+        .or_else(|| {
+            monitors
+                .iter()
+                .min_by_key(|monitor| monitor.bounds.distance_squared(x, y))
+        })?;
+
+    let scale_factor = monitor.scale_factor;
+    let work_area = monitor.work_area;
+
+    let width = (width * scale_factor).round() as i32;
+    let height = (height * scale_factor).round() as i32;
+    let offset = (TOOLBAR_OFFSET * scale_factor).round() as i32;
+
+    let mut left = x + offset;
+    if left + width > work_area.right() {
+        left = x - offset - width;
+    }
+
+    let mut top = y + offset;
+    if top + height > work_area.bottom() {
+        top = y - offset - height;
+    }
+
+    let left = left.min(work_area.right() - width).max(work_area.x);
+    let top = top.min(work_area.bottom() - height).max(work_area.y);
+
+    Some(ToolbarPlacement {
+        bounds: Rect {
+            x: left,
+            y: top,
+            width,
+            height,
+        },
+        scale_factor,
+    })
+}
+
+/// 获取划词工具栏窗口
+pub fn get_toolbar_window<R: Runtime>(app_handle: &AppHandle<R>) -> Option<WebviewWindow<R>> {
+    app_handle.get_webview_window(SELECTION_TOOLBAR_LABEL)
+}
+
+/// 把工具栏移动到划词位置并显示，不抢占当前应用的焦点
+pub fn show_toolbar<R: Runtime>(
+    window: &WebviewWindow<R>,
+    x: i32,
+    y: i32,
+) -> tauri::Result<Option<ToolbarPlacement>> {
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()?
+        .iter()
+        .map(MonitorArea::from)
+        .collect();
+
+    // 窗口大小换算为逻辑像素，到了目标显示器后再按目标缩放比例换算
+    let size = window
+        .outer_size()?
+        .to_logical::<f64>(window.scale_factor()?);
+
+    let Some(placement) = compute_toolbar_placement(x, y, size.width, size.height, &monitors)
+    else {
+        return Ok(None);
+    };
+
+    let bounds = placement.bounds;
+
+    window.set_position(PhysicalPosition::new(bounds.x, bounds.y))?;
+
+    show_without_focus(window)?;
+
+    set_visible_bounds(Some(bounds));
+
+    Ok(Some(placement))
+}
+
+/// 隐藏工具栏
+pub fn hide_toolbar<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    set_visible_bounds(None);
+
+    window.hide()
+}
+
+/// 工具栏可见时所在的区域
+pub fn visible_bounds() -> Option<Rect> {
+    VISIBLE_BOUNDS.lock().ok().and_then(|guard| *guard)
+}
+
+/// 取出工具栏的可见区域，并将其标记为不可见
+pub fn take_visible_bounds() -> Option<Rect> {
+    VISIBLE_BOUNDS
+        .lock()
+        .ok()
+        .and_then(|mut guard| guard.take())
+}
+
+fn set_visible_bounds(bounds: Option<Rect>) {
+    if let Ok(mut guard) = VISIBLE_BOUNDS.lock() {
+        *guard = bounds;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn show_without_focus<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_SHOWNOACTIVATE};
+
+    let hwnd = window.hwnd()?;
+
+    unsafe {
+        let _ = ShowWindow(HWND(hwnd.0), SW_SHOWNOACTIVATE);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn show_without_focus<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    window.show()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOOLBAR_WIDTH: f64 = 200.0;
+    const TOOLBAR_HEIGHT: f64 = 40.0;
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    // 1920x1080 的主显示器，底部有 40 像素的任务栏
+    fn primary() -> MonitorArea {
+        MonitorArea {
+            bounds: rect(0, 0, 1920, 1080),
+            work_area: rect(0, 0, 1920, 1040),
+            scale_factor: 1.0,
+        }
+    }
+
+    // 主显示器右侧的 2 倍缩放显示器，顶部有 50 像素的菜单栏
+    fn secondary() -> MonitorArea {
+        MonitorArea {
+            bounds: rect(1920, 0, 2560, 1440),
+            work_area: rect(1920, 50, 2560, 1390),
+            scale_factor: 2.0,
+        }
+    }
+
+    fn place(x: i32, y: i32, monitors: &[MonitorArea]) -> ToolbarPlacement {
+        compute_toolbar_placement(x, y, TOOLBAR_WIDTH, TOOLBAR_HEIGHT, monitors).unwrap()
+    }
+
+    #[test]
+    fn places_below_right_of_point() {
+        let placement = place(100, 100, &[primary()]);
+
+        assert_eq!(placement.bounds, rect(110, 110, 200, 40));
+        assert_eq!(placement.scale_factor, 1.0);
+    }
+
+    #[test]
+    fn flips_to_left_at_right_edge() {
+        let placement = place(1900, 100, &[primary()]);
+
+        assert_eq!(placement.bounds, rect(1690, 110, 200, 40));
+    }
+
+    #[test]
+    fn flips_above_at_bottom_edge() {
+        // 任务栏上方的空间不够，翻转到划词位置上方
+        let placement = place(100, 1020, &[primary()]);
+
+        assert_eq!(placement.bounds, rect(110, 970, 200, 40));
+    }
+
+    #[test]
+    fn clamps_into_work_area_when_flip_does_not_fit() {
+        // 工作区比工具栏还窄，翻转后仍然放不下，只能贴着工作区的边缘
+        let narrow = MonitorArea {
+            bounds: rect(0, 0, 150, 30),
+            work_area: rect(0, 0, 150, 30),
+            scale_factor: 1.0,
+        };
+
+        let placement = place(140, 20, &[narrow]);
+
+        assert_eq!(placement.bounds, rect(0, 0, 200, 40));
+    }
+
+    #[test]
+    fn uses_scale_factor_of_secondary_monitor() {
+        let placement = place(2000, 200, &[primary(), secondary()]);
+
+        assert_eq!(placement.bounds, rect(2020, 220, 400, 80));
+        assert_eq!(placement.scale_factor, 2.0);
+    }
+
+    #[test]
+    fn clamps_to_work_area_of_secondary_monitor() {
+        // 靠近副显示器顶部的菜单栏，翻转后也不能进入菜单栏
+        let monitors = [primary(), secondary()];
+
+        let placement = place(4470, 60, &monitors);
+
+        assert_eq!(placement.bounds, rect(4050, 80, 400, 80));
+
+        let placement = place(3000, 1430, &monitors);
+
+        assert_eq!(placement.bounds, rect(3020, 1330, 400, 80));
+    }
+
+    #[test]
+    fn falls_back_to_nearest_monitor_for_point_outside() {
+        let monitors = [primary(), secondary()];
+
+        // 副显示器右侧，离副显示器最近
+        let placement = place(5000, 100, &monitors);
+
+        assert_eq!(placement.scale_factor, 2.0);
+        assert_eq!(placement.bounds, rect(4080, 120, 400, 80));
+
+        // 主显示器下方，离主显示器最近
+        let placement = place(100, 1500, &monitors);
+
+        assert_eq!(placement.scale_factor, 1.0);
+        assert_eq!(placement.bounds, rect(110, 1000, 200, 40));
+    }
+
+    #[test]
+    fn returns_none_without_monitors() {
+        assert_eq!(
+            compute_toolbar_placement(0, 0, TOOLBAR_WIDTH, TOOLBAR_HEIGHT, &[]),
+            None
+        );
+    }
+}
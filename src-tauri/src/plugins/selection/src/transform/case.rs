@@ -0,0 +1,97 @@
+//! 大小写和命名风格转换
+//!
+//! 命名风格转换按行进行，每行视为一个标识符，保留原有的换行。
+
+use heck::{ToKebabCase, ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+
+use super::TransformResult;
+
+pub fn upper(text: &str) -> TransformResult {
+    Ok(text.to_uppercase())
+}
+
+pub fn lower(text: &str) -> TransformResult {
+    Ok(text.to_lowercase())
+}
+
+pub fn title(text: &str) -> TransformResult {
+    Ok(title_case(text))
+}
+
+/// 每个单词首字母大写，其余字符不变，粘贴转换也使用这个实现
+pub fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word_start = true;
+
+    for c in text.chars() {
+        if word_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+        } else {
+            result.push(c);
+        }
+
+        word_start = c.is_whitespace() || matches!(c, '-' | '(' | '[' | '"' | '\'');
+    }
+
+    result
+}
+
+/// 每句话首字母大写，其余字母小写
+pub fn sentence(text: &str) -> TransformResult {
+    let mut result = String::with_capacity(text.len());
+    let mut sentence_start = true;
+
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            if sentence_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+
+            sentence_start = false;
+        } else {
+            result.push(c);
+
+            if matches!(c, '.' | '!' | '?' | '\n' | '。' | '！' | '？') {
+                sentence_start = true;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn camel(text: &str) -> TransformResult {
+    Ok(per_line(text, |line| line.to_lower_camel_case()))
+}
+
+pub fn pascal(text: &str) -> TransformResult {
+    Ok(per_line(text, |line| line.to_upper_camel_case()))
+}
+
+pub fn snake(text: &str) -> TransformResult {
+    Ok(per_line(text, |line| line.to_snake_case()))
+}
+
+pub fn constant(text: &str) -> TransformResult {
+    Ok(per_line(text, |line| line.to_shouty_snake_case()))
+}
+
+pub fn kebab(text: &str) -> TransformResult {
+    Ok(per_line(text, |line| line.to_kebab_case()))
+}
+
+fn per_line(text: &str, convert: impl Fn(&str) -> String) -> String {
+    text.split('\n')
+        .map(|line| {
+            let (line, cr) = match line.strip_suffix('\r') {
+                Some(line) => (line, "\r"),
+                None => (line, ""),
+            };
+
+            format!("{}{}", convert(line), cr)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,76 @@
+//! 编码解码和哈希
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use md5::Md5;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use super::{invalid, TransformResult};
+
+/// 与 JavaScript 的 `encodeURIComponent` 一致，保留 `A-Z a-z 0-9 - _ . ! ~ * ' ( )`
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+pub fn base64_encode(text: &str) -> TransformResult {
+    Ok(STANDARD.encode(text))
+}
+
+/// 同时支持标准和 URL 安全的字母表，可以省略填充，忽略空白字符
+pub fn base64_decode(text: &str) -> TransformResult {
+    let input: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let bytes = [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&input).ok())
+        .ok_or_else(|| invalid("not a valid Base64 string"))?;
+
+    String::from_utf8(bytes).map_err(|_| invalid("decoded data is not valid UTF-8 text"))
+}
+
+pub fn url_encode(text: &str) -> TransformResult {
+    Ok(utf8_percent_encode(text, URI_COMPONENT).to_string())
+}
+
+/// 解码百分号编码，`+` 视为空格
+pub fn url_decode(text: &str) -> TransformResult {
+    let text = text.trim().replace('+', " ");
+
+    percent_decode_str(&text)
+        .decode_utf8()
+        .map(|text| text.into_owned())
+        .map_err(|_| invalid("decoded data is not valid UTF-8 text"))
+}
+
+pub fn md5(text: &str) -> TransformResult {
+    Ok(digest::<Md5>(text))
+}
+
+pub fn sha1(text: &str) -> TransformResult {
+    Ok(digest::<Sha1>(text))
+}
+
+pub fn sha256(text: &str) -> TransformResult {
+    Ok(digest::<Sha256>(text))
+}
+
+pub fn sha512(text: &str) -> TransformResult {
+    Ok(digest::<Sha512>(text))
+}
+
+// 十六进制小写的摘要
+fn digest<D: Digest>(text: &str) -> String {
+    D::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,222 @@
+//! 颜色转换
+//!
+//! 支持 `#rgb`、`#rgba`、`#rrggbb`、`#rrggbbaa`、`rgb()`、`rgba()`、`hsl()` 和 `hsla()`，
+//! 输出十六进制、RGB 和 HSL 三种写法，每行一个。
+
+use super::{format_number, invalid, TransformError, TransformResult};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgba {
+    r: u8,
+    g: u8,
+    b: u8,
+    /// 0 到 1
+    a: f64,
+}
+
+pub fn convert(text: &str) -> TransformResult {
+    let color = parse(text.trim())?;
+
+    Ok(format!(
+        "{}\n{}\n{}",
+        to_hex(color),
+        to_rgb(color),
+        to_hsl(color)
+    ))
+}
+
+fn parse(text: &str) -> Result<Rgba, TransformError> {
+    let lower = text.to_ascii_lowercase();
+
+    if let Some(hex) = lower.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    if let Some((name, args)) = lower
+        .strip_suffix(')')
+        .and_then(|text| text.split_once('('))
+    {
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+
+        return match name.trim() {
+            "rgb" | "rgba" => parse_rgb(&args),
+            "hsl" | "hsla" => parse_hsl(&args),
+            name => Err(invalid(format!("unsupported color function {:?}", name))),
+        };
+    }
+
+    // 没有 # 的十六进制
+    if matches!(lower.len(), 3 | 4 | 6 | 8) && lower.chars().all(|c| c.is_ascii_hexdigit()) {
+        return parse_hex(&lower);
+    }
+
+    Err(invalid("not a recognized color"))
+}
+
+fn parse_hex(hex: &str) -> Result<Rgba, TransformError> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid(format!("invalid hex color #{}", hex)));
+    }
+
+    let digits: Vec<u8> = match hex.len() {
+        // 简写形式的每一位重复一次
+        3 | 4 => hex
+            .chars()
+            .map(|c| {
+                let value = c.to_digit(16).unwrap_or_default() as u8;
+
+                value * 16 + value
+            })
+            .collect(),
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap_or_default())
+            .collect(),
+        _ => return Err(invalid(format!("invalid hex color #{}", hex))),
+    };
+
+    Ok(Rgba {
+        r: digits[0],
+        g: digits[1],
+        b: digits[2],
+        a: digits.get(3).map_or(1.0, |a| *a as f64 / 255.0),
+    })
+}
+
+fn parse_rgb(args: &[&str]) -> Result<Rgba, TransformError> {
+    if !matches!(args.len(), 3 | 4) {
+        return Err(invalid("rgb() expects 3 or 4 arguments"));
+    }
+
+    let channel = |arg: &str| -> Result<u8, TransformError> {
+        let value = match arg.strip_suffix('%') {
+            Some(percent) => parse_number(percent)? * 255.0 / 100.0,
+            None => parse_number(arg)?,
+        };
+
+        Ok(value.round().clamp(0.0, 255.0) as u8)
+    };
+
+    Ok(Rgba {
+        r: channel(args[0])?,
+        g: channel(args[1])?,
+        b: channel(args[2])?,
+        a: args.get(3).map_or(Ok(1.0), |arg| parse_alpha(arg))?,
+    })
+}
+
+fn parse_hsl(args: &[&str]) -> Result<Rgba, TransformError> {
+    if !matches!(args.len(), 3 | 4) {
+        return Err(invalid("hsl() expects 3 or 4 arguments"));
+    }
+
+    let hue = parse_number(args[0].trim_end_matches("deg"))?.rem_euclid(360.0);
+    let saturation = parse_number(args[1].trim_end_matches('%'))?.clamp(0.0, 100.0) / 100.0;
+    let lightness = parse_number(args[2].trim_end_matches('%'))?.clamp(0.0, 100.0) / 100.0;
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    Ok(Rgba {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+        a: args.get(3).map_or(Ok(1.0), |arg| parse_alpha(arg))?,
+    })
+}
+
+fn parse_alpha(arg: &str) -> Result<f64, TransformError> {
+    let value = match arg.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(arg)?,
+    };
+
+    Ok(value.clamp(0.0, 1.0))
+}
+
+fn parse_number(text: &str) -> Result<f64, TransformError> {
+    text.parse()
+        .map_err(|_| invalid(format!("invalid number {:?}", text)))
+}
+
+fn to_hex(color: Rgba) -> String {
+    let hex = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+
+    if color.a < 1.0 {
+        format!("{}{:02x}", hex, (color.a * 255.0).round() as u8)
+    } else {
+        hex
+    }
+}
+
+fn to_rgb(color: Rgba) -> String {
+    if color.a < 1.0 {
+        format!(
+            "rgba({}, {}, {}, {})",
+            color.r,
+            color.g,
+            color.b,
+            format_number(color.a, 2)
+        )
+    } else {
+        format!("rgb({}, {}, {})", color.r, color.g, color.b)
+    }
+}
+
+fn to_hsl(color: Rgba) -> String {
+    let r = color.r as f64 / 255.0;
+    let g = color.g as f64 / 255.0;
+    let b = color.b as f64 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let lightness = (max + min) / 2.0;
+
+    let (hue, saturation) = if delta == 0.0 {
+        (0.0, 0.0)
+    } else {
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, saturation)
+    };
+
+    let hue = format_number(hue, 0);
+    let saturation = format_number(saturation * 100.0, 0);
+    let lightness = format_number(lightness * 100.0, 0);
+
+    if color.a < 1.0 {
+        format!(
+            "hsla({}, {}%, {}%, {})",
+            hue,
+            saturation,
+            lightness,
+            format_number(color.a, 2)
+        )
+    } else {
+        format!("hsl({}, {}%, {}%)", hue, saturation, lightness)
+    }
+}
@@ -0,0 +1,125 @@
+//! 字数统计
+//!
+//! 中日韩文字每个字计为一个词，其它文字按字母和数字组成的连续片段计数。
+//! 结果以 JSON 返回，由前端按界面语言格式化。
+
+use super::TransformResult;
+use serde::Serialize;
+
+/// 统计结果
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub characters: usize,
+    pub non_whitespace: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+impl TextStats {
+    pub fn of(text: &str) -> Self {
+        let mut stats = Self {
+            bytes: text.len(),
+            lines: if text.is_empty() {
+                0
+            } else {
+                text.lines().count()
+            },
+            ..Self::default()
+        };
+
+        let mut in_word = false;
+
+        for c in text.chars() {
+            stats.characters += 1;
+
+            if !c.is_whitespace() {
+                stats.non_whitespace += 1;
+            }
+
+            if is_cjk(c) {
+                stats.words += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() || (in_word && matches!(c, '\'' | '’' | '-' | '_')) {
+                if !in_word {
+                    stats.words += 1;
+                    in_word = true;
+                }
+            } else {
+                in_word = false;
+            }
+        }
+
+        stats
+    }
+}
+
+pub fn count(text: &str) -> TransformResult {
+    serde_json::to_string(&TextStats::of(text)).map_err(|e| super::invalid(e.to_string()))
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+            | 0x3400..=0x4DBF   // 扩展 A
+            | 0x4E00..=0x9FFF   // 基本汉字
+            | 0xAC00..=0xD7AF   // 韩文音节
+            | 0xF900..=0xFAFF   // 兼容汉字
+            | 0x20000..=0x2FA1F // 扩展 B 及以后
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_mixed_text() {
+        assert_eq!(
+            TextStats::of("Hello, world! 你好世界\r\nit's a well-known fact\n"),
+            TextStats {
+                characters: 43,
+                non_whitespace: 35,
+                words: 10,
+                lines: 2,
+                bytes: 51,
+            }
+        );
+    }
+
+    #[test]
+    fn counts_empty_and_blank_text() {
+        assert_eq!(TextStats::of(""), TextStats::default());
+
+        let stats = TextStats::of(" \n\n");
+
+        assert_eq!(stats.words, 0);
+        assert_eq!(stats.non_whitespace, 0);
+        assert_eq!(stats.lines, 2);
+    }
+
+    #[test]
+    fn counts_kana_and_hangul_per_character() {
+        assert_eq!(TextStats::of("ひらがなカタカナ").words, 8);
+        assert_eq!(TextStats::of("안녕 하세요").words, 5);
+        assert_eq!(TextStats::of("𠀀x").words, 2);
+    }
+
+    #[test]
+    fn returns_json() {
+        let value: serde_json::Value = serde_json::from_str(&count("a b").unwrap()).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "characters": 3,
+                "nonWhitespace": 2,
+                "words": 2,
+                "lines": 1,
+                "bytes": 3,
+            })
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! JSON 和 XML 格式化
+
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use serde::de::IgnoredAny;
+
+use super::{invalid, TransformResult};
+
+const INDENT: &str = "  ";
+
+/// 格式化 JSON，保留键的顺序和数字的原始写法
+pub fn json_pretty(text: &str) -> TransformResult {
+    validate_json(text)?;
+
+    Ok(reformat_json(text, true))
+}
+
+/// 压缩 JSON，去掉字符串以外的空白
+pub fn json_minify(text: &str) -> TransformResult {
+    validate_json(text)?;
+
+    Ok(reformat_json(text, false))
+}
+
+fn validate_json(text: &str) -> Result<(), super::TransformError> {
+    serde_json::from_str::<IgnoredAny>(text)
+        .map(|_| ())
+        .map_err(|e| invalid(format!("not valid JSON: {}", e)))
+}
+
+// 输入已经是有效的 JSON，只需要区分字符串内外，重新排列空白
+fn reformat_json(text: &str, pretty: bool) -> String {
+    let mut result = String::with_capacity(text.len() * 2);
+    let mut depth = 0usize;
+    let mut chars = text.chars().peekable();
+
+    let newline = |result: &mut String, depth: usize| {
+        if pretty {
+            result.push('\n');
+            result.push_str(&INDENT.repeat(depth));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                result.push(c);
+
+                while let Some(c) = chars.next() {
+                    result.push(c);
+
+                    match c {
+                        '\\' => result.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '{' | '[' => {
+                result.push(c);
+
+                // 空对象和空数组保持在一行
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+
+                if matches!(chars.peek(), Some('}' | ']')) {
+                    result.extend(chars.next());
+                } else {
+                    depth += 1;
+                    newline(&mut result, depth);
+                }
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut result, depth);
+                result.push(c);
+            }
+            ',' => {
+                result.push(c);
+                newline(&mut result, depth);
+            }
+            ':' => {
+                result.push(c);
+
+                if pretty {
+                    result.push(' ');
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// 格式化 XML，去掉元素之间的空白后重新缩进
+pub fn xml_pretty(text: &str) -> TransformResult {
+    let mut reader = Reader::from_str(text.trim());
+    reader.config_mut().trim_text(true);
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', INDENT.len());
+    let mut depth = 0usize;
+    let mut has_root = false;
+
+    loop {
+        let event = reader.read_event().map_err(|e| {
+            invalid(format!(
+                "not valid XML at position {}: {}",
+                reader.error_position(),
+                e
+            ))
+        })?;
+
+        match &event {
+            Event::Eof => break,
+            Event::Start(_) => {
+                depth += 1;
+                has_root = true;
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Empty(_) => has_root = true,
+            _ => {}
+        }
+
+        writer
+            .write_event(event)
+            .map_err(|e| invalid(e.to_string()))?;
+    }
+
+    if !has_root {
+        return Err(invalid("no XML element found"));
+    }
+
+    if depth > 0 {
+        return Err(invalid("unclosed XML element"));
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|e| invalid(e.to_string()))
+}
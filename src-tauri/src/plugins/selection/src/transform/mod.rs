@@ -0,0 +1,321 @@
+//! 本地文本转换
+//!
+//! 划词工具栏中不需要 AI 服务的功能，例如大小写转换、JSON 格式化、编码解码和哈希。
+//! 每个转换由 id 标识，通过 [`run`] 执行，全部在本地完成，不需要网络。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+mod case;
+mod codec;
+mod color;
+mod count;
+mod format;
+mod timestamp;
+mod unit;
+
+pub use case::title_case;
+
+/// 转换的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformCategory {
+    Case,
+    Format,
+    Encoding,
+    Hash,
+    Time,
+    Text,
+    Convert,
+}
+
+/// 转换的信息，用于在前端列出可用的转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformInfo {
+    pub id: &'static str,
+    pub category: TransformCategory,
+}
+
+/// 转换失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// 没有对应 id 的转换
+    Unknown(String),
+    /// 输入的格式不正确，例如无效的 JSON 或 Base64
+    InvalidInput(String),
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(id) => write!(f, "Unknown transform: {}", id),
+            Self::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+pub(crate) type TransformResult = Result<String, TransformError>;
+
+struct Transform {
+    id: &'static str,
+    category: TransformCategory,
+    run: fn(&str) -> TransformResult,
+}
+
+const fn transform(
+    id: &'static str,
+    category: TransformCategory,
+    run: fn(&str) -> TransformResult,
+) -> Transform {
+    Transform { id, category, run }
+}
+
+use TransformCategory::*;
+
+static TRANSFORMS: &[Transform] = &[
+    transform("case.upper", Case, case::upper),
+    transform("case.lower", Case, case::lower),
+    transform("case.title", Case, case::title),
+    transform("case.sentence", Case, case::sentence),
+    transform("case.camel", Case, case::camel),
+    transform("case.pascal", Case, case::pascal),
+    transform("case.snake", Case, case::snake),
+    transform("case.constant", Case, case::constant),
+    transform("case.kebab", Case, case::kebab),
+    transform("json.pretty", Format, format::json_pretty),
+    transform("json.minify", Format, format::json_minify),
+    transform("xml.pretty", Format, format::xml_pretty),
+    transform("base64.encode", Encoding, codec::base64_encode),
+    transform("base64.decode", Encoding, codec::base64_decode),
+    transform("url.encode", Encoding, codec::url_encode),
+    transform("url.decode", Encoding, codec::url_decode),
+    transform("hash.md5", Hash, codec::md5),
+    transform("hash.sha1", Hash, codec::sha1),
+    transform("hash.sha256", Hash, codec::sha256),
+    transform("hash.sha512", Hash, codec::sha512),
+    transform("timestamp.convert", Time, timestamp::convert),
+    transform("text.count", Text, count::count),
+    transform("unit.convert", Convert, unit::convert),
+    transform("color.convert", Convert, color::convert),
+];
+
+/// 所有可用的转换
+pub fn transforms() -> Vec<TransformInfo> {
+    TRANSFORMS
+        .iter()
+        .map(|transform| TransformInfo {
+            id: transform.id,
+            category: transform.category,
+        })
+        .collect()
+}
+
+/// 执行 `id` 对应的转换
+pub fn run(id: &str, text: &str) -> TransformResult {
+    let transform = TRANSFORMS
+        .iter()
+        .find(|transform| transform.id == id)
+        .ok_or_else(|| TransformError::Unknown(id.to_string()))?;
+
+    (transform.run)(text)
+}
+
+pub(crate) fn invalid(message: impl Into<String>) -> TransformError {
+    TransformError::InvalidInput(message.into())
+}
+
+/// 格式化数字，最多保留 `precision` 位小数并去掉末尾的 0
+pub(crate) fn format_number(value: f64, precision: usize) -> String {
+    let text = format!("{:.*}", precision, value);
+
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每个转换一组输入和期望的输出
+    const CASES: &[(&str, &str, &str)] = &[
+        ("case.upper", "Hello wörld", "HELLO WÖRLD"),
+        ("case.lower", "Hello WÖRLD", "hello wörld"),
+        (
+            "case.title",
+            "hello wide-world (again)",
+            "Hello Wide-World (Again)",
+        ),
+        (
+            "case.sentence",
+            "hELLO. wORLD! 好。yes",
+            "Hello. World! 好。Yes",
+        ),
+        ("case.camel", "hello world\nfoo_bar", "helloWorld\nfooBar"),
+        ("case.pascal", "hello world\nfoo_bar", "HelloWorld\nFooBar"),
+        ("case.snake", "helloWorld\nFoo Bar", "hello_world\nfoo_bar"),
+        ("case.constant", "helloWorld", "HELLO_WORLD"),
+        ("case.kebab", "HelloWorld", "hello-world"),
+        (
+            "json.pretty",
+            r#"{"a":[1,2.50],"b":{"c":"x, y"}}"#,
+            "{\n  \"a\": [\n    1,\n    2.50\n  ],\n  \"b\": {\n    \"c\": \"x, y\"\n  }\n}",
+        ),
+        (
+            "json.minify",
+            "{ \"a\" : [ 1 , \"b c\" ] }",
+            r#"{"a":[1,"b c"]}"#,
+        ),
+        (
+            "xml.pretty",
+            "<a> <b>x</b><c/></a>",
+            "<a>\n  <b>x</b>\n  <c/>\n</a>",
+        ),
+        ("base64.encode", "你好", "5L2g5aW9"),
+        ("base64.decode", "5L2g\n5aW9", "你好"),
+        ("url.encode", "a b&c/你(1)", "a%20b%26c%2F%E4%BD%A0(1)"),
+        ("url.decode", "a+b%26c%2F%E4%BD%A0", "a b&c/你"),
+        ("hash.md5", "abc", "900150983cd24fb0d6963f7d28e17f72"),
+        (
+            "hash.sha1",
+            "abc",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+        ),
+        (
+            "hash.sha256",
+            "abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            "hash.sha512",
+            "abc",
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        ),
+        (
+            "timestamp.convert",
+            "2024-01-02T03:04:05Z",
+            "1704164645\n1704164645000",
+        ),
+        (
+            "text.count",
+            "hi 你好\nok",
+            r#"{"characters":8,"nonWhitespace":6,"words":4,"lines":2,"bytes":12}"#,
+        ),
+        (
+            "unit.convert",
+            "1,000 g",
+            "1000000 mg\n1 kg\n0.001 t\n35.273962 oz\n2.204623 lb",
+        ),
+        (
+            "color.convert",
+            "rgba(255, 0, 0, 50%)",
+            "#ff000080\nrgba(255, 0, 0, 0.5)\nhsla(0, 100%, 50%, 0.5)",
+        ),
+    ];
+
+    #[test]
+    fn lists_every_transform_once() {
+        let ids: Vec<_> = transforms().iter().map(|info| info.id).collect();
+
+        assert_eq!(ids.len(), 24);
+
+        for (index, id) in ids.iter().enumerate() {
+            assert!(!ids[..index].contains(id), "duplicate transform {}", id);
+        }
+
+        let tested: Vec<_> = CASES.iter().map(|(id, ..)| *id).collect();
+
+        assert_eq!(ids, tested);
+    }
+
+    #[test]
+    fn runs_every_transform() {
+        for (id, input, expected) in CASES {
+            assert_eq!(run(id, input).as_deref(), Ok(*expected), "{}", id);
+        }
+    }
+
+    #[test]
+    fn reports_unknown_transform() {
+        assert_eq!(
+            run("case.shout", "x"),
+            Err(TransformError::Unknown("case.shout".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let cases = [
+            ("json.pretty", "{\"a\":}"),
+            ("json.minify", "[1,"),
+            ("xml.pretty", "<a><b></a>"),
+            ("xml.pretty", "just text"),
+            ("base64.decode", "***"),
+            ("base64.decode", "//79"),
+            ("url.decode", "%FF"),
+            ("timestamp.convert", "yesterday"),
+            ("unit.convert", "12 parsecs"),
+            ("unit.convert", "km"),
+            ("color.convert", "#12345"),
+            ("color.convert", "cmyk(0, 0, 0, 0)"),
+        ];
+
+        for (id, input) in cases {
+            assert!(
+                matches!(run(id, input), Err(TransformError::InvalidInput(_))),
+                "{} {:?}",
+                id,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn converts_timestamps_by_digits() {
+        let utc = |input: &str| {
+            run("timestamp.convert", input)
+                .unwrap()
+                .lines()
+                .nth(1)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(utc("0"), "1970-01-01T00:00:00Z");
+        assert_eq!(utc("1704164645"), "2024-01-02T03:04:05Z");
+        assert_eq!(utc("1704164645123"), "2024-01-02T03:04:05.123Z");
+        assert_eq!(utc("1704164645123456"), "2024-01-02T03:04:05.123456Z");
+        assert_eq!(utc("1704164645123456789"), "2024-01-02T03:04:05.123456789Z");
+    }
+
+    #[test]
+    fn converts_temperatures() {
+        assert_eq!(
+            run("unit.convert", "100°F").as_deref(),
+            Ok("37.777778 °C\n310.927778 K")
+        );
+        assert_eq!(
+            run("unit.convert", "-40 c").as_deref(),
+            Ok("-40 °F\n233.15 K")
+        );
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_number(1.5000, 6), "1.5");
+        assert_eq!(format_number(2.0, 6), "2");
+        assert_eq!(format_number(-0.0000001, 6), "0");
+        assert_eq!(format_number(1234.0, 0), "1234");
+    }
+}
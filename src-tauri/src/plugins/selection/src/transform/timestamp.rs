@@ -0,0 +1,81 @@
+//! 时间戳转换
+//!
+//! 输入 Unix 时间戳时输出本地时间和 UTC 时间，按位数区分秒、毫秒、微秒和纳秒；
+//! 输入日期时间时输出秒和毫秒时间戳，没有时区的日期时间视为本地时间。
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+
+use super::{invalid, TransformResult};
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d"];
+
+pub fn convert(text: &str) -> TransformResult {
+    let text = text.trim();
+
+    if let Ok(value) = text.parse::<i64>() {
+        return from_timestamp(value);
+    }
+
+    let datetime = parse_datetime(text)
+        .ok_or_else(|| invalid("not a Unix timestamp or a recognized date and time"))?;
+
+    Ok(format!(
+        "{}\n{}",
+        datetime.timestamp(),
+        datetime.timestamp_millis()
+    ))
+}
+
+fn from_timestamp(value: i64) -> TransformResult {
+    let digits = value.unsigned_abs().to_string().len();
+
+    let datetime = match digits {
+        0..=11 => DateTime::from_timestamp(value, 0),
+        12..=14 => DateTime::from_timestamp_millis(value),
+        15..=17 => DateTime::from_timestamp_micros(value),
+        _ => Some(DateTime::from_timestamp_nanos(value)),
+    }
+    .ok_or_else(|| invalid("timestamp out of range"))?;
+
+    Ok(format!(
+        "{}\n{}",
+        datetime
+            .with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, false),
+        datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    ))
+}
+
+fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc2822(text) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    let naive = DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            DATE_FORMATS.iter().find_map(|format| {
+                NaiveDate::parse_from_str(text, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+        })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
@@ -0,0 +1,146 @@
+//! 单位换算
+//!
+//! 输入形如 `12.5 km`、`100°F`、`3 GB` 的文本，输出同一类单位下的其它写法，每行一个。
+//! 数据大小按 1024 进制换算。
+
+use super::{format_number, invalid, TransformResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Temperature,
+    Data,
+}
+
+struct Unit {
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    /// 换算到基本单位的倍数，温度单位不使用
+    factor: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        aliases,
+        dimension,
+        factor,
+    }
+}
+
+use Dimension::*;
+
+const KIB: f64 = 1024.0;
+
+static UNITS: &[Unit] = &[
+    unit("mm", &["millimeter", "millimeters", "毫米"], Length, 0.001),
+    unit("cm", &["centimeter", "centimeters", "厘米"], Length, 0.01),
+    unit("m", &["meter", "meters", "米"], Length, 1.0),
+    unit(
+        "km",
+        &["kilometer", "kilometers", "公里", "千米"],
+        Length,
+        1000.0,
+    ),
+    unit("in", &["inch", "inches", "\"", "英寸"], Length, 0.0254),
+    unit("ft", &["foot", "feet", "'", "英尺"], Length, 0.3048),
+    unit("yd", &["yard", "yards", "码"], Length, 0.9144),
+    unit("mi", &["mile", "miles", "英里"], Length, 1609.344),
+    unit("mg", &["milligram", "milligrams", "毫克"], Mass, 0.001),
+    unit("g", &["gram", "grams", "克"], Mass, 1.0),
+    unit(
+        "kg",
+        &["kilogram", "kilograms", "公斤", "千克"],
+        Mass,
+        1000.0,
+    ),
+    unit("t", &["tonne", "tonnes", "吨"], Mass, 1_000_000.0),
+    unit("oz", &["ounce", "ounces", "盎司"], Mass, 28.349523125),
+    unit("lb", &["lbs", "pound", "pounds", "磅"], Mass, 453.59237),
+    unit("°C", &["c", "celsius", "℃", "摄氏度"], Temperature, 0.0),
+    unit("°F", &["f", "fahrenheit", "℉", "华氏度"], Temperature, 0.0),
+    unit("K", &["kelvin"], Temperature, 0.0),
+    unit("B", &["byte", "bytes"], Data, 1.0),
+    unit("KB", &["kib"], Data, KIB),
+    unit("MB", &["mib"], Data, KIB * KIB),
+    unit("GB", &["gib"], Data, KIB * KIB * KIB),
+    unit("TB", &["tib"], Data, KIB * KIB * KIB * KIB),
+];
+
+pub fn convert(text: &str) -> TransformResult {
+    let text = text.trim();
+
+    let split = text
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+')))
+        .map(|(index, _)| index)
+        .ok_or_else(|| invalid("missing unit"))?;
+
+    let (number, symbol) = text.split_at(split);
+
+    let value: f64 = number
+        .replace(',', "")
+        .parse()
+        .map_err(|_| invalid(format!("invalid number {:?}", number)))?;
+
+    let source = find_unit(symbol.trim())
+        .ok_or_else(|| invalid(format!("unsupported unit {:?}", symbol.trim())))?;
+
+    let lines: Vec<String> = UNITS
+        .iter()
+        .filter(|unit| unit.dimension == source.dimension && unit.symbol != source.symbol)
+        .map(|target| {
+            let result = if source.dimension == Temperature {
+                from_kelvin(to_kelvin(value, source.symbol), target.symbol)
+            } else {
+                value * source.factor / target.factor
+            };
+
+            format!("{} {}", format_number(result, 6), target.symbol)
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+// 先精确匹配符号，再忽略大小写匹配符号和别名
+fn find_unit(symbol: &str) -> Option<&'static Unit> {
+    UNITS
+        .iter()
+        .find(|unit| unit.symbol == symbol)
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find(|unit| unit.symbol.eq_ignore_ascii_case(symbol))
+        })
+        .or_else(|| {
+            let symbol = symbol.to_lowercase();
+
+            UNITS
+                .iter()
+                .find(|unit| unit.aliases.contains(&symbol.as_str()))
+        })
+}
+
+fn to_kelvin(value: f64, symbol: &str) -> f64 {
+    match symbol {
+        "°C" => value + 273.15,
+        "°F" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value,
+    }
+}
+
+fn from_kelvin(value: f64, symbol: &str) -> f64 {
+    match symbol {
+        "°C" => value - 273.15,
+        "°F" => (value - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => value,
+    }
+}
@@ -0,0 +1,21 @@
+const COMMANDS: &[&str] = &[
+    "start_sync",
+    "stop_sync",
+    "sync_status",
+    "list_sync_peers",
+    "start_sync_pairing",
+    "cancel_sync_pairing",
+    "pair_sync_device",
+    "confirm_sync_pairing",
+    "unpair_sync_device",
+    "send_sync_entry",
+    "start_remote_sync",
+    "stop_remote_sync",
+    "sync_remote_now",
+    "remote_sync_status",
+    "test_remote_sync",
+];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,311 @@
+//! 加密通道
+//!
+//! 连接建立后发起方先发送一个字节表示连接的用途，然后进行 Noise XX 握手：
+//! 普通连接由双方检查对方的静态公钥是否已经配对；配对连接使用 XXpsk3，
+//! 预共享密钥由配对码派生，配对码不一致时握手会失败。
+//!
+//! 6 位配对码可以被截获握手消息的一方离线穷举，因此配对握手完成后双方还要交换随机数，
+//! 由握手的哈希和随机数派生确认码显示在两台设备上，用户确认一致后才保存对方的公钥。
+//!
+//! 握手完成后每条消息先发送加密的 4 字节长度，再按 Noise 消息的最大长度分块加密发送，
+//! 每个 Noise 消息在网络上以 2 字节长度开头。
+
+use sha2::{Digest, Sha256};
+use snow::{Builder, HandshakeState, TransportState};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::error::SyncError;
+use crate::identity::Identity;
+
+/// 普通连接的 Noise 参数
+pub const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// 配对连接的 Noise 参数
+const PAIRING_PARAMS: &str = "Noise_XXpsk3_25519_ChaChaPoly_BLAKE2s";
+
+// 双方必须一致，不同版本的协议无法完成握手
+const PROLOGUE: &[u8] = b"eco-sync/1";
+
+const MAX_NOISE_LEN: usize = 65535;
+const TAG_LEN: usize = 16;
+
+// 单条消息的最大长度，图片会放在消息中一起发送
+const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+// 读写超时，避免对方不响应时一直占用线程
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 连接的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// 已配对的设备之间同步数据
+    Sync,
+    /// 使用配对码交换公钥
+    Pairing,
+}
+
+impl Mode {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Sync => 0,
+            Self::Pairing => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, SyncError> {
+        match byte {
+            0 => Ok(Self::Sync),
+            1 => Ok(Self::Pairing),
+            _ => Err(SyncError::Protocol(format!("unknown mode {}", byte))),
+        }
+    }
+}
+
+/// 握手完成的加密连接
+pub struct Channel {
+    stream: TcpStream,
+    transport: TransportState,
+    remote_key: Vec<u8>,
+    handshake_hash: Vec<u8>,
+}
+
+impl Channel {
+    /// 作为发起方连接，配对时需要传入配对码
+    pub fn connect(
+        mut stream: TcpStream,
+        identity: &Identity,
+        pairing_code: Option<&str>,
+    ) -> Result<Self, SyncError> {
+        set_timeout(&stream)?;
+
+        let mode = if pairing_code.is_some() {
+            Mode::Pairing
+        } else {
+            Mode::Sync
+        };
+
+        stream.write_all(&[mode.to_byte()])?;
+
+        let key = pairing_code.map(pairing_key);
+        let mut handshake = handshake(identity, key.as_ref())?.build_initiator()?;
+        let mut buffer = vec![0u8; MAX_NOISE_LEN];
+
+        let len = handshake.write_message(&[], &mut buffer)?;
+        write_frame(&mut stream, &buffer[..len])?;
+
+        handshake.read_message(&read_frame(&mut stream)?, &mut buffer)?;
+
+        let len = handshake.write_message(&[], &mut buffer)?;
+        write_frame(&mut stream, &buffer[..len])?;
+
+        Self::finish(stream, handshake)
+    }
+
+    /// 读取发起方发送的连接用途
+    pub fn read_mode(stream: &mut TcpStream) -> Result<Mode, SyncError> {
+        set_timeout(stream)?;
+
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte)?;
+
+        Mode::from_byte(byte[0])
+    }
+
+    /// 作为响应方完成握手，需要先调用 [`Channel::read_mode`]
+    pub fn accept(
+        mut stream: TcpStream,
+        identity: &Identity,
+        pairing_code: Option<&str>,
+    ) -> Result<Self, SyncError> {
+        let key = pairing_code.map(pairing_key);
+        let mut handshake = handshake(identity, key.as_ref())?.build_responder()?;
+        let mut buffer = vec![0u8; MAX_NOISE_LEN];
+
+        handshake.read_message(&read_frame(&mut stream)?, &mut buffer)?;
+
+        let len = handshake.write_message(&[], &mut buffer)?;
+        write_frame(&mut stream, &buffer[..len])?;
+
+        handshake.read_message(&read_frame(&mut stream)?, &mut buffer)?;
+
+        Self::finish(stream, handshake)
+    }
+
+    fn finish(stream: TcpStream, handshake: HandshakeState) -> Result<Self, SyncError> {
+        let remote_key = handshake
+            .get_remote_static()
+            .ok_or_else(|| SyncError::Noise("missing remote static key".into()))?
+            .to_vec();
+        let handshake_hash = handshake.get_handshake_hash().to_vec();
+
+        Ok(Self {
+            stream,
+            transport: handshake.into_transport_mode()?,
+            remote_key,
+            handshake_hash,
+        })
+    }
+
+    /// 对方的静态公钥
+    pub fn remote_key(&self) -> &[u8] {
+        &self.remote_key
+    }
+
+    /// 握手的哈希，中间人与双方分别握手时两边的哈希不同
+    pub fn handshake_hash(&self) -> &[u8] {
+        &self.handshake_hash
+    }
+
+    /// 修改读取的超时，等待用户操作时使用更长的超时
+    pub fn set_read_timeout(&self, timeout: Duration) -> Result<(), SyncError> {
+        self.stream.set_read_timeout(Some(timeout))?;
+
+        Ok(())
+    }
+
+    /// 发送一条消息
+    pub fn send(&mut self, data: &[u8]) -> Result<(), SyncError> {
+        if data.len() > MAX_MESSAGE_LEN {
+            return Err(SyncError::Protocol("message too large".into()));
+        }
+
+        self.send_chunk(&(data.len() as u32).to_be_bytes())?;
+
+        for chunk in data.chunks(MAX_NOISE_LEN - TAG_LEN) {
+            self.send_chunk(chunk)?;
+        }
+
+        self.stream.flush()?;
+
+        Ok(())
+    }
+
+    /// 接收一条消息，对方关闭连接时返回 None
+    pub fn recv(&mut self) -> Result<Option<Vec<u8>>, SyncError> {
+        let header = match self.recv_chunk() {
+            Ok(header) => header,
+            Err(SyncError::Io(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Ok(None);
+            }
+            Err(error) => return Err(error),
+        };
+
+        let len: [u8; 4] = header
+            .as_slice()
+            .try_into()
+            .map_err(|_| SyncError::Protocol("invalid message header".into()))?;
+        let len = u32::from_be_bytes(len) as usize;
+
+        if len > MAX_MESSAGE_LEN {
+            return Err(SyncError::Protocol("message too large".into()));
+        }
+
+        let mut data = Vec::with_capacity(len);
+
+        while data.len() < len {
+            data.extend(self.recv_chunk()?);
+        }
+
+        if data.len() != len {
+            return Err(SyncError::Protocol("invalid message length".into()));
+        }
+
+        Ok(Some(data))
+    }
+
+    fn send_chunk(&mut self, chunk: &[u8]) -> Result<(), SyncError> {
+        let mut buffer = vec![0u8; chunk.len() + TAG_LEN];
+        let len = self.transport.write_message(chunk, &mut buffer)?;
+
+        write_frame(&mut self.stream, &buffer[..len])
+    }
+
+    fn recv_chunk(&mut self) -> Result<Vec<u8>, SyncError> {
+        let frame = read_frame(&mut self.stream)?;
+        let mut buffer = vec![0u8; frame.len()];
+        let len = self.transport.read_message(&frame, &mut buffer)?;
+
+        buffer.truncate(len);
+
+        Ok(buffer)
+    }
+}
+
+/// 由配对码派生预共享密钥
+pub fn pairing_key(code: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"eco-sync-pairing");
+    hasher.update(code.trim().as_bytes());
+
+    hasher.finalize().into()
+}
+
+/// 配对时响应方先发送的随机数承诺
+pub fn nonce_commitment(nonce: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"eco-sync-commitment");
+    hasher.update(nonce);
+
+    hasher.finalize().into()
+}
+
+/// 由握手的哈希和双方的随机数派生 6 位确认码
+///
+/// 响应方在看到发起方的随机数之前已经承诺了自己的随机数，
+/// 中间人无法通过反复尝试让两边的确认码相同
+pub fn verification_code(
+    handshake_hash: &[u8],
+    initiator_nonce: &[u8],
+    responder_nonce: &[u8],
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"eco-sync-verification");
+    hasher.update(handshake_hash);
+    hasher.update(initiator_nonce);
+    hasher.update(responder_nonce);
+
+    let digest = hasher.finalize();
+    let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+
+    format!("{:06}", value % 1_000_000)
+}
+
+fn handshake<'a>(
+    identity: &'a Identity,
+    pairing_key: Option<&'a [u8; 32]>,
+) -> Result<Builder<'a>, SyncError> {
+    let builder = match pairing_key {
+        Some(key) => Builder::new(PAIRING_PARAMS.parse()?).psk(3, key),
+        None => Builder::new(NOISE_PARAMS.parse()?),
+    };
+
+    Ok(builder
+        .local_private_key(&identity.private_key)
+        .prologue(PROLOGUE))
+}
+
+fn set_timeout(stream: &TcpStream) -> Result<(), SyncError> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    Ok(())
+}
+
+fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> Result<(), SyncError> {
+    stream.write_all(&(frame.len() as u16).to_be_bytes())?;
+    stream.write_all(frame)?;
+
+    Ok(())
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, SyncError> {
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len)?;
+
+    let mut frame = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut frame)?;
+
+    Ok(frame)
+}
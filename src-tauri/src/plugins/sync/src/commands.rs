@@ -0,0 +1,367 @@
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{async_runtime::spawn_blocking, command, AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_eco_secret::SecretStore;
+
+use crate::error::SyncError;
+use crate::identity::{default_device_name, Identity};
+use crate::node::{NodeConfig, NodeStatus, PeerInfo, SyncEvent, SyncNode};
+use crate::peers::{PairedPeer, PeerStore, PEERS_FILE_NAME};
+use crate::remote::{
+    self, Cipher, RemoteConfig, RemoteEngine, RemoteStatus, RemoteSync, SyncReport,
+    CREDENTIAL_SECRET_NAME, PASSPHRASE_SECRET_NAME,
+};
+
+/// 收到其它设备同步的记录
+pub const ENTRY_EVENT: &str = "sync:entry";
+
+/// 配对握手完成，等待用户确认两台设备显示的确认码一致
+pub const PAIRING_CONFIRM_EVENT: &str = "sync:pairing-confirm";
+
+/// 与新的设备完成配对
+pub const PAIRED_EVENT: &str = "sync:paired";
+
+/// 设备列表发生变化
+pub const PEERS_EVENT: &str = "sync:peers";
+
+/// 远程同步完成一次，内容为同步的状态
+pub const REMOTE_EVENT: &str = "sync:remote";
+
+// 远程同步的进度，保存在应用配置目录
+const REMOTE_STATE_FILE_NAME: &str = "remote-sync-state.json";
+
+/// 正在运行的同步节点
+#[derive(Default)]
+pub struct SyncState(Mutex<Option<Arc<SyncNode>>>);
+
+impl SyncState {
+    fn lock(&self) -> MutexGuard<'_, Option<Arc<SyncNode>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn node(&self) -> Result<Arc<SyncNode>, SyncError> {
+        self.lock().clone().ok_or(SyncError::NotRunning)
+    }
+
+    /// 停止同步节点
+    pub fn stop(&self) {
+        if let Some(node) = self.lock().take() {
+            node.stop();
+        }
+    }
+}
+
+// 网络操作会阻塞，放到单独的线程中执行
+async fn with_node<R, T, F>(app: AppHandle<R>, f: F) -> Result<T, String>
+where
+    R: Runtime,
+    T: Send + 'static,
+    F: FnOnce(&SyncNode) -> Result<T, SyncError> + Send + 'static,
+{
+    let node = app.state::<SyncState>().node().map_err(|e| e.to_string())?;
+
+    spawn_blocking(move || f(&node))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+fn peers_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+
+    Ok(dir.join(PEERS_FILE_NAME))
+}
+
+fn emit_event<R: Runtime>(app: &AppHandle<R>, event: SyncEvent) {
+    let result = match &event {
+        SyncEvent::Entry { .. } => app.emit(ENTRY_EVENT, &event),
+        SyncEvent::PairingConfirm { .. } => app.emit(PAIRING_CONFIRM_EVENT, &event),
+        SyncEvent::Paired(peer) => app.emit(PAIRED_EVENT, peer),
+        SyncEvent::PeersChanged => app.emit(PEERS_EVENT, ()),
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to emit sync event: {}", e);
+    }
+}
+
+/// 启动同步，已经启动时直接返回状态
+///
+/// `images_dir` 是保存收到的图片的目录，`port` 为空时由系统分配
+#[command]
+pub async fn start_sync<R: Runtime>(
+    app: AppHandle<R>,
+    port: Option<u16>,
+    images_dir: PathBuf,
+) -> Result<NodeStatus, String> {
+    if let Ok(node) = app.state::<SyncState>().node() {
+        return Ok(node.status());
+    }
+
+    let peers_path = peers_path(&app)?;
+
+    let node = spawn_blocking({
+        let app = app.clone();
+
+        move || {
+            let identity =
+                Identity::load_or_create(&app.state::<SecretStore>(), default_device_name())?;
+
+            let config = NodeConfig {
+                port: port.unwrap_or_default(),
+                images_dir,
+                discovery: true,
+            };
+
+            let handler = {
+                let app = app.clone();
+
+                Arc::new(move |event| emit_event(&app, event))
+            };
+
+            SyncNode::start(identity, PeerStore::open(peers_path), config, handler)
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let status = node.status();
+
+    // 并发启动时保留先启动的节点
+    let state = app.state::<SyncState>();
+    let mut current = state.lock();
+
+    if let Some(running) = current.as_ref() {
+        node.stop();
+
+        return Ok(running.status());
+    }
+
+    *current = Some(Arc::new(node));
+
+    Ok(status)
+}
+
+/// 停止同步
+#[command]
+pub async fn stop_sync<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    spawn_blocking(move || {
+        app.state::<SyncState>().stop();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 获取同步的状态，没有启动时返回 null
+#[command]
+pub async fn sync_status<R: Runtime>(app: AppHandle<R>) -> Option<NodeStatus> {
+    let node = app.state::<SyncState>().node().ok();
+
+    node.map(|node| node.status())
+}
+
+/// 获取已配对和发现的设备，没有启动时只返回已配对的设备
+#[command]
+pub async fn list_sync_peers<R: Runtime>(app: AppHandle<R>) -> Result<Vec<PeerInfo>, String> {
+    if let Ok(node) = app.state::<SyncState>().node() {
+        return Ok(node.peers());
+    }
+
+    let peers = PeerStore::open(peers_path(&app)?)
+        .list()
+        .into_iter()
+        .map(|peer| PeerInfo {
+            device_id: peer.device_id,
+            name: peer.name,
+            paired: true,
+            online: false,
+            addresses: Vec::new(),
+        })
+        .collect();
+
+    Ok(peers)
+}
+
+/// 生成配对码，在另一台设备上输入后完成配对
+#[command]
+pub async fn start_sync_pairing<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    with_node(app, |node| node.start_pairing()).await
+}
+
+/// 取消正在等待的配对
+#[command]
+pub async fn cancel_sync_pairing<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    with_node(app, |node| {
+        node.cancel_pairing();
+
+        Ok(())
+    })
+    .await
+}
+
+/// 输入另一台设备显示的配对码完成配对，依次尝试设备的每个地址
+#[command]
+pub async fn pair_sync_device<R: Runtime>(
+    app: AppHandle<R>,
+    addresses: Vec<SocketAddr>,
+    code: String,
+) -> Result<PairedPeer, String> {
+    with_node(app, move |node| node.pair(&addresses, &code)).await
+}
+
+/// 确认或拒绝正在等待确认的配对，两台设备都确认后才完成配对
+#[command]
+pub async fn confirm_sync_pairing<R: Runtime>(
+    app: AppHandle<R>,
+    accepted: bool,
+) -> Result<(), String> {
+    with_node(app, move |node| node.confirm_pairing(accepted)).await
+}
+
+/// 取消配对，返回设备是否存在
+#[command]
+pub async fn unpair_sync_device<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+) -> Result<bool, String> {
+    if app.state::<SyncState>().node().is_err() {
+        return PeerStore::open(peers_path(&app)?)
+            .remove(&device_id)
+            .map_err(|e| e.to_string());
+    }
+
+    with_node(app, move |node| node.unpair(&device_id)).await
+}
+
+/// 把一条记录发送给所有在线的已配对设备，返回成功发送的设备数量
+///
+/// `image` 是图片记录的文件路径，文件会和记录一起发送
+#[command]
+pub async fn send_sync_entry<R: Runtime>(
+    app: AppHandle<R>,
+    entry: Value,
+    image: Option<PathBuf>,
+) -> Result<usize, String> {
+    with_node(app, move |node| node.send_entry(entry, image.as_deref())).await
+}
+
+// 读取同步密码和存储服务的密码，创建客户端
+fn remote_client<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &RemoteConfig,
+) -> Result<(Box<dyn remote::RemoteBackend>, Cipher), SyncError> {
+    let store = app.state::<SecretStore>();
+
+    let secret = |name: &str| {
+        store
+            .get(name)
+            .map_err(|e| SyncError::Secret(e.to_string()))
+    };
+
+    let passphrase = secret(PASSPHRASE_SECRET_NAME)?.unwrap_or_default();
+    let backend = config.backend(secret(CREDENTIAL_SECRET_NAME)?)?;
+
+    Ok((backend, Cipher::new(&passphrase)?))
+}
+
+/// 启动远程同步，立即同步一次，之后每隔 `interval_minutes` 分钟同步一次，已经启动时按新的配置重新启动
+///
+/// `database_path` 是历史记录数据库的路径，`images_dir` 是保存图片的目录
+#[command]
+pub async fn start_remote_sync<R: Runtime>(
+    app: AppHandle<R>,
+    config: RemoteConfig,
+    interval_minutes: u64,
+    database_path: PathBuf,
+    images_dir: PathBuf,
+) -> Result<(), String> {
+    let state_path = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?
+        .join(REMOTE_STATE_FILE_NAME);
+
+    spawn_blocking(move || {
+        let identity =
+            Identity::load_or_create(&app.state::<SecretStore>(), default_device_name())?;
+
+        let (backend, cipher) = remote_client(&app, &config)?;
+
+        let engine = RemoteEngine::new(
+            backend,
+            cipher,
+            identity.device_id,
+            config.target(),
+            database_path,
+            images_dir,
+            Some(state_path),
+        );
+
+        let interval = Duration::from_secs(interval_minutes.max(1) * 60);
+
+        let on_finish = {
+            let app = app.clone();
+
+            move |_: &Result<SyncReport, String>| {
+                let status = app.state::<RemoteSync>().status();
+
+                if let Err(e) = app.emit(REMOTE_EVENT, status) {
+                    log::error!("Failed to emit sync event: {}", e);
+                }
+            }
+        };
+
+        app.state::<RemoteSync>().start(engine, interval, on_finish);
+
+        Ok::<_, SyncError>(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// 停止远程同步，正在进行的同步会先完成
+#[command]
+pub async fn stop_remote_sync<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    spawn_blocking(move || {
+        app.state::<RemoteSync>().stop();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 立即同步一次并等待结果
+#[command]
+pub async fn sync_remote_now<R: Runtime>(app: AppHandle<R>) -> Result<SyncReport, String> {
+    spawn_blocking(move || app.state::<RemoteSync>().sync_now())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 获取远程同步的状态
+#[command]
+pub async fn remote_sync_status<R: Runtime>(app: AppHandle<R>) -> RemoteStatus {
+    let status = app.state::<RemoteSync>().status();
+
+    status
+}
+
+/// 检查能否连接存储服务，以及同步密码与其它设备是否一致
+#[command]
+pub async fn test_remote_sync<R: Runtime>(
+    app: AppHandle<R>,
+    config: RemoteConfig,
+) -> Result<(), String> {
+    spawn_blocking(move || {
+        let (backend, cipher) = remote_client(&app, &config)?;
+
+        remote::check(backend.as_ref(), &cipher)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
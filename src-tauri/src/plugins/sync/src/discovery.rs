@@ -0,0 +1,159 @@
+//! 局域网内的设备发现
+//!
+//! 通过 mDNS 广播本机的同步服务，同时监听其它设备的服务。
+//! 服务实例名使用设备 ID，同一台机器上运行的多个节点也可以互相发现。
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::error::SyncError;
+use crate::identity::Identity;
+
+/// 同步服务的类型
+pub const SERVICE_TYPE: &str = "_ecopaste-sync._tcp.local.";
+
+/// 发现的设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredPeer {
+    pub device_id: String,
+    pub name: String,
+    pub addresses: Vec<SocketAddr>,
+}
+
+// 以服务的完整名称为键，服务下线时只能拿到完整名称
+type Peers = Arc<Mutex<HashMap<String, DiscoveredPeer>>>;
+
+/// 服务发现
+pub struct Discovery {
+    daemon: ServiceDaemon,
+    fullname: String,
+    peers: Peers,
+}
+
+impl Discovery {
+    /// 广播本机的服务并开始监听，发现的设备变化时调用 `on_change`
+    pub fn start(
+        identity: &Identity,
+        port: u16,
+        on_change: impl Fn() + Send + 'static,
+    ) -> Result<Self, SyncError> {
+        let daemon = ServiceDaemon::new()?;
+
+        let host_name = format!("{}.local.", identity.device_id);
+        let properties = [
+            ("id", identity.device_id.as_str()),
+            ("name", identity.name.as_str()),
+        ];
+
+        let service = ServiceInfo::new(
+            SERVICE_TYPE,
+            &identity.device_id,
+            &host_name,
+            "",
+            port,
+            &properties[..],
+        )?
+        .enable_addr_auto();
+
+        let fullname = service.get_fullname().to_string();
+
+        daemon.register(service)?;
+
+        let receiver = daemon.browse(SERVICE_TYPE)?;
+        let peers: Peers = Arc::default();
+        let own_id = identity.device_id.clone();
+
+        thread::spawn({
+            let peers = peers.clone();
+
+            move || {
+                while let Ok(event) = receiver.recv() {
+                    let changed = match event {
+                        ServiceEvent::ServiceResolved(info) => {
+                            let Some(peer) = to_peer(&info).filter(|peer| peer.device_id != own_id)
+                            else {
+                                continue;
+                            };
+
+                            let mut peers = peers.lock().unwrap_or_else(|e| e.into_inner());
+
+                            peers.insert(info.get_fullname().to_string(), peer.clone())
+                                != Some(peer)
+                        }
+                        ServiceEvent::ServiceRemoved(_, fullname) => peers
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .remove(&fullname)
+                            .is_some(),
+                        ServiceEvent::SearchStopped(_) => break,
+                        _ => false,
+                    };
+
+                    if changed {
+                        on_change();
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            daemon,
+            fullname,
+            peers,
+        })
+    }
+
+    /// 当前在线的设备
+    pub fn peers(&self) -> Vec<DiscoveredPeer> {
+        let peers = self.peers.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut list: Vec<DiscoveredPeer> = peers.values().cloned().collect();
+
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+
+        list
+    }
+
+    /// 查找设备的地址
+    pub fn addresses(&self, device_id: &str) -> Vec<SocketAddr> {
+        self.peers()
+            .into_iter()
+            .find(|peer| peer.device_id == device_id)
+            .map(|peer| peer.addresses)
+            .unwrap_or_default()
+    }
+
+    /// 停止广播和监听
+    pub fn stop(&self) {
+        let _ = self.daemon.unregister(&self.fullname);
+        let _ = self.daemon.shutdown();
+    }
+}
+
+fn to_peer(info: &ServiceInfo) -> Option<DiscoveredPeer> {
+    let device_id = info.get_property_val_str("id")?.to_string();
+    let name = info
+        .get_property_val_str("name")
+        .unwrap_or(&device_id)
+        .to_string();
+
+    let mut addresses: Vec<SocketAddr> = info
+        .get_addresses()
+        .iter()
+        .map(|ip| SocketAddr::new(*ip, info.get_port()))
+        .collect();
+
+    // 优先使用 IPv4 地址，IPv6 的链路本地地址需要指定网卡才能连接
+    addresses.sort_by_key(|address| (address.is_ipv6(), *address));
+
+    Some(DiscoveredPeer {
+        device_id,
+        name,
+        addresses,
+    })
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+
+/// 同步失败的原因
+#[derive(Debug)]
+pub enum SyncError {
+    /// 网络或文件读写失败
+    Io(std::io::Error),
+    /// Noise 握手或加解密失败，配对码错误时也会是这个错误
+    Noise(String),
+    /// 对方发送了无法识别的消息
+    Protocol(String),
+    /// 对方的公钥不在已配对的设备中
+    NotPaired(String),
+    /// 当前没有等待配对的配对码，或者配对码已过期
+    Pairing(String),
+    /// mDNS 服务发现失败
+    Discovery(String),
+    /// 读写本机密钥失败
+    Secret(String),
+    /// WebDAV 或 S3 请求失败
+    Remote(String),
+    /// 读写历史记录数据库失败
+    Database(String),
+    /// 加密或解密同步的数据失败，同步密码错误时也会是这个错误
+    Crypto(String),
+    /// 同步服务没有启动
+    NotRunning,
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Noise(message) => write!(f, "Noise error: {}", message),
+            Self::Protocol(message) => write!(f, "Protocol error: {}", message),
+            Self::NotPaired(device_id) => write!(f, "Device is not paired: {}", device_id),
+            Self::Pairing(message) => write!(f, "Pairing error: {}", message),
+            Self::Discovery(message) => write!(f, "Discovery error: {}", message),
+            Self::Secret(message) => write!(f, "Secret error: {}", message),
+            Self::Remote(message) => write!(f, "Remote error: {}", message),
+            Self::Database(message) => write!(f, "Database error: {}", message),
+            Self::Crypto(message) => write!(f, "Crypto error: {}", message),
+            Self::NotRunning => write!(f, "Sync is not running"),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<std::io::Error> for SyncError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<snow::Error> for SyncError {
+    fn from(error: snow::Error) -> Self {
+        Self::Noise(error.to_string())
+    }
+}
+
+impl From<mdns_sd::Error> for SyncError {
+    fn from(error: mdns_sd::Error) -> Self {
+        Self::Discovery(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for SyncError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Protocol(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for SyncError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Remote(error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for SyncError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Database(error.to_string())
+    }
+}
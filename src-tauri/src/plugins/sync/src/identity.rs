@@ -0,0 +1,100 @@
+//! 本机的设备标识
+//!
+//! 每台设备有一对 X25519 静态密钥，保存在密钥存储中，公钥在配对时交给对方固定下来。
+//! 设备 ID 由公钥计算得到，因此无法冒用其它设备的 ID。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
+use snow::Builder;
+use tauri_plugin_eco_secret::SecretStore;
+
+use crate::channel::NOISE_PARAMS;
+use crate::error::SyncError;
+
+/// 密钥在密钥存储中的名称
+pub const KEYPAIR_NAME: &str = "sync.keypair";
+
+const KEY_LEN: usize = 32;
+
+/// 本机的设备标识和静态密钥
+#[derive(Clone)]
+pub struct Identity {
+    pub device_id: String,
+    /// 显示给其它设备的名称
+    pub name: String,
+    pub private_key: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+impl Identity {
+    /// 生成新的密钥
+    pub fn generate(name: impl Into<String>) -> Result<Self, SyncError> {
+        let keypair = Builder::new(NOISE_PARAMS.parse()?).generate_keypair()?;
+
+        Ok(Self::new(name, keypair.private, keypair.public))
+    }
+
+    /// 读取保存的密钥，没有时生成并保存
+    pub fn load_or_create(store: &SecretStore, name: impl Into<String>) -> Result<Self, SyncError> {
+        let name = name.into();
+
+        let saved = store
+            .get(KEYPAIR_NAME)
+            .map_err(|e| SyncError::Secret(e.to_string()))?;
+
+        if let Some(identity) = saved.and_then(|value| Self::decode(name.clone(), &value)) {
+            return Ok(identity);
+        }
+
+        let identity = Self::generate(name)?;
+
+        store
+            .set(KEYPAIR_NAME, &identity.encode())
+            .map_err(|e| SyncError::Secret(e.to_string()))?;
+
+        Ok(identity)
+    }
+
+    // 私钥和公钥用 `.` 连接，snow 不提供由私钥计算公钥的方法，所以一起保存
+    fn encode(&self) -> String {
+        format!(
+            "{}.{}",
+            STANDARD.encode(&self.private_key),
+            STANDARD.encode(&self.public_key)
+        )
+    }
+
+    fn decode(name: String, value: &str) -> Option<Self> {
+        let (private_key, public_key) = value.split_once('.')?;
+        let private_key = STANDARD.decode(private_key).ok()?;
+        let public_key = STANDARD.decode(public_key).ok()?;
+
+        (private_key.len() == KEY_LEN && public_key.len() == KEY_LEN)
+            .then(|| Self::new(name, private_key, public_key))
+    }
+
+    fn new(name: impl Into<String>, private_key: Vec<u8>, public_key: Vec<u8>) -> Self {
+        Self {
+            device_id: device_id(&public_key),
+            name: name.into(),
+            private_key,
+            public_key,
+        }
+    }
+}
+
+/// 由公钥计算设备 ID
+pub fn device_id(public_key: &[u8]) -> String {
+    Sha256::digest(public_key)[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 默认的设备名称，读取不到主机名时使用应用名称
+pub fn default_device_name() -> String {
+    ["COMPUTERNAME", "HOSTNAME", "USER", "USERNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_else(|| "EcoPaste".to_string())
+}
@@ -0,0 +1,66 @@
+use tauri::{
+    generate_handler,
+    plugin::{Builder, TauriPlugin},
+    Manager, RunEvent, Runtime,
+};
+
+mod channel;
+mod commands;
+mod discovery;
+mod error;
+mod identity;
+mod node;
+mod peers;
+mod protocol;
+mod remote;
+
+pub use commands::*;
+pub use error::SyncError;
+pub use identity::Identity;
+pub use node::{EventHandler, NodeConfig, NodeStatus, PeerInfo, SyncEvent, SyncNode};
+pub use peers::{PairedPeer, PeerStore};
+pub use remote::{
+    history, Cipher, RemoteBackend, RemoteConfig, RemoteEngine, RemoteStatus, RemoteSync,
+    SyncReport,
+};
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("eco-sync")
+        .invoke_handler(generate_handler![
+            commands::start_sync,
+            commands::stop_sync,
+            commands::sync_status,
+            commands::list_sync_peers,
+            commands::start_sync_pairing,
+            commands::cancel_sync_pairing,
+            commands::pair_sync_device,
+            commands::confirm_sync_pairing,
+            commands::unpair_sync_device,
+            commands::send_sync_entry,
+            commands::start_remote_sync,
+            commands::stop_remote_sync,
+            commands::sync_remote_now,
+            commands::remote_sync_status,
+            commands::test_remote_sync,
+        ])
+        .setup(|app, _api| {
+            // 同步默认关闭，由前端在用户开启后启动
+            app.manage(SyncState::default());
+            app.manage(RemoteSync::default());
+
+            Ok(())
+        })
+        .on_event(|app, event| {
+            // 退出时停止广播，让其它设备尽快看到本机下线，并等待正在进行的远程同步完成
+            if let RunEvent::Exit = event {
+                if let Some(state) = app.try_state::<SyncState>() {
+                    state.stop();
+                }
+
+                if let Some(remote) = app.try_state::<RemoteSync>() {
+                    remote.stop();
+                }
+            }
+        })
+        .build()
+}
@@ -0,0 +1,1111 @@
+//! 同步节点
+//!
+//! 节点监听一个 TCP 端口接收其它设备的连接，每同步一条记录都建立一次新的连接，发送完成后立即关闭。
+//! 对方的地址优先使用 mDNS 发现的地址，其次使用配对或者同步时对方告知的地址，
+//! 因此关闭服务发现时也可以在同一台机器上启动两个节点互相配对和同步。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::channel::{nonce_commitment, verification_code, Channel, Mode};
+use crate::discovery::Discovery;
+use crate::error::SyncError;
+use crate::identity::{device_id, Identity};
+use crate::peers::{PairedPeer, PeerStore};
+use crate::protocol::{Message, SyncEntry, SyncImage};
+
+// 配对码的有效期
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(120);
+
+// 配对码输错的次数达到上限后作废，避免被逐个尝试
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+
+// 等待用户确认配对的时间
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+const NONCE_LEN: usize = 32;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 节点的配置
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    /// 监听的端口，为 0 时由系统分配
+    pub port: u16,
+    /// 保存收到的图片的目录
+    pub images_dir: PathBuf,
+    /// 是否通过 mDNS 广播和发现设备
+    pub discovery: bool,
+}
+
+/// 节点产生的事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyncEvent {
+    /// 收到已配对设备发送的记录，图片记录的文件已经保存到图片目录
+    #[serde(rename_all = "camelCase")]
+    Entry {
+        from: String,
+        entry: Value,
+        image: Option<PathBuf>,
+    },
+    /// 配对握手完成，等待用户确认两台设备显示的确认码一致，
+    /// 确认后调用 [`SyncNode::confirm_pairing`]
+    #[serde(rename_all = "camelCase")]
+    PairingConfirm {
+        device_id: String,
+        name: String,
+        code: String,
+    },
+    /// 与新的设备完成配对
+    Paired(PairedPeer),
+    /// 发现的设备或者已配对的设备发生变化
+    PeersChanged,
+}
+
+/// 处理节点事件的回调，在节点的线程中调用
+pub type EventHandler = Arc<dyn Fn(SyncEvent) + Send + Sync>;
+
+/// 节点的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatus {
+    pub device_id: String,
+    pub name: String,
+    pub port: u16,
+    /// 正在等待配对的配对码
+    pub pairing_code: Option<String>,
+}
+
+/// 设备列表中的一项，合并了已配对和发现的设备
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub device_id: String,
+    pub name: String,
+    pub paired: bool,
+    pub online: bool,
+    pub addresses: Vec<SocketAddr>,
+}
+
+struct PairingSession {
+    code: String,
+    expires_at: Instant,
+    attempts: u32,
+}
+
+struct Inner {
+    identity: Identity,
+    peers: PeerStore,
+    port: u16,
+    images_dir: PathBuf,
+    handler: EventHandler,
+    discovery: Option<Discovery>,
+    // 对方在 Hello 中告知的地址
+    addresses: Mutex<HashMap<String, SocketAddr>>,
+    pairing: Mutex<Option<PairingSession>>,
+    // 正在等待用户确认的配对
+    confirmation: Mutex<Option<SyncSender<bool>>>,
+    stopped: AtomicBool,
+}
+
+/// 同步节点
+pub struct SyncNode {
+    inner: Arc<Inner>,
+    listener: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SyncNode {
+    /// 开始监听并广播本机的服务
+    pub fn start(
+        identity: Identity,
+        peers: PeerStore,
+        config: NodeConfig,
+        handler: EventHandler,
+    ) -> Result<Self, SyncError> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, config.port))?;
+        let port = listener.local_addr()?.port();
+
+        let discovery = if config.discovery {
+            let handler = handler.clone();
+
+            // 服务发现失败时（例如没有可用的网卡）仍然可以使用已知的地址同步
+            match Discovery::start(&identity, port, move || handler(SyncEvent::PeersChanged)) {
+                Ok(discovery) => Some(discovery),
+                Err(e) => {
+                    log::warn!("Failed to start sync discovery: {}", e);
+
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let inner = Arc::new(Inner {
+            identity,
+            peers,
+            port,
+            images_dir: config.images_dir,
+            handler,
+            discovery,
+            addresses: Mutex::default(),
+            pairing: Mutex::default(),
+            confirmation: Mutex::default(),
+            stopped: AtomicBool::new(false),
+        });
+
+        let listener = thread::spawn({
+            let inner = inner.clone();
+
+            move || {
+                for stream in listener.incoming() {
+                    if inner.stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+
+                    let inner = inner.clone();
+
+                    thread::spawn(move || {
+                        let address = stream.peer_addr().ok();
+
+                        if let Err(e) = inner.handle_connection(stream) {
+                            log::warn!("Sync connection from {:?} failed: {}", address, e);
+                        }
+                    });
+                }
+            }
+        });
+
+        log::info!(
+            "Sync node {} listening on port {}",
+            inner.identity.device_id,
+            port
+        );
+
+        Ok(Self {
+            inner,
+            listener: Mutex::new(Some(listener)),
+        })
+    }
+
+    pub fn status(&self) -> NodeStatus {
+        NodeStatus {
+            device_id: self.inner.identity.device_id.clone(),
+            name: self.inner.identity.name.clone(),
+            port: self.inner.port,
+            pairing_code: self
+                .inner
+                .pairing_session()
+                .as_ref()
+                .map(|session| session.code.clone()),
+        }
+    }
+
+    /// 已配对和发现的设备，已配对的设备排在前面
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        let discovered = self
+            .inner
+            .discovery
+            .as_ref()
+            .map(Discovery::peers)
+            .unwrap_or_default();
+
+        let mut list: Vec<PeerInfo> = self
+            .inner
+            .peers
+            .list()
+            .into_iter()
+            .map(|peer| {
+                let online = discovered
+                    .iter()
+                    .find(|item| item.device_id == peer.device_id);
+
+                PeerInfo {
+                    online: online.is_some(),
+                    addresses: online
+                        .map(|item| item.addresses.clone())
+                        .unwrap_or_default(),
+                    device_id: peer.device_id,
+                    name: peer.name,
+                    paired: true,
+                }
+            })
+            .collect();
+
+        for peer in discovered {
+            if self.inner.peers.contains(&peer.device_id) {
+                continue;
+            }
+
+            list.push(PeerInfo {
+                device_id: peer.device_id,
+                name: peer.name,
+                paired: false,
+                online: true,
+                addresses: peer.addresses,
+            });
+        }
+
+        list
+    }
+
+    /// 生成新的配对码，在对方设备上输入后完成配对
+    pub fn start_pairing(&self) -> Result<String, SyncError> {
+        let mut bytes = [0u8; 4];
+        getrandom::getrandom(&mut bytes).map_err(|e| SyncError::Pairing(e.to_string()))?;
+
+        let code = format!("{:06}", u32::from_le_bytes(bytes) % 1_000_000);
+
+        *self.inner.pairing_lock() = Some(PairingSession {
+            code: code.clone(),
+            expires_at: Instant::now() + PAIRING_TIMEOUT,
+            attempts: 0,
+        });
+
+        Ok(code)
+    }
+
+    pub fn cancel_pairing(&self) {
+        self.inner.pairing_lock().take();
+    }
+
+    /// 使用对方显示的配对码与对方配对，依次尝试每个地址
+    ///
+    /// 握手完成后会发出 [`SyncEvent::PairingConfirm`]，双方都确认后才完成配对
+    pub fn pair(&self, addresses: &[SocketAddr], code: &str) -> Result<PairedPeer, SyncError> {
+        let mut last_error = SyncError::Pairing("no address".into());
+
+        for address in addresses {
+            let (mut channel, name, remote_key) = match self.inner.connect(*address, Some(code)) {
+                Ok(connection) => connection,
+                Err(e) => {
+                    last_error = e;
+
+                    continue;
+                }
+            };
+
+            self.inner.verify_pairing(&mut channel, &name, true)?;
+
+            // 等待对方保存配对结果后关闭连接，之后马上同步时对方已经能识别本机
+            while Message::recv(&mut channel)?.is_some() {}
+
+            let peer = self.inner.peers.add(&name, &remote_key, now())?;
+
+            self.inner.emit(SyncEvent::Paired(peer.clone()));
+            self.inner.emit(SyncEvent::PeersChanged);
+
+            return Ok(peer);
+        }
+
+        Err(last_error)
+    }
+
+    /// 用户确认或拒绝正在等待确认的配对
+    pub fn confirm_pairing(&self, accepted: bool) -> Result<(), SyncError> {
+        let sender = self
+            .inner
+            .confirmation_lock()
+            .take()
+            .ok_or_else(|| SyncError::Pairing("no pairing to confirm".into()))?;
+
+        sender
+            .send(accepted)
+            .map_err(|_| SyncError::Pairing("pairing expired".into()))
+    }
+
+    /// 取消配对，返回设备是否存在
+    pub fn unpair(&self, device_id: &str) -> Result<bool, SyncError> {
+        let removed = self.inner.peers.remove(device_id)?;
+
+        if removed {
+            self.inner.emit(SyncEvent::PeersChanged);
+        }
+
+        Ok(removed)
+    }
+
+    /// 把一条记录发送给所有在线的已配对设备，返回成功发送的设备数量
+    pub fn send_entry(&self, entry: Value, image: Option<&Path>) -> Result<usize, SyncError> {
+        let image = match image {
+            Some(path) => Some(SyncImage {
+                file_name: file_name(path)
+                    .ok_or_else(|| SyncError::Protocol("invalid image path".into()))?,
+                data: STANDARD.encode(fs::read(path)?),
+            }),
+            None => None,
+        };
+
+        let message = Message::Entry(SyncEntry { entry, image });
+
+        let handles: Vec<_> = self
+            .inner
+            .peers
+            .list()
+            .into_iter()
+            .filter_map(|peer| {
+                let addresses = self.inner.addresses_of(&peer.device_id);
+
+                if addresses.is_empty() {
+                    return None;
+                }
+
+                let inner = self.inner.clone();
+                let message = message.clone();
+
+                Some(thread::spawn(move || {
+                    let result = inner.send_to(&peer, &addresses, &message);
+
+                    if let Err(e) = &result {
+                        log::warn!("Failed to sync entry to {}: {}", peer.name, e);
+                    }
+
+                    result.is_ok()
+                }))
+            })
+            .collect();
+
+        Ok(handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .filter(|sent| *sent)
+            .count())
+    }
+
+    /// 停止监听和服务发现，等待监听线程退出
+    pub fn stop(&self) {
+        if self.inner.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if let Some(discovery) = &self.inner.discovery {
+            discovery.stop();
+        }
+
+        // 正在等待确认的配对视为拒绝
+        self.inner.confirmation_lock().take();
+
+        // 连接一次自己，让监听线程从 accept 中返回
+        let _ = TcpStream::connect_timeout(
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, self.inner.port)),
+            CONNECT_TIMEOUT,
+        );
+
+        let listener = self
+            .listener
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        if let Some(listener) = listener {
+            let _ = listener.join();
+        }
+    }
+}
+
+impl Drop for SyncNode {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Inner {
+    fn emit(&self, event: SyncEvent) {
+        (self.handler)(event);
+    }
+
+    fn hello(&self) -> Message {
+        Message::Hello {
+            device_id: self.identity.device_id.clone(),
+            name: self.identity.name.clone(),
+            port: self.port,
+        }
+    }
+
+    fn pairing_lock(&self) -> MutexGuard<'_, Option<PairingSession>> {
+        self.pairing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn confirmation_lock(&self) -> MutexGuard<'_, Option<SyncSender<bool>>> {
+        self.confirmation.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // 返回未过期的配对码，过期的配对码会被清除
+    fn pairing_session(&self) -> MutexGuard<'_, Option<PairingSession>> {
+        let mut session = self.pairing_lock();
+
+        if session
+            .as_ref()
+            .is_some_and(|session| session.expires_at <= Instant::now())
+        {
+            session.take();
+        }
+
+        session
+    }
+
+    fn addresses_of(&self, device_id: &str) -> Vec<SocketAddr> {
+        let mut addresses = self
+            .discovery
+            .as_ref()
+            .map(|discovery| discovery.addresses(device_id))
+            .unwrap_or_default();
+
+        let known = self
+            .addresses
+            .lock()
+            .ok()
+            .and_then(|map| map.get(device_id).copied());
+
+        if let Some(address) = known.filter(|address| !addresses.contains(address)) {
+            addresses.push(address);
+        }
+
+        addresses
+    }
+
+    fn remember_address(&self, device_id: &str, address: SocketAddr) {
+        if let Ok(mut map) = self.addresses.lock() {
+            map.insert(device_id.to_string(), address);
+        }
+    }
+
+    // 交换 Hello，检查对方的设备 ID 与公钥一致并记下对方监听的地址，返回对方的名称
+    fn exchange_hello(
+        &self,
+        channel: &mut Channel,
+        ip: Option<std::net::IpAddr>,
+        initiator: bool,
+    ) -> Result<String, SyncError> {
+        if initiator {
+            self.hello().send(channel)?;
+        }
+
+        let Some(Message::Hello {
+            device_id: remote_id,
+            name,
+            port,
+        }) = Message::recv(channel)?
+        else {
+            return Err(SyncError::Protocol("expected hello".into()));
+        };
+
+        if remote_id != device_id(channel.remote_key()) {
+            return Err(SyncError::Protocol("device id mismatch".into()));
+        }
+
+        if !initiator {
+            self.hello().send(channel)?;
+        }
+
+        if let Some(ip) = ip {
+            self.remember_address(&remote_id, SocketAddr::new(ip, port));
+        }
+
+        Ok(name)
+    }
+
+    // 交换随机数派生确认码，等待本机用户确认后交换双方的确认结果
+    fn verify_pairing(
+        &self,
+        channel: &mut Channel,
+        name: &str,
+        initiator: bool,
+    ) -> Result<(), SyncError> {
+        let mut local_nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut local_nonce).map_err(|e| SyncError::Pairing(e.to_string()))?;
+
+        let code = if initiator {
+            let Some(Message::Commit { hash }) = Message::recv(channel)? else {
+                return Err(SyncError::Protocol("expected commit".into()));
+            };
+
+            Message::Nonce {
+                nonce: STANDARD.encode(local_nonce),
+            }
+            .send(channel)?;
+
+            let remote_nonce = recv_nonce(channel)?;
+
+            if STANDARD.encode(nonce_commitment(&remote_nonce)) != hash {
+                return Err(SyncError::Pairing("nonce does not match commitment".into()));
+            }
+
+            verification_code(channel.handshake_hash(), &local_nonce, &remote_nonce)
+        } else {
+            Message::Commit {
+                hash: STANDARD.encode(nonce_commitment(&local_nonce)),
+            }
+            .send(channel)?;
+
+            let remote_nonce = recv_nonce(channel)?;
+
+            Message::Nonce {
+                nonce: STANDARD.encode(local_nonce),
+            }
+            .send(channel)?;
+
+            verification_code(channel.handshake_hash(), &remote_nonce, &local_nonce)
+        };
+
+        let (sender, receiver) = mpsc::sync_channel(1);
+
+        {
+            let mut confirmation = self.confirmation_lock();
+
+            if confirmation.is_some() {
+                return Err(SyncError::Pairing("another pairing is in progress".into()));
+            }
+
+            *confirmation = Some(sender);
+        }
+
+        self.emit(SyncEvent::PairingConfirm {
+            device_id: device_id(channel.remote_key()),
+            name: name.to_string(),
+            code,
+        });
+
+        // 超时或者节点停止时视为拒绝
+        let accepted = receiver.recv_timeout(CONFIRM_TIMEOUT).unwrap_or(false);
+
+        self.confirmation_lock().take();
+
+        Message::Confirm { accepted }.send(channel)?;
+
+        if !accepted {
+            return Err(SyncError::Pairing("pairing rejected".into()));
+        }
+
+        // 对方的用户可能比本机晚一些开始确认
+        channel.set_read_timeout(CONFIRM_TIMEOUT * 2)?;
+
+        match Message::recv(channel)? {
+            Some(Message::Confirm { accepted: true }) => Ok(()),
+            Some(Message::Confirm { accepted: false }) | None => {
+                Err(SyncError::Pairing("pairing rejected by peer".into()))
+            }
+            Some(_) => Err(SyncError::Protocol("expected confirm".into())),
+        }
+    }
+
+    // 作为发起方连接并完成握手和 Hello，返回通道、对方的名称和公钥
+    fn connect(
+        &self,
+        address: SocketAddr,
+        pairing_code: Option<&str>,
+    ) -> Result<(Channel, String, Vec<u8>), SyncError> {
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        let mut channel = Channel::connect(stream, &self.identity, pairing_code)?;
+        let name = self.exchange_hello(&mut channel, Some(address.ip()), true)?;
+        let remote_key = channel.remote_key().to_vec();
+
+        Ok((channel, name, remote_key))
+    }
+
+    fn send_to(
+        &self,
+        peer: &PairedPeer,
+        addresses: &[SocketAddr],
+        message: &Message,
+    ) -> Result<(), SyncError> {
+        let mut last_error = SyncError::NotPaired(peer.device_id.clone());
+
+        for address in addresses {
+            let mut channel = match self.connect(*address, None) {
+                Ok((channel, _, remote_key)) => {
+                    // 对方的公钥也必须是配对时固定的公钥
+                    if STANDARD.encode(&remote_key) != peer.public_key {
+                        last_error = SyncError::NotPaired(device_id(&remote_key));
+
+                        continue;
+                    }
+
+                    channel
+                }
+                Err(e) => {
+                    last_error = e;
+
+                    continue;
+                }
+            };
+
+            return message.send(&mut channel);
+        }
+
+        Err(last_error)
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) -> Result<(), SyncError> {
+        let ip = stream.peer_addr().ok().map(|address| address.ip());
+
+        match Channel::read_mode(&mut stream)? {
+            Mode::Pairing => self.handle_pairing(stream, ip),
+            Mode::Sync => self.handle_sync(stream, ip),
+        }
+    }
+
+    fn handle_pairing(
+        &self,
+        stream: TcpStream,
+        ip: Option<std::net::IpAddr>,
+    ) -> Result<(), SyncError> {
+        let code = self
+            .pairing_session()
+            .as_ref()
+            .map(|session| session.code.clone())
+            .ok_or_else(|| SyncError::Pairing("no pairing code".into()))?;
+
+        let mut channel = match Channel::accept(stream, &self.identity, Some(&code)) {
+            Ok(channel) => channel,
+            Err(e) => {
+                let mut session = self.pairing_lock();
+
+                if let Some(current) = session.as_mut() {
+                    current.attempts += 1;
+
+                    if current.attempts >= MAX_PAIRING_ATTEMPTS {
+                        session.take();
+                    }
+                }
+
+                return Err(e);
+            }
+        };
+
+        // 握手成功后配对码即作废，无论用户是否确认，截获的配对码都不能再使用
+        self.pairing_lock().take();
+
+        let name = self.exchange_hello(&mut channel, ip, false)?;
+
+        self.verify_pairing(&mut channel, &name, false)?;
+
+        let peer = self.peers.add(&name, channel.remote_key(), now())?;
+
+        self.emit(SyncEvent::Paired(peer));
+        self.emit(SyncEvent::PeersChanged);
+
+        Ok(())
+    }
+
+    fn handle_sync(
+        &self,
+        stream: TcpStream,
+        ip: Option<std::net::IpAddr>,
+    ) -> Result<(), SyncError> {
+        let mut channel = Channel::accept(stream, &self.identity, None)?;
+
+        let peer = self
+            .peers
+            .find_by_key(channel.remote_key())
+            .ok_or_else(|| SyncError::NotPaired(device_id(channel.remote_key())))?;
+
+        self.exchange_hello(&mut channel, ip, false)?;
+
+        while let Some(message) = Message::recv(&mut channel)? {
+            match message {
+                Message::Entry(entry) => self.receive_entry(&peer, entry)?,
+                _ => return Err(SyncError::Protocol("unexpected message".into())),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn receive_entry(&self, peer: &PairedPeer, entry: SyncEntry) -> Result<(), SyncError> {
+        let image = match entry.image {
+            Some(image) => {
+                // 只使用文件名，避免对方通过路径写入图片目录之外的文件
+                let file_name = file_name(Path::new(&image.file_name))
+                    .ok_or_else(|| SyncError::Protocol("invalid image name".into()))?;
+
+                let data = STANDARD
+                    .decode(image.data)
+                    .map_err(|e| SyncError::Protocol(e.to_string()))?;
+
+                let path = self.images_dir.join(file_name);
+
+                if !path.exists() {
+                    fs::create_dir_all(&self.images_dir)?;
+                    fs::write(&path, data)?;
+                }
+
+                Some(path)
+            }
+            None => None,
+        };
+
+        self.emit(SyncEvent::Entry {
+            from: peer.device_id.clone(),
+            entry: entry.entry,
+            image,
+        });
+
+        Ok(())
+    }
+}
+
+fn recv_nonce(channel: &mut Channel) -> Result<Vec<u8>, SyncError> {
+    let Some(Message::Nonce { nonce }) = Message::recv(channel)? else {
+        return Err(SyncError::Protocol("expected nonce".into()));
+    };
+
+    let nonce = STANDARD
+        .decode(nonce)
+        .map_err(|e| SyncError::Protocol(e.to_string()))?;
+
+    if nonce.len() != NONCE_LEN {
+        return Err(SyncError::Protocol("invalid nonce".into()));
+    }
+
+    Ok(nonce)
+}
+
+/// 文件名，不接受隐藏文件和路径
+pub(crate) fn file_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.starts_with('.'))
+        .map(str::to_string)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::mpsc::{self, Receiver};
+
+    const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    struct TestNode {
+        node: SyncNode,
+        identity: Identity,
+        events: Receiver<SyncEvent>,
+        dir: PathBuf,
+    }
+
+    impl TestNode {
+        fn start(name: &str) -> Self {
+            let identity = Identity::generate(name).unwrap();
+            let dir = temp_dir(&identity.device_id);
+            let (sender, events) = mpsc::channel();
+
+            let node = SyncNode::start(
+                identity.clone(),
+                PeerStore::in_memory(),
+                NodeConfig {
+                    port: 0,
+                    images_dir: dir.join("images"),
+                    discovery: false,
+                },
+                Arc::new(move |event| {
+                    let _ = sender.send(event);
+                }),
+            )
+            .unwrap();
+
+            Self {
+                node,
+                identity,
+                events,
+                dir,
+            }
+        }
+
+        fn address(&self) -> SocketAddr {
+            SocketAddr::from((Ipv4Addr::LOCALHOST, self.node.status().port))
+        }
+
+        fn attempts(&self) -> Option<u32> {
+            self.node
+                .inner
+                .pairing_lock()
+                .as_ref()
+                .map(|session| session.attempts)
+        }
+
+        // 等待下一个符合条件的事件，跳过其它事件
+        fn next_event<T>(
+            &self,
+            timeout: Duration,
+            filter: impl Fn(SyncEvent) -> Option<T>,
+        ) -> Option<T> {
+            let deadline = Instant::now() + timeout;
+
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                if let Some(value) = filter(self.events.recv_timeout(remaining).ok()?) {
+                    return Some(value);
+                }
+            }
+        }
+
+        fn next_entry(&self, timeout: Duration) -> Option<(String, Value, Option<PathBuf>)> {
+            self.next_event(timeout, |event| match event {
+                SyncEvent::Entry { from, entry, image } => Some((from, entry, image)),
+                _ => None,
+            })
+        }
+
+        // 等待确认配对的事件，返回对方的设备 ID 和确认码
+        fn next_confirm(&self) -> Option<(String, String)> {
+            self.next_event(EVENT_TIMEOUT, |event| match event {
+                SyncEvent::PairingConfirm {
+                    device_id, code, ..
+                } => Some((device_id, code)),
+                _ => None,
+            })
+        }
+    }
+
+    impl Drop for TestNode {
+        fn drop(&mut self) {
+            self.node.stop();
+
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("eco-sync-test-{}", name));
+
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + EVENT_TIMEOUT;
+
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        condition()
+    }
+
+    // 由 A 输入 B 的配对码，双方的用户分别确认后返回 A 的配对结果
+    fn pair_with(
+        a: &TestNode,
+        b: &TestNode,
+        accept_a: bool,
+        accept_b: bool,
+    ) -> Result<PairedPeer, SyncError> {
+        let code = b.node.start_pairing().unwrap();
+        let address = b.address();
+        let node = &a.node;
+
+        thread::scope(|scope| {
+            let pairing = scope.spawn(move || node.pair(&[address], &code));
+
+            let (remote_b, code_a) = a.next_confirm().unwrap();
+            let (remote_a, code_b) = b.next_confirm().unwrap();
+
+            // 没有中间人时两台设备显示的确认码一致
+            assert_eq!(code_a, code_b);
+            assert_eq!(code_a.len(), 6);
+            assert_eq!(remote_b, b.identity.device_id);
+            assert_eq!(remote_a, a.identity.device_id);
+
+            a.node.confirm_pairing(accept_a).unwrap();
+            b.node.confirm_pairing(accept_b).unwrap();
+
+            pairing.join().unwrap()
+        })
+    }
+
+    fn pair(a: &TestNode, b: &TestNode) {
+        let peer = pair_with(a, b, true, true).unwrap();
+
+        assert_eq!(peer.device_id, b.identity.device_id);
+        assert_eq!(peer.name, "B");
+    }
+
+    #[test]
+    fn pairs_and_syncs_entries() {
+        let a = TestNode::start("A");
+        let b = TestNode::start("B");
+
+        pair(&a, &b);
+
+        // 对方在关闭配对连接前已经保存了本机
+        assert!(b.node.inner.peers.contains(&a.identity.device_id));
+        assert_eq!(b.node.status().pairing_code, None);
+
+        let image = a.dir.join("image.png");
+        let data = [0x89, b'P', b'N', b'G', 0, 1, 2, 3];
+
+        fs::write(&image, data).unwrap();
+
+        let entry = json!({ "id": "1", "type": "image", "value": "image.png" });
+
+        assert_eq!(a.node.send_entry(entry.clone(), Some(&image)).unwrap(), 1);
+
+        let (from, received, path) = b.next_entry(EVENT_TIMEOUT).unwrap();
+        let path = path.unwrap();
+
+        assert_eq!(from, a.identity.device_id);
+        assert_eq!(received, entry);
+        assert_eq!(path, b.dir.join("images").join("image.png"));
+        assert_eq!(fs::read(&path).unwrap(), data);
+
+        // 对方通过配对时告知的端口反向同步
+        let entry = json!({ "id": "2", "type": "text", "value": "hello" });
+
+        assert_eq!(b.node.send_entry(entry.clone(), None).unwrap(), 1);
+
+        let (from, received, path) = a.next_entry(EVENT_TIMEOUT).unwrap();
+
+        assert_eq!(from, b.identity.device_id);
+        assert_eq!(received, entry);
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn wrong_pairing_code_burns_attempts() {
+        let a = TestNode::start("A");
+        let b = TestNode::start("B");
+
+        let code = b.node.start_pairing().unwrap();
+
+        for attempt in 1..MAX_PAIRING_ATTEMPTS {
+            assert!(a.node.pair(&[b.address()], "wrong").is_err());
+            assert!(wait_for(|| b.attempts() == Some(attempt)));
+        }
+
+        assert!(a.node.pair(&[b.address()], "wrong").is_err());
+
+        // 达到上限后配对码作废，正确的配对码也无法再使用
+        assert!(wait_for(|| b.attempts().is_none()));
+        assert_eq!(b.node.status().pairing_code, None);
+        assert!(a.node.pair(&[b.address()], &code).is_err());
+        assert!(a.node.inner.peers.list().is_empty());
+        assert!(b.node.inner.peers.list().is_empty());
+    }
+
+    #[test]
+    fn rejected_confirmation_does_not_pair() {
+        let a = TestNode::start("A");
+        let b = TestNode::start("B");
+
+        assert!(pair_with(&a, &b, true, false).is_err());
+        assert!(pair_with(&a, &b, false, true).is_err());
+
+        assert!(a.node.inner.peers.list().is_empty());
+        assert!(wait_for(|| b.node.inner.peers.list().is_empty()));
+
+        // 握手成功后配对码已经作废，拒绝后需要重新生成
+        assert_eq!(b.node.status().pairing_code, None);
+        assert!(a.node.confirm_pairing(true).is_err());
+    }
+
+    #[test]
+    fn verification_code_depends_on_every_input() {
+        let hash = [1u8; 32];
+        let nonce_a = [2u8; NONCE_LEN];
+        let nonce_b = [3u8; NONCE_LEN];
+
+        let code = verification_code(&hash, &nonce_a, &nonce_b);
+
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(code, verification_code(&hash, &nonce_a, &nonce_b));
+        assert_ne!(code, verification_code(&[4u8; 32], &nonce_a, &nonce_b));
+        assert_ne!(code, verification_code(&hash, &nonce_b, &nonce_a));
+        assert_ne!(
+            nonce_commitment(&nonce_a).to_vec(),
+            nonce_commitment(&nonce_b).to_vec()
+        );
+    }
+
+    #[test]
+    fn rejects_unpaired_key() {
+        let a = TestNode::start("A");
+        let b = TestNode::start("B");
+
+        // 只有发起方单方面记录了对方，对方不认识发起方的公钥
+        let peer = PairedPeer {
+            device_id: b.identity.device_id.clone(),
+            name: "B".to_string(),
+            public_key: STANDARD.encode(&b.identity.public_key),
+            paired_at: 0,
+        };
+
+        let message = Message::Entry(SyncEntry {
+            entry: json!({ "id": "1" }),
+            image: None,
+        });
+
+        assert!(a
+            .node
+            .inner
+            .send_to(&peer, &[b.address()], &message)
+            .is_err());
+        assert!(b.next_entry(Duration::from_millis(500)).is_none());
+
+        // 没有配对的设备不会收到记录
+        assert_eq!(a.node.send_entry(json!({ "id": "2" }), None).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_pinned_key_mismatch() {
+        let a = TestNode::start("A");
+        let b = TestNode::start("B");
+        let c = TestNode::start("C");
+
+        pair(&a, &b);
+
+        // 配对时固定的公钥与实际连接到的设备不一致
+        let peer = PairedPeer {
+            public_key: STANDARD.encode(&c.identity.public_key),
+            ..a.node.inner.peers.list().remove(0)
+        };
+
+        let message = Message::Entry(SyncEntry {
+            entry: json!({ "id": "1" }),
+            image: None,
+        });
+
+        assert!(matches!(
+            a.node.inner.send_to(&peer, &[b.address()], &message),
+            Err(SyncError::NotPaired(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_image_paths() {
+        assert_eq!(
+            file_name(Path::new("a/b/image.png")).as_deref(),
+            Some("image.png")
+        );
+        assert_eq!(file_name(Path::new("../../.bashrc")), None);
+        assert_eq!(file_name(Path::new("/")), None);
+    }
+}
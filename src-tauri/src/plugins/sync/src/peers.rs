@@ -0,0 +1,140 @@
+//! 已配对的设备
+//!
+//! 保存对方的设备 ID、名称和配对时固定下来的公钥，之后的连接只接受这些公钥。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::error::SyncError;
+use crate::identity::device_id;
+
+/// 保存已配对设备的文件名
+pub const PEERS_FILE_NAME: &str = "sync-peers.json";
+
+/// 已配对的设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedPeer {
+    pub device_id: String,
+    pub name: String,
+    /// Base64 编码的静态公钥
+    pub public_key: String,
+    /// 配对的时间戳（毫秒）
+    pub paired_at: u64,
+}
+
+/// 已配对设备的列表，修改后立即写入文件
+pub struct PeerStore {
+    path: Option<PathBuf>,
+    peers: Mutex<Vec<PairedPeer>>,
+}
+
+impl PeerStore {
+    /// 从文件中读取，文件不存在或者无法解析时为空列表
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let peers = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path),
+            peers: Mutex::new(peers),
+        }
+    }
+
+    /// 只保存在内存中，用于在同一个进程中运行多个节点
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            peers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn list(&self) -> Vec<PairedPeer> {
+        self.peers
+            .lock()
+            .map(|peers| peers.clone())
+            .unwrap_or_default()
+    }
+
+    /// 查找公钥对应的设备
+    pub fn find_by_key(&self, public_key: &[u8]) -> Option<PairedPeer> {
+        let encoded = STANDARD.encode(public_key);
+
+        self.list()
+            .into_iter()
+            .find(|peer| peer.public_key == encoded)
+    }
+
+    pub fn contains(&self, device_id: &str) -> bool {
+        self.list().iter().any(|peer| peer.device_id == device_id)
+    }
+
+    /// 添加配对的设备，已存在时更新名称和公钥
+    pub fn add(
+        &self,
+        name: &str,
+        public_key: &[u8],
+        paired_at: u64,
+    ) -> Result<PairedPeer, SyncError> {
+        let peer = PairedPeer {
+            device_id: device_id(public_key),
+            name: name.to_string(),
+            public_key: STANDARD.encode(public_key),
+            paired_at,
+        };
+
+        self.update(|peers| {
+            peers.retain(|item| item.device_id != peer.device_id);
+            peers.push(peer.clone());
+        })?;
+
+        Ok(peer)
+    }
+
+    /// 删除配对的设备，返回是否存在
+    pub fn remove(&self, device_id: &str) -> Result<bool, SyncError> {
+        let mut removed = false;
+
+        self.update(|peers| {
+            let len = peers.len();
+
+            peers.retain(|peer| peer.device_id != device_id);
+
+            removed = peers.len() != len;
+        })?;
+
+        Ok(removed)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<PairedPeer>)) -> Result<(), SyncError> {
+        let mut peers = self
+            .peers
+            .lock()
+            .map_err(|e| SyncError::Pairing(e.to_string()))?;
+
+        f(&mut peers);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // 先写临时文件再重命名，避免写入中断时丢失已配对的设备
+        let temp_path = path.with_extension("tmp");
+
+        fs::write(&temp_path, serde_json::to_vec_pretty(&*peers)?)?;
+        fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! 通道中传输的消息
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::channel::Channel;
+use crate::error::SyncError;
+
+/// 同步的消息，以 JSON 编码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Message {
+    /// 握手完成后双方首先发送的设备信息，`port` 是本机监听的端口，
+    /// 对方没有通过 mDNS 发现本机时也可以用它连接回来
+    #[serde(rename_all = "camelCase")]
+    Hello {
+        device_id: String,
+        name: String,
+        port: u16,
+    },
+    /// 一条新的历史记录
+    Entry(SyncEntry),
+    /// 配对时响应方先发送随机数的承诺，Base64 编码
+    Commit { hash: String },
+    /// 配对时发起方先发送随机数，响应方收到后再公开自己的随机数，Base64 编码
+    Nonce { nonce: String },
+    /// 用户是否确认两台设备显示的确认码一致
+    Confirm { accepted: bool },
+}
+
+/// 同步的历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// 数据库中的一行，原样交给前端保存
+    pub entry: Value,
+    /// 图片记录的文件
+    pub image: Option<SyncImage>,
+}
+
+/// 图片文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncImage {
+    pub file_name: String,
+    /// Base64 编码的文件内容
+    pub data: String,
+}
+
+impl Message {
+    pub fn send(&self, channel: &mut Channel) -> Result<(), SyncError> {
+        channel.send(&serde_json::to_vec(self)?)
+    }
+
+    /// 接收一条消息，对方关闭连接时返回 None
+    pub fn recv(channel: &mut Channel) -> Result<Option<Self>, SyncError> {
+        match channel.recv()? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! 增量文件的加密
+//!
+//! 文件格式为 `ECOSYNC1 | 盐（16 字节） | nonce（12 字节） | 密文`，
+//! 密钥由同步密码和盐通过 PBKDF2-HMAC-SHA256 派生，使用 ChaCha20-Poly1305 加密。
+//! 本机在一次运行中只生成一个盐，解密其它设备的文件时缓存每个盐派生的密钥。
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::error::SyncError;
+
+const MAGIC: &[u8] = b"ECOSYNC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const ITERATIONS: u32 = 100_000;
+
+pub struct Cipher {
+    passphrase: String,
+    salt: [u8; SALT_LEN],
+    key: Key,
+    keys: Mutex<HashMap<[u8; SALT_LEN], Key>>,
+}
+
+impl Cipher {
+    pub fn new(passphrase: &str) -> Result<Self, SyncError> {
+        if passphrase.is_empty() {
+            return Err(SyncError::Crypto("passphrase is empty".into()));
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        random(&mut salt)?;
+
+        Ok(Self {
+            passphrase: passphrase.to_string(),
+            key: derive_key(passphrase, &salt),
+            salt,
+            keys: Mutex::default(),
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, SyncError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        random(&mut nonce)?;
+
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|e| SyncError::Crypto(e.to_string()))?;
+
+        let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&self.salt);
+        data.extend_from_slice(&nonce);
+        data.extend(ciphertext);
+
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, SyncError> {
+        let data = data
+            .strip_prefix(MAGIC)
+            .filter(|data| data.len() > SALT_LEN + NONCE_LEN)
+            .ok_or_else(|| SyncError::Crypto("invalid file".into()))?;
+
+        let (salt, data) = data.split_at(SALT_LEN);
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let salt: [u8; SALT_LEN] = salt.try_into().expect("salt length checked");
+
+        let key = if salt == self.salt {
+            self.key
+        } else {
+            let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+
+            *keys
+                .entry(salt)
+                .or_insert_with(|| derive_key(&self.passphrase, &salt))
+        };
+
+        // 认证失败说明同步密码与对方不一致，或者文件被修改过
+        ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| SyncError::Crypto("wrong passphrase or corrupted file".into()))
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key {
+    let mut key = Key::default();
+
+    pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, ITERATIONS, &mut key);
+
+    key
+}
+
+fn random(buffer: &mut [u8]) -> Result<(), SyncError> {
+    getrandom::getrandom(buffer).map_err(|e| SyncError::Crypto(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let cipher = Cipher::new("passphrase").unwrap();
+        let data = cipher.encrypt(b"hello").unwrap();
+
+        assert!(data.starts_with(MAGIC));
+        assert_eq!(cipher.decrypt(&data).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn decrypts_other_salts_with_same_passphrase() {
+        let a = Cipher::new("passphrase").unwrap();
+        let b = Cipher::new("passphrase").unwrap();
+
+        assert_ne!(a.salt, b.salt);
+        assert_eq!(b.decrypt(&a.encrypt(b"hello").unwrap()).unwrap(), b"hello");
+        assert_eq!(b.decrypt(&a.encrypt(b"world").unwrap()).unwrap(), b"world");
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase() {
+        let data = Cipher::new("passphrase")
+            .unwrap()
+            .encrypt(b"hello")
+            .unwrap();
+
+        assert!(matches!(
+            Cipher::new("other").unwrap().decrypt(&data),
+            Err(SyncError::Crypto(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_modified_and_truncated_files() {
+        let cipher = Cipher::new("passphrase").unwrap();
+        let data = cipher.encrypt(b"hello").unwrap();
+
+        let mut modified = data.clone();
+        *modified.last_mut().unwrap() ^= 1;
+
+        assert!(cipher.decrypt(&modified).is_err());
+        assert!(cipher.decrypt(&data[..MAGIC.len() + SALT_LEN]).is_err());
+        assert!(cipher.decrypt(b"hello").is_err());
+        assert!(cipher.decrypt(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_passphrase() {
+        assert!(matches!(Cipher::new(""), Err(SyncError::Crypto(_))));
+    }
+}
@@ -0,0 +1,1116 @@
+//! 一次同步：先下载其它设备的增量文件合并到本地，再上传本机新增的记录
+//!
+//! 增量文件保存在 `deltas/<设备 ID>-<上传时间>-<序号>.delta`，文件名按时间排序，
+//! 本机记下每台设备处理到的文件名，只下载之后的文件。
+//! 合并时相同 ID 的记录保留修改时间（`updatedAt`）较新的一条，内容相同但 ID 不同的记录只更新时间。
+//! 用户删除的记录以删除时间同步，删除晚于其它设备上最后一次修改时一起删除，否则保留修改后的记录。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::cipher::Cipher;
+use super::history::{HistoryDatabase, HistoryRow, UPDATED_AT};
+use super::RemoteBackend;
+use crate::error::SyncError;
+use crate::node::file_name;
+use crate::protocol::{SyncEntry, SyncImage};
+
+const DELTA_DIR: &str = "deltas";
+const DELTA_EXTENSION: &str = ".delta";
+
+// 单个增量文件的大小上限，首次同步时历史记录会分成多个文件上传
+const MAX_DELTA_LEN: usize = 8 * 1024 * 1024;
+const MAX_DELTA_ENTRIES: usize = 500;
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// 上传的记录数量
+    pub pushed: usize,
+    /// 新增或更新的本地记录数量
+    pub pulled: usize,
+    /// 本地已有更新版本而跳过的记录数量
+    pub skipped: usize,
+    /// 完成的时间戳（毫秒）
+    pub finished_at: u64,
+}
+
+// 增量文件解密后的内容
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Delta {
+    device_id: String,
+    created_at: u64,
+    entries: Vec<SyncEntry>,
+    // 旧版本上传的文件没有删除的记录
+    #[serde(default)]
+    deleted: Vec<Tombstone>,
+}
+
+// 删除的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Tombstone {
+    id: String,
+    deleted_at: String,
+}
+
+// 已上传的位置：最新的时间，以及这个时间已上传的 ID
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Watermark {
+    at: Option<String>,
+    ids: Vec<String>,
+}
+
+impl Watermark {
+    fn contains(&self, id: &str, time: Option<&str>) -> bool {
+        self.at.as_deref() == time && self.ids.iter().any(|pushed| pushed == id)
+    }
+
+    // 同一毫秒内之后新增的记录下次仍然会被查到，需要记下这个时间已处理的 ID，`items` 按时间排序
+    fn advance<'a>(&mut self, items: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        let items: Vec<_> = items.into_iter().collect();
+
+        let Some((_, last)) = items.last().copied() else {
+            return;
+        };
+
+        self.ids = items
+            .iter()
+            .filter(|(_, time)| *time == last)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        self.at = Some(last.to_string());
+    }
+}
+
+// 本机的同步进度，保存在应用配置目录
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SyncState {
+    // 同步的位置，变化后进度作废
+    target: String,
+    // 已上传的记录的修改时间，旧版本按创建时间记录的进度不再使用，
+    // 升级后重新上传一次，其它设备会跳过修改时间相同的记录
+    pushed: Watermark,
+    // 已上传的删除时间
+    deleted: Watermark,
+    // 上次上传后从其它设备合并的记录和删除，值为修改或删除的时间，不需要再上传
+    pulled: HashMap<String, String>,
+    // 每台设备已处理的最后一个增量文件
+    cursors: HashMap<String, String>,
+}
+
+impl SyncState {
+    fn load(path: Option<&Path>, target: &str) -> Self {
+        let state: Option<Self> = path
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|content| serde_json::from_slice(&content).ok());
+
+        match state {
+            Some(state) if state.target == target => state,
+            _ => Self {
+                target: target.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn save(&self, path: Option<&Path>) -> Result<(), SyncError> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = path.with_extension("tmp");
+
+        fs::write(&temp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+}
+
+enum Merge {
+    Applied,
+    Skipped,
+}
+
+/// 同步引擎，不依赖 Tauri
+pub struct RemoteEngine {
+    backend: Box<dyn RemoteBackend>,
+    cipher: Cipher,
+    device_id: String,
+    target: String,
+    database_path: PathBuf,
+    images_dir: PathBuf,
+    state_path: Option<PathBuf>,
+}
+
+impl RemoteEngine {
+    /// `target` 标识同步的位置，`state_path` 为空时同步进度只保存在内存中
+    pub fn new(
+        backend: Box<dyn RemoteBackend>,
+        cipher: Cipher,
+        device_id: impl Into<String>,
+        target: impl Into<String>,
+        database_path: impl Into<PathBuf>,
+        images_dir: impl Into<PathBuf>,
+        state_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            backend,
+            cipher,
+            device_id: device_id.into(),
+            target: target.into(),
+            database_path: database_path.into(),
+            images_dir: images_dir.into(),
+            state_path,
+        }
+    }
+
+    pub fn sync(&self) -> Result<SyncReport, SyncError> {
+        let state_path = self.state_path.as_deref();
+        let mut state = SyncState::load(state_path, &self.target);
+        let database = HistoryDatabase::open(&self.database_path)?;
+        let mut report = SyncReport::default();
+
+        database.fill_updated_at()?;
+
+        // 每处理完一个文件都保存进度，中途失败时下次从失败的文件继续
+        let result = self.pull(&database, &mut state, &mut report, state_path);
+
+        state.save(state_path)?;
+        result?;
+
+        self.push(&database, &mut state, &mut report)?;
+
+        state.save(state_path)?;
+
+        report.finished_at = now();
+
+        Ok(report)
+    }
+
+    fn pull(
+        &self,
+        database: &HistoryDatabase,
+        state: &mut SyncState,
+        report: &mut SyncReport,
+        state_path: Option<&Path>,
+    ) -> Result<(), SyncError> {
+        let mut names: Vec<(String, String)> = self
+            .backend
+            .list(DELTA_DIR)?
+            .into_iter()
+            .filter_map(|name| {
+                let device_id = name
+                    .strip_suffix(DELTA_EXTENSION)?
+                    .split_once('-')?
+                    .0
+                    .to_string();
+
+                Some((device_id, name))
+            })
+            .filter(|(device_id, name)| {
+                *device_id != self.device_id
+                    && state
+                        .cursors
+                        .get(device_id)
+                        .map_or(true, |cursor| name > cursor)
+            })
+            .collect();
+
+        names.sort();
+
+        for (device_id, name) in names {
+            let Some(data) = self.backend.get(&format!("{}/{}", DELTA_DIR, name))? else {
+                continue;
+            };
+
+            let delta: Delta = serde_json::from_slice(&self.cipher.decrypt(&data)?)?;
+
+            for entry in delta.entries {
+                let text = |key: &str| {
+                    entry
+                        .entry
+                        .get(key)
+                        .and_then(|value| value.as_str())
+                        .map(str::to_string)
+                };
+
+                let (Some(id), Some(create_time)) = (text("id"), text("createTime")) else {
+                    continue;
+                };
+
+                // 旧版本上传的记录没有修改时间，按创建时间计算
+                let updated_at = match text(UPDATED_AT) {
+                    Some(time) => time,
+                    None => match database.legacy_updated_at(&create_time)? {
+                        Some(time) => time,
+                        None => continue,
+                    },
+                };
+
+                match self.merge(database, entry, &updated_at)? {
+                    Merge::Applied => {
+                        report.pulled += 1;
+
+                        state.pulled.insert(id, updated_at);
+                    }
+                    Merge::Skipped => report.skipped += 1,
+                }
+            }
+
+            for tombstone in delta.deleted {
+                match self.merge_deleted(database, &tombstone)? {
+                    Merge::Applied => report.pulled += 1,
+                    Merge::Skipped => report.skipped += 1,
+                }
+
+                // 本机记下的删除不需要再上传
+                state.pulled.insert(tombstone.id, tombstone.deleted_at);
+            }
+
+            state.cursors.insert(device_id, name);
+            state.save(state_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn merge(
+        &self,
+        database: &HistoryDatabase,
+        entry: SyncEntry,
+        updated_at: &str,
+    ) -> Result<Merge, SyncError> {
+        let Some(mut row) = entry.entry.as_object().cloned() else {
+            return Ok(Merge::Skipped);
+        };
+
+        row.insert(UPDATED_AT.to_string(), updated_at.into());
+
+        let text = |key: &str| {
+            row.get(key)
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+        };
+
+        let newer = |local: Option<&str>| local.map_or(true, |local| updated_at > local);
+
+        // 本机在这次修改之后删除了记录，不再恢复
+        if database
+            .deleted_at(text("id"))?
+            .is_some_and(|deleted_at| !newer(Some(&deleted_at)))
+        {
+            return Ok(Merge::Skipped);
+        }
+
+        if let Some(local) = database.find(text("id"))? {
+            if !newer(local.get(UPDATED_AT).and_then(|time| time.as_str())) {
+                return Ok(Merge::Skipped);
+            }
+        } else if let Some((local_id, local_time)) =
+            database.find_by_content(text("type"), text("value"))?
+        {
+            // 两台设备复制了相同的内容，保留本地的记录
+            if !newer(Some(&local_time)) {
+                return Ok(Merge::Skipped);
+            }
+
+            database.touch(&local_id, text("createTime"), updated_at)?;
+
+            return Ok(Merge::Applied);
+        }
+
+        if let Some(image) = entry.image {
+            self.save_image(image)?;
+        }
+
+        database.upsert(&row)?;
+
+        Ok(Merge::Applied)
+    }
+
+    fn merge_deleted(
+        &self,
+        database: &HistoryDatabase,
+        tombstone: &Tombstone,
+    ) -> Result<Merge, SyncError> {
+        let local = database.find(&tombstone.id)?;
+
+        // 删除之后本机又修改过的记录保留
+        let modified = local.as_ref().is_some_and(|row| {
+            row.get(UPDATED_AT)
+                .and_then(|time| time.as_str())
+                .is_some_and(|updated_at| updated_at > tombstone.deleted_at.as_str())
+        });
+
+        if modified {
+            return Ok(Merge::Skipped);
+        }
+
+        // 本机没有这条记录时也记下删除时间，之后收到更早的修改时不会恢复
+        let Some(row) = database.delete(&tombstone.id, &tombstone.deleted_at)? else {
+            return Ok(Merge::Skipped);
+        };
+
+        self.remove_image(&row);
+
+        Ok(Merge::Applied)
+    }
+
+    fn save_image(&self, image: SyncImage) -> Result<(), SyncError> {
+        let file_name = file_name(Path::new(&image.file_name))
+            .ok_or_else(|| SyncError::Protocol("invalid image name".into()))?;
+
+        let path = self.images_dir.join(file_name);
+
+        if path.exists() {
+            return Ok(());
+        }
+
+        let data = STANDARD
+            .decode(image.data)
+            .map_err(|e| SyncError::Protocol(e.to_string()))?;
+
+        fs::create_dir_all(&self.images_dir)?;
+        fs::write(path, data)?;
+
+        Ok(())
+    }
+
+    // 删除图片记录的文件，只删除图片目录中的文件
+    fn remove_image(&self, row: &HistoryRow) {
+        if row.get("type").and_then(|kind| kind.as_str()) != Some("image") {
+            return;
+        }
+
+        let file_name = row
+            .get("value")
+            .and_then(|value| value.as_str())
+            .and_then(|value| file_name(Path::new(value)));
+
+        if let Some(file_name) = file_name {
+            let _ = fs::remove_file(self.images_dir.join(file_name));
+        }
+    }
+
+    fn push(
+        &self,
+        database: &HistoryDatabase,
+        state: &mut SyncState,
+        report: &mut SyncReport,
+    ) -> Result<(), SyncError> {
+        let changed = database.changed_since(state.pushed.at.as_deref())?;
+        let deleted = database.deleted_since(state.deleted.at.as_deref())?;
+
+        if changed.is_empty() && deleted.is_empty() {
+            state.pulled.clear();
+
+            return Ok(());
+        }
+
+        let text = |row: &HistoryRow, key: &str| {
+            row.get(key)
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let pulled = |id: &str, time: &str| state.pulled.get(id).map(String::as_str) == Some(time);
+
+        let rows: Vec<&HistoryRow> = changed
+            .iter()
+            .filter(|row| {
+                let id = text(row, "id");
+                let updated_at = text(row, UPDATED_AT);
+
+                !state.pushed.contains(&id, Some(&updated_at)) && !pulled(&id, &updated_at)
+            })
+            .collect();
+
+        let tombstones: Vec<Tombstone> = deleted
+            .iter()
+            .filter(|(id, deleted_at)| {
+                !state.deleted.contains(id, Some(deleted_at)) && !pulled(id, deleted_at)
+            })
+            .map(|(id, deleted_at)| Tombstone {
+                id: id.clone(),
+                deleted_at: deleted_at.clone(),
+            })
+            .collect();
+
+        let created_at = now();
+        let mut entries = Vec::new();
+        let mut size = 0;
+        let mut index = 0;
+
+        for row in &rows {
+            let entry = self.to_entry(row);
+
+            size += entry.image.as_ref().map_or(0, |image| image.data.len());
+            entries.push(entry);
+
+            if size >= MAX_DELTA_LEN || entries.len() >= MAX_DELTA_ENTRIES {
+                self.upload(created_at, index, std::mem::take(&mut entries), Vec::new())?;
+
+                size = 0;
+                index += 1;
+            }
+        }
+
+        report.pushed = rows.len() + tombstones.len();
+
+        // 删除的记录放在最后一个文件中
+        if !entries.is_empty() || !tombstones.is_empty() {
+            self.upload(created_at, index, entries, tombstones)?;
+        }
+
+        // 进度包括合并的记录，否则下次同步会把它们当作新记录上传
+        state.pushed.advance(changed.iter().filter_map(|row| {
+            let id = row.get("id")?.as_str()?;
+            let updated_at = row.get(UPDATED_AT)?.as_str()?;
+
+            Some((id, updated_at))
+        }));
+        state.deleted.advance(
+            deleted
+                .iter()
+                .map(|(id, deleted_at)| (id.as_str(), deleted_at.as_str())),
+        );
+        state.pulled.clear();
+
+        Ok(())
+    }
+
+    fn to_entry(&self, row: &HistoryRow) -> SyncEntry {
+        let is_image = row.get("type").and_then(|kind| kind.as_str()) == Some("image");
+        let value = row.get("value").and_then(|value| value.as_str());
+
+        // 图片记录只保存文件名，文件不存在时只同步记录
+        let image = value.filter(|_| is_image).and_then(|value| {
+            let path = self.images_dir.join(value);
+
+            Some(SyncImage {
+                file_name: file_name(&path)?,
+                data: STANDARD.encode(fs::read(&path).ok()?),
+            })
+        });
+
+        SyncEntry {
+            entry: row.clone().into(),
+            image,
+        }
+    }
+
+    fn upload(
+        &self,
+        created_at: u64,
+        index: usize,
+        entries: Vec<SyncEntry>,
+        deleted: Vec<Tombstone>,
+    ) -> Result<(), SyncError> {
+        let delta = Delta {
+            device_id: self.device_id.clone(),
+            created_at,
+            entries,
+            deleted,
+        };
+
+        let data = self.cipher.encrypt(&serde_json::to_vec(&delta)?)?;
+
+        let name = format!(
+            "{}/{}-{:013}-{:04}{}",
+            DELTA_DIR, self.device_id, created_at, index, DELTA_EXTENSION
+        );
+
+        self.backend.put(&name, data)
+    }
+}
+
+/// 检查能否访问同步目录，已有增量文件时用最新的一个检查同步密码是否正确
+pub fn check(backend: &dyn RemoteBackend, cipher: &Cipher) -> Result<(), SyncError> {
+    let latest = backend
+        .list(DELTA_DIR)?
+        .into_iter()
+        .filter(|name| name.ends_with(DELTA_EXTENSION))
+        .max_by(|a, b| {
+            let time = |name: &str| name.split('-').nth(1).map(str::to_string);
+
+            time(a).cmp(&time(b))
+        });
+
+    let Some(name) = latest else {
+        return Ok(());
+    };
+
+    if let Some(data) = backend.get(&format!("{}/{}", DELTA_DIR, name))? {
+        cipher.decrypt(&data)?;
+    }
+
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{params, Connection};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use crate::remote::{S3Backend, WebDavBackend};
+
+    const PASSPHRASE: &str = "passphrase";
+
+    // 与前端创建的表结构一致
+    const SCHEMA: &str = r#"CREATE TABLE history (
+        id TEXT PRIMARY KEY, type TEXT, "group" TEXT, value TEXT, search TEXT,
+        count INTEGER, width INTEGER, height INTEGER, favorite INTEGER DEFAULT 0,
+        createTime TEXT, note TEXT, subtype TEXT, render TEXT, language TEXT, secret TEXT,
+        keepSecret INTEGER, updatedAt TEXT
+    )"#;
+
+    // 保存在内存中的存储，克隆后共享同一份文件
+    #[derive(Clone, Default)]
+    struct MemoryBackend {
+        files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    }
+
+    impl MemoryBackend {
+        fn names(&self) -> Vec<String> {
+            self.list(DELTA_DIR).unwrap()
+        }
+    }
+
+    impl RemoteBackend for MemoryBackend {
+        fn list(&self, dir: &str) -> Result<Vec<String>, SyncError> {
+            let prefix = format!("{}/", dir.trim_matches('/'));
+
+            Ok(self
+                .files
+                .lock()
+                .unwrap()
+                .keys()
+                .filter_map(|path| path.strip_prefix(&prefix))
+                .filter(|name| !name.contains('/'))
+                .map(str::to_string)
+                .collect())
+        }
+
+        fn get(&self, path: &str) -> Result<Option<Vec<u8>>, SyncError> {
+            Ok(self.files.lock().unwrap().get(path).cloned())
+        }
+
+        fn put(&self, path: &str, data: Vec<u8>) -> Result<(), SyncError> {
+            self.files.lock().unwrap().insert(path.to_string(), data);
+
+            Ok(())
+        }
+    }
+
+    // 一台设备的数据库、图片目录和同步进度
+    struct Device {
+        id: String,
+        dir: PathBuf,
+    }
+
+    impl Device {
+        fn new(id: &str) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+            let dir = std::env::temp_dir().join(format!(
+                "eco-remote-test-{}-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed),
+                id
+            ));
+
+            fs::create_dir_all(dir.join("images")).unwrap();
+            Connection::open(dir.join("history.db"))
+                .unwrap()
+                .execute(SCHEMA, [])
+                .unwrap();
+
+            Self {
+                id: id.to_string(),
+                dir,
+            }
+        }
+
+        // 每次创建新的引擎，与应用重启后一样只能从文件中读取同步进度
+        fn engine(&self, backend: impl RemoteBackend + 'static, passphrase: &str) -> RemoteEngine {
+            RemoteEngine::new(
+                Box::new(backend),
+                Cipher::new(passphrase).unwrap(),
+                &self.id,
+                "memory",
+                self.dir.join("history.db"),
+                self.dir.join("images"),
+                Some(self.dir.join("sync.json")),
+            )
+        }
+
+        fn sync(&self, backend: &MemoryBackend) -> SyncReport {
+            self.engine(backend.clone(), PASSPHRASE).sync().unwrap()
+        }
+
+        fn insert(&self, id: &str, kind: &str, value: &str, create_time: &str) {
+            Connection::open(self.dir.join("history.db"))
+                .unwrap()
+                .execute(
+                    "INSERT INTO history (id, type, value, createTime) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, kind, value, create_time],
+                )
+                .unwrap();
+        }
+
+        fn execute(&self, sql: &str, params: impl rusqlite::Params) {
+            Connection::open(self.dir.join("history.db"))
+                .unwrap()
+                .execute(sql, params)
+                .unwrap();
+        }
+
+        // 与前端一样删除记录并记下删除时间
+        fn delete(&self, id: &str, deleted_at: &str) {
+            self.execute("DELETE FROM history WHERE id = ?1", params![id]);
+            self.execute(
+                "INSERT OR REPLACE INTO history_tombstone (id, deletedAt) VALUES (?1, ?2)",
+                params![id, deleted_at],
+            );
+        }
+
+        fn row(&self, id: &str) -> Option<HistoryRow> {
+            HistoryDatabase::open(&self.dir.join("history.db"))
+                .unwrap()
+                .find(id)
+                .unwrap()
+        }
+
+        fn text(&self, id: &str, column: &str) -> Option<String> {
+            self.row(id)?.get(column)?.as_str().map(str::to_string)
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn pushes_and_pulls_between_devices() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        fs::write(a.dir.join("images/1.png"), b"png").unwrap();
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.insert("2", "image", "1.png", "2024-01-01 00:00:02");
+
+        let report = a.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (2, 0, 0));
+        assert!(report.finished_at > 0);
+
+        let names = backend.names();
+        assert_eq!(names.len(), 1);
+        assert!(names[0].starts_with("aaaa-") && names[0].ends_with("-0000.delta"));
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (0, 2, 0));
+        assert_eq!(b.text("1", "value").as_deref(), Some("hello"));
+        assert_eq!(b.text("2", "value").as_deref(), Some("1.png"));
+        assert_eq!(fs::read(b.dir.join("images/1.png")).unwrap(), b"png");
+
+        // 从其它设备合并的记录不会再上传
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (0, 0, 0));
+
+        b.insert("3", "text", "world", "2024-01-01 00:00:03");
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled), (1, 0));
+
+        let report = a.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (0, 1, 0));
+        assert_eq!(a.text("3", "value").as_deref(), Some("world"));
+        assert_eq!(backend.names().len(), 2);
+    }
+
+    #[test]
+    fn keeps_newer_row_with_same_id() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "older", "2024-01-01 00:00:01");
+        b.insert("1", "text", "newer", "2024-01-01 00:00:02");
+
+        assert_eq!(a.sync(&backend).pushed, 1);
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (1, 0, 1));
+        assert_eq!(b.text("1", "value").as_deref(), Some("newer"));
+
+        let report = a.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (0, 1, 0));
+        assert_eq!(a.text("1", "value").as_deref(), Some("newer"));
+        assert_eq!(
+            a.text("1", "createTime").as_deref(),
+            Some("2024-01-01 00:00:02")
+        );
+    }
+
+    #[test]
+    fn touches_row_with_same_content() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+        let c = Device::new("cccc");
+
+        a.insert("1", "text", "same", "2024-01-01 00:00:02");
+        b.insert("2", "text", "same", "2024-01-01 00:00:01");
+        c.insert("3", "text", "same", "2024-01-01 00:00:03");
+
+        a.sync(&backend);
+
+        // 本地的记录较旧，只更新创建时间
+        let report = b.sync(&backend);
+        assert_eq!((report.pulled, report.skipped), (1, 0));
+        assert!(b.row("1").is_none());
+        assert_eq!(
+            b.text("2", "createTime").as_deref(),
+            Some("2024-01-01 00:00:02")
+        );
+
+        // 本地的记录较新，保持不变
+        // B 上传了更新创建时间后的记录，两个文件都被跳过
+        let report = c.sync(&backend);
+        assert_eq!((report.pulled, report.skipped), (0, 2));
+        assert!(c.row("1").is_none());
+        assert_eq!(
+            c.text("3", "createTime").as_deref(),
+            Some("2024-01-01 00:00:03")
+        );
+    }
+
+    #[test]
+    fn merges_updates_by_updated_at() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.sync(&backend);
+        b.sync(&backend);
+
+        // 没有修改时间的记录按创建时间补上 UTC 时间
+        let updated_at = a.text("1", UPDATED_AT).unwrap();
+        assert!(updated_at.starts_with("20") && updated_at.ends_with("Z"));
+        assert_eq!(b.text("1", UPDATED_AT), Some(updated_at));
+
+        // 修改备注不改变创建时间，也会同步到其它设备
+        a.execute(
+            "UPDATE history SET note = 'note', updatedAt = '2030-01-01T00:00:00.000Z' WHERE id = '1'",
+            [],
+        );
+
+        assert_eq!(a.sync(&backend).pushed, 1);
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pulled, report.skipped), (1, 0));
+        assert_eq!(b.text("1", "note").as_deref(), Some("note"));
+        assert_eq!(
+            b.text("1", "createTime").as_deref(),
+            Some("2024-01-01 00:00:01")
+        );
+
+        // 较旧的修改不会覆盖本地较新的修改
+        b.execute(
+            "UPDATE history SET note = 'newer', updatedAt = '2030-01-03T00:00:00.000Z' WHERE id = '1'",
+            [],
+        );
+        a.execute(
+            "UPDATE history SET note = 'older', updatedAt = '2030-01-02T00:00:00.000Z' WHERE id = '1'",
+            [],
+        );
+
+        a.sync(&backend);
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (1, 0, 1));
+        assert_eq!(b.text("1", "note").as_deref(), Some("newer"));
+
+        a.sync(&backend);
+        assert_eq!(a.text("1", "note").as_deref(), Some("newer"));
+    }
+
+    #[test]
+    fn propagates_deletions() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        fs::write(a.dir.join("images/1.png"), b"png").unwrap();
+        a.insert("1", "image", "1.png", "2024-01-01 00:00:01");
+        a.insert("2", "text", "hello", "2024-01-01 00:00:02");
+        a.sync(&backend);
+        b.sync(&backend);
+
+        assert!(b.dir.join("images/1.png").exists());
+
+        a.delete("1", "2030-01-01T00:00:00.000Z");
+
+        assert_eq!(a.sync(&backend).pushed, 1);
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled), (0, 1));
+        assert!(b.row("1").is_none());
+        assert!(b.row("2").is_some());
+        assert!(!b.dir.join("images/1.png").exists());
+
+        // 从其它设备合并的删除不会再上传，之前的修改也不会恢复删除的记录
+        assert_eq!(b.sync(&backend).pushed, 0);
+
+        fs::remove_file(b.dir.join("sync.json")).unwrap();
+        b.sync(&backend);
+        assert!(b.row("1").is_none());
+    }
+
+    #[test]
+    fn keeps_rows_modified_after_deletion() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.sync(&backend);
+        b.sync(&backend);
+
+        a.delete("1", "2030-01-01T00:00:00.000Z");
+        b.execute(
+            "UPDATE history SET favorite = 1, updatedAt = '2030-01-02T00:00:00.000Z' WHERE id = '1'",
+            [],
+        );
+
+        a.sync(&backend);
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pushed, report.pulled, report.skipped), (1, 0, 1));
+        assert!(b.row("1").is_some());
+
+        // 删除之后的修改在删除的设备上恢复记录
+        assert_eq!(a.sync(&backend).pulled, 1);
+        assert_eq!(a.row("1").unwrap().get("favorite"), Some(&1.into()));
+    }
+
+    #[test]
+    fn pulls_legacy_deltas() {
+        let backend = MemoryBackend::default();
+        let b = Device::new("bbbb");
+
+        // 旧版本上传的文件没有修改时间和删除的记录
+        let delta = serde_json::json!({
+            "deviceId": "aaaa",
+            "createdAt": 1,
+            "entries": [{
+                "entry": { "id": "1", "type": "text", "value": "hello", "createTime": "2024-01-01 00:00:01" },
+                "image": null
+            }]
+        });
+        let data = Cipher::new(PASSPHRASE)
+            .unwrap()
+            .encrypt(&serde_json::to_vec(&delta).unwrap())
+            .unwrap();
+
+        backend
+            .put("deltas/aaaa-0000000000001-0000.delta", data)
+            .unwrap();
+
+        assert_eq!(b.sync(&backend).pulled, 1);
+        assert!(b
+            .text("1", UPDATED_AT)
+            .is_some_and(|time| time.ends_with("Z")));
+    }
+
+    #[test]
+    fn persists_cursors_between_runs() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.sync(&backend);
+
+        assert_eq!(b.sync(&backend).pulled, 1);
+
+        let state: SyncState =
+            serde_json::from_slice(&fs::read(b.dir.join("sync.json")).unwrap()).unwrap();
+        assert_eq!(state.target, "memory");
+        assert_eq!(state.cursors.get("aaaa"), backend.names().first());
+
+        // 新的引擎从文件中读取进度，不会重新下载已处理的文件
+        let report = b.sync(&backend);
+        assert_eq!((report.pulled, report.skipped), (0, 0));
+
+        // 进度丢失后重新下载，相同的记录被跳过
+        fs::remove_file(b.dir.join("sync.json")).unwrap();
+
+        let report = b.sync(&backend);
+        assert_eq!((report.pulled, report.skipped), (0, 1));
+    }
+
+    #[test]
+    fn resets_cursors_when_target_changes() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.sync(&backend);
+        b.sync(&backend);
+
+        let engine = RemoteEngine::new(
+            Box::new(backend.clone()),
+            Cipher::new(PASSPHRASE).unwrap(),
+            "bbbb",
+            "other",
+            b.dir.join("history.db"),
+            b.dir.join("images"),
+            Some(b.dir.join("sync.json")),
+        );
+
+        assert_eq!(engine.sync().unwrap().skipped, 1);
+    }
+
+    #[test]
+    fn fails_with_wrong_passphrase() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.sync(&backend);
+
+        assert!(check(&backend, &Cipher::new(PASSPHRASE).unwrap()).is_ok());
+        assert!(matches!(
+            check(&backend, &Cipher::new("other").unwrap()),
+            Err(SyncError::Crypto(_))
+        ));
+
+        assert!(matches!(
+            b.engine(backend.clone(), "other").sync(),
+            Err(SyncError::Crypto(_))
+        ));
+        assert!(b.row("1").is_none());
+
+        // 失败的文件没有记入进度，密码改正后仍然会合并
+        assert_eq!(b.sync(&backend).pulled, 1);
+    }
+
+    #[test]
+    fn does_not_push_secrets() {
+        let backend = MemoryBackend::default();
+        let a = Device::new("aaaa");
+
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        Connection::open(a.dir.join("history.db"))
+            .unwrap()
+            .execute("UPDATE history SET secret = 'token' WHERE id = '1'", [])
+            .unwrap();
+
+        assert_eq!(a.sync(&backend).pushed, 0);
+        assert!(backend.names().is_empty());
+    }
+
+    // 在两个相互独立的客户端之间同步，客户端共享同一个存储服务
+    fn sync_through(backend: impl Fn() -> Box<dyn RemoteBackend>) {
+        let a = Device::new("aaaa");
+        let b = Device::new("bbbb");
+
+        let engine = |device: &Device| {
+            RemoteEngine::new(
+                backend(),
+                Cipher::new(PASSPHRASE).unwrap(),
+                &device.id,
+                "server",
+                device.dir.join("history.db"),
+                device.dir.join("images"),
+                Some(device.dir.join("sync.json")),
+            )
+        };
+
+        assert!(check(backend().as_ref(), &Cipher::new(PASSPHRASE).unwrap()).is_ok());
+
+        fs::write(a.dir.join("images/1.png"), b"png").unwrap();
+        a.insert("1", "text", "hello", "2024-01-01 00:00:01");
+        a.insert("2", "image", "1.png", "2024-01-01 00:00:02");
+
+        assert_eq!(engine(&a).sync().unwrap().pushed, 2);
+        assert_eq!(engine(&b).sync().unwrap().pulled, 2);
+        assert_eq!(b.text("1", "value").as_deref(), Some("hello"));
+        assert_eq!(fs::read(b.dir.join("images/1.png")).unwrap(), b"png");
+
+        assert!(check(backend().as_ref(), &Cipher::new(PASSPHRASE).unwrap()).is_ok());
+        assert!(check(backend().as_ref(), &Cipher::new("other").unwrap()).is_err());
+    }
+
+    // 每次运行使用新的目录，不受上次运行留下的文件影响
+    fn run_dir() -> String {
+        format!("eco-remote-test-{}", now())
+    }
+
+    // dufs -A -p 5000 <目录>，或通过 ECO_SYNC_TEST_WEBDAV 指定其它服务
+    #[test]
+    #[ignore = "需要本地运行 dufs"]
+    fn syncs_through_webdav() {
+        let url = std::env::var("ECO_SYNC_TEST_WEBDAV")
+            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string());
+        let url = format!("{}/{}", url.trim_end_matches('/'), run_dir());
+
+        sync_through(|| Box::new(WebDavBackend::new(&url, "", "").unwrap()));
+    }
+
+    // minio server <目录>，需要先创建 ecopaste 存储桶，或通过 ECO_SYNC_TEST_S3 指定其它服务
+    #[test]
+    #[ignore = "需要本地运行 MinIO"]
+    fn syncs_through_s3() {
+        let endpoint = std::env::var("ECO_SYNC_TEST_S3")
+            .unwrap_or_else(|_| "http://127.0.0.1:9000".to_string());
+        let prefix = run_dir();
+
+        sync_through(|| {
+            Box::new(
+                S3Backend::new(
+                    &endpoint,
+                    "us-east-1",
+                    "ecopaste",
+                    &prefix,
+                    "minioadmin",
+                    "minioadmin",
+                    true,
+                )
+                .unwrap(),
+            )
+        });
+    }
+}
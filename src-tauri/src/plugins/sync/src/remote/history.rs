@@ -0,0 +1,267 @@
+//! 直接读写前端创建的历史记录数据库
+//!
+//! 表结构由前端维护，这里按列名读写，不认识的列原样保留，旧版本的数据库缺少的列会被忽略。
+//!
+//! 合并依据 `updatedAt` 列，它是记录最后一次修改的 UTC 时间（ISO 8601，精确到毫秒），
+//! 不同时区的设备之间也可以直接按字符串比较。用户删除的记录保存在 `history_tombstone` 表中，
+//! 同步到其它设备后一起删除。
+
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Row};
+use serde_json::{Map, Number, Value};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::SyncError;
+
+// 前端也在写入数据库，等待对方的事务结束
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 历史记录表中的一行，键为列名
+pub type HistoryRow = Map<String, Value>;
+
+/// 记录最后修改时间的列
+pub const UPDATED_AT: &str = "updatedAt";
+
+// 与前端的 `toISOString` 格式一致
+const UTC_FORMAT: &str = "%Y-%m-%dT%H:%M:%fZ";
+
+pub struct HistoryDatabase {
+    connection: Connection,
+    columns: Vec<String>,
+}
+
+impl HistoryDatabase {
+    /// 打开已存在的数据库，数据库由前端创建
+    pub fn open(path: &Path) -> Result<Self, SyncError> {
+        let connection = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+
+        let columns = connection
+            .prepare("SELECT name FROM pragma_table_info('history')")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if columns.is_empty() {
+            return Err(SyncError::Database("history table not found".into()));
+        }
+
+        if !columns.iter().any(|column| column == UPDATED_AT) {
+            return Err(SyncError::Database(
+                "history table has no updatedAt column".into(),
+            ));
+        }
+
+        // 前端也会创建，这里保证同步早于前端升级数据库时也能记录删除
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS history_tombstone (id TEXT PRIMARY KEY, deletedAt TEXT)",
+            [],
+        )?;
+
+        Ok(Self {
+            connection,
+            columns,
+        })
+    }
+
+    /// 为没有修改时间的记录补上修改时间，例如旧版本保存或者导入的记录，按创建时间计算
+    pub fn fill_updated_at(&self) -> Result<usize, SyncError> {
+        // 创建时间是本地时间，由 SQLite 按系统时区转换为 UTC 时间
+        let count = self.connection.execute(
+            "UPDATE history SET updatedAt = COALESCE(strftime(?1, createTime, 'utc'), strftime(?1, 'now'))
+             WHERE updatedAt IS NULL",
+            params![UTC_FORMAT],
+        )?;
+
+        Ok(count)
+    }
+
+    /// 按创建时间计算修改时间，用于旧版本上传的没有修改时间的记录
+    pub fn legacy_updated_at(&self, create_time: &str) -> Result<Option<String>, SyncError> {
+        let time = self.connection.query_row(
+            "SELECT strftime(?1, ?2, 'utc')",
+            params![UTC_FORMAT, create_time],
+            |row| row.get(0),
+        )?;
+
+        Ok(time)
+    }
+
+    /// 修改时间不早于 `since` 的记录，按修改时间排序，不包含识别为密钥的记录
+    pub fn changed_since(&self, since: Option<&str>) -> Result<Vec<HistoryRow>, SyncError> {
+        let secret_filter = if self.has_column("secret") {
+            "secret IS NULL"
+        } else {
+            "1"
+        };
+
+        let sql = format!(
+            "SELECT * FROM history WHERE {} AND updatedAt IS NOT NULL AND (?1 IS NULL OR updatedAt >= ?1) ORDER BY updatedAt",
+            secret_filter
+        );
+
+        let mut statement = self.connection.prepare(&sql)?;
+
+        let rows = statement
+            .query_map(params![since], to_map)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// 删除时间不早于 `since` 的删除记录，返回 ID 和删除时间，按删除时间排序
+    pub fn deleted_since(&self, since: Option<&str>) -> Result<Vec<(String, String)>, SyncError> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, deletedAt FROM history_tombstone WHERE ?1 IS NULL OR deletedAt >= ?1 ORDER BY deletedAt",
+        )?;
+
+        let rows = statement
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// 记录的删除时间，没有删除过时返回 None
+    pub fn deleted_at(&self, id: &str) -> Result<Option<String>, SyncError> {
+        let time = self
+            .connection
+            .query_row(
+                "SELECT deletedAt FROM history_tombstone WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(time)
+    }
+
+    pub fn find(&self, id: &str) -> Result<Option<HistoryRow>, SyncError> {
+        let row = self
+            .connection
+            .query_row("SELECT * FROM history WHERE id = ?1", params![id], to_map)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// 查找内容相同的记录，返回 ID 和修改时间
+    pub fn find_by_content(
+        &self,
+        kind: &str,
+        value: &str,
+    ) -> Result<Option<(String, String)>, SyncError> {
+        let row = self
+            .connection
+            .query_row(
+                "SELECT id, updatedAt FROM history WHERE type = ?1 AND value = ?2",
+                params![kind, value],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    ))
+                },
+            )
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// 插入或替换整行
+    pub fn upsert(&self, row: &HistoryRow) -> Result<(), SyncError> {
+        let (columns, values): (Vec<&String>, Vec<SqlValue>) = row
+            .iter()
+            .filter(|(column, _)| self.has_column(column))
+            .map(|(column, value)| (column, to_sql(value)))
+            .unzip();
+
+        if columns.is_empty() {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "INSERT OR REPLACE INTO history ({}) VALUES ({})",
+            columns
+                .iter()
+                .map(|column| format!("\"{}\"", column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+
+        self.connection
+            .execute(&sql, rusqlite::params_from_iter(values))?;
+
+        Ok(())
+    }
+
+    /// 更新记录的创建时间和修改时间
+    pub fn touch(&self, id: &str, create_time: &str, updated_at: &str) -> Result<(), SyncError> {
+        self.connection.execute(
+            "UPDATE history SET createTime = ?1, updatedAt = ?2 WHERE id = ?3",
+            params![create_time, updated_at, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// 删除记录并记下删除时间，已有更晚的删除时间时保留，返回被删除的行
+    pub fn delete(&self, id: &str, deleted_at: &str) -> Result<Option<HistoryRow>, SyncError> {
+        let row = self.find(id)?;
+
+        self.connection
+            .execute("DELETE FROM history WHERE id = ?1", params![id])?;
+
+        self.connection.execute(
+            "INSERT INTO history_tombstone (id, deletedAt) VALUES (?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET deletedAt = MAX(deletedAt, excluded.deletedAt)",
+            params![id, deleted_at],
+        )?;
+
+        Ok(row)
+    }
+
+    fn has_column(&self, name: &str) -> bool {
+        self.columns.iter().any(|column| column == name)
+    }
+}
+
+/// 把查询到的一行转换为 JSON，文本和二进制都读取为字符串
+pub fn to_map(row: &Row<'_>) -> rusqlite::Result<HistoryRow> {
+    let mut map = Map::new();
+
+    for (index, name) in row.as_ref().column_names().iter().enumerate() {
+        let value = match row.get_ref(index)? {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(value) => Value::from(value),
+            ValueRef::Real(value) => Number::from_f64(value).map_or(Value::Null, Value::Number),
+            ValueRef::Text(value) | ValueRef::Blob(value) => {
+                Value::String(String::from_utf8_lossy(value).into_owned())
+            }
+        };
+
+        map.insert(name.to_string(), value);
+    }
+
+    Ok(map)
+}
+
+/// 把 JSON 的值转换为写入数据库的值，布尔值写为整数
+pub fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(value) => SqlValue::Integer(i64::from(*value)),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => SqlValue::Integer(value),
+            None => SqlValue::Real(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => SqlValue::Text(value.clone()),
+        // 数组和对象按 JSON 保存，与前端保存文件列表的方式一致
+        value => SqlValue::Text(value.to_string()),
+    }
+}
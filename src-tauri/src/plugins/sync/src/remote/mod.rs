@@ -0,0 +1,105 @@
+//! 通过自建的 WebDAV 或 S3 兼容存储同步
+//!
+//! 每台设备定时把新的历史记录加密后作为一个增量文件上传到 `deltas/` 目录，
+//! 再下载其它设备上传的增量文件合并到本地数据库，相同 ID 的记录保留修改时间较新的一条，
+//! 用户删除的记录也会在其它设备上删除。
+//! 存储服务只能看到文件名中的设备 ID 和上传时间，看不到记录的内容。
+
+mod cipher;
+mod engine;
+pub mod history;
+mod s3;
+mod scheduler;
+mod webdav;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SyncError;
+
+pub use cipher::Cipher;
+pub use engine::{check, RemoteEngine, SyncReport};
+pub use history::HistoryDatabase;
+pub use s3::{sign_v4, CanonicalRequest, S3Backend};
+pub use scheduler::{RemoteStatus, RemoteSync};
+pub use webdav::WebDavBackend;
+
+/// 同步密码在密钥存储中的名称，用于派生加密增量文件的密钥
+pub const PASSPHRASE_SECRET_NAME: &str = "sync.remote.passphrase";
+
+/// WebDAV 密码或 S3 Secret Access Key 在密钥存储中的名称
+pub const CREDENTIAL_SECRET_NAME: &str = "sync.remote.credential";
+
+/// 对象存储，路径使用 `/` 分隔，相对于配置的根目录
+pub trait RemoteBackend: Send + Sync {
+    /// 列出目录下的文件名，目录不存在时为空
+    fn list(&self, dir: &str) -> Result<Vec<String>, SyncError>;
+
+    /// 读取文件，文件不存在时返回 None
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>, SyncError>;
+
+    /// 写入文件，需要时创建上级目录
+    fn put(&self, path: &str, data: Vec<u8>) -> Result<(), SyncError>;
+}
+
+/// 存储服务的配置，密码从密钥存储中读取
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RemoteConfig {
+    /// WebDAV，`url` 是保存同步数据的目录，例如 `http://127.0.0.1:5000/ecopaste`
+    #[serde(rename_all = "camelCase")]
+    Webdav { url: String, username: String },
+    /// S3 兼容存储，MinIO 等自建服务通常需要开启 `path_style`
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        #[serde(default)]
+        path_style: bool,
+    },
+}
+
+impl RemoteConfig {
+    /// 创建存储服务的客户端，`credential` 是 WebDAV 密码或 S3 Secret Access Key
+    pub fn backend(&self, credential: Option<String>) -> Result<Box<dyn RemoteBackend>, SyncError> {
+        let credential = credential.unwrap_or_default();
+
+        Ok(match self {
+            Self::Webdav { url, username } => {
+                Box::new(WebDavBackend::new(url, username, &credential)?)
+            }
+            Self::S3 {
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                access_key_id,
+                path_style,
+            } => Box::new(S3Backend::new(
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                access_key_id,
+                &credential,
+                *path_style,
+            )?),
+        })
+    }
+
+    /// 标识同步的位置，位置变化后重新下载所有增量文件
+    pub fn target(&self) -> String {
+        match self {
+            Self::Webdav { url, username } => format!("webdav:{}@{}", username, url),
+            Self::S3 {
+                endpoint,
+                bucket,
+                prefix,
+                ..
+            } => format!("s3:{}/{}/{}", endpoint, bucket, prefix),
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! S3 兼容存储，使用 AWS Signature Version 4 签名，兼容 MinIO、Cloudflare R2 和各家对象存储
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::blocking::{Client, Response};
+use reqwest::{Method, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use super::RemoteBackend;
+use crate::error::SyncError;
+
+// SigV4 只保留 `A-Z a-z 0-9 - _ . ~`
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 需要签名的请求
+pub struct CanonicalRequest<'a> {
+    pub method: &'a str,
+    /// 已经编码的路径
+    pub path: &'a str,
+    /// 未编码的查询参数
+    pub query: &'a [(String, String)],
+    /// 名称为小写的请求头，需要包含 `host`
+    pub headers: &'a [(String, String)],
+    /// 请求体的 SHA-256
+    pub payload_hash: &'a str,
+}
+
+impl CanonicalRequest<'_> {
+    /// 按 SigV4 的要求排序和编码的查询字符串
+    pub fn query_string(&self) -> String {
+        let mut pairs: Vec<(String, String)> = self
+            .query
+            .iter()
+            .map(|(key, value)| (encode(key), encode(value)))
+            .collect();
+
+        pairs.sort();
+
+        pairs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// 计算 SigV4 签名，返回 `Authorization` 请求头的值
+///
+/// `amz_date` 与请求头 `x-amz-date` 一致，格式为 `20130524T000000Z`
+pub fn sign_v4(
+    request: &CanonicalRequest<'_>,
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    amz_date: &str,
+) -> String {
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    headers.sort();
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method,
+        request.path,
+        request.query_string(),
+        canonical_headers,
+        signed_headers,
+        request.payload_hash
+    );
+
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [date, region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", secret_access_key).into_bytes(),
+        |key, part| hmac(&key, part.as_bytes()),
+    );
+
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
+
+pub struct S3Backend {
+    client: Client,
+    scheme: String,
+    host: String,
+    // 路径风格时为 `/bucket`，虚拟主机风格时为空
+    base_path: String,
+    prefix: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3Backend {
+    pub fn new(
+        endpoint: &str,
+        region: &str,
+        bucket: &str,
+        prefix: &str,
+        access_key_id: &str,
+        secret_access_key: &str,
+        path_style: bool,
+    ) -> Result<Self, SyncError> {
+        let url = Url::parse(endpoint.trim())
+            .map_err(|e| SyncError::Remote(format!("invalid S3 endpoint: {}", e)))?;
+
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(SyncError::Remote("invalid S3 endpoint".into())),
+        };
+
+        let (host, base_path) = if path_style {
+            (host, format!("/{}", encode(bucket)))
+        } else {
+            (format!("{}.{}", bucket, host), String::new())
+        };
+
+        Ok(Self {
+            client: Client::builder().timeout(TIMEOUT).build()?,
+            scheme: url.scheme().to_string(),
+            host,
+            base_path,
+            prefix: prefix.trim_matches('/').to_string(),
+            region: if region.is_empty() {
+                "us-east-1".to_string()
+            } else {
+                region.to_string()
+            },
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+        })
+    }
+
+    fn key(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+
+        if self.prefix.is_empty() {
+            return path.to_string();
+        }
+
+        format!("{}/{}", self.prefix, path)
+    }
+
+    fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(String, String)],
+        body: Vec<u8>,
+    ) -> Result<Response, SyncError> {
+        let path = if key.is_empty() {
+            format!("{}/", self.base_path)
+        } else {
+            let key = key.split('/').map(encode).collect::<Vec<_>>().join("/");
+
+            format!("{}/{}", self.base_path, key)
+        };
+
+        let payload_hash = hex(&Sha256::digest(&body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let headers = vec![
+            ("host".to_string(), self.host.clone()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), amz_date.clone()),
+        ];
+
+        let request = CanonicalRequest {
+            method: method.as_str(),
+            path: &path,
+            query,
+            headers: &headers,
+            payload_hash: &payload_hash,
+        };
+
+        let authorization = sign_v4(
+            &request,
+            &self.access_key_id,
+            &self.secret_access_key,
+            &self.region,
+            &amz_date,
+        );
+
+        let mut url = format!("{}://{}{}", self.scheme, self.host, path);
+        let query = request.query_string();
+
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+
+        let response = self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization)
+            .body(body)
+            .send()?;
+
+        Ok(response)
+    }
+}
+
+impl RemoteBackend for S3Backend {
+    fn list(&self, dir: &str) -> Result<Vec<String>, SyncError> {
+        let prefix = format!("{}/", self.key(dir));
+        let mut names = Vec::new();
+        let mut token: Option<String> = None;
+
+        loop {
+            let mut query = vec![
+                ("list-type".to_string(), "2".to_string()),
+                ("prefix".to_string(), prefix.clone()),
+            ];
+
+            if let Some(token) = token.take() {
+                query.push(("continuation-token".to_string(), token));
+            }
+
+            let response = self.send(Method::GET, "", &query, Vec::new())?;
+
+            if !response.status().is_success() {
+                return Err(status_error("ListObjectsV2", response));
+            }
+
+            let (keys, next) = parse_list(&response.text()?);
+
+            // 只保留目录下直接包含的文件
+            names.extend(keys.into_iter().filter_map(|key| {
+                key.strip_prefix(&prefix)
+                    .filter(|name| !name.is_empty() && !name.contains('/'))
+                    .map(str::to_string)
+            }));
+
+            match next {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(names)
+    }
+
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>, SyncError> {
+        let response = self.send(Method::GET, &self.key(path), &[], Vec::new())?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("GetObject", response));
+        }
+
+        Ok(Some(response.bytes()?.to_vec()))
+    }
+
+    fn put(&self, path: &str, data: Vec<u8>) -> Result<(), SyncError> {
+        let response = self.send(Method::PUT, &self.key(path), &[], data)?;
+
+        if !response.status().is_success() {
+            return Err(status_error("PutObject", response));
+        }
+
+        Ok(())
+    }
+}
+
+fn status_error(action: &str, response: Response) -> SyncError {
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+
+    // 错误信息在 XML 的 Message 元素中
+    let message = text_of(&body, b"Message").unwrap_or_default();
+
+    SyncError::Remote(format!("S3 {} returned {} {}", action, status, message))
+}
+
+// 返回对象的键和下一页的 continuation token
+fn parse_list(body: &str) -> (Vec<String>, Option<String>) {
+    let mut reader = Reader::from_str(body);
+    let mut keys = Vec::new();
+    let mut next = None;
+    let mut truncated = false;
+    let mut current: Option<Vec<u8>> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => current = Some(element.local_name().as_ref().to_vec()),
+            Ok(Event::Text(text)) => {
+                let Some(name) = current.as_deref() else {
+                    continue;
+                };
+
+                let Ok(text) = text.unescape() else {
+                    continue;
+                };
+
+                match name {
+                    b"Key" => keys.push(text.to_string()),
+                    b"NextContinuationToken" => next = Some(text.to_string()),
+                    b"IsTruncated" => truncated = text.trim() == "true",
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => current = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    (keys, next.filter(|_| truncated))
+}
+
+fn text_of(body: &str, name: &[u8]) -> Option<String> {
+    let mut reader = Reader::from_str(body);
+    let mut inside = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => inside = element.local_name().as_ref() == name,
+            Ok(Event::Text(text)) if inside => return text.unescape().ok().map(|t| t.to_string()),
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
+    }
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, UNRESERVED).to_string()
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,148 @@
+//! 定时同步
+//!
+//! 同步在单独的线程中按间隔执行，手动同步也交给这个线程，保证同一时间只有一次同步。
+
+use serde::Serialize;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::engine::{RemoteEngine, SyncReport};
+use crate::error::SyncError;
+
+/// 定时同步的状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub syncing: bool,
+    pub last_report: Option<SyncReport>,
+    pub last_error: Option<String>,
+}
+
+type Reply = Sender<Result<SyncReport, String>>;
+
+enum Command {
+    SyncNow(Reply),
+    Stop,
+}
+
+struct Worker {
+    sender: Sender<Command>,
+    handle: JoinHandle<()>,
+}
+
+/// 定时同步，同一时间只运行一个同步线程
+#[derive(Default)]
+pub struct RemoteSync {
+    worker: Mutex<Option<Worker>>,
+    status: Arc<Mutex<RemoteStatus>>,
+}
+
+impl RemoteSync {
+    /// 启动同步线程，立即同步一次，之后每隔 `interval` 同步一次，已经启动时先停止
+    ///
+    /// 每次同步结束后调用 `on_finish`
+    pub fn start(
+        &self,
+        engine: RemoteEngine,
+        interval: Duration,
+        on_finish: impl Fn(&Result<SyncReport, String>) + Send + 'static,
+    ) {
+        self.stop();
+
+        let (sender, receiver) = mpsc::channel::<Command>();
+        let status = self.status.clone();
+
+        lock(&status).running = true;
+
+        let handle = thread::spawn(move || {
+            let mut reply: Option<Reply> = None;
+
+            loop {
+                lock(&status).syncing = true;
+
+                let result = engine.sync().map_err(|e| e.to_string());
+
+                {
+                    let mut status = lock(&status);
+
+                    status.syncing = false;
+
+                    match &result {
+                        Ok(report) => {
+                            status.last_report = Some(report.clone());
+                            status.last_error = None;
+                        }
+                        Err(e) => {
+                            log::error!("Remote sync failed: {}", e);
+
+                            status.last_error = Some(e.clone());
+                        }
+                    }
+                }
+
+                on_finish(&result);
+
+                if let Some(reply) = reply.take() {
+                    let _ = reply.send(result);
+                }
+
+                match receiver.recv_timeout(interval) {
+                    Ok(Command::SyncNow(sender)) => reply = Some(sender),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(Command::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            lock(&status).running = false;
+        });
+
+        *self.worker() = Some(Worker { sender, handle });
+    }
+
+    /// 立即同步一次并等待结果
+    pub fn sync_now(&self) -> Result<SyncReport, String> {
+        let (sender, receiver) = mpsc::channel();
+
+        {
+            let worker = self.worker();
+
+            let Some(worker) = worker.as_ref() else {
+                return Err(SyncError::NotRunning.to_string());
+            };
+
+            worker
+                .sender
+                .send(Command::SyncNow(sender))
+                .map_err(|_| SyncError::NotRunning.to_string())?;
+        }
+
+        receiver
+            .recv()
+            .map_err(|_| SyncError::NotRunning.to_string())?
+    }
+
+    /// 停止同步线程，正在进行的同步会先完成
+    pub fn stop(&self) {
+        let Some(worker) = self.worker().take() else {
+            return;
+        };
+
+        let _ = worker.sender.send(Command::Stop);
+        let _ = worker.handle.join();
+    }
+
+    pub fn status(&self) -> RemoteStatus {
+        lock(&self.status).clone()
+    }
+
+    fn worker(&self) -> MutexGuard<'_, Option<Worker>> {
+        self.worker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn lock(status: &Mutex<RemoteStatus>) -> MutexGuard<'_, RemoteStatus> {
+    status.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,243 @@
+//! WebDAV 存储，只用到 PROPFIND、GET、PUT 和 MKCOL，兼容 dufs、Nextcloud 和坚果云等服务
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode, Url};
+use std::time::Duration;
+
+use super::RemoteBackend;
+use crate::error::SyncError;
+
+// 路径中需要编码的字符，保留 `/`
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><propfind xmlns="DAV:"><prop><resourcetype/></prop></propfind>"#;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct WebDavBackend {
+    client: Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+impl WebDavBackend {
+    pub fn new(url: &str, username: &str, password: &str) -> Result<Self, SyncError> {
+        let base_url = url.trim().trim_end_matches('/').to_string();
+
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(SyncError::Remote(format!("invalid WebDAV url: {}", url)));
+        }
+
+        Ok(Self {
+            client: Client::builder().timeout(TIMEOUT).build()?,
+            base_url,
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+
+        if path.is_empty() {
+            return format!("{}/", self.base_url);
+        }
+
+        format!("{}/{}", self.base_url, utf8_percent_encode(path, PATH))
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.client.request(method, url);
+
+        if self.username.is_empty() {
+            return request;
+        }
+
+        request.basic_auth(&self.username, Some(&self.password))
+    }
+
+    // 从服务器的根目录开始逐级创建目录，已存在时服务会返回 405
+    fn create_dirs(&self, path: &str) -> Result<(), SyncError> {
+        let url = Url::parse(&self.base_url)
+            .map_err(|e| SyncError::Remote(format!("invalid WebDAV url: {}", e)))?;
+
+        let base: Vec<String> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|dir| !dir.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let dirs = path
+            .split('/')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| utf8_percent_encode(dir, PATH).to_string());
+
+        let mut current = url.origin().ascii_serialization();
+
+        for (index, dir) in base.iter().cloned().chain(dirs).enumerate() {
+            current = format!("{}/{}", current, dir);
+
+            let response = self
+                .request(
+                    Method::from_bytes(b"MKCOL").expect("valid method"),
+                    &format!("{}/", current),
+                )
+                .send()?;
+
+            let status = response.status();
+
+            // 配置的目录之上的目录可能没有权限，只要之后的目录能创建成功即可
+            if status.is_success() || status == StatusCode::METHOD_NOT_ALLOWED || index < base.len()
+            {
+                continue;
+            }
+
+            return Err(status_error("MKCOL", response));
+        }
+
+        Ok(())
+    }
+}
+
+impl RemoteBackend for WebDavBackend {
+    fn list(&self, dir: &str) -> Result<Vec<String>, SyncError> {
+        let url = format!("{}/", self.url(dir).trim_end_matches('/'));
+
+        let response = self
+            .request(Method::from_bytes(b"PROPFIND").expect("valid method"), &url)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY)
+            .send()?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("PROPFIND", response));
+        }
+
+        let body = response.text()?;
+
+        Ok(parse_names(&body))
+    }
+
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>, SyncError> {
+        let response = self.request(Method::GET, &self.url(path)).send()?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("GET", response));
+        }
+
+        Ok(Some(response.bytes()?.to_vec()))
+    }
+
+    fn put(&self, path: &str, data: Vec<u8>) -> Result<(), SyncError> {
+        let send = || {
+            self.request(Method::PUT, &self.url(path))
+                .body(data.clone())
+                .send()
+        };
+
+        let mut response = send()?;
+
+        // 上级目录不存在时部分服务返回 409，创建后重试
+        if matches!(
+            response.status(),
+            StatusCode::CONFLICT | StatusCode::NOT_FOUND
+        ) {
+            if let Some((dir, _)) = path.rsplit_once('/') {
+                self.create_dirs(dir)?;
+
+                response = send()?;
+            }
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("PUT", response));
+        }
+
+        Ok(())
+    }
+}
+
+fn status_error(method: &str, response: Response) -> SyncError {
+    SyncError::Remote(format!(
+        "WebDAV {} {} returned {}",
+        method,
+        response.url().path(),
+        response.status()
+    ))
+}
+
+// 取出 PROPFIND 响应中每个文件的文件名，跳过目录本身和子目录
+fn parse_names(body: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(body);
+    let mut names = Vec::new();
+    let mut href: Option<String> = None;
+    let mut in_href = false;
+    let mut is_collection = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => match element.local_name().as_ref() {
+                b"response" => {
+                    href = None;
+                    is_collection = false;
+                }
+                b"href" => in_href = true,
+                b"collection" => is_collection = true,
+                _ => {}
+            },
+            Ok(Event::Empty(element)) if element.local_name().as_ref() == b"collection" => {
+                is_collection = true;
+            }
+            Ok(Event::Text(text)) if in_href => {
+                href = text.unescape().ok().map(|text| text.trim().to_string());
+            }
+            Ok(Event::End(element)) => match element.local_name().as_ref() {
+                b"href" => in_href = false,
+                b"response" => {
+                    let name = href
+                        .take()
+                        .filter(|href| !is_collection && !href.ends_with('/'))
+                        .and_then(|href| {
+                            let decoded = percent_decode_str(&href).decode_utf8().ok()?;
+
+                            decoded.rsplit('/').next().map(str::to_string)
+                        });
+
+                    if let Some(name) = name.filter(|name| !name.is_empty()) {
+                        names.push(name);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    names
+}
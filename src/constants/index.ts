@@ -31,7 +31,9 @@ export const LISTEN_KEY = {
   CLIPBOARD_ITEM_SELECT_PREV: "clipboard-item-select-prev",
   CLOSE_DATABASE: "close-database",
   REFRESH_CLIPBOARD_LIST: "refresh-clipboard-list",
+  REFRESH_SNIPPET_LIST: "refresh-snippet-list",
   SHOW_WINDOW: "show-window",
+  SNIPPET_ITEM_PASTE: "snippet-item-paste",
   STORE_CHANGED: "store-changed",
   TOGGLE_LISTEN_CLIPBOARD: "toggle-listen-clipboard",
   UPDATE_APP: "update-app",
@@ -44,3 +46,9 @@ export const PRESET_SHORTCUT = {
   OPEN_PREFERENCES: isMac ? "meta.comma" : "ctrl.comma",
   SEARCH: isMac ? "meta.f" : "ctrl.f",
 };
+
+export const SECRET_NAME = {
+  AI_API_KEY: "selection-assistant.api-key",
+  SYNC_CREDENTIAL: "sync.remote.credential",
+  SYNC_PASSPHRASE: "sync.remote.passphrase",
+};
@@ -22,10 +22,19 @@ export const selectHistory = async (
   return qb.execute() as Promise<DatabaseSchemaHistory[]>;
 };
 
+// 修改时间使用 UTC 时间，不同时区的设备之间可以直接比较
+const now = () => new Date().toISOString();
+
+/**
+ * 插入记录，其它设备同步的记录保留原来的修改时间
+ */
 export const insertHistory = async (data: DatabaseSchemaHistory) => {
   const db = await getDatabase();
 
-  return db.insertInto("history").values(data).execute();
+  return db
+    .insertInto("history")
+    .values({ ...data, updatedAt: data.updatedAt ?? now() })
+    .execute();
 };
 
 export const updateHistory = async (
@@ -34,16 +43,36 @@ export const updateHistory = async (
 ) => {
   const db = await getDatabase();
 
-  return db.updateTable("history").set(nextData).where("id", "=", id).execute();
+  return db
+    .updateTable("history")
+    .set({ ...nextData, updatedAt: now() })
+    .where("id", "=", id)
+    .execute();
 };
 
-export const deleteHistory = async (data: DatabaseSchemaHistory) => {
+/**
+ * 删除记录和图片文件
+ * @param data 删除的记录
+ * @param tombstone 是否记下删除，远程同步时在其它设备上也删除；按保留时长自动清理时各设备分别处理，不需要记下
+ */
+export const deleteHistory = async (
+  data: DatabaseSchemaHistory,
+  tombstone = true,
+) => {
   const { id, type, value } = data;
 
   const db = await getDatabase();
 
   await db.deleteFrom("history").where("id", "=", id).execute();
 
+  if (tombstone) {
+    await db
+      .insertInto("history_tombstone")
+      .values({ deletedAt: now(), id })
+      .onConflict((oc) => oc.column("id").doUpdateSet({ deletedAt: now() }))
+      .execute();
+  }
+
   if (type !== "image") return;
 
   let path = value;
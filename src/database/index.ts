@@ -1,13 +1,115 @@
 import Database from "@tauri-apps/plugin-sql";
-import { isBoolean } from "es-toolkit";
-import { Kysely } from "kysely";
+import { chunk, isBoolean } from "es-toolkit";
+import { type ColumnDataType, Kysely, sql } from "kysely";
 import { TauriSqliteDialect } from "kysely-dialect-tauri";
 import { SerializePlugin } from "kysely-plugin-serialize";
+import { classifyTexts, sanitizeHtml } from "@/plugins/clipboard";
 import type { DatabaseSchema } from "@/types/database";
 import { getSaveDatabasePath } from "@/utils/path";
 
 let db: Kysely<DatabaseSchema> | null = null;
 
+/**
+ * 之后版本新增的列，旧版本创建的表需要补上
+ */
+const HISTORY_COLUMNS: [string, ColumnDataType][] = [
+  ["render", "text"],
+  ["language", "text"],
+  ["secret", "text"],
+  ["keepSecret", "integer"],
+  ["updatedAt", "text"],
+];
+
+const addMissingColumns = async (
+  db: Kysely<DatabaseSchema>,
+  table: keyof DatabaseSchema,
+  columns: [string, ColumnDataType][],
+) => {
+  const { rows } = await sql<{ name: string }>`
+    PRAGMA table_info(${sql.table(table)})
+  `.execute(db);
+
+  for (const [name, type] of columns) {
+    if (rows.some((row) => row.name === name)) continue;
+
+    await db.schema.alterTable(table).addColumn(name, type).execute();
+  }
+};
+
+// 类型识别移到 Rust 之后支持了更多类型、代码高亮和密钥识别，重新识别旧版本保存的文本。
+// 旧记录中识别出的密钥只隐藏内容，不按密钥的保留时长删除，避免升级后用户的记录突然消失
+const reclassifyHistory = async (db: Kysely<DatabaseSchema>) => {
+  const list = await db
+    .selectFrom("history")
+    .select(["id", "value"])
+    .where("type", "=", "text")
+    .execute();
+
+  for (const items of chunk(list, 200)) {
+    const results = await classifyTexts(items.map(({ value }) => value));
+
+    for (const [index, result] of results.entries()) {
+      const { subtype, language, render, secret } = result;
+
+      if (!subtype && !secret) continue;
+
+      await db
+        .updateTable("history")
+        .set({
+          keepSecret: secret ? true : undefined,
+          language: language ?? undefined,
+          render: render ?? undefined,
+          secret: secret ?? undefined,
+          subtype: subtype ?? undefined,
+        })
+        .where("id", "=", items[index].id)
+        .execute();
+    }
+  }
+};
+
+/**
+ * 按顺序执行的数据迁移，已完成的数量保存在数据库的 user_version 中，
+ * 中途退出时未完成的迁移会在下次启动时重新执行，所以每个迁移都要能重复执行
+ */
+const MIGRATIONS = [reclassifyHistory];
+
+const migrateData = async (db: Kysely<DatabaseSchema>) => {
+  const { rows } = await sql<{ user_version: number }>`
+    PRAGMA user_version
+  `.execute(db);
+
+  const version = rows[0]?.user_version ?? 0;
+
+  for (const [index, migrate] of MIGRATIONS.entries()) {
+    if (index < version) continue;
+
+    await migrate(db);
+
+    await sql`PRAGMA user_version = ${sql.lit(index + 1)}`.execute(db);
+  }
+};
+
+// 旧版本保存以及导入、同步得到的 HTML 没有清理后的副本，补上之后才能安全渲染
+const backfillHtmlRender = async (db: Kysely<DatabaseSchema>) => {
+  const list = await db
+    .selectFrom("history")
+    .select(["id", "value"])
+    .where("type", "=", "html")
+    .where("render", "is", null)
+    .execute();
+
+  for (const { id, value } of list) {
+    const render = await sanitizeHtml(value);
+
+    await db
+      .updateTable("history")
+      .set({ render })
+      .where("id", "=", id)
+      .execute();
+  }
+};
+
 export const getDatabase = async () => {
   if (db) return db;
 
@@ -46,13 +148,61 @@ export const getDatabase = async () => {
     .addColumn("createTime", "text")
     .addColumn("note", "text")
     .addColumn("subtype", "text")
+    .addColumn("render", "text")
+    .addColumn("language", "text")
+    .addColumn("secret", "text")
+    .addColumn("keepSecret", "integer")
+    .addColumn("updatedAt", "text")
     .execute();
 
+  // 用户删除的记录，远程同步时在其它设备上也删除
+  await db.schema
+    .createTable("history_tombstone")
+    .ifNotExists()
+    .addColumn("id", "text", (col) => col.primaryKey())
+    .addColumn("deletedAt", "text")
+    .execute();
+
+  // 片段和历史记录分开保存，不会被自动清理
+  await db.schema
+    .createTable("snippet_folder")
+    .ifNotExists()
+    .addColumn("id", "text", (col) => col.primaryKey())
+    .addColumn("name", "text")
+    .addColumn("createTime", "text")
+    .execute();
+
+  await db.schema
+    .createTable("snippet")
+    .ifNotExists()
+    .addColumn("id", "text", (col) => col.primaryKey())
+    .addColumn("folderId", "text")
+    .addColumn("name", "text")
+    .addColumn("value", "text")
+    .addColumn("abbreviation", "text")
+    .addColumn("shortcut", "text")
+    .addColumn("createTime", "text")
+    .execute();
+
+  await addMissingColumns(db, "history", HISTORY_COLUMNS);
+
+  migrateData(db).catch((error) => {
+    console.error("数据迁移失败:", error);
+  });
+
+  // 只处理还没有副本的记录，中途退出后下次启动会继续
+  backfillHtmlRender(db).catch((error) => {
+    console.error("补全 HTML 渲染内容失败:", error);
+  });
+
   return db;
 };
 
 export const destroyDatabase = async () => {
-  const db = await getDatabase();
+  if (!db) return;
+
+  await db.destroy();
 
-  return db.destroy();
+  // 存储路径可能已经变更，下次使用时重新打开
+  db = null;
 };
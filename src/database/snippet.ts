@@ -0,0 +1,79 @@
+import type {
+  DatabaseSchemaSnippet,
+  DatabaseSchemaSnippetFolder,
+} from "@/types/database";
+import { getDatabase } from ".";
+
+export const selectSnippets = async () => {
+  const db = await getDatabase();
+
+  return db
+    .selectFrom("snippet")
+    .selectAll()
+    .orderBy("name")
+    .execute() as Promise<DatabaseSchemaSnippet[]>;
+};
+
+export const insertSnippet = async (data: DatabaseSchemaSnippet) => {
+  const db = await getDatabase();
+
+  return db.insertInto("snippet").values(data).execute();
+};
+
+export const updateSnippet = async (
+  id: string,
+  nextData: Partial<DatabaseSchemaSnippet>,
+) => {
+  const db = await getDatabase();
+
+  return db.updateTable("snippet").set(nextData).where("id", "=", id).execute();
+};
+
+export const deleteSnippet = async (id: string) => {
+  const db = await getDatabase();
+
+  return db.deleteFrom("snippet").where("id", "=", id).execute();
+};
+
+export const selectSnippetFolders = async () => {
+  const db = await getDatabase();
+
+  return db
+    .selectFrom("snippet_folder")
+    .selectAll()
+    .orderBy("name")
+    .execute() as Promise<DatabaseSchemaSnippetFolder[]>;
+};
+
+export const insertSnippetFolder = async (
+  data: DatabaseSchemaSnippetFolder,
+) => {
+  const db = await getDatabase();
+
+  return db.insertInto("snippet_folder").values(data).execute();
+};
+
+export const updateSnippetFolder = async (id: string, name: string) => {
+  const db = await getDatabase();
+
+  return db
+    .updateTable("snippet_folder")
+    .set({ name })
+    .where("id", "=", id)
+    .execute();
+};
+
+/**
+ * 删除文件夹，其中的片段移出文件夹，不会被删除
+ */
+export const deleteSnippetFolder = async (id: string) => {
+  const db = await getDatabase();
+
+  await db
+    .updateTable("snippet")
+    .set({ folderId: "" })
+    .where("folderId", "=", id)
+    .execute();
+
+  return db.deleteFrom("snippet_folder").where("id", "=", id).execute();
+};
@@ -0,0 +1,20 @@
+import { globalStore } from "@/stores/global";
+import { restartAutoBackup } from "@/utils/backup";
+import { useImmediate } from "./useImmediate";
+import { useSubscribeKey } from "./useSubscribeKey";
+
+export const useBackup = () => {
+  const toggleAutoBackup = async () => {
+    try {
+      await restartAutoBackup();
+    } catch (error) {
+      console.error("自动备份启动失败:", error);
+    }
+  };
+
+  // 监听自动备份的设置变更
+  useImmediate(globalStore.backup.auto, toggleAutoBackup);
+
+  // 存储路径变更后备份到新的目录
+  useSubscribeKey(globalStore.env, "saveDataDir", toggleAutoBackup);
+};
@@ -14,11 +14,33 @@ import {
   updateHistory,
 } from "@/database/history";
 import type { State } from "@/pages/Main";
-import { getClipboardTextSubtype } from "@/plugins/clipboard";
+import {
+  classifyTexts,
+  convertContent,
+  sanitizeHtml,
+} from "@/plugins/clipboard";
+import { sendSyncEntry } from "@/plugins/sync";
 import { clipboardStore } from "@/stores/clipboard";
+import { globalStore } from "@/stores/global";
 import type { DatabaseSchemaHistory } from "@/types/database";
+import type { ContentFormat } from "@/types/plugin";
 import { formatDate } from "@/utils/dayjs";
 
+// 富文本的搜索内容由原文转换得到，保留列表、链接和表格中的文字，转换失败时使用剪贴板中的纯文本
+const getRichTextSearch = async (
+  value: string,
+  format: ContentFormat,
+  fallback = "",
+) => {
+  try {
+    const search = await convertContent(value, format, "text");
+
+    return search || fallback;
+  } catch {
+    return fallback;
+  }
+};
+
 export const useClipboard = (
   state: State,
   options?: ClipboardChangeOptions,
@@ -47,14 +69,24 @@ export const useClipboard = (
           search: files.value.join(" "),
         });
       } else if (html && !copyPlain) {
-        Object.assign(data, html);
+        Object.assign(data, html, {
+          render: await sanitizeHtml(html.value),
+          search: await getRichTextSearch(html.value, "html", text?.value),
+        });
       } else if (rtf && !copyPlain) {
-        Object.assign(data, rtf);
+        Object.assign(data, rtf, {
+          search: await getRichTextSearch(rtf.value, "rtf", text?.value),
+        });
       } else if (text) {
-        const subtype = await getClipboardTextSubtype(text.value);
+        const [{ subtype, language, render, secret }] = await classifyTexts([
+          text.value,
+        ]);
 
         Object.assign(data, text, {
-          subtype,
+          language: language ?? undefined,
+          render: render ?? undefined,
+          secret: secret ?? undefined,
+          subtype: subtype ?? undefined,
         });
       } else if (image) {
         Object.assign(data, image, {
@@ -100,7 +132,12 @@ export const useClipboard = (
         state.list.unshift(data);
       }
 
-      insertHistory(sqlData);
+      await insertHistory(sqlData);
+
+      // 包含密钥的记录只保留在本机
+      if (!globalStore.sync.lan.enable || sqlData.secret) return;
+
+      sendSyncEntry(sqlData, type === "image" ? value : undefined);
     }, options);
   });
 };
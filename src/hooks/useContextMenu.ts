@@ -1,7 +1,13 @@
-import { Menu, MenuItem, type MenuItemOptions } from "@tauri-apps/api/menu";
-import { downloadDir } from "@tauri-apps/api/path";
-import { copyFile, writeTextFile } from "@tauri-apps/plugin-fs";
+import {
+  Menu,
+  MenuItem,
+  type MenuItemOptions,
+  Submenu,
+} from "@tauri-apps/api/menu";
+import { downloadDir, homeDir } from "@tauri-apps/api/path";
+import { copyFile, exists, writeTextFile } from "@tauri-apps/plugin-fs";
 import { openUrl, revealItemInDir } from "@tauri-apps/plugin-opener";
+import { message } from "antd";
 import { find, isArray, remove } from "es-toolkit/compat";
 import { type MouseEvent, useContext } from "react";
 import { useTranslation } from "react-i18next";
@@ -9,7 +15,12 @@ import { useSnapshot } from "valtio";
 import { deleteHistory, updateHistory } from "@/database/history";
 import { MainContext } from "@/pages/Main";
 import type { ItemProps } from "@/pages/Main/components/HistoryList/components/Item";
-import { pasteToClipboard, writeToClipboard } from "@/plugins/clipboard";
+import {
+  pasteAsMarkdown,
+  pasteToClipboard,
+  writeToClipboard,
+} from "@/plugins/clipboard";
+import { PASTE_TRANSFORMS } from "@/plugins/paste";
 import { clipboardStore } from "@/stores/clipboard";
 import { globalStore } from "@/stores/global";
 import { isMac } from "@/utils/is";
@@ -77,9 +88,18 @@ export const useContextMenu = (props: UseContextMenuProps) => {
     revealItemInDir(path);
   };
 
-  const openToFinder = () => {
+  const openToFinder = async () => {
     if (type === "text") {
-      return revealItemInDir(value);
+      // 识别路径时不访问文件系统，打开前才检查是否存在
+      const path = value.startsWith("~/")
+        ? join(await homeDir(), value.slice(2))
+        : value;
+
+      if (!(await exists(path))) {
+        return message.warning(t("clipboard.hints.path_not_found"));
+      }
+
+      return revealItemInDir(path);
     }
 
     const [file] = value;
@@ -135,6 +155,11 @@ export const useContextMenu = (props: UseContextMenuProps) => {
         hide: type !== "html" && type !== "rtf",
         text: t("clipboard.button.context_menu.paste_as_plain_text"),
       },
+      {
+        action: () => pasteAsMarkdown(data),
+        hide: type !== "html" && type !== "rtf",
+        text: t("clipboard.button.context_menu.paste_as_markdown"),
+      },
       {
         action: pasteAsText,
         hide: type !== "files",
@@ -187,6 +212,19 @@ export const useContextMenu = (props: UseContextMenuProps) => {
       await menu.append(menuItem);
     }
 
+    // 文本内容可以转换后粘贴
+    if (group === "text") {
+      const submenu = await Submenu.new({
+        items: PASTE_TRANSFORMS.map((transform) => ({
+          action: () => pasteToClipboard(data, true, [transform]),
+          text: t(`clipboard.paste_transform.${transform}`),
+        })),
+        text: t("clipboard.button.context_menu.paste_as"),
+      });
+
+      await menu.insert(submenu, 2);
+    }
+
     menu.popup();
   };
 
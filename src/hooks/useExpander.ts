@@ -0,0 +1,59 @@
+import { useUpdateEffect } from "ahooks";
+import { find } from "es-toolkit/compat";
+import { LISTEN_KEY } from "@/constants";
+import type { State } from "@/pages/Main";
+import {
+  EXPANDER_EVENT,
+  type Expansion,
+  setExpanderConfig,
+  startExpander,
+  stopExpander,
+} from "@/plugins/expander";
+import { globalStore } from "@/stores/global";
+import { useImmediate } from "./useImmediate";
+import { useTauriListen } from "./useTauriListen";
+
+export const useExpander = (state: State) => {
+  const updateConfig = () => {
+    const abbreviations = state.snippets.flatMap((item) => {
+      return item.abbreviation ? [item.abbreviation] : [];
+    });
+
+    return setExpanderConfig({
+      abbreviations,
+      excludedApps: globalStore.expander.excludedApps,
+    });
+  };
+
+  // 监听展开缩写的设置变更
+  useImmediate(globalStore.expander, async () => {
+    try {
+      await updateConfig();
+
+      if (globalStore.expander.enable) {
+        await startExpander();
+      } else {
+        await stopExpander();
+      }
+    } catch (error) {
+      console.error("缩写展开启动失败:", error);
+    }
+  });
+
+  // 片段变更后更新需要展开的缩写
+  useUpdateEffect(() => {
+    updateConfig();
+  }, [state.snippets]);
+
+  // 缩写已经删除，粘贴对应的片段
+  useTauriListen<Expansion>(EXPANDER_EVENT.EXPAND, ({ payload }) => {
+    const data = find(state.snippets, { abbreviation: payload.abbreviation });
+
+    if (!data) return;
+
+    state.eventBus?.emit({
+      action: LISTEN_KEY.SNIPPET_ITEM_PASTE,
+      id: data.id,
+    });
+  });
+};
@@ -0,0 +1,21 @@
+import { useMount } from "ahooks";
+import { checkDatabase } from "@/plugins/backup";
+import { runDatabaseMaintenance } from "@/utils/backup";
+import { getSaveDatabasePath } from "@/utils/path";
+
+export const useMaintenance = () => {
+  // 启动时检查数据库，损坏时用还能读取的记录重建
+  useMount(async () => {
+    try {
+      const problems = await checkDatabase(await getSaveDatabasePath());
+
+      if (problems.length === 0) return;
+
+      console.error("数据库已损坏:", problems);
+
+      await runDatabaseMaintenance();
+    } catch (error) {
+      console.error("数据库检查失败:", error);
+    }
+  });
+};
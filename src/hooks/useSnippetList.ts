@@ -0,0 +1,25 @@
+import { useMount } from "ahooks";
+import { LISTEN_KEY } from "@/constants";
+import { selectSnippetFolders, selectSnippets } from "@/database/snippet";
+import type { State } from "@/pages/Main";
+import { useTauriListen } from "./useTauriListen";
+
+export const useSnippetList = (state: State) => {
+  const reload = async () => {
+    const [snippets, folders] = await Promise.all([
+      selectSnippets(),
+      selectSnippetFolders(),
+    ]);
+
+    state.snippets = snippets;
+    state.snippetFolders = folders;
+  };
+
+  useMount(reload);
+
+  // 偏好设置中修改了片段
+  useTauriListen(LISTEN_KEY.REFRESH_SNIPPET_LIST, reload);
+
+  // 恢复备份或修复数据库后重新读取
+  useTauriListen(LISTEN_KEY.REFRESH_CLIPBOARD_LIST, reload);
+};
@@ -0,0 +1,81 @@
+import { emit } from "@tauri-apps/api/event";
+import { debounce } from "es-toolkit";
+import { getDefaultSaveImagePath } from "tauri-plugin-clipboard-x-api";
+import { LISTEN_KEY } from "@/constants";
+import { insertHistory, selectHistory } from "@/database/history";
+import {
+  type RemoteSyncStatus,
+  SYNC_EVENT,
+  type SyncEntryPayload,
+  startSync,
+  stopSync,
+} from "@/plugins/sync";
+import { globalStore } from "@/stores/global";
+import { restartRemoteSync } from "@/utils/sync";
+import { useImmediate } from "./useImmediate";
+import { useImmediateKey } from "./useImmediateKey";
+import { useSubscribeKey } from "./useSubscribeKey";
+import { useTauriListen } from "./useTauriListen";
+
+export const useSync = () => {
+  const toggleSync = async () => {
+    const { enable, port } = globalStore.sync.lan;
+
+    try {
+      await stopSync();
+
+      if (!enable) return;
+
+      await startSync(await getDefaultSaveImagePath(), port);
+    } catch (error) {
+      console.error("局域网同步启动失败:", error);
+    }
+  };
+
+  // 监听局域网同步的启用状态变更
+  useImmediateKey(globalStore.sync.lan, "enable", toggleSync);
+
+  // 端口变更后重新启动
+  useSubscribeKey(globalStore.sync.lan, "port", toggleSync);
+
+  const toggleRemoteSync = debounce(async () => {
+    try {
+      await restartRemoteSync();
+    } catch (error) {
+      console.error("远程同步启动失败:", error);
+    }
+  }, 1000);
+
+  // 远程同步的设置变更后重新启动，输入地址时等待输入完成
+  useImmediate(globalStore.sync.remote, toggleRemoteSync);
+
+  // 存储路径变更后同步新的数据库
+  useSubscribeKey(globalStore.env, "saveDataDir", toggleRemoteSync);
+
+  // 远程同步直接写入数据库，合并了新的记录时刷新列表
+  useTauriListen<RemoteSyncStatus>(SYNC_EVENT.REMOTE, ({ payload }) => {
+    if (payload.lastError || !payload.lastReport?.pulled) return;
+
+    emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+  });
+
+  // 保存其它设备同步的记录，已存在相同的记录时跳过
+  useTauriListen<SyncEntryPayload>(SYNC_EVENT.ENTRY, async ({ payload }) => {
+    const { entry } = payload;
+
+    const [matched] = await selectHistory((qb) => {
+      return qb.where((eb) => {
+        return eb.or([
+          eb("id", "=", entry.id),
+          eb.and([eb("type", "=", entry.type), eb("value", "=", entry.value)]),
+        ]);
+      });
+    });
+
+    if (matched) return;
+
+    await insertHistory(entry);
+
+    emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+  });
+};
@@ -37,6 +37,10 @@ const GroupList = () => {
       id: "favorite",
       name: t("clipboard.label.tab.favorite"),
     },
+    {
+      id: "snippet",
+      name: t("clipboard.label.tab.snippet"),
+    },
   ];
 
   useKeyPress("tab", (event) => {
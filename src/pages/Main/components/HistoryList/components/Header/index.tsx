@@ -24,7 +24,8 @@ interface HeaderProps {
 
 const Header: FC<HeaderProps> = (props) => {
   const { data } = props;
-  const { id, type, value, count, createTime, favorite, subtype } = data;
+  const { id, type, value, count, createTime, favorite, subtype, secret } =
+    data;
   const { rootState } = useContext(MainContext);
   const { t, i18n } = useTranslation();
   const { content } = useSnapshot(clipboardStore);
@@ -36,6 +37,10 @@ const Header: FC<HeaderProps> = (props) => {
   }, [content.operationButtons]);
 
   const renderType = () => {
+    if (secret) {
+      return t("clipboard.label.secret");
+    }
+
     switch (subtype) {
       case "url":
         return t("clipboard.label.link");
@@ -45,6 +50,24 @@ const Header: FC<HeaderProps> = (props) => {
         return t("clipboard.label.color");
       case "path":
         return t("clipboard.label.path");
+      case "phone":
+        return t("clipboard.label.phone");
+      case "ip":
+        return t("clipboard.label.ip");
+      case "json":
+        return t("clipboard.label.json");
+      case "code":
+        return t("clipboard.label.code");
+      case "uuid":
+        return t("clipboard.label.uuid");
+      case "jwt":
+        return t("clipboard.label.jwt");
+      case "card":
+        return t("clipboard.label.card");
+      case "timestamp":
+        return t("clipboard.label.timestamp");
+      case "command":
+        return t("clipboard.label.command");
     }
 
     switch (type) {
@@ -28,7 +28,7 @@ export interface ItemProps {
 
 const Item: FC<ItemProps> = (props) => {
   const { index, data, handleNote } = props;
-  const { id, type, note, value } = data;
+  const { id, type, note, value, render, search } = data;
   const { rootState } = useContext(MainContext);
   const { content } = useSnapshot(clipboardStore);
 
@@ -93,7 +93,12 @@ const Item: FC<ItemProps> = (props) => {
       case "rtf":
         return <Rtf {...data} />;
       case "html":
-        return <SafeHtml {...data} />;
+        // 还没有清理后的副本时只显示纯文本，原始 HTML 可能加载远程资源
+        if (!render) {
+          return <Marker mark={rootState.search}>{search}</Marker>;
+        }
+
+        return <SafeHtml value={render} />;
       case "image":
         return <Image {...data} />;
       case "files":
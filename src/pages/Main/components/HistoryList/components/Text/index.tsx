@@ -1,12 +1,37 @@
 import { Flex } from "antd";
 import clsx from "clsx";
+import { upperFirst } from "es-toolkit";
 import { type CSSProperties, type FC, useContext } from "react";
 import { Marker } from "react-mark.js";
 import { MainContext } from "@/pages/Main";
 import type { DatabaseSchemaHistory } from "@/types/database";
 
+// 识别出的语言的显示名称，没有列出的首字母大写
+const LANGUAGE_NAMES: Record<string, string> = {
+  cpp: "C++",
+  csharp: "C#",
+  css: "CSS",
+  html: "HTML",
+  javascript: "JavaScript",
+  php: "PHP",
+  sql: "SQL",
+  typescript: "TypeScript",
+  xml: "XML",
+  yaml: "YAML",
+};
+
+// 隐藏密钥，单行的长内容保留开头和结尾的几个字符用于区分
+const maskSecret = (value: string) => {
+  const text = value.trim();
+  const mask = "•".repeat(12);
+
+  if (text.includes("\n") || text.length < 16) return mask;
+
+  return text.slice(0, 4) + mask + text.slice(-4);
+};
+
 const Text: FC<DatabaseSchemaHistory<"text">> = (props) => {
-  const { value, subtype } = props;
+  const { value, subtype, language, render, secret } = props;
   const { rootState } = useContext(MainContext);
 
   const renderMarker = () => {
@@ -35,11 +60,39 @@ const Text: FC<DatabaseSchemaHistory<"text">> = (props) => {
     );
   };
 
+  const renderCode = (html: string) => {
+    return (
+      <>
+        {language && (
+          <span className="absolute top-0 right-0 z-1 rounded bg-color-3 px-1 text-color-2 text-xs">
+            {LANGUAGE_NAMES[language] ?? upperFirst(language)}
+          </span>
+        )}
+
+        <Marker mark={rootState.search}>
+          <pre
+            className="m-0 whitespace-pre-wrap break-all font-mono"
+            dangerouslySetInnerHTML={{ __html: html }}
+          />
+        </Marker>
+      </>
+    );
+  };
+
   const renderContent = () => {
+    if (secret) {
+      return <span className="font-mono">{maskSecret(value)}</span>;
+    }
+
     if (subtype === "color") {
       return renderColor();
     }
 
+    // 高亮后的 HTML 由 Rust 生成，只包含转义后的文本和带 class 的 span
+    if (render && (subtype === "code" || subtype === "json")) {
+      return renderCode(render);
+    }
+
     return renderMarker();
   };
 
@@ -0,0 +1,84 @@
+import { useBoolean } from "ahooks";
+import { Form, Input, type InputRef, Modal } from "antd";
+import { t } from "i18next";
+import { forwardRef, useImperativeHandle, useRef, useState } from "react";
+import { pasteSnippet } from "@/plugins/clipboard";
+import type { DatabaseSchemaSnippet } from "@/types/database";
+
+export interface SnippetInputModalRef {
+  open: (data: DatabaseSchemaSnippet, names: string[]) => void;
+}
+
+type FormFields = Record<string, string | undefined>;
+
+const SnippetInputModal = forwardRef<SnippetInputModalRef>((_, ref) => {
+  const [open, { toggle }] = useBoolean();
+  const [item, setItem] = useState<DatabaseSchemaSnippet>();
+  const [names, setNames] = useState<string[]>([]);
+  const [form] = Form.useForm<FormFields>();
+  const inputRef = useRef<InputRef>(null);
+
+  useImperativeHandle(ref, () => ({
+    open: (data, names) => {
+      form.resetFields();
+
+      setItem(data);
+      setNames(names);
+
+      toggle();
+    },
+  }));
+
+  const handleOk = async () => {
+    const values = form.getFieldsValue();
+
+    toggle();
+
+    if (!item) return;
+
+    const inputs: Record<string, string> = {};
+
+    for (const name of names) {
+      inputs[name] = values[name] ?? "";
+    }
+
+    pasteSnippet(item.value, inputs);
+  };
+
+  const handleAfterOpenChange = (open: boolean) => {
+    if (!open) return;
+
+    inputRef.current?.focus();
+  };
+
+  return (
+    <Modal
+      afterOpenChange={handleAfterOpenChange}
+      centered
+      forceRender
+      onCancel={toggle}
+      onOk={handleOk}
+      open={open}
+      title={item?.name}
+    >
+      <Form form={form} layout="vertical" onFinish={handleOk}>
+        {names.map((name, index) => (
+          <Form.Item key={name} label={name} name={name}>
+            <Input
+              autoComplete="off"
+              placeholder={t("component.snippet_input_modal.hints.input", {
+                name,
+              })}
+              ref={index === 0 ? inputRef : void 0}
+            />
+          </Form.Item>
+        ))}
+
+        {/* 没有提交按钮时回车不会提交表单 */}
+        <button hidden type="submit" />
+      </Form>
+    </Modal>
+  );
+});
+
+export default SnippetInputModal;
@@ -0,0 +1,137 @@
+import { useKeyPress } from "ahooks";
+import { Empty, Flex, Tag } from "antd";
+import clsx from "clsx";
+import { find, findIndex } from "es-toolkit/compat";
+import { useContext, useEffect, useMemo, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Marker } from "react-mark.js";
+import { useSnapshot } from "valtio";
+import Scrollbar from "@/components/Scrollbar";
+import { LISTEN_KEY } from "@/constants";
+import { clipboardStore } from "@/stores/clipboard";
+import { scrollElementToCenter } from "@/utils/dom";
+import { isBlank } from "@/utils/is";
+import { MainContext } from "../..";
+
+const SnippetList = () => {
+  const { rootState } = useContext(MainContext);
+  const { content } = useSnapshot(clipboardStore);
+  const [activeId, setActiveId] = useState<string>();
+  const { t } = useTranslation();
+
+  // 按名称、缩写和内容搜索
+  const list = useMemo(() => {
+    const { snippets, search } = rootState;
+
+    if (isBlank(search)) return snippets;
+
+    const keyword = search!.toLowerCase();
+
+    return snippets.filter(({ name, abbreviation, value }) => {
+      return [name, abbreviation, value].some((item) => {
+        return item?.toLowerCase().includes(keyword);
+      });
+    });
+  }, [rootState.snippets, rootState.search]);
+
+  useEffect(() => {
+    if (find(list, { id: activeId })) return;
+
+    setActiveId(list[0]?.id);
+  }, [list]);
+
+  useEffect(() => {
+    scrollElementToCenter(activeId);
+  }, [activeId]);
+
+  const handlePaste = (id: string) => {
+    rootState.eventBus?.emit({
+      action: LISTEN_KEY.SNIPPET_ITEM_PASTE,
+      id,
+    });
+  };
+
+  const handleClick = (id: string, type: typeof content.autoPaste) => {
+    setActiveId(id);
+
+    if (content.autoPaste !== type) return;
+
+    handlePaste(id);
+  };
+
+  useKeyPress(["uparrow", "downarrow", "enter"], (event, key) => {
+    event.preventDefault();
+
+    if (!activeId) return;
+
+    if (key === "enter") {
+      return handlePaste(activeId);
+    }
+
+    const index = findIndex(list, { id: activeId });
+    const nextIndex = key === "uparrow" ? index - 1 : index + 1;
+
+    if (nextIndex < 0 || nextIndex >= list.length) return;
+
+    setActiveId(list[nextIndex].id);
+  });
+
+  if (list.length === 0) {
+    return (
+      <Empty
+        className="flex-1"
+        description={t("clipboard.hints.empty_snippet")}
+        image={Empty.PRESENTED_IMAGE_SIMPLE}
+      />
+    );
+  }
+
+  return (
+    <Scrollbar className="flex-1" offsetX={3}>
+      <Flex gap={12} vertical>
+        {list.map((item) => {
+          const { id, name, value, folderId, abbreviation } = item;
+
+          const folder = find(rootState.snippetFolders, { id: folderId });
+
+          return (
+            <Flex
+              className={clsx(
+                "b hover:b-primary-5 b-color-2 mx-3 max-h-30 rounded-md p-1.5 transition",
+                {
+                  "b-primary bg-primary-1": activeId === id,
+                },
+              )}
+              gap={4}
+              id={id}
+              key={id}
+              onClick={() => handleClick(id, "single")}
+              onDoubleClick={() => handleClick(id, "double")}
+              vertical
+            >
+              <Flex align="center" gap={4}>
+                <span className="flex-1 truncate font-bold">
+                  <Marker mark={rootState.search}>{name}</Marker>
+                </span>
+
+                {folder && <Tag className="m-0!">{folder.name}</Tag>}
+
+                {abbreviation && (
+                  <Tag className="m-0! font-mono" color="blue">
+                    {abbreviation}
+                  </Tag>
+                )}
+              </Flex>
+
+              <div className="line-clamp-3 whitespace-pre-wrap break-all text-color-2">
+                <Marker mark={rootState.search}>{value}</Marker>
+              </div>
+            </Flex>
+          );
+        })}
+      </Flex>
+    </Scrollbar>
+  );
+};
+
+export default SnippetList;
@@ -0,0 +1,22 @@
+import { type FC, useContext } from "react";
+import { LISTEN_KEY } from "@/constants";
+import { useRegister } from "@/hooks/useRegister";
+import type { DatabaseSchemaSnippet } from "@/types/database";
+import { MainContext } from "../..";
+
+const SnippetShortcut: FC<{ data: DatabaseSchemaSnippet }> = (props) => {
+  const { id, shortcut } = props.data;
+  const { rootState } = useContext(MainContext);
+
+  // 每个片段单独注册全局快捷键
+  useRegister(() => {
+    rootState.eventBus?.emit({
+      action: LISTEN_KEY.SNIPPET_ITEM_PASTE,
+      id,
+    });
+  }, [shortcut]);
+
+  return null;
+};
+
+export default SnippetShortcut;
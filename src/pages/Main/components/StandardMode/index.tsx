@@ -1,16 +1,20 @@
 import { Flex } from "antd";
 import clsx from "clsx";
+import { useContext } from "react";
 import { useSnapshot } from "valtio";
 import UnoIcon from "@/components/UnoIcon";
 import { showWindow } from "@/plugins/window";
 import { clipboardStore } from "@/stores/clipboard";
 import { isLinux, isWin } from "@/utils/is";
+import { MainContext } from "../..";
 import GroupList from "../GroupList";
 import HistoryList from "../HistoryList";
 import SearchInput from "../SearchInput";
+import SnippetList from "../SnippetList";
 import WindowPin from "../WindowPin";
 
 const StandardMode = () => {
+  const { rootState } = useContext(MainContext);
   const { search } = useSnapshot(clipboardStore);
 
   return (
@@ -54,7 +58,7 @@ const StandardMode = () => {
           </Flex>
         </Flex>
 
-        <HistoryList />
+        {rootState.group === "snippet" ? <SnippetList /> : <HistoryList />}
       </Flex>
     </Flex>
   );
@@ -7,12 +7,22 @@ import { startListening, stopListening } from "tauri-plugin-clipboard-x-api";
 import { useSnapshot } from "valtio";
 import Audio, { type AudioRef } from "@/components/Audio";
 import { LISTEN_KEY, PRESET_SHORTCUT } from "@/constants";
+import { useBackup } from "@/hooks/useBackup";
 import { useClipboard } from "@/hooks/useClipboard";
+import { useExpander } from "@/hooks/useExpander";
 import { useImmediateKey } from "@/hooks/useImmediateKey";
+import { useMaintenance } from "@/hooks/useMaintenance";
 import { useRegister } from "@/hooks/useRegister";
+import { useSnippetList } from "@/hooks/useSnippetList";
 import { useSubscribeKey } from "@/hooks/useSubscribeKey";
+import { useSync } from "@/hooks/useSync";
 import { useTauriListen } from "@/hooks/useTauriListen";
-import { pasteToClipboard } from "@/plugins/clipboard";
+import {
+  getHighlightStyle,
+  pasteSnippet,
+  pasteToClipboard,
+} from "@/plugins/clipboard";
+import { snippetInputs } from "@/plugins/paste";
 import {
   showTaskbarIcon,
   showWindow,
@@ -23,10 +33,16 @@ import { globalStore } from "@/stores/global";
 import type {
   DatabaseSchemaGroupId,
   DatabaseSchemaHistory,
+  DatabaseSchemaSnippet,
+  DatabaseSchemaSnippetFolder,
 } from "@/types/database";
 import type { Store } from "@/types/store";
 import { deepAssign } from "@/utils/object";
 import DockMode from "./components/DockMode";
+import SnippetInputModal, {
+  type SnippetInputModalRef,
+} from "./components/SnippetInputModal";
+import SnippetShortcut from "./components/SnippetShortcut";
 import StandardMode from "./components/StandardMode";
 
 interface EventBusPayload {
@@ -42,12 +58,16 @@ export interface State {
   list: DatabaseSchemaHistory[];
   eventBus?: EventEmitter<EventBusPayload>;
   quickPasteKeys: string[];
+  snippets: DatabaseSchemaSnippet[];
+  snippetFolders: DatabaseSchemaSnippetFolder[];
 }
 
 const INITIAL_STATE: State = {
   group: "all",
   list: [],
   quickPasteKeys: [],
+  snippetFolders: [],
+  snippets: [],
 };
 
 interface MainContextValue {
@@ -64,9 +84,17 @@ const Main = () => {
   const { window } = useSnapshot(clipboardStore);
   const eventBus = useEventEmitter<EventBusPayload>();
   const audioRef = useRef<AudioRef>(null);
+  const snippetInputModalRef = useRef<SnippetInputModalRef>(null);
 
-  useMount(() => {
+  useMount(async () => {
     state.eventBus = eventBus;
+
+    // 代码高亮的颜色，高亮后的 HTML 只带 class
+    const style = document.createElement("style");
+
+    style.innerHTML = await getHighlightStyle();
+
+    document.head.appendChild(style);
   });
 
   useClipboard(state, {
@@ -77,6 +105,21 @@ const Main = () => {
     },
   });
 
+  // 片段和文件夹
+  useSnippetList(state);
+
+  // 输入缩写后展开片段
+  useExpander(state);
+
+  // 局域网和远程同步
+  useSync();
+
+  // 定时备份
+  useBackup();
+
+  // 检查和修复数据库
+  useMaintenance();
+
   // 任务栏图标的显示与隐藏
   useImmediateKey(globalStore.app, "showTaskbarIcon", showTaskbarIcon);
 
@@ -142,6 +185,15 @@ const Main = () => {
     pasteToClipboard(data, true);
   });
 
+  // 监听粘贴转换后内容的全局快捷键，没有选中的内容时粘贴最新一条
+  useRegister(() => {
+    const data = find(state.list, { id: state.activeId }) ?? state.list[0];
+
+    if (!data) return;
+
+    pasteToClipboard(data, true, clipboardStore.content.pasteTransforms);
+  }, [shortcut.pasteTransformed]);
+
   // 监听快速粘贴的快捷键
   useRegister(
     async (event) => {
@@ -156,6 +208,25 @@ const Main = () => {
     [state.quickPasteKeys],
   );
 
+  // 粘贴片段，需要输入内容时先显示窗口
+  eventBus.useSubscription(async (payload) => {
+    if (payload.action !== LISTEN_KEY.SNIPPET_ITEM_PASTE) return;
+
+    const data = find(state.snippets, { id: payload.id });
+
+    if (!data) return;
+
+    const names = await snippetInputs(data.value);
+
+    if (names.length === 0) {
+      return pasteSnippet(data.value);
+    }
+
+    showWindow();
+
+    snippetInputModalRef.current?.open(data, names);
+  });
+
   return (
     <MainContext.Provider
       value={{
@@ -164,6 +235,14 @@ const Main = () => {
     >
       <Audio ref={audioRef} />
 
+      <SnippetInputModal ref={snippetInputModalRef} />
+
+      {state.snippets.map((item) => {
+        if (!item.shortcut) return;
+
+        return <SnippetShortcut data={item} key={item.id} />;
+      })}
+
       {window.style === "standard" ? <StandardMode /> : <DockMode />}
     </MainContext.Provider>
   );
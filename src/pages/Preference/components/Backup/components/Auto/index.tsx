@@ -0,0 +1,158 @@
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
+import { useMount, useReactive } from "ahooks";
+import { Button, InputNumber, List, message, Select } from "antd";
+import { filesize } from "filesize";
+import type { FC } from "react";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import ProSwitch from "@/components/ProSwitch";
+import UnoIcon from "@/components/UnoIcon";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  BACKUP_EVENT,
+  type BackupFile,
+  type BackupReport,
+  backupNow,
+  listBackups,
+} from "@/plugins/backup";
+import { globalStore } from "@/stores/global";
+import { getBackupConfig } from "@/utils/backup";
+import { formatDate } from "@/utils/dayjs";
+import { saveStore } from "@/utils/store";
+import type { State } from "../..";
+
+const Auto: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const { backup } = useSnapshot(globalStore);
+  const { t } = useTranslation();
+  const files = useReactive<{ list: BackupFile[] }>({ list: [] });
+
+  const refresh = async () => {
+    try {
+      files.list = await listBackups(await getBackupConfig());
+    } catch (error) {
+      message.error(String(error));
+    }
+  };
+
+  useMount(refresh);
+
+  useTauriListen<BackupReport>(BACKUP_EVENT.FINISHED, ({ payload }) => {
+    if (payload.status === "failed") {
+      message.error(
+        t("preference.data_backup.auto_backup.hints.backup_failed", {
+          error: payload.error,
+        }),
+      );
+    }
+
+    refresh();
+  });
+
+  const handleBackupNow = async () => {
+    try {
+      state.spinning = true;
+
+      await saveStore(true);
+
+      await backupNow(await getBackupConfig());
+
+      message.success(
+        t("preference.data_backup.auto_backup.hints.backup_success"),
+      );
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.spinning = false;
+
+      refresh();
+    }
+  };
+
+  return (
+    <ProList header={t("preference.data_backup.auto_backup.title")}>
+      <ProSwitch
+        description={t("preference.data_backup.auto_backup.hints.enable")}
+        onChange={(value) => {
+          globalStore.backup.auto.enable = value;
+        }}
+        title={t("preference.data_backup.auto_backup.label.enable")}
+        value={backup.auto.enable}
+      />
+
+      <ProListItem
+        title={t("preference.data_backup.auto_backup.label.interval")}
+      >
+        <Select
+          className="w-30"
+          onChange={(value: typeof backup.auto.interval) => {
+            globalStore.backup.auto.interval = value;
+          }}
+          options={[
+            {
+              label: t("preference.data_backup.auto_backup.label.daily"),
+              value: "daily",
+            },
+            {
+              label: t("preference.data_backup.auto_backup.label.weekly"),
+              value: "weekly",
+            },
+          ]}
+          value={backup.auto.interval}
+        />
+      </ProListItem>
+
+      <ProListItem
+        description={t("preference.data_backup.auto_backup.hints.keep")}
+        title={t("preference.data_backup.auto_backup.label.keep")}
+      >
+        <InputNumber
+          className="w-30"
+          max={100}
+          min={1}
+          onChange={(value) => {
+            globalStore.backup.auto.keep = value ?? 7;
+          }}
+          value={backup.auto.keep}
+        />
+      </ProListItem>
+
+      <ProListItem
+        description={t("preference.data_backup.auto_backup.hints.backup_now")}
+        title={t("preference.data_backup.auto_backup.label.backup_now")}
+      >
+        <Button onClick={handleBackupNow}>
+          {t("preference.data_backup.auto_backup.button.backup_now")}
+        </Button>
+      </ProListItem>
+
+      {files.list.map((file) => {
+        const { name, path, size, createdAt } = file;
+
+        return (
+          <List.Item
+            actions={[
+              <Button
+                icon={<UnoIcon name="i-lucide:folder-open" />}
+                key="reveal"
+                onClick={() => revealItemInDir(path)}
+                title={t("preference.data_backup.auto_backup.button.reveal")}
+                type="text"
+              />,
+            ]}
+            key={name}
+          >
+            <List.Item.Meta
+              description={filesize(size, { standard: "jedec" })}
+              title={formatDate(createdAt)}
+            />
+          </List.Item>
+        );
+      })}
+    </ProList>
+  );
+};
+
+export default Auto;
@@ -0,0 +1,252 @@
+import { emit } from "@tauri-apps/api/event";
+import { downloadDir } from "@tauri-apps/api/path";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
+import { useBoolean } from "ahooks";
+import {
+  Button,
+  Checkbox,
+  DatePicker,
+  Drawer,
+  Form,
+  message,
+  Select,
+  Space,
+} from "antd";
+import type { Dayjs } from "dayjs";
+import type { FC } from "react";
+import { useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { getDefaultSaveImagePath } from "tauri-plugin-clipboard-x-api";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import { LISTEN_KEY } from "@/constants";
+import {
+  type ExportFormat,
+  type ExportImageMode,
+  exportHistory,
+  importHistory,
+} from "@/plugins/backup";
+import { dayjs, formatDate } from "@/utils/dayjs";
+import { getSaveDatabasePath, join } from "@/utils/path";
+import type { State } from "../..";
+
+const { RangePicker } = DatePicker;
+
+interface FormFields {
+  group: string;
+  range?: (Dayjs | null)[];
+  favorite: boolean;
+  format: ExportFormat;
+  images: ExportImageMode;
+}
+
+const History: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const [visible, { toggle }] = useBoolean();
+  const [exporting, { setTrue, setFalse }] = useBoolean();
+  const [form] = Form.useForm<FormFields>();
+  const { t } = useTranslation();
+
+  useEffect(form.resetFields, [visible]);
+
+  const handleExport = async () => {
+    try {
+      const { group, range, favorite, format, images } =
+        form.getFieldsValue();
+
+      const [start, end] = range ?? [];
+
+      const filename = formatDate(dayjs(), "YYYY_MM_DD_HH_mm_ss");
+
+      const path = await save({
+        defaultPath: join(await downloadDir(), `${filename}.${format}`),
+        filters: [{ extensions: [format], name: format.toUpperCase() }],
+      });
+
+      if (!path) return;
+
+      setTrue();
+
+      const { exported } = await exportHistory({
+        databasePath: await getSaveDatabasePath(),
+        filter: {
+          end: end ? formatDate(end) : void 0,
+          favorite,
+          group: group === "all" ? void 0 : group,
+          start: start ? formatDate(start) : void 0,
+        },
+        format,
+        images,
+        imagesDir: await getDefaultSaveImagePath(),
+        path,
+      });
+
+      toggle();
+
+      message.success(
+        t("preference.data_backup.history.hints.export_success", {
+          count: exported,
+        }),
+      );
+
+      await revealItemInDir(path);
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      setFalse();
+    }
+  };
+
+  const handleImport = async () => {
+    try {
+      const path = await open({
+        filters: [{ extensions: ["jsonl", "csv"], name: "" }],
+      });
+
+      if (!path) return;
+
+      state.spinning = true;
+
+      const { imported, skipped, invalid } = await importHistory({
+        databasePath: await getSaveDatabasePath(),
+        imagesDir: await getDefaultSaveImagePath(),
+        path,
+      });
+
+      emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+
+      message.success(
+        t("preference.data_backup.history.hints.import_success", {
+          imported,
+          invalid,
+          skipped,
+        }),
+      );
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.spinning = false;
+    }
+  };
+
+  return (
+    <ProList header={t("preference.data_backup.history.title")}>
+      <ProListItem
+        description={t("preference.data_backup.history.hints.export")}
+        title={t("preference.data_backup.history.label.export")}
+      >
+        <Button onClick={toggle}>
+          {t("preference.data_backup.history.button.export")}
+        </Button>
+      </ProListItem>
+
+      <ProListItem
+        description={t("preference.data_backup.history.hints.import")}
+        title={t("preference.data_backup.history.label.import")}
+      >
+        <Button onClick={handleImport}>
+          {t("preference.data_backup.history.button.import")}
+        </Button>
+      </ProListItem>
+
+      <Drawer
+        classNames={{
+          body: "py-4!",
+          footer: "flex justify-end",
+        }}
+        closable={false}
+        footer={
+          <Space>
+            <Button disabled={exporting} onClick={toggle}>
+              {t("preference.data_backup.history.button.cancel")}
+            </Button>
+
+            <Button loading={exporting} onClick={handleExport} type="primary">
+              {t("preference.data_backup.history.button.confirm_export")}
+            </Button>
+          </Space>
+        }
+        open={visible}
+        title={t("preference.data_backup.history.label.export")}
+        width="100%"
+      >
+        <Form
+          form={form}
+          initialValues={{
+            favorite: false,
+            format: "jsonl",
+            group: "all",
+            images: "embed",
+          }}
+          labelCol={{ span: 6 }}
+        >
+          <Form.Item
+            label={t("preference.data_backup.history.label.group")}
+            name="group"
+          >
+            <Select
+              options={["all", "text", "image", "files"].map((value) => ({
+                label: t(`clipboard.label.tab.${value}`),
+                value,
+              }))}
+            />
+          </Form.Item>
+
+          <Form.Item
+            label={t("preference.data_backup.history.label.range")}
+            name="range"
+          >
+            <RangePicker
+              allowEmpty={[true, true]}
+              disabledDate={(current) => current > dayjs().endOf("day")}
+              showTime
+            />
+          </Form.Item>
+
+          <Form.Item
+            label={t("preference.data_backup.history.label.format")}
+            name="format"
+          >
+            <Select
+              options={[
+                { label: "JSON Lines", value: "jsonl" },
+                { label: "CSV", value: "csv" },
+              ]}
+            />
+          </Form.Item>
+
+          <Form.Item
+            label={t("preference.data_backup.history.label.images")}
+            name="images"
+          >
+            <Select
+              options={[
+                {
+                  label: t("preference.data_backup.history.label.embed"),
+                  value: "embed",
+                },
+                {
+                  label: t("preference.data_backup.history.label.reference"),
+                  value: "reference",
+                },
+              ]}
+            />
+          </Form.Item>
+
+          <Form.Item
+            name="favorite"
+            valuePropName="checked"
+            wrapperCol={{ offset: 6 }}
+          >
+            <Checkbox>
+              {t("preference.data_backup.history.label.favorite")}
+            </Checkbox>
+          </Form.Item>
+        </Form>
+      </Drawer>
+    </ProList>
+  );
+};
+
+export default History;
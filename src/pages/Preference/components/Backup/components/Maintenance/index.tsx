@@ -0,0 +1,100 @@
+import { useMount } from "ahooks";
+import { Button, message } from "antd";
+import { filesize } from "filesize";
+import type { FC } from "react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { fullName } from "tauri-plugin-fs-pro-api";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  BACKUP_EVENT,
+  lastMaintenance,
+  type MaintainReport,
+} from "@/plugins/backup";
+import { runDatabaseMaintenance } from "@/utils/backup";
+import { formatDate } from "@/utils/dayjs";
+import type { State } from "../..";
+
+const Maintenance: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const [summary, setSummary] = useState("");
+  const { t } = useTranslation();
+
+  const describe = async (report: MaintainReport) => {
+    const { status, problems, salvaged, corruptPath, finishedAt } = report;
+
+    const time = formatDate(finishedAt);
+
+    if (status === "repaired") {
+      return t("preference.data_backup.maintenance.hints.repaired", {
+        name: corruptPath ? await fullName(corruptPath) : "",
+        problems: problems.length,
+        salvaged,
+        time,
+      });
+    }
+
+    return t("preference.data_backup.maintenance.hints.healthy", {
+      after: filesize(report.sizeAfter, { standard: "jedec" }),
+      before: filesize(report.sizeBefore, { standard: "jedec" }),
+      time,
+    });
+  };
+
+  const update = async (report: MaintainReport | null) => {
+    setSummary(report ? await describe(report) : "");
+  };
+
+  useMount(async () => {
+    try {
+      await update(await lastMaintenance());
+    } catch (error) {
+      message.error(String(error));
+    }
+  });
+
+  // 启动时自动修复的结果也在这里显示
+  useTauriListen<MaintainReport>(BACKUP_EVENT.MAINTAINED, ({ payload }) => {
+    update(payload);
+  });
+
+  const handleMaintain = async () => {
+    try {
+      state.spinning = true;
+
+      const report = await runDatabaseMaintenance();
+
+      if (report.status === "repaired") {
+        message.warning(await describe(report));
+      } else {
+        message.success(await describe(report));
+      }
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.spinning = false;
+    }
+  };
+
+  return (
+    <ProList header={t("preference.data_backup.maintenance.title")}>
+      <ProListItem
+        description={
+          <>
+            {t("preference.data_backup.maintenance.hints.maintain")}
+            {summary && <div>{summary}</div>}
+          </>
+        }
+        title={t("preference.data_backup.maintenance.label.maintain")}
+      >
+        <Button onClick={handleMaintain}>
+          {t("preference.data_backup.maintenance.button.maintain")}
+        </Button>
+      </ProListItem>
+    </ProList>
+  );
+};
+
+export default Maintenance;
@@ -0,0 +1,96 @@
+import { emit } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Button, message, Select } from "antd";
+import type { FC } from "react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { getDefaultSaveImagePath } from "tauri-plugin-clipboard-x-api";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import { LISTEN_KEY } from "@/constants";
+import { type MigrateSource, migrateHistory } from "@/plugins/backup";
+import { getSaveDatabasePath } from "@/utils/path";
+import type { State } from "../..";
+
+interface SourceInfo {
+  name: string;
+  extensions: string[];
+}
+
+const SOURCES: Record<MigrateSource, SourceInfo> = {
+  clipy: { extensions: ["xml"], name: "Clipy" },
+  copyq: { extensions: ["dat"], name: "CopyQ" },
+  ditto: { extensions: ["db"], name: "Ditto" },
+  gpaste: { extensions: ["xml"], name: "GPaste" },
+  maccy: { extensions: ["sqlite"], name: "Maccy" },
+};
+
+const Migrate: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const [source, setSource] = useState<MigrateSource>("copyq");
+  const { t } = useTranslation();
+
+  const handleImport = async () => {
+    try {
+      const { name, extensions } = SOURCES[source];
+
+      const path = await open({
+        filters: [{ extensions, name }],
+      });
+
+      if (!path) return;
+
+      state.spinning = true;
+
+      const { imported, skipped, unsupported } = await migrateHistory({
+        databasePath: await getSaveDatabasePath(),
+        imagesDir: await getDefaultSaveImagePath(),
+        path,
+        source,
+      });
+
+      emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+
+      message.success(
+        t("preference.data_backup.migrate.hints.import_success", {
+          imported,
+          skipped,
+          unsupported,
+        }),
+      );
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.spinning = false;
+    }
+  };
+
+  return (
+    <ProList header={t("preference.data_backup.migrate.title")}>
+      <ProListItem
+        description={t(`preference.data_backup.migrate.hints.${source}`)}
+        title={t("preference.data_backup.migrate.label.source")}
+      >
+        <Select
+          onChange={setSource}
+          options={Object.entries(SOURCES).map(([value, { name }]) => ({
+            label: name,
+            value,
+          }))}
+          value={source}
+        />
+      </ProListItem>
+
+      <ProListItem
+        description={t("preference.data_backup.migrate.hints.import")}
+        title={t("preference.data_backup.migrate.label.import")}
+      >
+        <Button onClick={handleImport}>
+          {t("preference.data_backup.migrate.button.import")}
+        </Button>
+      </ProListItem>
+    </ProList>
+  );
+};
+
+export default Migrate;
@@ -8,19 +8,28 @@ import { Button, message, Space, Tooltip } from "antd";
 import { isEqual, isString } from "es-toolkit";
 import { type FC, useState } from "react";
 import { useTranslation } from "react-i18next";
-import { fullName, transfer } from "tauri-plugin-fs-pro-api";
+import { fullName } from "tauri-plugin-fs-pro-api";
 import ProList from "@/components/ProList";
 import ProListItem from "@/components/ProListItem";
 import { LISTEN_KEY } from "@/constants";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  BACKUP_EVENT,
+  type RelocateProgress,
+  relocateDataDir,
+} from "@/plugins/backup";
+import { stopRemoteSync } from "@/plugins/sync";
 import { globalStore } from "@/stores/global";
 import {
   getSaveDatabasePath,
   getSaveDataDirName,
   getSaveDataPath,
   getSaveImagePath,
+  getSaveStorePath,
   join,
 } from "@/utils/path";
 import { wait } from "@/utils/shared";
+import { restartRemoteSync } from "@/utils/sync";
 import type { State } from "../..";
 
 const SavePath: FC<{ state: State }> = (props) => {
@@ -34,6 +43,18 @@ const SavePath: FC<{ state: State }> = (props) => {
     setLogDir(await appLogDir());
   });
 
+  // 复制和校验各占一半的进度
+  useTauriListen<RelocateProgress>(
+    BACKUP_EVENT.RELOCATE_PROGRESS,
+    ({ payload }) => {
+      const { stage, processed, total } = payload;
+
+      const percent = total ? (processed / total) * 50 : 50;
+
+      state.percent = Math.floor(stage === "copying" ? percent : 50 + percent);
+    },
+  );
+
   const handleChange = async (isDefault = false) => {
     try {
       const dstDir = isDefault ? dataDir : await open({ directory: true });
@@ -44,28 +65,49 @@ const SavePath: FC<{ state: State }> = (props) => {
 
       state.spinning = true;
 
+      // 移动期间停止所有使用数据库的功能，失败时恢复
       emit(LISTEN_KEY.CLOSE_DATABASE);
 
-      await wait();
-
-      await transfer(getSaveDataPath(), dstPath, {
-        includes: [
-          await fullName(getSaveImagePath()),
-          await fullName(await getSaveDatabasePath()),
-        ],
-      });
+      await stopRemoteSync();
 
-      globalStore.env.saveDataDir = dstPath;
-
-      emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+      await wait();
 
-      message.success(
-        t("preference.data_backup.storage_settings.hints.change_success"),
-      );
+      try {
+        const { cleaned } = await relocateDataDir({
+          database: await fullName(await getSaveDatabasePath()),
+          includes: [
+            await fullName(getSaveImagePath()),
+            await fullName(await getSaveStorePath(true)),
+          ],
+          source: getSaveDataPath(),
+          storePath: await getSaveStorePath(),
+          target: dstPath,
+        });
+
+        globalStore.env.saveDataDir = dstPath;
+
+        if (cleaned) {
+          message.success(
+            t("preference.data_backup.storage_settings.hints.change_success"),
+          );
+        } else {
+          message.warning(
+            t("preference.data_backup.storage_settings.hints.clean_failed"),
+          );
+        }
+      } catch (error) {
+        // 定时备份由移动数据目录的命令在失败时恢复
+        await restartRemoteSync();
+
+        throw error;
+      } finally {
+        emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+      }
     } catch (error: any) {
-      message.error(error);
+      message.error(String(error));
     } finally {
       state.spinning = false;
+      state.percent = void 0;
     }
   };
 
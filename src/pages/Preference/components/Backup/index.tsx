@@ -1,10 +1,15 @@
 import { useReactive } from "ahooks";
 import { Spin } from "antd";
+import Auto from "./components/Auto";
+import History from "./components/History";
+import Maintenance from "./components/Maintenance";
 import Manual from "./components/Manual";
+import Migrate from "./components/Migrate";
 import SavePath from "./components/SavePath";
 
 export interface State {
   spinning: boolean;
+  percent?: number;
 }
 
 const Backup = () => {
@@ -14,11 +19,23 @@ const Backup = () => {
 
   return (
     <>
-      <Spin fullscreen percent="auto" spinning={state.spinning} />
+      <Spin
+        fullscreen
+        percent={state.percent ?? "auto"}
+        spinning={state.spinning}
+      />
 
       <SavePath state={state} />
 
       <Manual state={state} />
+
+      <History state={state} />
+
+      <Migrate state={state} />
+
+      <Auto state={state} />
+
+      <Maintenance state={state} />
     </>
   );
 };
@@ -0,0 +1,35 @@
+import { Select } from "antd";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProListItem from "@/components/ProListItem";
+import { PASTE_TRANSFORMS } from "@/plugins/paste";
+import { clipboardStore } from "@/stores/clipboard";
+
+const PasteTransforms = () => {
+  const { content } = useSnapshot(clipboardStore);
+  const { t } = useTranslation();
+
+  return (
+    <ProListItem
+      description={t(
+        "preference.clipboard.content_settings.hints.paste_transforms",
+      )}
+      title={t("preference.clipboard.content_settings.label.paste_transforms")}
+    >
+      <Select
+        className="w-60"
+        mode="multiple"
+        onChange={(value) => {
+          clipboardStore.content.pasteTransforms = value;
+        }}
+        options={PASTE_TRANSFORMS.map((value) => ({
+          label: t(`clipboard.paste_transform.${value}`),
+          value,
+        }))}
+        value={[...content.pasteTransforms]}
+      />
+    </ProListItem>
+  );
+};
+
+export default PasteTransforms;
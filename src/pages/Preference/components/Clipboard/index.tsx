@@ -6,6 +6,7 @@ import { clipboardStore } from "@/stores/clipboard";
 import AudioSettings from "./components/AudioSettings";
 import AutoPaste from "./components/AutoPaste";
 import OperationButton from "./components/OperationButton";
+import PasteTransforms from "./components/PasteTransforms";
 import SearchPosition from "./components/SearchPosition";
 import WindowPosition from "./components/WindowPosition";
 
@@ -93,6 +94,8 @@ const ClipboardSettings = () => {
           value={content.pastePlain}
         />
 
+        <PasteTransforms />
+
         <OperationButton />
 
         <ProSwitch
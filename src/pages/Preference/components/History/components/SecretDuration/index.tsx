@@ -0,0 +1,29 @@
+import { InputNumber } from "antd";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProListItem from "@/components/ProListItem";
+import { clipboardStore } from "@/stores/clipboard";
+
+const SecretDuration = () => {
+  const { history } = useSnapshot(clipboardStore);
+  const { t } = useTranslation();
+
+  return (
+    <ProListItem
+      description={t("preference.history.history.hints.secret_duration")}
+      title={t("preference.history.history.label.secret_duration")}
+    >
+      <InputNumber
+        addonAfter={t("preference.history.history.label.secret_duration_unit")}
+        className="w-30"
+        min={0}
+        onChange={(value) => {
+          clipboardStore.history.secretDuration = value ?? 0;
+        }}
+        value={history.secretDuration}
+      />
+    </ProListItem>
+  );
+};
+
+export default SecretDuration;
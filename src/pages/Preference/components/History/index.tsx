@@ -3,16 +3,19 @@ import { useTranslation } from "react-i18next";
 import ProList from "@/components/ProList";
 import { deleteHistory, selectHistory } from "@/database/history";
 import { useImmediate } from "@/hooks/useImmediate";
+import { useImmediateKey } from "@/hooks/useImmediateKey";
 import { clipboardStore } from "@/stores/clipboard";
 import type { Interval } from "@/types/shared";
 import { dayjs } from "@/utils/dayjs";
 import Delete from "./components/Delete";
 import Duration from "./components/Duration";
 import MaxCount from "./components/MaxCount";
+import SecretDuration from "./components/SecretDuration";
 
 const History = () => {
   const { t } = useTranslation();
   const timerRef = useRef<Interval>();
+  const secretTimerRef = useRef<Interval>();
 
   useImmediate(clipboardStore.history, async () => {
     const { duration, maxCount } = clipboardStore.history;
@@ -36,7 +39,36 @@ const History = () => {
 
         if (!isExpired && !isOverMaxCount) continue;
 
-        deleteHistory(item);
+        deleteHistory(item, false);
+      }
+    }, delay);
+  });
+
+  // 包含密钥的条目保留时间较短，需要更频繁地检查，升级时从旧记录中识别出的密钥不删除
+  useImmediateKey(clipboardStore.history, "secretDuration", (duration) => {
+    clearInterval(secretTimerRef.current);
+
+    if (duration === 0) return;
+
+    const delay = 1000 * 60;
+
+    secretTimerRef.current = setInterval(async () => {
+      const list = await selectHistory((qb) => {
+        return qb
+          .where("favorite", "=", false)
+          .where("secret", "is not", null)
+          .where((eb) =>
+            eb.or([eb("keepSecret", "is", null), eb("keepSecret", "=", false)]),
+          );
+      });
+
+      for (const item of list) {
+        const { createTime } = item;
+        const diffMinutes = dayjs().diff(createTime, "minutes");
+
+        if (diffMinutes < duration) continue;
+
+        deleteHistory(item, false);
       }
     }, delay);
   });
@@ -46,6 +78,8 @@ const History = () => {
       <Duration />
 
       <MaxCount />
+
+      <SecretDuration />
     </ProList>
   );
 };
@@ -1,49 +1,85 @@
-import { Button, Input, message } from "antd";
+import { useAsyncEffect } from "ahooks";
+import { Button, Input, message, Select, Space } from "antd";
 import { useState } from "react";
 import { useTranslation } from "react-i18next";
 import { useSnapshot } from "valtio";
 import ProList from "@/components/ProList";
 import ProListItem from "@/components/ProListItem";
 import UnoIcon from "@/components/UnoIcon";
+import { deleteSecret, hasSecret, setSecret } from "@/plugins/secret";
 import { selectionAssistantStore } from "@/stores/selection-assistant";
+import type { AIProvider } from "@/types/selection-assistant";
+import { AI_PROVIDERS, callAI, getAPIKeySecretName } from "@/utils/ai-api";
 
 const ApiSettings = () => {
     const { apiConfig } = useSnapshot(selectionAssistantStore);
     const { t } = useTranslation();
     const [testing, setTesting] = useState(false);
+    // 新输入的 API Key，保存到系统钥匙串后清空，不会回显已保存的密钥
+    const [apiKey, setApiKey] = useState("");
+    const [keySaved, setKeySaved] = useState(false);
 
-    const handleTestApi = async () => {
-        const { baseUrl, apiKey, model } = selectionAssistantStore.apiConfig;
+    const { provider } = apiConfig;
+    const providerConfig = apiConfig.providers[provider];
+    const secretName = getAPIKeySecretName(provider);
+    // 本地的 Ollama 不需要密钥
+    const keyOptional = provider === "ollama";
+
+    useAsyncEffect(async () => {
+        setApiKey("");
+        setKeySaved(await hasSecret(secretName));
+    }, [secretName]);
+
+    const handleProviderChange = (value: AIProvider) => {
+        selectionAssistantStore.apiConfig.provider = value;
+    };
+
+    const handleSaveKey = async () => {
+        const value = apiKey.trim();
+
+        if (!value) return;
+
+        try {
+            await setSecret(secretName, value);
+
+            setApiKey("");
+            setKeySaved(true);
+
+            message.success(t("preference.selection_assistant.api.key_saved"));
+        } catch (error) {
+            message.error(String(error));
+        }
+    };
+
+    const handleDeleteKey = async () => {
+        try {
+            await deleteSecret(secretName);
+
+            setKeySaved(false);
+        } catch (error) {
+            message.error(String(error));
+        }
+    };
 
-        if (!apiKey) {
+    const handleTestApi = async () => {
+        if (!keySaved && !keyOptional) {
             message.error(t("preference.selection_assistant.api.key_required"));
             return;
         }
 
         setTesting(true);
         try {
-            const response = await fetch(`${baseUrl}/v1/chat/completions`, {
-                method: "POST",
-                headers: {
-                    "Content-Type": "application/json",
-                    "Authorization": `Bearer ${apiKey}`,
-                },
-                body: JSON.stringify({
-                    model: model || "gpt-3.5-turbo",
-                    messages: [{ role: "user", content: "Hi" }],
-                    max_tokens: 5,
-                }),
+            const response = await callAI({
+                apiConfig: selectionAssistantStore.apiConfig,
+                prompt: "Hi",
+                text: "",
             });
 
-            const data = await response.json();
-
-            if (response.ok && data.choices) {
+            if (response.success) {
                 message.success(t("preference.selection_assistant.api.test_success"));
             } else {
-                message.error(data.error?.message || t("preference.selection_assistant.api.test_failed"));
+                message.error(response.error || t("preference.selection_assistant.api.test_failed"));
             }
-        } catch (error) {
-            message.error(t("preference.selection_assistant.api.test_failed"));
         } finally {
             setTesting(false);
         }
@@ -51,17 +87,31 @@ const ApiSettings = () => {
 
     return (
         <ProList header={t("preference.selection_assistant.api.title")}>
+            <ProListItem
+                title={t("preference.selection_assistant.api.provider")}
+                description={t("preference.selection_assistant.api.provider_hint")}
+            >
+                <Select
+                    className="w-48"
+                    value={provider}
+                    onChange={handleProviderChange}
+                    options={AI_PROVIDERS.map((value) => ({
+                        label: t(`preference.selection_assistant.api.providers.${value}`),
+                        value,
+                    }))}
+                />
+            </ProListItem>
+
             <ProListItem
                 title="API Base URL"
                 description={t("preference.selection_assistant.api.base_url_hint")}
             >
                 <Input
                     className="w-72"
-                    value={apiConfig.baseUrl}
+                    value={providerConfig.baseUrl}
                     onChange={(e) => {
-                        selectionAssistantStore.apiConfig.baseUrl = e.target.value;
+                        selectionAssistantStore.apiConfig.providers[provider].baseUrl = e.target.value;
                     }}
-                    placeholder="https://api.openai.com"
                 />
             </ProListItem>
 
@@ -69,14 +119,28 @@ const ApiSettings = () => {
                 title="API Key"
                 description={t("preference.selection_assistant.api.key_hint")}
             >
-                <Input.Password
-                    className="w-72"
-                    value={apiConfig.apiKey}
-                    onChange={(e) => {
-                        selectionAssistantStore.apiConfig.apiKey = e.target.value;
-                    }}
-                    placeholder="sk-..."
-                />
+                <Space.Compact className="w-72">
+                    <Input.Password
+                        value={apiKey}
+                        onChange={(e) => setApiKey(e.target.value)}
+                        onPressEnter={handleSaveKey}
+                        onBlur={handleSaveKey}
+                        placeholder={
+                            keySaved
+                                ? t("preference.selection_assistant.api.key_saved_placeholder")
+                                : keyOptional
+                                  ? t("preference.selection_assistant.api.key_optional")
+                                  : "sk-..."
+                        }
+                    />
+                    {keySaved && (
+                        <Button
+                            icon={<UnoIcon name="i-lucide:trash-2" />}
+                            onClick={handleDeleteKey}
+                            title={t("preference.selection_assistant.api.key_delete")}
+                        />
+                    )}
+                </Space.Compact>
             </ProListItem>
 
             <ProListItem
@@ -85,11 +149,10 @@ const ApiSettings = () => {
             >
                 <Input
                     className="w-48"
-                    value={apiConfig.model}
+                    value={providerConfig.model}
                     onChange={(e) => {
-                        selectionAssistantStore.apiConfig.model = e.target.value;
+                        selectionAssistantStore.apiConfig.providers[provider].model = e.target.value;
                     }}
-                    placeholder="gpt-3.5-turbo"
                 />
             </ProListItem>
 
@@ -1,14 +1,51 @@
-import { Button, Flex, Input, Popconfirm, Switch, Tag } from "antd";
+import { useMount } from "ahooks";
+import {
+    Button,
+    Dropdown,
+    Flex,
+    Input,
+    Popconfirm,
+    Select,
+    Switch,
+    Tag,
+    Typography,
+} from "antd";
+import { useState } from "react";
 import { useTranslation } from "react-i18next";
 import { useSnapshot } from "valtio";
 import ProList from "@/components/ProList";
 import ProListItem from "@/components/ProListItem";
 import UnoIcon from "@/components/UnoIcon";
+import { listTransforms, validatePrompt } from "@/plugins/selection";
 import { selectionAssistantStore } from "@/stores/selection-assistant";
+import type {
+    AIProvider,
+    PromptIssue,
+    TransformCategory,
+    TransformInfo,
+} from "@/types/selection-assistant";
+import { AI_PROVIDERS } from "@/utils/ai-api";
+
+// 本地转换按分类使用的图标
+const transformIcons: Record<TransformCategory, string> = {
+    case: "i-lucide:case-sensitive",
+    format: "i-lucide:braces",
+    encoding: "i-lucide:binary",
+    hash: "i-lucide:fingerprint",
+    time: "i-lucide:clock",
+    text: "i-lucide:hash",
+    convert: "i-lucide:arrow-left-right",
+};
 
 const AgentList = () => {
-    const { agents } = useSnapshot(selectionAssistantStore);
+    const { agents, apiConfig } = useSnapshot(selectionAssistantStore);
     const { t } = useTranslation();
+    const [promptIssues, setPromptIssues] = useState<Record<string, PromptIssue[]>>({});
+    const [transforms, setTransforms] = useState<TransformInfo[]>([]);
+
+    useMount(async () => {
+        setTransforms(await listTransforms());
+    });
 
     const handleToggle = (id: string, enabled: boolean) => {
         const index = selectionAssistantStore.agents.findIndex((a) => a.id === id);
@@ -37,6 +74,18 @@ const AgentList = () => {
         });
     };
 
+    const handleAddTransformAgent = (transform: TransformInfo) => {
+        selectionAssistantStore.agents.push({
+            id: `transform_${Date.now()}`,
+            name: t(`preference.selection_assistant.transforms.${transform.id}`),
+            icon: transformIcons[transform.category],
+            enabled: true,
+            order: selectionAssistantStore.agents.length,
+            isBuiltin: false,
+            transform: transform.id,
+        });
+    };
+
     const handleNameChange = (id: string, name: string) => {
         const index = selectionAssistantStore.agents.findIndex((a) => a.id === id);
         if (index !== -1) {
@@ -51,6 +100,27 @@ const AgentList = () => {
         }
     };
 
+    // 失去焦点时检查模板，提示未知的变量和过滤器
+    const handlePromptBlur = async (id: string, prompt: string) => {
+        const issues = await validatePrompt(prompt);
+
+        setPromptIssues((prev) => ({ ...prev, [id]: issues }));
+    };
+
+    const handleProviderChange = (id: string, provider?: AIProvider) => {
+        const index = selectionAssistantStore.agents.findIndex((a) => a.id === id);
+        if (index !== -1) {
+            selectionAssistantStore.agents[index].provider = provider;
+        }
+    };
+
+    const handleModelChange = (id: string, model: string) => {
+        const index = selectionAssistantStore.agents.findIndex((a) => a.id === id);
+        if (index !== -1) {
+            selectionAssistantStore.agents[index].model = model || undefined;
+        }
+    };
+
     // 预设功能的中文名映射
     const builtinLabels: Record<string, string> = {
         translate: "翻译",
@@ -58,6 +128,8 @@ const AgentList = () => {
         summarize: "总结",
         search: "搜索",
         copy: "复制",
+        format_json: "格式化 JSON",
+        count_words: "字数统计",
     };
 
     return (
@@ -96,6 +168,33 @@ const AgentList = () => {
                 </Button>
             </ProListItem>
 
+            {/* 添加本地转换 */}
+            <ProListItem
+                title={t("preference.selection_assistant.agents.add_transform")}
+                description={t("preference.selection_assistant.agents.add_transform_hint")}
+            >
+                <Dropdown
+                    menu={{
+                        items: transforms.map((transform) => ({
+                            key: transform.id,
+                            label: t(`preference.selection_assistant.transforms.${transform.id}`),
+                        })),
+                        onClick: ({ key }) => {
+                            const transform = transforms.find((item) => item.id === key);
+
+                            if (transform) {
+                                handleAddTransformAgent(transform);
+                            }
+                        },
+                    }}
+                    trigger={["click"]}
+                >
+                    <Button icon={<UnoIcon name="i-lucide:wand-sparkles" />}>
+                        {t("preference.selection_assistant.agents.add_transform")}
+                    </Button>
+                </Dropdown>
+            </ProListItem>
+
             {/* Agent 列表 */}
             {agents.map((agent) => (
                 <ProListItem
@@ -146,19 +245,59 @@ const AgentList = () => {
             ))}
 
             {/* 自定义 Agent 提示词编辑 */}
-            {agents.filter((a) => !a.isBuiltin).map((agent) => (
+            {agents.filter((a) => !a.isBuiltin && !a.transform).map((agent) => (
                 <ProListItem
                     key={`prompt_${agent.id}`}
                     title={`${agent.name} ${t("preference.selection_assistant.agents.prompt")}`}
                     description={t("preference.selection_assistant.agents.prompt_hint")}
                 >
-                    <Input.TextArea
-                        className="w-80"
-                        rows={3}
-                        value={agent.prompt || ""}
-                        onChange={(e) => handlePromptChange(agent.id, e.target.value)}
-                        placeholder={t("preference.selection_assistant.agents.prompt_placeholder")}
-                    />
+                    <Flex vertical gap={4} className="w-80">
+                        <Input.TextArea
+                            rows={3}
+                            value={agent.prompt || ""}
+                            status={promptIssues[agent.id]?.length ? "warning" : undefined}
+                            onChange={(e) => handlePromptChange(agent.id, e.target.value)}
+                            onBlur={(e) => handlePromptBlur(agent.id, e.target.value)}
+                            placeholder={t("preference.selection_assistant.agents.prompt_placeholder")}
+                        />
+                        {promptIssues[agent.id]?.map((issue) => (
+                            <Typography.Text key={`${issue.kind}_${issue.offset}`} type="warning">
+                                {t(`preference.selection_assistant.agents.prompt_issues.${issue.kind}`, {
+                                    message: issue.message,
+                                    name: issue.name,
+                                })}
+                            </Typography.Text>
+                        ))}
+                    </Flex>
+                </ProListItem>
+            ))}
+
+            {/* AI Agent 使用的服务和模型 */}
+            {agents.filter((a) => a.prompt !== undefined).map((agent) => (
+                <ProListItem
+                    key={`model_${agent.id}`}
+                    title={`${builtinLabels[agent.id] || agent.name} ${t("preference.selection_assistant.agents.model")}`}
+                    description={t("preference.selection_assistant.agents.model_hint")}
+                >
+                    <Flex align="center" gap="small">
+                        <Select
+                            allowClear
+                            className="w-40"
+                            value={agent.provider}
+                            onChange={(value) => handleProviderChange(agent.id, value)}
+                            placeholder={t("preference.selection_assistant.agents.default_provider")}
+                            options={AI_PROVIDERS.map((value) => ({
+                                label: t(`preference.selection_assistant.api.providers.${value}`),
+                                value,
+                            }))}
+                        />
+                        <Input
+                            className="w-40"
+                            value={agent.model || ""}
+                            onChange={(e) => handleModelChange(agent.id, e.target.value)}
+                            placeholder={apiConfig.providers[agent.provider ?? apiConfig.provider].model}
+                        />
+                    </Flex>
                 </ProListItem>
             ))}
         </ProList>
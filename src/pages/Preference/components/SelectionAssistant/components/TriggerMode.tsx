@@ -1,4 +1,4 @@
-import { Segmented } from "antd";
+import { InputNumber, Segmented } from "antd";
 import { useTranslation } from "react-i18next";
 import { useSnapshot } from "valtio";
 import ProListItem from "@/components/ProListItem";
@@ -31,6 +31,22 @@ const TriggerMode = () => {
                 />
             </ProListItem>
 
+            <ProListItem
+                description={t("preference.selection_assistant.trigger.capture_timeout_hint")}
+                title={t("preference.selection_assistant.trigger.capture_timeout")}
+            >
+                <InputNumber
+                    addonAfter={t("preference.selection_assistant.trigger.capture_timeout_unit")}
+                    className="w-30"
+                    max={5000}
+                    min={50}
+                    onChange={(value) => {
+                        selectionAssistantStore.trigger.captureTimeout = value ?? 300;
+                    }}
+                    value={trigger.captureTimeout}
+                />
+            </ProListItem>
+
             <ProSwitch
                 onChange={(value) => {
                     selectionAssistantStore.toolbar.compactMode = value;
@@ -40,6 +40,15 @@ const Shortcut = () => {
           title={t("preference.shortcut.shortcut.label.paste_as_plain")}
           value={shortcut.pastePlain}
         />
+
+        <ProShortcut
+          description={t("preference.shortcut.shortcut.hints.paste_transformed")}
+          onChange={(value) => {
+            globalStore.shortcut.pasteTransformed = value;
+          }}
+          title={t("preference.shortcut.shortcut.label.paste_transformed")}
+          value={shortcut.pasteTransformed}
+        />
       </ProList>
 
       <Preset />
@@ -0,0 +1,44 @@
+import { Select } from "antd";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import ProSwitch from "@/components/ProSwitch";
+import { globalStore } from "@/stores/global";
+
+const Expander = () => {
+  const { expander } = useSnapshot(globalStore);
+  const { t } = useTranslation();
+
+  return (
+    <ProList header={t("preference.snippet.expander.title")}>
+      <ProSwitch
+        description={t("preference.snippet.expander.hints.enable")}
+        onChange={(value) => {
+          globalStore.expander.enable = value;
+        }}
+        title={t("preference.snippet.expander.label.enable")}
+        value={expander.enable}
+      />
+
+      <ProListItem
+        description={t("preference.snippet.expander.hints.excluded_apps")}
+        title={t("preference.snippet.expander.label.excluded_apps")}
+      >
+        <Select
+          className="w-60"
+          mode="tags"
+          onChange={(value: string[]) => {
+            globalStore.expander.excludedApps = value;
+          }}
+          open={false}
+          suffixIcon={null}
+          tokenSeparators={[",", " "]}
+          value={[...expander.excludedApps]}
+        />
+      </ProListItem>
+    </ProList>
+  );
+};
+
+export default Expander;
@@ -0,0 +1,112 @@
+import { Button, Flex, Input, Popconfirm } from "antd";
+import { nanoid } from "nanoid";
+import type { FC } from "react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import UnoIcon from "@/components/UnoIcon";
+import {
+  deleteSnippetFolder,
+  insertSnippetFolder,
+  updateSnippetFolder,
+} from "@/database/snippet";
+import { formatDate } from "@/utils/dayjs";
+import { isBlank } from "@/utils/is";
+import type { State } from "../..";
+
+const Folders: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const [name, setName] = useState("");
+  const { t } = useTranslation();
+
+  const handleAdd = async () => {
+    if (isBlank(name)) return;
+
+    await insertSnippetFolder({
+      createTime: formatDate(),
+      id: nanoid(),
+      name: name.trim(),
+    });
+
+    setName("");
+
+    state.reload();
+  };
+
+  const handleRename = async (id: string, value: string) => {
+    const folder = state.folders.find((item) => item.id === id);
+
+    if (!folder || isBlank(value) || folder.name === value.trim()) return;
+
+    await updateSnippetFolder(id, value.trim());
+
+    state.reload();
+  };
+
+  const handleDelete = async (id: string) => {
+    await deleteSnippetFolder(id);
+
+    state.reload();
+  };
+
+  return (
+    <ProList header={t("preference.snippet.folder.title")}>
+      <ProListItem title={t("preference.snippet.folder.label.add")}>
+        <Flex gap="small">
+          <Input
+            className="w-40"
+            onChange={(event) => setName(event.target.value)}
+            onPressEnter={handleAdd}
+            placeholder={t("preference.snippet.folder.hints.name")}
+            value={name}
+          />
+
+          <Button disabled={isBlank(name)} onClick={handleAdd} type="primary">
+            {t("preference.snippet.folder.button.add")}
+          </Button>
+        </Flex>
+      </ProListItem>
+
+      {state.folders.map((item) => {
+        const { id, name } = item;
+
+        const count = state.snippets.filter((snippet) => {
+          return snippet.folderId === id;
+        }).length;
+
+        return (
+          <ProListItem
+            description={t("preference.snippet.folder.hints.count", {
+              count,
+            })}
+            key={id}
+            title={
+              <Input
+                className="w-40"
+                defaultValue={name}
+                onBlur={(event) => handleRename(id, event.target.value)}
+                size="small"
+                variant="borderless"
+              />
+            }
+          >
+            <Popconfirm
+              description={t("preference.snippet.folder.hints.delete")}
+              onConfirm={() => handleDelete(id)}
+              title={t("preference.snippet.folder.label.delete")}
+            >
+              <Button
+                danger
+                icon={<UnoIcon name="i-lucide:trash-2" />}
+                type="text"
+              />
+            </Popconfirm>
+          </ProListItem>
+        );
+      })}
+    </ProList>
+  );
+};
+
+export default Folders;
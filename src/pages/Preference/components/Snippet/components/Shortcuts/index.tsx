@@ -0,0 +1,42 @@
+import type { FC } from "react";
+import { useTranslation } from "react-i18next";
+import ProList from "@/components/ProList";
+import ProShortcut from "@/components/ProShortcut";
+import { updateSnippet } from "@/database/snippet";
+import type { State } from "../..";
+
+const Shortcuts: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const { t } = useTranslation();
+
+  if (state.snippets.length === 0) return;
+
+  const handleChange = async (id: string, shortcut: string) => {
+    await updateSnippet(id, { shortcut });
+
+    state.reload();
+  };
+
+  return (
+    <ProList header={t("preference.snippet.shortcut.title")}>
+      {state.snippets.map((item) => {
+        const { id, name, shortcut } = item;
+
+        return (
+          <ProShortcut
+            key={id}
+            onChange={(value) => {
+              if (value === (shortcut ?? "")) return;
+
+              handleChange(id, value);
+            }}
+            title={name}
+            value={shortcut}
+          />
+        );
+      })}
+    </ProList>
+  );
+};
+
+export default Shortcuts;
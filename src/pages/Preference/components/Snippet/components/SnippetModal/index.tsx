@@ -0,0 +1,153 @@
+import { useBoolean } from "ahooks";
+import { Form, Input, Modal, Select } from "antd";
+import { nanoid } from "nanoid";
+import { forwardRef, useImperativeHandle, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { insertSnippet, updateSnippet } from "@/database/snippet";
+import type { DatabaseSchemaSnippet } from "@/types/database";
+import { formatDate } from "@/utils/dayjs";
+import type { State } from "../..";
+
+export interface SnippetModalRef {
+  open: (data?: DatabaseSchemaSnippet) => void;
+}
+
+interface FormFields {
+  name: string;
+  folderId?: string;
+  abbreviation?: string;
+  value: string;
+}
+
+const SnippetModal = forwardRef<SnippetModalRef, { state: State }>(
+  (props, ref) => {
+    const { state } = props;
+    const [open, { toggle }] = useBoolean();
+    const [item, setItem] = useState<DatabaseSchemaSnippet>();
+    const [form] = Form.useForm<FormFields>();
+    const { t } = useTranslation();
+
+    useImperativeHandle(ref, () => ({
+      open: (data) => {
+        form.resetFields();
+
+        form.setFieldsValue({
+          abbreviation: data?.abbreviation,
+          folderId: data?.folderId,
+          name: data?.name,
+          value: data?.value,
+        });
+
+        setItem(data);
+
+        toggle();
+      },
+    }));
+
+    const handleOk = async () => {
+      const { name, folderId, abbreviation, value } =
+        await form.validateFields();
+
+      // 清空的可选项保存为空字符串，省略时不会更新
+      const nextData = {
+        abbreviation: abbreviation?.trim() ?? "",
+        folderId: folderId ?? "",
+        name: name.trim(),
+        value,
+      };
+
+      if (item) {
+        await updateSnippet(item.id, nextData);
+      } else {
+        await insertSnippet({
+          ...nextData,
+          createTime: formatDate(),
+          id: nanoid(),
+        });
+      }
+
+      toggle();
+
+      state.reload();
+    };
+
+    // 缩写用于在任意位置输入后展开，不能包含空白字符，也不能重复
+    const validateAbbreviation = async (_: unknown, value?: string) => {
+      if (!value?.trim()) return;
+
+      if (/\s/.test(value.trim())) {
+        throw new Error(
+          t("preference.snippet.snippet.hints.abbreviation_space"),
+        );
+      }
+
+      const duplicate = state.snippets.some((snippet) => {
+        return snippet.id !== item?.id && snippet.abbreviation === value.trim();
+      });
+
+      if (duplicate) {
+        throw new Error(
+          t("preference.snippet.snippet.hints.abbreviation_duplicate"),
+        );
+      }
+    };
+
+    return (
+      <Modal
+        centered
+        forceRender
+        onCancel={toggle}
+        onOk={handleOk}
+        open={open}
+        title={t(
+          item
+            ? "preference.snippet.snippet.label.edit"
+            : "preference.snippet.snippet.label.add",
+        )}
+      >
+        <Form form={form} layout="vertical">
+          <Form.Item
+            label={t("preference.snippet.snippet.label.name")}
+            name="name"
+            rules={[{ required: true, whitespace: true }]}
+          >
+            <Input autoComplete="off" />
+          </Form.Item>
+
+          <Form.Item
+            label={t("preference.snippet.snippet.label.folder")}
+            name="folderId"
+          >
+            <Select
+              allowClear
+              options={state.folders.map(({ id, name }) => ({
+                label: name,
+                value: id,
+              }))}
+            />
+          </Form.Item>
+
+          <Form.Item
+            extra={t("preference.snippet.snippet.hints.abbreviation")}
+            label={t("preference.snippet.snippet.label.abbreviation")}
+            name="abbreviation"
+            rules={[{ validator: validateAbbreviation }]}
+          >
+            <Input autoComplete="off" className="font-mono" />
+          </Form.Item>
+
+          <Form.Item
+            extra={t("preference.snippet.snippet.hints.value")}
+            label={t("preference.snippet.snippet.label.value")}
+            name="value"
+            rules={[{ required: true }]}
+          >
+            <Input.TextArea autoSize={{ maxRows: 10, minRows: 4 }} />
+          </Form.Item>
+        </Form>
+      </Modal>
+    );
+  },
+);
+
+export default SnippetModal;
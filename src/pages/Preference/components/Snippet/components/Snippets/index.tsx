@@ -0,0 +1,87 @@
+import { Button, Flex, Popconfirm, Tag } from "antd";
+import { find } from "es-toolkit/compat";
+import type { FC } from "react";
+import { useRef } from "react";
+import { useTranslation } from "react-i18next";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import UnoIcon from "@/components/UnoIcon";
+import { deleteSnippet } from "@/database/snippet";
+import type { State } from "../..";
+import SnippetModal, { type SnippetModalRef } from "../SnippetModal";
+
+const Snippets: FC<{ state: State }> = (props) => {
+  const { state } = props;
+  const modalRef = useRef<SnippetModalRef>(null);
+  const { t } = useTranslation();
+
+  const handleDelete = async (id: string) => {
+    await deleteSnippet(id);
+
+    state.reload();
+  };
+
+  return (
+    <ProList header={t("preference.snippet.snippet.title")}>
+      <ProListItem
+        description={t("preference.snippet.snippet.hints.add")}
+        title={t("preference.snippet.snippet.label.add")}
+      >
+        <Button
+          icon={<UnoIcon name="i-lucide:plus" />}
+          onClick={() => modalRef.current?.open()}
+          type="primary"
+        >
+          {t("preference.snippet.snippet.button.add")}
+        </Button>
+      </ProListItem>
+
+      {state.snippets.map((item) => {
+        const { id, name, value, folderId, abbreviation } = item;
+
+        const folder = find(state.folders, { id: folderId });
+
+        return (
+          <ProListItem
+            description={<div className="line-clamp-2">{value}</div>}
+            key={id}
+            title={
+              <Flex align="center" gap={4}>
+                <span className="truncate">{name}</span>
+
+                {folder && <Tag className="m-0!">{folder.name}</Tag>}
+
+                {abbreviation && (
+                  <Tag className="m-0! font-mono" color="blue">
+                    {abbreviation}
+                  </Tag>
+                )}
+              </Flex>
+            }
+          >
+            <Button
+              icon={<UnoIcon name="i-lucide:pencil" />}
+              onClick={() => modalRef.current?.open(item)}
+              type="text"
+            />
+
+            <Popconfirm
+              onConfirm={() => handleDelete(id)}
+              title={t("preference.snippet.snippet.label.delete")}
+            >
+              <Button
+                danger
+                icon={<UnoIcon name="i-lucide:trash-2" />}
+                type="text"
+              />
+            </Popconfirm>
+          </ProListItem>
+        );
+      })}
+
+      <SnippetModal ref={modalRef} state={state} />
+    </ProList>
+  );
+};
+
+export default Snippets;
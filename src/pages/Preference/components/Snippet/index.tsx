@@ -0,0 +1,60 @@
+import { emit } from "@tauri-apps/api/event";
+import { useMount, useReactive } from "ahooks";
+import { message } from "antd";
+import { LISTEN_KEY } from "@/constants";
+import { selectSnippetFolders, selectSnippets } from "@/database/snippet";
+import type {
+  DatabaseSchemaSnippet,
+  DatabaseSchemaSnippetFolder,
+} from "@/types/database";
+import Expander from "./components/Expander";
+import Folders from "./components/Folders";
+import Shortcuts from "./components/Shortcuts";
+import Snippets from "./components/Snippets";
+
+export interface State {
+  folders: DatabaseSchemaSnippetFolder[];
+  snippets: DatabaseSchemaSnippet[];
+  /**
+   * 修改后重新读取，并通知主窗口刷新片段
+   */
+  reload: () => Promise<void>;
+}
+
+const Snippet = () => {
+  const state = useReactive<State>({
+    folders: [],
+    reload: async () => {
+      try {
+        const [snippets, folders] = await Promise.all([
+          selectSnippets(),
+          selectSnippetFolders(),
+        ]);
+
+        state.snippets = snippets;
+        state.folders = folders;
+
+        emit(LISTEN_KEY.REFRESH_SNIPPET_LIST);
+      } catch (error) {
+        message.error(String(error));
+      }
+    },
+    snippets: [],
+  });
+
+  useMount(state.reload);
+
+  return (
+    <>
+      <Snippets state={state} />
+
+      <Folders state={state} />
+
+      <Shortcuts state={state} />
+
+      <Expander />
+    </>
+  );
+};
+
+export default Snippet;
@@ -0,0 +1,139 @@
+import { Button, Input, List, Modal, message, Tag } from "antd";
+import type { FC } from "react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import {
+  pairSyncDevice,
+  type SyncPeer,
+  unpairSyncDevice,
+} from "@/plugins/sync";
+
+interface DevicesProps {
+  peers: SyncPeer[];
+  running: boolean;
+  onChange: () => void;
+}
+
+const Devices: FC<DevicesProps> = (props) => {
+  const { peers, running, onChange } = props;
+  const { t } = useTranslation();
+  const [pairingPeer, setPairingPeer] = useState<SyncPeer>();
+  const [code, setCode] = useState("");
+  const [loading, setLoading] = useState(false);
+
+  const openPairing = (peer: SyncPeer) => {
+    setCode("");
+    setPairingPeer(peer);
+  };
+
+  const handlePair = async () => {
+    if (!pairingPeer || !code.trim()) return;
+
+    try {
+      setLoading(true);
+
+      await pairSyncDevice(pairingPeer.addresses, code.trim());
+
+      message.success(
+        t("preference.sync.devices.hints.pair_success", {
+          name: pairingPeer.name,
+        }),
+      );
+
+      setPairingPeer(void 0);
+    } catch {
+      message.error(t("preference.sync.devices.hints.pair_failed"));
+    } finally {
+      setLoading(false);
+
+      onChange();
+    }
+  };
+
+  const handleUnpair = async (peer: SyncPeer) => {
+    await unpairSyncDevice(peer.deviceId);
+
+    onChange();
+  };
+
+  return (
+    <ProList header={t("preference.sync.devices.title")}>
+      {peers.length === 0 && (
+        <List.Item>
+          <span className="text-color-3">
+            {t(
+              running
+                ? "preference.sync.devices.hints.empty"
+                : "preference.sync.devices.hints.not_running",
+            )}
+          </span>
+        </List.Item>
+      )}
+
+      {peers.map((peer) => {
+        const { deviceId, name, paired, online } = peer;
+
+        return (
+          <ProListItem
+            description={deviceId}
+            key={deviceId}
+            title={
+              <>
+                {name}
+
+                <Tag
+                  bordered={false}
+                  className="ml-2"
+                  color={online ? "green" : "default"}
+                >
+                  {t(
+                    online
+                      ? "preference.sync.devices.label.online"
+                      : "preference.sync.devices.label.offline",
+                  )}
+                </Tag>
+              </>
+            }
+          >
+            {paired ? (
+              <Button danger onClick={() => handleUnpair(peer)}>
+                {t("preference.sync.devices.button.unpair")}
+              </Button>
+            ) : (
+              <Button disabled={!online} onClick={() => openPairing(peer)}>
+                {t("preference.sync.devices.button.pair")}
+              </Button>
+            )}
+          </ProListItem>
+        );
+      })}
+
+      <Modal
+        centered
+        confirmLoading={loading}
+        onCancel={() => setPairingPeer(void 0)}
+        onOk={handlePair}
+        open={Boolean(pairingPeer)}
+        title={t("preference.sync.devices.label.pairing_code", {
+          name: pairingPeer?.name,
+        })}
+      >
+        <p className="text-color-2">
+          {t("preference.sync.devices.hints.pairing_code")}
+        </p>
+
+        <Input
+          autoFocus
+          maxLength={6}
+          onChange={(event) => setCode(event.target.value)}
+          onPressEnter={handlePair}
+          value={code}
+        />
+      </Modal>
+    </ProList>
+  );
+};
+
+export default Devices;
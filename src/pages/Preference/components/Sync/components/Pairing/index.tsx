@@ -0,0 +1,56 @@
+import { Button, message, Space, Typography } from "antd";
+import type { FC } from "react";
+import { useTranslation } from "react-i18next";
+import ProListItem from "@/components/ProListItem";
+import { cancelSyncPairing, startSyncPairing } from "@/plugins/sync";
+
+interface PairingProps {
+  code?: string | null;
+  onChange: () => void;
+}
+
+const Pairing: FC<PairingProps> = (props) => {
+  const { code, onChange } = props;
+  const { t } = useTranslation();
+
+  const handleStart = async () => {
+    try {
+      await startSyncPairing();
+    } catch (error) {
+      message.error(String(error));
+    }
+
+    onChange();
+  };
+
+  const handleCancel = async () => {
+    await cancelSyncPairing();
+
+    onChange();
+  };
+
+  return (
+    <ProListItem
+      description={t("preference.sync.lan.hints.pairing")}
+      title={t("preference.sync.lan.label.pairing")}
+    >
+      {code ? (
+        <Space>
+          <Typography.Text className="font-mono text-lg" copyable strong>
+            {code}
+          </Typography.Text>
+
+          <Button onClick={handleCancel}>
+            {t("preference.sync.lan.button.cancel_pairing")}
+          </Button>
+        </Space>
+      ) : (
+        <Button onClick={handleStart}>
+          {t("preference.sync.lan.button.start_pairing")}
+        </Button>
+      )}
+    </ProListItem>
+  );
+};
+
+export default Pairing;
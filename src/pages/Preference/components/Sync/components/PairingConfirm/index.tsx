@@ -0,0 +1,58 @@
+import { Modal, message, Typography } from "antd";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  confirmSyncPairing,
+  type PairingConfirmPayload,
+  SYNC_EVENT,
+} from "@/plugins/sync";
+
+const PairingConfirm = () => {
+  const { t } = useTranslation();
+  const [pairing, setPairing] = useState<PairingConfirmPayload>();
+
+  // 发起配对和显示配对码的设备都会收到确认码
+  useTauriListen<PairingConfirmPayload>(
+    SYNC_EVENT.PAIRING_CONFIRM,
+    ({ payload }) => {
+      setPairing(payload);
+    },
+  );
+
+  const handleConfirm = async (accepted: boolean) => {
+    setPairing(void 0);
+
+    try {
+      await confirmSyncPairing(accepted);
+    } catch {
+      message.error(t("preference.sync.devices.hints.pair_failed"));
+    }
+  };
+
+  return (
+    <Modal
+      cancelText={t("preference.sync.devices.button.reject")}
+      centered
+      closable={false}
+      maskClosable={false}
+      okText={t("preference.sync.devices.button.accept")}
+      onCancel={() => handleConfirm(false)}
+      onOk={() => handleConfirm(true)}
+      open={Boolean(pairing)}
+      title={t("preference.sync.devices.label.confirm_pairing", {
+        name: pairing?.name,
+      })}
+    >
+      <p className="text-color-2">
+        {t("preference.sync.devices.hints.confirm_pairing")}
+      </p>
+
+      <Typography.Text className="font-mono text-2xl" strong>
+        {pairing?.code}
+      </Typography.Text>
+    </Modal>
+  );
+};
+
+export default PairingConfirm;
@@ -0,0 +1,255 @@
+import { useInterval, useMount, useReactive } from "ahooks";
+import { Button, Input, InputNumber, message, Select, Space } from "antd";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import ProSwitch from "@/components/ProSwitch";
+import { SECRET_NAME } from "@/constants";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  getRemoteSyncStatus,
+  type RemoteSyncStatus,
+  SYNC_EVENT,
+  syncRemoteNow,
+  testRemoteSync,
+} from "@/plugins/sync";
+import { globalStore } from "@/stores/global";
+import { formatDate } from "@/utils/dayjs";
+import { getRemoteConfig, restartRemoteSync } from "@/utils/sync";
+import SecretInput from "../SecretInput";
+
+interface State {
+  status?: RemoteSyncStatus;
+  testing: boolean;
+  syncing: boolean;
+}
+
+const Remote = () => {
+  const { sync } = useSnapshot(globalStore);
+  const { t } = useTranslation();
+  const state = useReactive<State>({
+    syncing: false,
+    testing: false,
+  });
+
+  const { remote } = sync;
+
+  const refresh = async () => {
+    state.status = await getRemoteSyncStatus();
+  };
+
+  useMount(refresh);
+
+  useInterval(refresh, 2000);
+
+  useTauriListen(SYNC_EVENT.REMOTE, refresh);
+
+  // 密码保存在密钥存储中，同步开始时读取，修改后需要重新启动
+  const handleSecretSaved = async () => {
+    try {
+      await restartRemoteSync();
+    } catch (error) {
+      message.error(String(error));
+    }
+  };
+
+  const handleTest = async () => {
+    try {
+      state.testing = true;
+
+      await testRemoteSync(getRemoteConfig());
+
+      message.success(t("preference.sync.remote.hints.test_success"));
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.testing = false;
+    }
+  };
+
+  const handleSyncNow = async () => {
+    try {
+      state.syncing = true;
+
+      const { pushed, pulled } = await syncRemoteNow();
+
+      message.success(
+        t("preference.sync.remote.hints.sync_success", { pulled, pushed }),
+      );
+    } catch (error) {
+      message.error(String(error));
+    } finally {
+      state.syncing = false;
+
+      refresh();
+    }
+  };
+
+  const renderInput = (key: keyof typeof remote, placeholder?: string) => {
+    return (
+      <Input
+        className="w-60"
+        onChange={(event) => {
+          Object.assign(globalStore.sync.remote, {
+            [key]: event.target.value,
+          });
+        }}
+        placeholder={placeholder}
+        value={String(remote[key])}
+      />
+    );
+  };
+
+  const renderStatus = () => {
+    const { lastReport, lastError } = state.status ?? {};
+
+    if (lastError) {
+      return t("preference.sync.remote.hints.last_error", { error: lastError });
+    }
+
+    if (!lastReport) {
+      return t("preference.sync.remote.hints.never_synced");
+    }
+
+    return t("preference.sync.remote.hints.last_sync", {
+      pulled: lastReport.pulled,
+      pushed: lastReport.pushed,
+      time: formatDate(lastReport.finishedAt),
+    });
+  };
+
+  return (
+    <ProList header={t("preference.sync.remote.title")}>
+      <ProSwitch
+        description={t("preference.sync.remote.hints.enable")}
+        onChange={(value) => {
+          globalStore.sync.remote.enable = value;
+        }}
+        title={t("preference.sync.remote.label.enable")}
+        value={remote.enable}
+      />
+
+      <ProListItem title={t("preference.sync.remote.label.kind")}>
+        <Select
+          className="w-60"
+          onChange={(value: typeof remote.kind) => {
+            globalStore.sync.remote.kind = value;
+          }}
+          options={[
+            { label: "WebDAV", value: "webdav" },
+            { label: t("preference.sync.remote.label.s3"), value: "s3" },
+          ]}
+          value={remote.kind}
+        />
+      </ProListItem>
+
+      {remote.kind === "webdav" ? (
+        <>
+          <ProListItem
+            description={t("preference.sync.remote.hints.url")}
+            title={t("preference.sync.remote.label.url")}
+          >
+            {renderInput("url", "https://example.com/dav/EcoPaste")}
+          </ProListItem>
+
+          <ProListItem title={t("preference.sync.remote.label.username")}>
+            {renderInput("username")}
+          </ProListItem>
+
+          <ProListItem title={t("preference.sync.remote.label.password")}>
+            <SecretInput
+              name={SECRET_NAME.SYNC_CREDENTIAL}
+              onSaved={handleSecretSaved}
+            />
+          </ProListItem>
+        </>
+      ) : (
+        <>
+          <ProListItem title={t("preference.sync.remote.label.endpoint")}>
+            {renderInput("endpoint", "https://s3.amazonaws.com")}
+          </ProListItem>
+
+          <ProListItem title={t("preference.sync.remote.label.region")}>
+            {renderInput("region", "us-east-1")}
+          </ProListItem>
+
+          <ProListItem title={t("preference.sync.remote.label.bucket")}>
+            {renderInput("bucket")}
+          </ProListItem>
+
+          <ProListItem
+            description={t("preference.sync.remote.hints.prefix")}
+            title={t("preference.sync.remote.label.prefix")}
+          >
+            {renderInput("prefix", "EcoPaste")}
+          </ProListItem>
+
+          <ProListItem title="Access Key ID">
+            {renderInput("accessKeyId")}
+          </ProListItem>
+
+          <ProListItem title="Secret Access Key">
+            <SecretInput
+              name={SECRET_NAME.SYNC_CREDENTIAL}
+              onSaved={handleSecretSaved}
+            />
+          </ProListItem>
+
+          <ProSwitch
+            description={t("preference.sync.remote.hints.path_style")}
+            onChange={(value) => {
+              globalStore.sync.remote.pathStyle = value;
+            }}
+            title={t("preference.sync.remote.label.path_style")}
+            value={remote.pathStyle}
+          />
+        </>
+      )}
+
+      <ProListItem
+        description={t("preference.sync.remote.hints.passphrase")}
+        title={t("preference.sync.remote.label.passphrase")}
+      >
+        <SecretInput
+          name={SECRET_NAME.SYNC_PASSPHRASE}
+          onSaved={handleSecretSaved}
+        />
+      </ProListItem>
+
+      <ProListItem title={t("preference.sync.remote.label.interval")}>
+        <InputNumber
+          addonAfter={t("preference.sync.remote.label.minutes")}
+          className="w-30"
+          min={1}
+          onChange={(value) => {
+            globalStore.sync.remote.interval = value ?? 15;
+          }}
+          value={remote.interval}
+        />
+      </ProListItem>
+
+      <ProListItem
+        description={renderStatus()}
+        title={t("preference.sync.remote.label.status")}
+      >
+        <Space>
+          <Button loading={state.testing} onClick={handleTest}>
+            {t("preference.sync.remote.button.test")}
+          </Button>
+
+          <Button
+            disabled={!state.status?.running}
+            loading={state.syncing || state.status?.syncing}
+            onClick={handleSyncNow}
+            type="primary"
+          >
+            {t("preference.sync.remote.button.sync_now")}
+          </Button>
+        </Space>
+      </ProListItem>
+    </ProList>
+  );
+};
+
+export default Remote;
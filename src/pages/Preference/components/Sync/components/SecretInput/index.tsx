@@ -0,0 +1,53 @@
+import { useAsyncEffect } from "ahooks";
+import { Input, message } from "antd";
+import type { FC } from "react";
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { hasSecret, setSecret } from "@/plugins/secret";
+
+interface SecretInputProps {
+  name: string;
+  onSaved?: () => void;
+}
+
+// 输入的内容保存到密钥存储后清空，不会回显已保存的内容
+const SecretInput: FC<SecretInputProps> = (props) => {
+  const { name, onSaved } = props;
+  const { t } = useTranslation();
+  const [value, setValue] = useState("");
+  const [saved, setSaved] = useState(false);
+
+  useAsyncEffect(async () => {
+    setSaved(await hasSecret(name));
+  }, [name]);
+
+  const handleSave = async () => {
+    if (!value) return;
+
+    try {
+      await setSecret(name, value);
+
+      setValue("");
+      setSaved(true);
+
+      onSaved?.();
+    } catch (error) {
+      message.error(String(error));
+    }
+  };
+
+  return (
+    <Input.Password
+      className="w-60"
+      onBlur={handleSave}
+      onChange={(event) => setValue(event.target.value)}
+      onPressEnter={handleSave}
+      placeholder={
+        saved ? t("preference.sync.remote.hints.secret_saved") : void 0
+      }
+      value={value}
+    />
+  );
+};
+
+export default SecretInput;
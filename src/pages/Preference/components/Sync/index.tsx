@@ -0,0 +1,103 @@
+import { useInterval, useMount, useReactive } from "ahooks";
+import { InputNumber } from "antd";
+import { useTranslation } from "react-i18next";
+import { useSnapshot } from "valtio";
+import ProList from "@/components/ProList";
+import ProListItem from "@/components/ProListItem";
+import ProSwitch from "@/components/ProSwitch";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  getSyncStatus,
+  listSyncPeers,
+  SYNC_EVENT,
+  type SyncPeer,
+  type SyncStatus,
+} from "@/plugins/sync";
+import { globalStore } from "@/stores/global";
+import Devices from "./components/Devices";
+import Pairing from "./components/Pairing";
+import PairingConfirm from "./components/PairingConfirm";
+import Remote from "./components/Remote";
+
+interface State {
+  status: SyncStatus | null;
+  peers: SyncPeer[];
+}
+
+const Sync = () => {
+  const { sync } = useSnapshot(globalStore);
+  const { t } = useTranslation();
+  const state = useReactive<State>({
+    peers: [],
+    status: null,
+  });
+
+  const refresh = async () => {
+    state.status = await getSyncStatus();
+    state.peers = await listSyncPeers();
+  };
+
+  useMount(refresh);
+
+  // 同步服务由主窗口启动，定时刷新状态和发现的设备
+  useInterval(refresh, 2000);
+
+  useTauriListen(SYNC_EVENT.PEERS, refresh);
+
+  useTauriListen(SYNC_EVENT.PAIRED, refresh);
+
+  return (
+    <>
+      <ProList header={t("preference.sync.lan.title")}>
+        <ProSwitch
+          description={t("preference.sync.lan.hints.enable")}
+          onChange={(value) => {
+            globalStore.sync.lan.enable = value;
+          }}
+          title={t("preference.sync.lan.label.enable")}
+          value={sync.lan.enable}
+        />
+
+        <ProListItem
+          description={t("preference.sync.lan.hints.port")}
+          title={t("preference.sync.lan.label.port")}
+        >
+          <InputNumber
+            className="w-30"
+            max={65535}
+            min={0}
+            onChange={(value) => {
+              globalStore.sync.lan.port = value ?? 0;
+            }}
+            value={sync.lan.port}
+          />
+        </ProListItem>
+
+        {state.status && (
+          <>
+            <ProListItem
+              description={`${state.status.deviceId} · ${state.status.port}`}
+              title={t("preference.sync.lan.label.device")}
+            >
+              {state.status.name}
+            </ProListItem>
+
+            <Pairing code={state.status.pairingCode} onChange={refresh} />
+          </>
+        )}
+      </ProList>
+
+      <Devices
+        onChange={refresh}
+        peers={state.peers}
+        running={Boolean(state.status)}
+      />
+
+      <Remote />
+
+      <PairingConfirm />
+    </>
+  );
+};
+
+export default Sync;
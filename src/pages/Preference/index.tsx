@@ -21,11 +21,14 @@ import { raf } from "@/utils/bom";
 import { isMac } from "@/utils/is";
 import { saveStore } from "@/utils/store";
 import About from "./components/About";
+import Backup from "./components/Backup";
 import Clipboard from "./components/Clipboard";
 import General from "./components/General";
 import History from "./components/History";
 import SelectionAssistant from "./components/SelectionAssistant";
 import Shortcut from "./components/Shortcut";
+import Snippet from "./components/Snippet";
+import Sync from "./components/Sync";
 
 const Preference = () => {
   const { t } = useTranslation();
@@ -62,6 +65,11 @@ const Preference = () => {
     emit(LISTEN_KEY.STORE_CHANGED, { clipboardStore, globalStore, selectionAssistantStore });
 
     saveStore();
+
+    // 自动备份读取备份用的配置文件，需要保持最新
+    if (globalStore.backup.auto.enable) {
+      saveStore(true);
+    }
   };
 
   const menuItems = useCreation(() => {
@@ -84,6 +92,12 @@ const Preference = () => {
         key: "general",
         label: t("preference.menu.title.general"),
       },
+      {
+        content: <Snippet />,
+        icon: "i-lucide:text-cursor-input",
+        key: "snippet",
+        label: t("preference.menu.title.snippet"),
+      },
       {
         content: <Shortcut />,
         icon: "i-lucide:keyboard",
@@ -96,12 +110,18 @@ const Preference = () => {
         key: "selection-assistant",
         label: t("preference.menu.title.selection_assistant"),
       },
-      // {
-      //   content: <Backup />,
-      //   icon: "i-lucide:database-backup",
-      //   key: "backup",
-      //   label: t("preference.menu.title.backup"),
-      // },
+      {
+        content: <Sync />,
+        icon: "i-lucide:refresh-cw",
+        key: "sync",
+        label: t("preference.menu.title.sync"),
+      },
+      {
+        content: <Backup />,
+        icon: "i-lucide:database-backup",
+        key: "backup",
+        label: t("preference.menu.title.backup"),
+      },
       {
         content: <About />,
         icon: "i-lucide:info",
@@ -1,10 +1,21 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { getCurrentWindow, PhysicalPosition } from "@tauri-apps/api/window";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
 import { useSnapshot } from "valtio";
 import UnoIcon from "@/components/UnoIcon";
+import { useTauriListen } from "@/hooks/useTauriListen";
+import {
+  getSelectedText,
+  hideSelectionToolbar,
+  runTransform,
+  setSelectionHideConfig,
+  showSelectionToolbar,
+  startSelectionMonitor,
+  stopSelectionMonitor,
+} from "@/plugins/selection";
 import { selectionAssistantStore } from "@/stores/selection-assistant";
+import type { CustomAgent, TextStats } from "@/types/selection-assistant";
 import { callAI } from "@/utils/ai-api";
 
 interface SelectionEvent {
@@ -13,8 +24,11 @@ interface SelectionEvent {
 }
 
 const SelectionToolbar = () => {
-  const { agents, toolbar } = useSnapshot(selectionAssistantStore);
+  const { agents, enabled, toolbar, trigger } = useSnapshot(
+    selectionAssistantStore,
+  );
   const [loading, setLoading] = useState(false);
+  const { t } = useTranslation();
 
   // 紧凑模式
   const compactMode = toolbar.compactMode;
@@ -25,7 +39,9 @@ const SelectionToolbar = () => {
   // 预设功能的中文名映射
   const builtinLabels: Record<string, string> = {
     copy: "复制",
+    count_words: "字数统计",
     explain: "解释",
+    format_json: "格式化 JSON",
     search: "搜索",
     summarize: "总结",
     translate: "翻译",
@@ -41,12 +57,8 @@ const SelectionToolbar = () => {
         const { x, y } = event.payload;
 
         try {
-          // 移动窗口到鼠标位置（稍微偏移一点，避免遮挡选区）
-          await window.setPosition(new PhysicalPosition(x + 10, y + 10));
-          // 显示窗口
-          await window.show();
-          await window.setFocus();
-          // 窗口已显示
+          // 由 Rust 端计算位置（限制在显示器工作区内）并在不抢占焦点的情况下显示
+          await showSelectionToolbar(x, y);
         } catch (error) {
           console.error("Failed to show toolbar:", error);
         }
@@ -67,27 +79,62 @@ const SelectionToolbar = () => {
     };
   }, []);
 
+  // 根据启用状态启动或停止划词监控
+  useEffect(() => {
+    if (enabled) {
+      startSelectionMonitor();
+    } else {
+      stopSelectionMonitor();
+    }
+  }, [enabled]);
+
+  // 同步自动隐藏的规则到 Rust 端
+  useEffect(() => {
+    setSelectionHideConfig({
+      ...toolbar.hideRules,
+      mode: trigger.mode,
+    });
+  }, [trigger.mode, toolbar.hideRules]);
+
+  // 监听隐藏工具栏事件（点击外部、滚动、按键、切换前台窗口）
+  useTauriListen("selection:hide-toolbar", () => {
+    hideSelectionToolbar();
+  });
+
+  // 按界面语言格式化结构化的转换结果
+  const formatTransformResult = (id: string, result: string) => {
+    if (id !== "text.count") return result;
+
+    const stats: TextStats = JSON.parse(result);
+
+    return t("preference.selection_assistant.transforms.text.count_result", {
+      ...stats,
+    });
+  };
+
   // 隐藏工具栏
   const hideToolbar = async () => {
-    const window = getCurrentWindow();
-    await window.hide();
-    // 窗口已隐藏
+    await hideSelectionToolbar();
   };
 
   // 处理 Agent 点击
-  const handleAgentClick = async (agentId: string, prompt?: string) => {
+  const handleAgentClick = async (agent: CustomAgent) => {
+    const { id: agentId, prompt, transform } = agent;
+
     try {
       // 先获取选中的文本
-      const selectedText = await invoke<string>(
-        "plugin:eco-selection|get_selected_text",
+      const capture = await getSelectedText(
+        selectionAssistantStore.trigger.captureTimeout,
       );
 
-      if (!selectedText) {
+      if (capture.kind === "noSelection") {
         console.warn("No text selected");
         await hideToolbar();
         return;
       }
 
+      const selectedText = capture.text;
+
       // 处理特殊 agent
       if (agentId === "copy") {
         await navigator.clipboard.writeText(selectedText);
@@ -103,16 +150,33 @@ const SelectionToolbar = () => {
         return;
       }
 
+      // 本地转换，结果复制到剪贴板
+      if (transform) {
+        try {
+          const result = await runTransform(transform, selectedText);
+          await navigator.clipboard.writeText(
+            formatTransformResult(transform, result),
+          );
+        } catch (error) {
+          console.error("Transform error:", error);
+        }
+
+        await hideToolbar();
+        return;
+      }
+
       // AI 功能
       if (prompt) {
         setLoading(true);
         try {
           const response = await callAI({
             apiConfig: selectionAssistantStore.apiConfig,
+            model: agent.model,
             prompt,
+            provider: agent.provider,
             text: selectedText,
           });
-          if (response.success && response.content) {
+          if (response.success && response.content && !response.cancelled) {
             // TODO: 显示结果弹窗
             console.log("AI Response:", response.content);
             // 可以将结果复制到剪贴板
@@ -143,7 +207,7 @@ const SelectionToolbar = () => {
           className="flex cursor-pointer items-center justify-center gap-1 rounded-md border-none bg-gray-700 px-2 py-1 text-white text-xs transition-colors hover:bg-gray-600"
           disabled={loading}
           key={agent.id}
-          onClick={() => handleAgentClick(agent.id, agent.prompt)}
+          onClick={() => handleAgentClick(agent)}
           title={builtinLabels[agent.id] || agent.name}
         >
           <UnoIcon name={agent.icon} size={16} />
@@ -0,0 +1,250 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export const COMMAND = {
+  BACKUP_NOW: "plugin:eco-backup|backup_now",
+  CHECK_DATABASE: "plugin:eco-backup|check_database",
+  EXPORT_HISTORY: "plugin:eco-backup|export_history",
+  IMPORT_HISTORY: "plugin:eco-backup|import_history",
+  LAST_MAINTENANCE: "plugin:eco-backup|last_maintenance",
+  LIST_BACKUPS: "plugin:eco-backup|list_backups",
+  MAINTAIN_DATABASE: "plugin:eco-backup|maintain_database",
+  MIGRATE_HISTORY: "plugin:eco-backup|migrate_history",
+  RELOCATE_DATA_DIR: "plugin:eco-backup|relocate_data_dir",
+  START_AUTO_BACKUP: "plugin:eco-backup|start_auto_backup",
+  STOP_AUTO_BACKUP: "plugin:eco-backup|stop_auto_backup",
+};
+
+export const BACKUP_EVENT = {
+  FINISHED: "backup:finished",
+  MAINTAINED: "backup:maintained",
+  RELOCATE_PROGRESS: "backup:relocate-progress",
+};
+
+/**
+ * 自动备份的间隔
+ */
+export type BackupInterval = "daily" | "weekly";
+
+/**
+ * 备份的内容和保存方式
+ */
+export interface BackupConfig {
+  dataDir: string;
+  database: string;
+  includes: string[];
+  extension: string;
+  interval: BackupInterval;
+  keep: number;
+}
+
+/**
+ * 已有的自动备份
+ */
+export interface BackupFile {
+  name: string;
+  path: string;
+  size: number;
+  createdAt: number;
+}
+
+/**
+ * 一次定时备份的结果
+ */
+export type BackupReport =
+  | { status: "created"; path: string }
+  | { status: "skipped" }
+  | { status: "failed"; error: string };
+
+/**
+ * 导出文件的格式
+ */
+export type ExportFormat = "jsonl" | "csv";
+
+/**
+ * 图片记录的导出方式，嵌入图片内容或只记下图片路径
+ */
+export type ExportImageMode = "embed" | "reference";
+
+/**
+ * 导出的条件，都为空时导出所有记录
+ */
+export interface HistoryFilter {
+  group?: string;
+  start?: string;
+  end?: string;
+  favorite?: boolean;
+  ids?: string[];
+}
+
+export interface ExportHistoryOptions {
+  databasePath: string;
+  imagesDir: string;
+  path: string;
+  format: ExportFormat;
+  images: ExportImageMode;
+  filter?: HistoryFilter;
+}
+
+export interface ImportHistoryOptions {
+  databasePath: string;
+  imagesDir: string;
+  path: string;
+}
+
+export interface ExportReport {
+  exported: number;
+  images: number;
+}
+
+export interface ImportReport {
+  imported: number;
+  skipped: number;
+  invalid: number;
+  images: number;
+}
+
+/**
+ * 可以导入历史记录的其它剪贴板工具
+ */
+export type MigrateSource = "copyq" | "ditto" | "maccy" | "clipy" | "gpaste";
+
+export interface MigrateHistoryOptions {
+  source: MigrateSource;
+  databasePath: string;
+  imagesDir: string;
+  path: string;
+}
+
+export interface MigrateReport {
+  imported: number;
+  skipped: number;
+  unsupported: number;
+  images: number;
+}
+
+export interface RelocateDataDirOptions {
+  source: string;
+  target: string;
+  database: string;
+  includes: string[];
+  storePath: string;
+}
+
+/**
+ * 移动数据目录的进度，按字节计算
+ */
+export interface RelocateProgress {
+  stage: "copying" | "verifying";
+  processed: number;
+  total: number;
+}
+
+export interface RelocateReport {
+  files: number;
+  bytes: number;
+  cleaned: boolean;
+}
+
+export interface MaintainDatabaseOptions {
+  databasePath: string;
+}
+
+/**
+ * 数据库维护的结果，损坏时用读取到的记录重建，否则只整理数据库
+ */
+export interface MaintainReport {
+  status: "healthy" | "repaired";
+  problems: string[];
+  salvaged: number;
+  corruptPath?: string;
+  sizeBefore: number;
+  sizeAfter: number;
+  finishedAt: number;
+}
+
+/**
+ * 启动定时备份，到了备份时间且内容有变化时备份
+ * @param config 备份的内容和保存方式
+ */
+export const startAutoBackup = (config: BackupConfig) => {
+  return invoke(COMMAND.START_AUTO_BACKUP, { config });
+};
+
+/**
+ * 停止定时备份
+ */
+export const stopAutoBackup = () => {
+  return invoke(COMMAND.STOP_AUTO_BACKUP);
+};
+
+/**
+ * 立即备份一次，返回备份文件的路径
+ * @param config 备份的内容和保存方式
+ */
+export const backupNow = (config: BackupConfig) => {
+  return invoke<string>(COMMAND.BACKUP_NOW, { config });
+};
+
+/**
+ * 获取已有的自动备份，最新的在前
+ * @param config 备份的内容和保存方式
+ */
+export const listBackups = (config: BackupConfig) => {
+  return invoke<BackupFile[]>(COMMAND.LIST_BACKUPS, { config });
+};
+
+/**
+ * 按条件导出历史记录为 JSON Lines 或 CSV，不包含识别为密钥的记录
+ * @param options 导出的条件和文件
+ */
+export const exportHistory = (options: ExportHistoryOptions) => {
+  return invoke<ExportReport>(COMMAND.EXPORT_HISTORY, { options });
+};
+
+/**
+ * 把导出的历史记录合并到数据库，ID 已存在的记录保持不变
+ * @param options 导入的文件
+ */
+export const importHistory = (options: ImportHistoryOptions) => {
+  return invoke<ImportReport>(COMMAND.IMPORT_HISTORY, { options });
+};
+
+/**
+ * 从其它剪贴板工具导入历史记录，已有相同内容的记录保持不变
+ * @param options 来源和数据文件
+ */
+export const migrateHistory = (options: MigrateHistoryOptions) => {
+  return invoke<MigrateReport>(COMMAND.MIGRATE_HISTORY, { options });
+};
+
+/**
+ * 移动数据目录，复制并校验完成后才切换到新的目录并删除旧的数据，调用前需要关闭数据库
+ * 移动期间暂停定时备份，失败时按原来的配置恢复，成功后需要按新的目录重新启动
+ * @param options 新旧目录和需要移动的内容
+ */
+export const relocateDataDir = (options: RelocateDataDirOptions) => {
+  return invoke<RelocateReport>(COMMAND.RELOCATE_DATA_DIR, { options });
+};
+
+/**
+ * 检查数据库的完整性，返回发现的问题，没有问题时为空
+ * @param databasePath 数据库的路径
+ */
+export const checkDatabase = (databasePath: string) => {
+  return invoke<string[]>(COMMAND.CHECK_DATABASE, { databasePath });
+};
+
+/**
+ * 检查数据库，损坏时用读取到的记录重建，否则整理数据库，调用前需要关闭数据库
+ * @param options 数据库的路径
+ */
+export const maintainDatabase = (options: MaintainDatabaseOptions) => {
+  return invoke<MaintainReport>(COMMAND.MAINTAIN_DATABASE, { options });
+};
+
+/**
+ * 获取最近一次数据库维护的结果，应用启动后还没有运行过时为空
+ */
+export const lastMaintenance = () => {
+  return invoke<MaintainReport | null>(COMMAND.LAST_MAINTENANCE);
+};
@@ -1,5 +1,6 @@
-import { exists } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 import {
+  readText,
   writeFiles,
   writeHTML,
   writeImage,
@@ -8,29 +9,56 @@ import {
 } from "tauri-plugin-clipboard-x-api";
 import { clipboardStore } from "@/stores/clipboard";
 import type { DatabaseSchemaHistory } from "@/types/database";
-import { isColor, isEmail, isURL } from "@/utils/is";
-import { paste } from "./paste";
+import type {
+  ContentFormat,
+  ContentOutputFormat,
+  PasteTransform,
+  TextClassification,
+} from "@/types/plugin";
+import { expandSnippet, moveCursorBack, paste, transformText } from "./paste";
 
-export const getClipboardTextSubtype = async (value: string) => {
-  try {
-    if (isURL(value)) {
-      return "url";
-    }
+export const COMMAND = {
+  CLASSIFY_TEXTS: "plugin:eco-clipboard|classify_texts",
+  CONVERT_CONTENT: "plugin:eco-clipboard|convert_content",
+  HIGHLIGHT_STYLE: "plugin:eco-clipboard|highlight_style",
+  SANITIZE_HTML: "plugin:eco-clipboard|sanitize_html",
+};
 
-    if (isEmail(value)) {
-      return "email";
-    }
+/**
+ * 把 HTML 或 RTF 转为 Markdown 或纯文本
+ * @param value HTML 或 RTF 内容
+ * @param from 内容的格式
+ * @param to 转换结果的格式
+ */
+export const convertContent = (
+  value: string,
+  from: ContentFormat,
+  to: ContentOutputFormat,
+) => {
+  return invoke<string>(COMMAND.CONVERT_CONTENT, { from, to, value });
+};
 
-    if (isColor(value)) {
-      return "color";
-    }
+/**
+ * 批量识别文本的类型，例如链接、JSON、代码及其语言，代码和 JSON 同时返回高亮后的 HTML，结果和传入的文本一一对应
+ * @param values 文本内容
+ */
+export const classifyTexts = (values: string[]) => {
+  return invoke<TextClassification[]>(COMMAND.CLASSIFY_TEXTS, { values });
+};
 
-    if (await exists(value)) {
-      return "path";
-    }
-  } catch {
-    return;
-  }
+/**
+ * 获取代码高亮的 CSS，包含亮色和暗色两套主题
+ */
+export const getHighlightStyle = () => {
+  return invoke<string>(COMMAND.HIGHLIGHT_STYLE);
+};
+
+/**
+ * 清理 HTML，去掉脚本、事件属性和远程资源，结果只用于渲染
+ * @param value HTML 内容
+ */
+export const sanitizeHtml = (value: string) => {
+  return invoke<string>(COMMAND.SANITIZE_HTML, { value });
 };
 
 export const writeToClipboard = (data: DatabaseSchemaHistory) => {
@@ -53,11 +81,17 @@ export const writeToClipboard = (data: DatabaseSchemaHistory) => {
 export const pasteToClipboard = async (
   data: DatabaseSchemaHistory,
   asPlain?: boolean,
+  transforms?: PasteTransform[],
 ) => {
-  const { type, value, search } = data;
+  const { type, value, search, group } = data;
   const { pastePlain } = clipboardStore.content;
 
-  if (asPlain ?? pastePlain) {
+  // 图片没有可以转换的文本，按原样粘贴
+  if (transforms?.length && group !== "image") {
+    const text = type === "files" ? value.join("\n") : search;
+
+    await writeText(await transformText(text, transforms));
+  } else if (asPlain ?? pastePlain) {
     if (type === "files") {
       await writeText(value.join("\n"));
     } else {
@@ -69,3 +103,46 @@ export const pasteToClipboard = async (
 
   return paste();
 };
+
+/**
+ * 把 HTML 或 RTF 转为 Markdown 后粘贴，保留列表、链接和表格
+ */
+export const pasteAsMarkdown = async (data: DatabaseSchemaHistory) => {
+  const { type, value, search } = data;
+
+  if (type !== "html" && type !== "rtf") {
+    return pasteToClipboard(data, true);
+  }
+
+  const markdown = await convertContent(value, type, "markdown");
+
+  await writeText(markdown || search);
+
+  return paste();
+};
+
+/**
+ * 展开片段后粘贴，有 `{cursor}` 时把光标移到对应的位置
+ * @param value 片段的内容
+ * @param inputs 用户输入的内容
+ */
+export const pasteSnippet = async (
+  value: string,
+  inputs: Record<string, string> = {},
+) => {
+  // 剪贴板中没有文本时读取失败
+  const clipboard = await readText().catch(() => "");
+
+  const { text, cursorBack } = await expandSnippet(value, {
+    clipboard,
+    inputs,
+  });
+
+  await writeText(text);
+
+  await paste();
+
+  if (!cursorBack) return;
+
+  return moveCursorBack(cursorBack);
+};
@@ -0,0 +1,49 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export const COMMAND = {
+  SET_EXPANDER_CONFIG: "plugin:eco-expander|set_expander_config",
+  START_EXPANDER: "plugin:eco-expander|start_expander",
+  STOP_EXPANDER: "plugin:eco-expander|stop_expander",
+};
+
+export const EXPANDER_EVENT = {
+  EXPAND: "expander:expand",
+};
+
+/**
+ * 需要展开的缩写和排除的应用
+ */
+export interface ExpanderConfig {
+  abbreviations: string[];
+  excludedApps: string[];
+}
+
+/**
+ * 识别出的缩写，`erase` 是已经删除的字符数
+ */
+export interface Expansion {
+  abbreviation: string;
+  erase: number;
+}
+
+/**
+ * 启动键盘监控，开始展开缩写
+ */
+export const startExpander = () => {
+  return invoke(COMMAND.START_EXPANDER);
+};
+
+/**
+ * 停止键盘监控
+ */
+export const stopExpander = () => {
+  return invoke(COMMAND.STOP_EXPANDER);
+};
+
+/**
+ * 更新需要展开的缩写和排除的应用
+ * @param config 缩写和排除的应用
+ */
+export const setExpanderConfig = (config: ExpanderConfig) => {
+  return invoke(COMMAND.SET_EXPANDER_CONFIG, { config });
+};
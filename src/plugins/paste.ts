@@ -1,12 +1,83 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { PasteTransform } from "@/types/plugin";
 
 export const COMMAND = {
+  EXPAND_SNIPPET: "plugin:eco-paste|expand_snippet",
+  MOVE_CURSOR_BACK: "plugin:eco-paste|move_cursor_back",
   PASTE: "plugin:eco-paste|paste",
+  SNIPPET_INPUTS: "plugin:eco-paste|snippet_inputs",
+  TRANSFORM_TEXT: "plugin:eco-paste|transform_text",
 };
 
+/**
+ * 粘贴前可用的转换
+ */
+export const PASTE_TRANSFORMS: PasteTransform[] = [
+  "trimmed",
+  "singleLine",
+  "upper",
+  "lower",
+  "title",
+  "quoted",
+  "jsonEscape",
+  "shellEscape",
+  "regexEscape",
+  "markdownLink",
+];
+
+/**
+ * 展开片段时使用的内容
+ */
+export interface SnippetValues {
+  clipboard: string;
+  inputs: Record<string, string>;
+}
+
+ * 展开后的片段，`cursorBack` 是粘贴后光标需要向左移动的次数，光标之后的内容太长时为空
+ * 展开后的片段，`cursorBack` 是粘贴后光标需要向左移动的字符数
+ */
+export interface ExpandedSnippet {
+  text: string;
+  cursorBack?: number;
+}
+
 /**
  * 粘贴剪贴板内容
  */
 export const paste = () => {
   return invoke(COMMAND.PASTE);
 };
+
+/**
+ * 按顺序对文本执行粘贴前的转换
+ * @param text 要转换的文本
+ * @param transforms 转换列表
+ */
+export const transformText = (text: string, transforms: PasteTransform[]) => {
+  return invoke<string>(COMMAND.TRANSFORM_TEXT, { text, transforms });
+};
+
+/**
+ * 获取片段中需要用户输入的名称
+ * @param template 片段的内容
+ */
+export const snippetInputs = (template: string) => {
+  return invoke<string[]>(COMMAND.SNIPPET_INPUTS, { template });
+};
+
+/**
+ * 展开片段中的占位符
+ * @param template 片段的内容
+ * @param values 剪贴板的文本和用户输入的内容
+ */
+export const expandSnippet = (template: string, values: SnippetValues) => {
+  return invoke<ExpandedSnippet>(COMMAND.EXPAND_SNIPPET, { template, values });
+};
+
+/**
+ * 光标向左移动
+ * @param count 移动的字符数
+ */
+export const moveCursorBack = (count: number) => {
+  return invoke(COMMAND.MOVE_CURSOR_BACK, { count });
+};
@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export const COMMAND = {
+  DELETE_SECRET: "plugin:eco-secret|delete_secret",
+  HAS_SECRET: "plugin:eco-secret|has_secret",
+  SET_SECRET: "plugin:eco-secret|set_secret",
+};
+
+/**
+ * 密钥实际保存的位置，系统钥匙串不可用时保存到加密文件
+ */
+export type SecretBackend = "keyring" | "file";
+
+/**
+ * 保存密钥
+ * @param name 密钥名称
+ * @param value 密钥内容
+ */
+export const setSecret = (name: string, value: string) => {
+  return invoke<SecretBackend>(COMMAND.SET_SECRET, { name, value });
+};
+
+/**
+ * 删除密钥
+ * @param name 密钥名称
+ */
+export const deleteSecret = (name: string) => {
+  return invoke<boolean>(COMMAND.DELETE_SECRET, { name });
+};
+
+/**
+ * 是否保存了密钥
+ * @param name 密钥名称
+ */
+export const hasSecret = (name: string) => {
+  return invoke<boolean>(COMMAND.HAS_SECRET, { name });
+};
@@ -0,0 +1,152 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+import { getLocale } from "tauri-plugin-locale-api";
+import type {
+  AIChatCompletion,
+  AIChatRequest,
+  PromptIssue,
+  SelectionCapture,
+  SelectionHideConfig,
+  SelectionMonitorStatus,
+  TransformInfo,
+} from "@/types/selection-assistant";
+
+export const COMMAND = {
+  AI_CHAT_STREAM: "plugin:eco-selection|ai_chat_stream",
+  CANCEL_AI_REQUEST: "plugin:eco-selection|cancel_ai_request",
+  GET_SELECTED_TEXT: "plugin:eco-selection|get_selected_text",
+  HIDE_SELECTION_TOOLBAR: "plugin:eco-selection|hide_selection_toolbar",
+  LIST_TRANSFORMS: "plugin:eco-selection|list_transforms",
+  RENDER_PROMPT: "plugin:eco-selection|render_prompt",
+  RESTART_SELECTION_MONITOR: "plugin:eco-selection|restart_selection_monitor",
+  RUN_TRANSFORM: "plugin:eco-selection|run_transform",
+  SELECTION_MONITOR_STATUS: "plugin:eco-selection|selection_monitor_status",
+  SET_SELECTION_HIDE_CONFIG: "plugin:eco-selection|set_selection_hide_config",
+  SHOW_SELECTION_TOOLBAR: "plugin:eco-selection|show_selection_toolbar",
+  START_SELECTION_MONITOR: "plugin:eco-selection|start_selection_monitor",
+  STOP_SELECTION_MONITOR: "plugin:eco-selection|stop_selection_monitor",
+  VALIDATE_PROMPT: "plugin:eco-selection|validate_prompt",
+};
+
+/**
+ * 启动划词监控
+ */
+export const startSelectionMonitor = () => {
+  return invoke(COMMAND.START_SELECTION_MONITOR);
+};
+
+/**
+ * 停止划词监控
+ */
+export const stopSelectionMonitor = () => {
+  return invoke(COMMAND.STOP_SELECTION_MONITOR);
+};
+
+/**
+ * 重启划词监控
+ */
+export const restartSelectionMonitor = () => {
+  return invoke(COMMAND.RESTART_SELECTION_MONITOR);
+};
+
+/**
+ * 获取划词监控的状态
+ */
+export const getSelectionMonitorStatus = () => {
+  return invoke<SelectionMonitorStatus>(COMMAND.SELECTION_MONITOR_STATUS);
+};
+
+/**
+ * 获取选中的文本
+ * @param timeout 等待剪贴板变化的超时时间（毫秒）
+ */
+export const getSelectedText = (timeout?: number) => {
+  return invoke<SelectionCapture>(COMMAND.GET_SELECTED_TEXT, { timeout });
+};
+
+/**
+ * 在划词位置显示工具栏
+ */
+export const showSelectionToolbar = (x: number, y: number) => {
+  return invoke(COMMAND.SHOW_SELECTION_TOOLBAR, { x, y });
+};
+
+/**
+ * 隐藏工具栏
+ */
+export const hideSelectionToolbar = () => {
+  return invoke(COMMAND.HIDE_SELECTION_TOOLBAR);
+};
+
+/**
+ * 设置工具栏自动隐藏的规则
+ */
+export const setSelectionHideConfig = (config: SelectionHideConfig) => {
+  return invoke(COMMAND.SET_SELECTION_HIDE_CONFIG, { config });
+};
+
+/**
+ * 发送流式 AI 请求
+ * @param id 请求 id，用于取消请求
+ * @param request 请求参数
+ * @param onDelta 收到一段回复时的回调
+ */
+export const aiChatStream = (
+  id: string,
+  request: AIChatRequest,
+  onDelta?: (delta: string) => void,
+) => {
+  const channel = new Channel<string>();
+
+  if (onDelta) {
+    channel.onmessage = onDelta;
+  }
+
+  return invoke<AIChatCompletion>(COMMAND.AI_CHAT_STREAM, {
+    id,
+    onDelta: channel,
+    request,
+  });
+};
+
+/**
+ * 取消 AI 请求
+ * @param id 请求 id
+ */
+export const cancelAIRequest = (id: string) => {
+  return invoke<boolean>(COMMAND.CANCEL_AI_REQUEST, { id });
+};
+
+/**
+ * 渲染提示词模板，变量由 Rust 端根据当前环境收集，系统语言从区域设置插件获取
+ * @param template 提示词模板
+ * @param text 选中的文本
+ */
+export const renderPrompt = async (template: string, text: string) => {
+  const language = await getLocale();
+
+  return invoke<string>(COMMAND.RENDER_PROMPT, { language, template, text });
+};
+
+/**
+ * 检查提示词模板
+ * @param template 提示词模板
+ */
+export const validatePrompt = (template: string) => {
+  return invoke<PromptIssue[]>(COMMAND.VALIDATE_PROMPT, { template });
+};
+
+/**
+ * 列出所有本地文本转换
+ */
+export const listTransforms = () => {
+  return invoke<TransformInfo[]>(COMMAND.LIST_TRANSFORMS);
+};
+
+/**
+ * 在本地执行文本转换，`text.count` 返回 `TextStats` 的 JSON，需要由前端格式化
+ * @param id 转换的 id，例如 `json.pretty`
+ * @param text 要转换的文本
+ */
+export const runTransform = (id: string, text: string) => {
+  return invoke<string>(COMMAND.RUN_TRANSFORM, { id, text });
+};
@@ -0,0 +1,253 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { DatabaseSchemaHistory } from "@/types/database";
+
+export const COMMAND = {
+  CANCEL_SYNC_PAIRING: "plugin:eco-sync|cancel_sync_pairing",
+  CONFIRM_SYNC_PAIRING: "plugin:eco-sync|confirm_sync_pairing",
+  LIST_SYNC_PEERS: "plugin:eco-sync|list_sync_peers",
+  PAIR_SYNC_DEVICE: "plugin:eco-sync|pair_sync_device",
+  REMOTE_SYNC_STATUS: "plugin:eco-sync|remote_sync_status",
+  SEND_SYNC_ENTRY: "plugin:eco-sync|send_sync_entry",
+  START_REMOTE_SYNC: "plugin:eco-sync|start_remote_sync",
+  START_SYNC: "plugin:eco-sync|start_sync",
+  START_SYNC_PAIRING: "plugin:eco-sync|start_sync_pairing",
+  STOP_REMOTE_SYNC: "plugin:eco-sync|stop_remote_sync",
+  STOP_SYNC: "plugin:eco-sync|stop_sync",
+  SYNC_REMOTE_NOW: "plugin:eco-sync|sync_remote_now",
+  SYNC_STATUS: "plugin:eco-sync|sync_status",
+  TEST_REMOTE_SYNC: "plugin:eco-sync|test_remote_sync",
+  UNPAIR_SYNC_DEVICE: "plugin:eco-sync|unpair_sync_device",
+};
+
+export const SYNC_EVENT = {
+  ENTRY: "sync:entry",
+  PAIRED: "sync:paired",
+  PAIRING_CONFIRM: "sync:pairing-confirm",
+  PEERS: "sync:peers",
+  REMOTE: "sync:remote",
+};
+
+/**
+ * 同步服务的状态
+ */
+export interface SyncStatus {
+  deviceId: string;
+  name: string;
+  port: number;
+  pairingCode: string | null;
+}
+
+/**
+ * 已配对和发现的设备
+ */
+export interface SyncPeer {
+  deviceId: string;
+  name: string;
+  paired: boolean;
+  online: boolean;
+  addresses: string[];
+}
+
+/**
+ * 已配对的设备
+ */
+export interface PairedPeer {
+  deviceId: string;
+  name: string;
+  publicKey: string;
+  pairedAt: number;
+}
+
+/**
+ * 配对握手完成，等待用户确认两台设备显示的确认码一致
+ */
+export interface PairingConfirmPayload {
+  deviceId: string;
+  name: string;
+  code: string;
+}
+
+/**
+ * 收到其它设备同步的记录，图片已经保存到图片目录
+ */
+export interface SyncEntryPayload {
+  from: string;
+  entry: DatabaseSchemaHistory;
+  image: string | null;
+}
+
+/**
+ * 远程同步的存储服务，密码保存在密钥存储中
+ */
+export type RemoteConfig =
+  | {
+      kind: "webdav";
+      url: string;
+      username: string;
+    }
+  | {
+      kind: "s3";
+      endpoint: string;
+      region: string;
+      bucket: string;
+      prefix: string;
+      accessKeyId: string;
+      pathStyle: boolean;
+    };
+
+/**
+ * 一次远程同步的结果
+ */
+export interface SyncReport {
+  pushed: number;
+  pulled: number;
+  skipped: number;
+  finishedAt: number;
+}
+
+/**
+ * 远程同步的状态
+ */
+export interface RemoteSyncStatus {
+  running: boolean;
+  syncing: boolean;
+  lastReport: SyncReport | null;
+  lastError: string | null;
+}
+
+/**
+ * 启动局域网同步
+ * @param imagesDir 保存收到的图片的目录
+ * @param port 监听的端口，为空时由系统分配
+ */
+export const startSync = (imagesDir: string, port?: number) => {
+  return invoke<SyncStatus>(COMMAND.START_SYNC, {
+    imagesDir,
+    port: port || null,
+  });
+};
+
+/**
+ * 停止局域网同步
+ */
+export const stopSync = () => {
+  return invoke(COMMAND.STOP_SYNC);
+};
+
+/**
+ * 获取同步服务的状态，没有启动时为 null
+ */
+export const getSyncStatus = () => {
+  return invoke<SyncStatus | null>(COMMAND.SYNC_STATUS);
+};
+
+/**
+ * 获取已配对和发现的设备
+ */
+export const listSyncPeers = () => {
+  return invoke<SyncPeer[]>(COMMAND.LIST_SYNC_PEERS);
+};
+
+/**
+ * 生成配对码，在另一台设备上输入后完成配对
+ */
+export const startSyncPairing = () => {
+  return invoke<string>(COMMAND.START_SYNC_PAIRING);
+};
+
+/**
+ * 取消正在等待的配对
+ */
+export const cancelSyncPairing = () => {
+  return invoke(COMMAND.CANCEL_SYNC_PAIRING);
+};
+
+/**
+ * 输入另一台设备显示的配对码完成配对
+ * @param addresses 设备的地址
+ * @param code 配对码
+ */
+export const pairSyncDevice = (addresses: string[], code: string) => {
+  return invoke<PairedPeer>(COMMAND.PAIR_SYNC_DEVICE, { addresses, code });
+};
+
+/**
+ * 确认或拒绝正在等待确认的配对，两台设备都确认后才完成配对
+ * @param accepted 两台设备显示的确认码是否一致
+ */
+export const confirmSyncPairing = (accepted: boolean) => {
+  return invoke(COMMAND.CONFIRM_SYNC_PAIRING, { accepted });
+};
+
+/**
+ * 取消配对
+ * @param deviceId 设备 ID
+ */
+export const unpairSyncDevice = (deviceId: string) => {
+  return invoke<boolean>(COMMAND.UNPAIR_SYNC_DEVICE, { deviceId });
+};
+
+/**
+ * 把新的记录发送给在线的已配对设备，返回成功发送的设备数量
+ * @param entry 数据库中的记录
+ * @param image 图片记录的文件路径
+ */
+export const sendSyncEntry = (
+  entry: DatabaseSchemaHistory,
+  image?: string,
+) => {
+  return invoke<number>(COMMAND.SEND_SYNC_ENTRY, {
+    entry,
+    image: image ?? null,
+  });
+};
+
+/**
+ * 启动远程同步，立即同步一次，之后定时同步
+ * @param config 存储服务
+ * @param intervalMinutes 同步间隔（分钟）
+ * @param databasePath 历史记录数据库的路径
+ * @param imagesDir 保存图片的目录
+ */
+export const startRemoteSync = (
+  config: RemoteConfig,
+  intervalMinutes: number,
+  databasePath: string,
+  imagesDir: string,
+) => {
+  return invoke(COMMAND.START_REMOTE_SYNC, {
+    config,
+    databasePath,
+    imagesDir,
+    intervalMinutes,
+  });
+};
+
+/**
+ * 停止远程同步
+ */
+export const stopRemoteSync = () => {
+  return invoke(COMMAND.STOP_REMOTE_SYNC);
+};
+
+/**
+ * 立即同步一次并等待结果
+ */
+export const syncRemoteNow = () => {
+  return invoke<SyncReport>(COMMAND.SYNC_REMOTE_NOW);
+};
+
+/**
+ * 获取远程同步的状态
+ */
+export const getRemoteSyncStatus = () => {
+  return invoke<RemoteSyncStatus>(COMMAND.REMOTE_SYNC_STATUS);
+};
+
+/**
+ * 检查能否连接存储服务，以及同步密码与其它设备是否一致
+ * @param config 存储服务
+ */
+export const testRemoteSync = (config: RemoteConfig) => {
+  return invoke(COMMAND.TEST_REMOTE_SYNC, { config });
+};
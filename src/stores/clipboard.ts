@@ -14,12 +14,14 @@ export const clipboardStore = proxy<ClipboardStore>({
     deleteConfirm: true,
     operationButtons: ["copy", "star", "delete"],
     pastePlain: false,
+    pasteTransforms: ["trimmed"],
     showOriginalContent: false,
   },
 
   history: {
     duration: 0,
     maxCount: 0,
+    secretDuration: 30,
     unit: 1,
   },
 
@@ -14,11 +14,25 @@ export const globalStore = proxy<GlobalStore>({
     theme: "auto",
   },
 
+  backup: {
+    auto: {
+      enable: false,
+      interval: "daily",
+      keep: 7,
+    },
+  },
+
   env: {},
 
+  expander: {
+    enable: false,
+    excludedApps: [],
+  },
+
   shortcut: {
     clipboard: "Alt+C",
     pastePlain: "",
+    pasteTransformed: "",
     preference: "Alt+X",
     quickPaste: {
       enable: false,
@@ -26,6 +40,26 @@ export const globalStore = proxy<GlobalStore>({
     },
   },
 
+  sync: {
+    lan: {
+      enable: false,
+      port: 0,
+    },
+    remote: {
+      accessKeyId: "",
+      bucket: "",
+      enable: false,
+      endpoint: "",
+      interval: 15,
+      kind: "webdav",
+      pathStyle: true,
+      prefix: "",
+      region: "",
+      url: "",
+      username: "",
+    },
+  },
+
   update: {
     auto: false,
     beta: false,
@@ -46,6 +46,24 @@ const defaultAgents = [
         order: 4,
         isBuiltin: true,
     },
+    {
+        id: "format_json",
+        name: "格式化 JSON",
+        icon: "i-lucide:braces",
+        enabled: false,
+        order: 5,
+        isBuiltin: true,
+        transform: "json.pretty",
+    },
+    {
+        id: "count_words",
+        name: "字数统计",
+        icon: "i-lucide:hash",
+        enabled: false,
+        order: 6,
+        isBuiltin: true,
+        transform: "text.count",
+    },
 ];
 
 export const selectionAssistantStore = proxy<SelectionAssistantStore>({
@@ -54,6 +72,7 @@ export const selectionAssistantStore = proxy<SelectionAssistantStore>({
     trigger: {
         mode: "selection",
         shortcut: "Alt+Q",
+        captureTimeout: 300,
     },
 
     toolbar: {
@@ -63,14 +82,50 @@ export const selectionAssistantStore = proxy<SelectionAssistantStore>({
         autoClose: false,
         autoTop: false,
         opacity: 100,
+        hideRules: {
+            selection: {
+                outsideClick: true,
+                scroll: true,
+                keyPress: true,
+                foregroundChange: true,
+            },
+            ctrl: {
+                outsideClick: true,
+                scroll: true,
+                keyPress: false,
+                foregroundChange: true,
+            },
+            shortcut: {
+                outsideClick: true,
+                scroll: true,
+                keyPress: false,
+                foregroundChange: true,
+            },
+        },
     },
 
     agents: defaultAgents,
 
     apiConfig: {
-        baseUrl: "https://api.openai.com",
-        apiKey: "",
-        model: "gpt-3.5-turbo",
+        provider: "openai",
+        providers: {
+            openai: {
+                baseUrl: "https://api.openai.com",
+                model: "gpt-3.5-turbo",
+            },
+            ollama: {
+                baseUrl: "http://localhost:11434",
+                model: "llama3.2",
+            },
+            anthropic: {
+                baseUrl: "https://api.anthropic.com",
+                model: "claude-3-5-haiku-latest",
+            },
+            gemini: {
+                baseUrl: "https://generativelanguage.googleapis.com",
+                model: "gemini-2.0-flash",
+            },
+        },
     },
 
     appFilter: {
@@ -4,7 +4,20 @@ import type {
 } from "tauri-plugin-clipboard-x-api";
 import type { LiteralUnion } from "type-fest";
 
-export type DatabaseSchemaHistorySubtype = "url" | "email" | "color" | "path";
+export type DatabaseSchemaHistorySubtype =
+  | "url"
+  | "email"
+  | "color"
+  | "path"
+  | "phone"
+  | "ip"
+  | "json"
+  | "code"
+  | "uuid"
+  | "jwt"
+  | "card"
+  | "timestamp"
+  | "command";
 
 export type DatabaseSchemaHistory<
   T extends ClipboardContentType = ClipboardContentType,
@@ -16,8 +29,41 @@ export type DatabaseSchemaHistory<
   createTime: string;
   note?: string;
   subtype?: DatabaseSchemaHistorySubtype;
+  /**
+   * 代码的语言，只有 `subtype` 为 `code` 时有值
+   */
+  language?: string;
+  /**
+   * 用于渲染的 HTML，`value` 保留原始内容用于复制和粘贴
+   *
+   * HTML 为清理后的内容，代码和 JSON 为高亮后的内容
+   */
+  render?: string;
+  /**
+   * 匹配到的密钥规则，例如 `aws-access-key`，有值时在列表中隐藏内容并按密钥的保留时长删除
+   */
+  secret?: string;
+  /**
+   * 升级时从旧记录中识别出的密钥，只隐藏内容，不按密钥的保留时长删除
+   */
+  keepSecret?: boolean;
+  /**
+   * 最后修改的 UTC 时间（ISO 8601），远程同步按它合并不同设备上的修改
+   */
+  updatedAt?: string;
 };
 
+/**
+ * 用户删除的记录，远程同步时在其它设备上也删除
+ */
+export interface DatabaseSchemaHistoryTombstone {
+  id: string;
+  /**
+   * 删除的 UTC 时间（ISO 8601）
+   */
+  deletedAt: string;
+}
+
 export type DatabaseSchemaGroupId = LiteralUnion<
   "all" | "text" | "image" | "files" | "favorite",
   string
@@ -29,7 +75,38 @@ export interface DatabaseSchemaGroup {
   createTime?: string;
 }
 
+export interface DatabaseSchemaSnippetFolder {
+  id: string;
+  name: string;
+  createTime: string;
+}
+
+export interface DatabaseSchemaSnippet {
+  id: string;
+  /**
+   * 所在的文件夹，为空时不在任何文件夹中
+   */
+  folderId?: string;
+  name: string;
+  /**
+   * 片段的内容，可以包含 `{date}`、`{clipboard}`、`{cursor}` 和 `{input:Name}` 等占位符
+   */
+  value: string;
+  /**
+   * 输入后展开片段的缩写，例如 `;sig`
+   */
+  abbreviation?: string;
+  /**
+   * 直接粘贴片段的全局快捷键
+   */
+  shortcut?: string;
+  createTime: string;
+}
+
 export interface DatabaseSchema {
   history: DatabaseSchemaHistory;
+  history_tombstone: DatabaseSchemaHistoryTombstone;
   group: DatabaseSchemaGroup;
+  snippet: DatabaseSchemaSnippet;
+  snippet_folder: DatabaseSchemaSnippetFolder;
 }
@@ -1,3 +1,5 @@
+import type { DatabaseSchemaHistorySubtype } from "./database";
+
 export type WindowLabel = (typeof WINDOW_LABEL)[keyof typeof WINDOW_LABEL];
 
 export interface ReadImage {
@@ -9,10 +11,33 @@ export interface ReadImage {
 export interface ClipboardPayload {
   type?: "text" | "rtf" | "html" | "image" | "files";
   group: "text" | "image" | "files";
-  subtype?: "url" | "email" | "color" | "path";
+  subtype?: DatabaseSchemaHistorySubtype;
   count: number;
   value: string;
   search: string;
   width?: number;
   height?: number;
 }
+
+export type PasteTransform =
+  | "trimmed"
+  | "singleLine"
+  | "upper"
+  | "lower"
+  | "title"
+  | "quoted"
+  | "jsonEscape"
+  | "shellEscape"
+  | "regexEscape"
+  | "markdownLink";
+
+export type ContentFormat = "html" | "rtf";
+
+export type ContentOutputFormat = "markdown" | "text";
+
+export interface TextClassification {
+  subtype: DatabaseSchemaHistorySubtype | null;
+  language: string | null;
+  render: string | null;
+  secret: string | null;
+}
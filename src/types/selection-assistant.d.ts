@@ -1,6 +1,19 @@
 // 划词助手触发方式
 export type SelectionTriggerMode = "selection" | "ctrl" | "shortcut";
 
+// 工具栏自动隐藏的规则
+export interface SelectionHideRules {
+    outsideClick: boolean;
+    scroll: boolean;
+    keyPress: boolean;
+    foregroundChange: boolean;
+}
+
+// 工具栏自动隐藏的配置
+export type SelectionHideConfig = Record<SelectionTriggerMode, SelectionHideRules> & {
+    mode: SelectionTriggerMode;
+};
+
 // 应用筛选模式
 export type AppFilterMode = "off" | "whitelist" | "blacklist";
 
@@ -13,15 +26,30 @@ export interface CustomAgent {
     order: number;
     isBuiltin: boolean;
     prompt?: string;
+    // 使用的 AI 服务，未设置时使用默认服务
+    provider?: AIProvider;
+    // 使用的模型，未设置时使用服务配置的模型
+    model?: string;
+    // 本地文本转换的 id，设置后不调用 AI 服务
+    transform?: string;
 }
 
-// OpenAI 格式 API 配置
-export interface APIConfig {
+// AI 服务的类型
+export type AIProvider = "openai" | "ollama" | "anthropic" | "gemini";
+
+// 单个 AI 服务的配置
+export interface AIProviderConfig {
     baseUrl: string;
-    apiKey: string;
     model: string;
 }
 
+// AI 服务配置，API Key 保存在系统钥匙串中
+export interface APIConfig {
+    // 默认使用的服务
+    provider: AIProvider;
+    providers: Record<AIProvider, AIProviderConfig>;
+}
+
 // 划词助手状态
 export interface SelectionAssistantStore {
     // 启用状态
@@ -31,6 +59,8 @@ export interface SelectionAssistantStore {
     trigger: {
         mode: SelectionTriggerMode;
         shortcut: string;
+        // 取词时等待剪贴板变化的超时时间（毫秒）
+        captureTimeout: number;
     };
 
     // 工具栏设置
@@ -41,12 +71,14 @@ export interface SelectionAssistantStore {
         autoClose: boolean;
         autoTop: boolean;
         opacity: number;
+        // 各触发方式下自动隐藏工具栏的规则
+        hideRules: Record<SelectionTriggerMode, SelectionHideRules>;
     };
 
     // Agent 列表（内置 + 自定义）
     agents: CustomAgent[];
 
-    // API 配置（OpenAI 兼容格式）
+    // AI 服务配置
     apiConfig: APIConfig;
 
     // 应用筛选
@@ -63,11 +95,66 @@ export interface SelectionEventPayload {
     y: number;
 }
 
+// 取词结果
+export type SelectionCapture =
+    | { kind: "text"; text: string }
+    | { kind: "noSelection" };
+
+// 划词监控的状态
+export interface SelectionMonitorStatus {
+    state: "stopped" | "running" | "stopping";
+    input?: string;
+    startedAt?: number;
+    startCount: number;
+    lastError?: string;
+}
+
 // AI 请求参数
 export interface AIRequestParams {
     text: string;
     prompt: string;
     apiConfig: APIConfig;
+    // 使用的服务和模型，未设置时使用默认配置
+    provider?: AIProvider;
+    model?: string;
+    // 请求 id，用于取消请求
+    id?: string;
+    // 收到一段流式回复时的回调
+    onDelta?: (delta: string) => void;
+}
+
+// 对话消息
+export interface AIChatMessage {
+    role: "system" | "user" | "assistant";
+    content: string;
+}
+
+// 请求的超时时间（毫秒）
+export interface AIChatTimeouts {
+    connect?: number;
+    idle?: number;
+    total?: number;
+}
+
+// 发送给 Rust 端的对话请求
+export interface AIChatRequest {
+    provider?: AIProvider;
+    baseUrl: string;
+    apiKey?: string;
+    // 保存 API Key 的密钥名称，由 Rust 端读取
+    apiKeySecret?: string;
+    model: string;
+    messages: AIChatMessage[];
+    temperature?: number;
+    maxTokens?: number;
+    timeouts?: AIChatTimeouts;
+}
+
+// 对话结果
+export interface AIChatCompletion {
+    content: string;
+    finishReason?: string;
+    cancelled: boolean;
 }
 
 // AI 响应
@@ -75,4 +162,48 @@ export interface AIResponse {
     success: boolean;
     content?: string;
     error?: string;
+    // 是否被取消，取消时 content 为已经收到的部分
+    cancelled?: boolean;
+}
+
+// 提示词模板中的问题类型
+export type PromptIssueKind =
+    | "syntax"
+    | "unknownVariable"
+    | "unknownFilter"
+    | "invalidArgument";
+
+// 提示词模板中的问题
+export interface PromptIssue {
+    kind: PromptIssueKind;
+    // 相关的变量或过滤器名称
+    name?: string;
+    // 在模板中的位置（字符偏移）
+    offset: number;
+    message: string;
+}
+
+// 本地文本转换的分类
+export type TransformCategory =
+    | "case"
+    | "format"
+    | "encoding"
+    | "hash"
+    | "time"
+    | "text"
+    | "convert";
+
+// 本地文本转换
+export interface TransformInfo {
+    id: string;
+    category: TransformCategory;
+}
+
+// `text.count` 转换返回的统计结果
+export interface TextStats {
+    characters: number;
+    nonWhitespace: number;
+    words: number;
+    lines: number;
+    bytes: number;
 }
@@ -1,4 +1,5 @@
 import type { Platform } from "@tauri-apps/plugin-os";
+import type { PasteTransform } from "./plugin";
 import type { SelectionAssistantStore } from "./selection-assistant";
 
 export type Theme = "auto" | "light" | "dark";
@@ -32,6 +33,45 @@ export interface GlobalStore {
     beta: boolean;
   };
 
+  // 同步设置
+  sync: {
+    // 局域网同步，端口为 0 时由系统分配
+    lan: {
+      enable: boolean;
+      port: number;
+    };
+    // 通过 WebDAV 或 S3 兼容存储同步，间隔的单位为分钟，密码保存在密钥存储中
+    remote: {
+      enable: boolean;
+      interval: number;
+      kind: "webdav" | "s3";
+      url: string;
+      username: string;
+      endpoint: string;
+      region: string;
+      bucket: string;
+      prefix: string;
+      accessKeyId: string;
+      pathStyle: boolean;
+    };
+  };
+
+  // 备份设置
+  backup: {
+    // 定时备份到存储目录，只保留最近的几份
+    auto: {
+      enable: boolean;
+      interval: "daily" | "weekly";
+      keep: number;
+    };
+  };
+
+  // 输入缩写后展开片段，排除的应用为可执行文件名（Windows）或 WM_CLASS（Linux）
+  expander: {
+    enable: boolean;
+    excludedApps: string[];
+  };
+
   // 快捷键设置
   shortcut: {
     clipboard: string;
@@ -41,6 +81,7 @@ export interface GlobalStore {
       value: string;
     };
     pastePlain: string;
+    pasteTransformed: string;
   };
 
   // 只在当前系统环境使用
@@ -87,6 +128,7 @@ export interface ClipboardStore {
     autoPaste: "single" | "double";
     copyPlain: boolean;
     pastePlain: boolean;
+    pasteTransforms: PasteTransform[];
     operationButtons: OperationButton[];
     autoFavorite: boolean;
     deleteConfirm: boolean;
@@ -99,5 +141,7 @@ export interface ClipboardStore {
     duration: number;
     unit: number;
     maxCount: number;
+    // 包含密钥的条目的保留时长，单位为分钟
+    secretDuration: number;
   };
 }
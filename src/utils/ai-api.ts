@@ -1,52 +1,83 @@
-import type { APIConfig, AIRequestParams, AIResponse } from "@/types/selection-assistant";
+import { nanoid } from "nanoid";
+import { SECRET_NAME } from "@/constants";
+import { aiChatStream, cancelAIRequest, renderPrompt } from "@/plugins/selection";
+import type {
+    AIProvider,
+    AIRequestParams,
+    AIResponse,
+} from "@/types/selection-assistant";
 
 /**
- * 调用 AI API（使用 OpenAI 兼容格式）
+ * 支持的 AI 服务
  */
-export async function callAI(params: AIRequestParams): Promise<AIResponse> {
-    const { text, prompt, apiConfig } = params;
+export const AI_PROVIDERS: AIProvider[] = [
+    "openai",
+    "ollama",
+    "anthropic",
+    "gemini",
+];
 
-    if (!apiConfig.apiKey) {
-        return { success: false, error: "API Key 未配置" };
+/**
+ * 保存 AI 服务 API Key 的密钥名称
+ */
+export function getAPIKeySecretName(provider: AIProvider) {
+    // OpenAI 沿用最早的名称，兼容已经保存的密钥
+    if (provider === "openai") {
+        return SECRET_NAME.AI_API_KEY;
     }
 
+    return `${SECRET_NAME.AI_API_KEY}.${provider}`;
+}
+
+/**
+ * 调用 AI API，由 Rust 端按服务的格式读取 API Key 并发出流式请求
+ */
+export async function callAI(params: AIRequestParams): Promise<AIResponse> {
+    const { text, prompt, apiConfig, id = nanoid(), onDelta } = params;
+
+    const provider = params.provider ?? apiConfig.provider;
+    const { baseUrl, model } = apiConfig.providers[provider];
+
     try {
-        const response = await fetch(`${apiConfig.baseUrl}/v1/chat/completions`, {
-            method: "POST",
-            headers: {
-                "Content-Type": "application/json",
-                "Authorization": `Bearer ${apiConfig.apiKey}`,
-            },
-            body: JSON.stringify({
-                model: apiConfig.model || "gpt-3.5-turbo",
+        const content = await renderPrompt(prompt, text);
+
+        const completion = await aiChatStream(
+            id,
+            {
+                apiKeySecret: getAPIKeySecretName(provider),
+                baseUrl,
                 messages: [
                     {
                         role: "user",
-                        content: prompt.replace("{{text}}", text),
+                        content,
                     },
                 ],
-                stream: false,
-            }),
-        });
-
-        const data = await response.json();
-
-        if (data.error) {
-            return { success: false, error: data.error.message };
-        }
+                model: params.model || model,
+                provider,
+            },
+            onDelta,
+        );
 
         return {
             success: true,
-            content: data.choices?.[0]?.message?.content || "",
+            content: completion.content,
+            cancelled: completion.cancelled,
         };
     } catch (error) {
         return {
             success: false,
-            error: error instanceof Error ? error.message : "Unknown error",
+            error: error instanceof Error ? error.message : String(error),
         };
     }
 }
 
+/**
+ * 取消 AI 请求
+ */
+export function cancelAI(id: string) {
+    return cancelAIRequest(id);
+}
+
 /**
  * 在浏览器中搜索
  */
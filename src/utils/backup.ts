@@ -0,0 +1,81 @@
+import { emit } from "@tauri-apps/api/event";
+import { fullName } from "tauri-plugin-fs-pro-api";
+import { LISTEN_KEY } from "@/constants";
+import {
+  type BackupConfig,
+  maintainDatabase,
+  startAutoBackup,
+  stopAutoBackup,
+} from "@/plugins/backup";
+import { stopRemoteSync } from "@/plugins/sync";
+import { globalStore } from "@/stores/global";
+import {
+  getSaveDatabasePath,
+  getSaveDataPath,
+  getSaveImagePath,
+  getSaveStorePath,
+} from "./path";
+import { wait } from "./shared";
+import { saveStore } from "./store";
+import { restartRemoteSync } from "./sync";
+
+/**
+ * 备份文件的扩展名
+ */
+export const getBackupExtname = () => {
+  return `${globalStore.env.appName}-backup`;
+};
+
+/**
+ * 自动备份的配置，备份的内容与手动导出的相同
+ */
+export const getBackupConfig = async (): Promise<BackupConfig> => {
+  const { interval, keep } = globalStore.backup.auto;
+
+  return {
+    dataDir: getSaveDataPath(),
+    database: await fullName(await getSaveDatabasePath()),
+    extension: getBackupExtname(),
+    includes: [
+      await fullName(getSaveImagePath()),
+      await fullName(await getSaveStorePath(true)),
+    ],
+    interval,
+    keep,
+  };
+};
+
+/**
+ * 按当前的设置重新启动自动备份，未开启时只停止
+ */
+export const restartAutoBackup = async () => {
+  await stopAutoBackup();
+
+  if (!globalStore.backup.auto.enable) return;
+
+  // 备份中的配置项来自备份用的配置文件，启动前先写入最新的配置
+  await saveStore(true);
+
+  await startAutoBackup(await getBackupConfig());
+};
+
+/**
+ * 关闭数据库后检查并修复或整理数据库，完成后刷新列表
+ */
+export const runDatabaseMaintenance = async () => {
+  emit(LISTEN_KEY.CLOSE_DATABASE);
+
+  await stopRemoteSync();
+
+  await wait();
+
+  try {
+    return await maintainDatabase({
+      databasePath: await getSaveDatabasePath(),
+    });
+  } finally {
+    emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+
+    await restartRemoteSync();
+  }
+};
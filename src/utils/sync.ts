@@ -0,0 +1,57 @@
+import { getDefaultSaveImagePath } from "tauri-plugin-clipboard-x-api";
+import {
+  type RemoteConfig,
+  startRemoteSync,
+  stopRemoteSync,
+} from "@/plugins/sync";
+import { globalStore } from "@/stores/global";
+import { getSaveDatabasePath } from "./path";
+
+/**
+ * 根据远程同步的设置生成存储服务的配置
+ */
+export const getRemoteConfig = (): RemoteConfig => {
+  const {
+    kind,
+    url,
+    username,
+    endpoint,
+    region,
+    bucket,
+    prefix,
+    accessKeyId,
+    pathStyle,
+  } = globalStore.sync.remote;
+
+  if (kind === "webdav") {
+    return { kind, url: url.trim(), username: username.trim() };
+  }
+
+  return {
+    accessKeyId: accessKeyId.trim(),
+    bucket: bucket.trim(),
+    endpoint: endpoint.trim(),
+    kind,
+    pathStyle,
+    prefix: prefix.trim(),
+    region: region.trim(),
+  };
+};
+
+/**
+ * 按当前的设置重新启动远程同步，没有启用时只停止
+ */
+export const restartRemoteSync = async () => {
+  const { enable, interval } = globalStore.sync.remote;
+
+  await stopRemoteSync();
+
+  if (!enable) return;
+
+  await startRemoteSync(
+    getRemoteConfig(),
+    interval,
+    await getSaveDatabasePath(),
+    await getDefaultSaveImagePath(),
+  );
+};